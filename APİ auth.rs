@@ -0,0 +1,336 @@
+//! Pluggable request authentication for the Synaptron inference engine
+
+use crate::{config::{ApiKeyConfig, AuthConfig, JwtConfig, API_KEYS_ENV}, error::SynaptronError};
+use async_trait::async_trait;
+use axum::{
+    extract::{Request, State},
+    http::{header, request::Parts, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{decode, errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Paths served without authentication
+const PUBLIC_PATHS: &[&str] = &["/health", "/ready"];
+
+/// Path prefix guarded by the admin token instead of the authenticator
+const ADMIN_PREFIX: &str = "/admin/";
+
+/// Routes outside `/admin` that are also guarded by the admin token
+const ADMIN_ROUTES: &[(Method, &str)] = &[
+    (Method::POST, "/graph"),
+    (Method::POST, "/models/activate"),
+    (Method::POST, "/models/deactivate"),
+];
+
+/// Authenticated caller, available to handlers via `Extension<Identity>`
+#[derive(Debug, Clone, Serialize)]
+pub struct Identity {
+    /// Caller subject
+    pub subject: String,
+    
+    /// Tenant the caller belongs to, if any
+    pub tenant: Option<String>,
+}
+
+/// Authentication failure
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    /// No credentials on the request
+    #[error("Missing bearer token")]
+    MissingCredentials,
+    
+    /// Credentials were valid but have expired
+    #[error("Token has expired")]
+    Expired,
+    
+    /// Credentials were rejected
+    #[error("Invalid credentials: {0}")]
+    Invalid(String),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            self.to_string(),
+        ).into_response()
+    }
+}
+
+/// Resolves the caller of a request
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    /// Authenticate a request from its head
+    async fn authenticate(&self, req: &Parts) -> Result<Identity, AuthError>;
+}
+
+/// Read the bearer token from the `Authorization` header
+fn bearer_token(req: &Parts) -> Result<&str, AuthError> {
+    req.headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or(AuthError::MissingCredentials)
+}
+
+/// Authenticator accepting a fixed set of API keys
+pub struct StaticKeyAuthenticator {
+    /// Identity per key
+    keys: HashMap<String, Identity>,
+}
+
+impl StaticKeyAuthenticator {
+    /// Create an authenticator from configured keys
+    pub fn new(keys: &[ApiKeyConfig]) -> Self {
+        let keys = keys.iter()
+            .map(|key| {
+                let identity = Identity {
+                    subject: key.subject.clone(),
+                    tenant: key.tenant.clone(),
+                };
+                (key.key.clone(), identity)
+            })
+            .collect();
+        
+        Self { keys }
+    }
+}
+
+#[async_trait]
+impl Authenticator for StaticKeyAuthenticator {
+    async fn authenticate(&self, req: &Parts) -> Result<Identity, AuthError> {
+        let token = bearer_token(req)?;
+        self.keys.get(token)
+            .cloned()
+            .ok_or_else(|| AuthError::Invalid("unknown API key".to_string()))
+    }
+}
+
+/// Authenticator validating JWT signature, expiry, audience and issuer
+pub struct JwtAuthenticator {
+    /// Signature verification key
+    key: DecodingKey,
+    
+    /// Claim validation rules
+    validation: Validation,
+    
+    /// Claim holding the caller's tenant
+    tenant_claim: String,
+}
+
+impl JwtAuthenticator {
+    /// Create an authenticator from JWT configuration
+    pub fn new(config: &JwtConfig) -> Result<Self, SynaptronError> {
+        let invalid = |message: String| SynaptronError::Config(config::ConfigError::Message(message));
+        
+        let algorithm = Algorithm::from_str(&config.algorithm)
+            .map_err(|_| invalid(format!("Unsupported auth.jwt.algorithm: {}", config.algorithm)))?;
+        
+        let key = match algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = config.secret.as_ref()
+                    .ok_or_else(|| invalid(format!("auth.jwt.secret is required for {:?}", algorithm)))?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => {
+                let pem = config.public_key_pem.as_ref()
+                    .ok_or_else(|| invalid(format!("auth.jwt.public_key_pem is required for {:?}", algorithm)))?;
+                let key = match algorithm {
+                    Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(pem.as_bytes()),
+                    Algorithm::EdDSA => DecodingKey::from_ed_pem(pem.as_bytes()),
+                    _ => DecodingKey::from_rsa_pem(pem.as_bytes()),
+                };
+                key.map_err(|e| invalid(format!("Invalid auth.jwt.public_key_pem: {}", e)))?
+            }
+        };
+        
+        let mut validation = Validation::new(algorithm);
+        match &config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        if let Some(issuer) = &config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        
+        Ok(Self {
+            key,
+            validation,
+            tenant_claim: config.tenant_claim.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Authenticator for JwtAuthenticator {
+    async fn authenticate(&self, req: &Parts) -> Result<Identity, AuthError> {
+        let token = bearer_token(req)?;
+        
+        let claims = decode::<serde_json::Map<String, serde_json::Value>>(token, &self.key, &self.validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => AuthError::Expired,
+                ErrorKind::InvalidAudience => AuthError::Invalid("token audience does not match".to_string()),
+                ErrorKind::InvalidIssuer => AuthError::Invalid("token issuer does not match".to_string()),
+                _ => AuthError::Invalid(e.to_string()),
+            })?
+            .claims;
+        
+        let subject = claims.get("sub")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AuthError::Invalid("token has no sub claim".to_string()))?
+            .to_string();
+        let tenant = claims.get(&self.tenant_claim)
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        
+        Ok(Identity { subject, tenant })
+    }
+}
+
+/// Build the configured authenticator, or `None` when authentication is disabled
+pub fn from_config(config: &AuthConfig) -> Result<Option<Arc<dyn Authenticator>>, SynaptronError> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    
+    info!("Enabling {} authentication", config.method);
+    
+    let authenticator: Arc<dyn Authenticator> = match config.method.as_str() {
+        "static" if config.api_keys.is_empty() => {
+            return Err(SynaptronError::Config(config::ConfigError::Message(format!(
+                "auth.method static needs at least one key in auth.api_keys or {}", API_KEYS_ENV
+            ))));
+        }
+        "static" => Arc::new(StaticKeyAuthenticator::new(&config.api_keys)),
+        "jwt" => Arc::new(JwtAuthenticator::new(&config.jwt)?),
+        other => {
+            return Err(SynaptronError::Config(config::ConfigError::Message(format!(
+                "Unknown auth.method: {}", other
+            ))));
+        }
+    };
+    
+    Ok(Some(authenticator))
+}
+
+/// Authenticate requests, attaching the caller's `Identity` for handlers
+///
+/// `/health` and `/ready` stay public, and `/admin` endpoints and the other admin
+/// routes keep their own admin token check.
+pub async fn auth_middleware(
+    State(authenticator): State<Arc<dyn Authenticator>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let admin_route = ADMIN_ROUTES.iter()
+        .any(|(method, route)| request.method() == method && path == *route);
+    if PUBLIC_PATHS.contains(&path) || path.starts_with(ADMIN_PREFIX) || admin_route {
+        return next.run(request).await;
+    }
+    
+    let (mut parts, body) = request.into_parts();
+    
+    match authenticator.authenticate(&parts).await {
+        Ok(identity) => {
+            parts.extensions.insert(identity);
+            next.run(Request::from_parts(parts, body)).await
+        }
+        Err(e) => {
+            warn!("Authentication failed for {}: {}", parts.uri.path(), e);
+            e.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    
+    /// Request head carrying `Authorization: Bearer <token>`, or no header for `None`
+    fn request_with(token: Option<&str>) -> Parts {
+        let mut builder = axum::http::Request::builder().uri("/infer");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+    
+    /// HS256 token signed with `secret`, expiring `expires_in` seconds from now
+    fn signed_token(secret: &str, expires_in: i64, claims: serde_json::Value) -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        let mut claims = claims;
+        claims["exp"] = (now + expires_in).into();
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+    
+    /// HS256 authenticator requiring audience `synaptron`
+    fn jwt_authenticator() -> JwtAuthenticator {
+        JwtAuthenticator::new(&JwtConfig {
+            secret: Some("test-secret".to_string()),
+            audience: Some("synaptron".to_string()),
+            ..JwtConfig::default()
+        }).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn static_keys_resolve_to_their_identity() {
+        let authenticator = StaticKeyAuthenticator::new(&[ApiKeyConfig {
+            key: "k1".to_string(),
+            subject: "ci".to_string(),
+            tenant: Some("acme".to_string()),
+        }]);
+        
+        let identity = authenticator.authenticate(&request_with(Some("k1"))).await.unwrap();
+        
+        assert_eq!(identity.subject, "ci");
+        assert_eq!(identity.tenant.as_deref(), Some("acme"));
+        assert!(matches!(authenticator.authenticate(&request_with(Some("k2"))).await, Err(AuthError::Invalid(_))));
+        assert!(matches!(authenticator.authenticate(&request_with(None)).await, Err(AuthError::MissingCredentials)));
+    }
+    
+    #[tokio::test]
+    async fn valid_jwts_carry_subject_and_tenant() {
+        let token = signed_token("test-secret", 60, serde_json::json!({
+            "sub": "alice", "aud": "synaptron", "tenant": "acme",
+        }));
+        
+        let identity = jwt_authenticator().authenticate(&request_with(Some(&token))).await.unwrap();
+        
+        assert_eq!(identity.subject, "alice");
+        assert_eq!(identity.tenant.as_deref(), Some("acme"));
+    }
+    
+    #[tokio::test]
+    async fn expired_and_foreign_jwts_are_rejected() {
+        let authenticator = jwt_authenticator();
+        let expired = signed_token("test-secret", -3600, serde_json::json!({"sub": "alice", "aud": "synaptron"}));
+        let other_audience = signed_token("test-secret", 60, serde_json::json!({"sub": "alice", "aud": "elsewhere"}));
+        let other_secret = signed_token("wrong-secret", 60, serde_json::json!({"sub": "alice", "aud": "synaptron"}));
+        
+        assert!(matches!(authenticator.authenticate(&request_with(Some(&expired))).await, Err(AuthError::Expired)));
+        assert!(matches!(authenticator.authenticate(&request_with(Some(&other_audience))).await, Err(AuthError::Invalid(_))));
+        assert!(matches!(authenticator.authenticate(&request_with(Some(&other_secret))).await, Err(AuthError::Invalid(_))));
+    }
+    
+    #[test]
+    fn authenticators_are_built_from_config() {
+        let enabled = |method: &str| AuthConfig { enabled: true, method: method.to_string(), ..AuthConfig::default() };
+        
+        assert!(from_config(&AuthConfig::default()).unwrap().is_none());
+        assert!(from_config(&enabled("static")).is_err(), "static auth needs keys");
+        assert!(from_config(&enabled("jwt")).is_err(), "HS256 needs a secret");
+        assert!(from_config(&enabled("kerberos")).is_err());
+    }
+}
@@ -1,17 +1,25 @@
 //! API handlers for the Synaptron inference engine
 
-use crate::engine::InferenceEngine;
+use crate::{api::{auth::Identity, middleware::RequestId}, backend::pool::BackendPoolStats, breaker::ModelHealth, cache::{CacheMode, CacheStats}, engine::{InferenceEngine, ModelScope, Prediction, Readiness}, error::SynaptronError, graph::{GraphNode, GraphSpec, NodeTiming}, memory::MemoryStats, metrics::{LabeledStats, LatencyPercentiles, ModelStats}, model::{ModelInputType, ModelMetadata, OutputTensor}, multimodal, postprocessing::{Pooling, PredictionResult, DEFAULT_TOP_K}};
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
+    body::{Body, Bytes},
+    extract::{rejection::JsonRejection, Extension, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse, Json, Response},
     debug_handler,
 };
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use futures::{stream, Stream, StreamExt};
+use std::convert::Infallible;
 use serde::{Deserialize, Serialize};
 use tracing::{info, error};
+use std::collections::HashMap;
 use std::time::Instant;
 
 /// Health check response
+///
+/// Liveness only: it says nothing about whether models are loaded, see `/ready`.
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -22,14 +30,159 @@ pub struct HealthResponse {
 /// Predict request
 #[derive(Deserialize)]
 pub struct PredictRequest {
+    #[serde(default)]
     pub input: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub input_type: Option<ModelInputType>,
+    #[serde(default)]
+    pub pre_tokenized: bool,
+    #[serde(default)]
+    pub token_ids: Option<Vec<u32>>,
+    #[serde(default)]
+    pub outputs: Option<Vec<String>>,
+    #[serde(default)]
+    pub refresh_cache: bool,
+    #[serde(default)]
+    pub no_cache: bool,
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    #[serde(default)]
+    pub input_base64: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+/// Input bytes: the decoded `input_base64` when given, otherwise the text `input`
+fn request_input(input: &str, input_base64: Option<&str>) -> Result<Vec<u8>, (StatusCode, String)> {
+    match input_base64 {
+        Some(encoded) => base64::engine::general_purpose::STANDARD.decode(encoded).map_err(|e| {
+            (StatusCode::BAD_REQUEST, format!("input_base64 is not valid base64: {}", e))
+        }),
+        None => Ok(input.as_bytes().to_vec()),
+    }
+}
+
+/// Declared input type, from `input_type` or else a `content_type` hint
+fn request_input_type(input_type: Option<&ModelInputType>, content_type: Option<&str>) -> Option<ModelInputType> {
+    input_type.cloned().or_else(|| content_type.and_then(multimodal::input_type_for_content_type))
+}
+
+impl PredictRequest {
+    /// Input bytes: the decoded `input_base64` when given, otherwise the text `input`
+    fn input_bytes(&self) -> Result<Vec<u8>, (StatusCode, String)> {
+        request_input(&self.input, self.input_base64.as_deref())
+    }
+    
+    /// Declared input type, from `input_type` or else the `content_type` hint
+    fn input_type_hint(&self) -> Option<ModelInputType> {
+        request_input_type(self.input_type.as_ref(), self.content_type.as_deref())
+    }
+    
+    /// Response cache handling requested
+    fn cache_mode(&self) -> CacheMode {
+        if self.no_cache {
+            CacheMode::Bypass
+        } else if self.refresh_cache {
+            CacheMode::Refresh
+        } else {
+            CacheMode::Use
+        }
+    }
+}
+
+/// Query parameters of a binary predict request
+#[derive(Deserialize)]
+pub struct BinaryPredictParams {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    #[serde(default)]
+    pub no_cache: bool,
+}
+
+/// Embed request
+#[derive(Deserialize)]
+pub struct EmbedRequest {
+    #[serde(default)]
+    pub input: String,
+    #[serde(default)]
+    pub input_base64: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub input_type: Option<ModelInputType>,
+    #[serde(default)]
+    pub pooling: Pooling,
+}
+
+/// Embed response
+#[derive(Serialize)]
+pub struct EmbedResponse {
+    pub model: String,
+    pub embedding: Vec<f32>,
+    pub dim: usize,
+    pub latency_ms: u128,
 }
 
 /// Predict response
+///
+/// The decoded result is flattened in, tagged by its `type`, while `prediction`
+/// keeps the flat string form for older clients.
 #[derive(Serialize)]
 pub struct PredictResponse {
     pub prediction: String,
     pub latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, OutputTensor>>,
+    #[serde(flatten)]
+    pub result: Option<PredictionResult>,
+}
+
+/// Batch predict request
+#[derive(Deserialize)]
+pub struct BatchPredictRequest {
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub input_type: Option<ModelInputType>,
+    #[serde(default)]
+    pub top_k: Option<usize>,
+}
+
+impl BatchPredictRequest {
+    /// Model scope shared by every input of the batch
+    fn scope(&self, identity: Option<Extension<Identity>>) -> ModelScope {
+        ModelScope {
+            model: self.model.clone(),
+            identity: identity.map(|Extension(identity)| identity),
+            input_type: self.input_type.clone(),
+        }
+    }
+}
+
+/// Result of one input of a batch prediction
+#[derive(Serialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prediction: Option<String>,
+    #[serde(flatten)]
+    pub result: Option<PredictionResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Batch predict response, one result per input in input order
+#[derive(Serialize)]
+pub struct BatchPredictResponse {
+    pub results: Vec<BatchItemResult>,
+    pub latency_ms: u128,
 }
 
 /// List models response
@@ -44,47 +197,416 @@ pub struct ActivateModelRequest {
     pub model_name: String,
 }
 
+/// Deactivate model request
+#[derive(Deserialize)]
+pub struct DeactivateModelRequest {
+    pub model_name: String,
+}
+
 /// Metrics response
 #[derive(Serialize)]
 pub struct MetricsResponse {
     pub total_requests: u64,
     pub avg_latency_ms: f64,
+    #[serde(flatten)]
+    pub latency_percentiles: LatencyPercentiles,
     pub throughput: f64,
+    pub retry_budget: f64,
+    pub unhealthy_models: Vec<String>,
+    pub active_inferences: usize,
+    pub queue_depth: usize,
+    pub requests_by_model: Vec<LabeledStats>,
+    pub backend_pool: BackendPoolStats,
+    pub memory: MemoryStats,
+}
+
+/// Model details response
+///
+/// `loaded` is whether a backend holds the model, on `device` with `backend`.
+#[derive(Serialize)]
+pub struct ModelResponse {
+    pub name: String,
+    pub health: ModelHealth,
+    pub format: String,
+    pub input_type: ModelInputType,
+    pub metadata: ModelMetadata,
+    pub loaded: bool,
+    pub device: Option<String>,
+    pub backend: Option<String>,
+}
+
+/// Loaded model summary
+#[derive(Serialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub path: String,
+    pub format: String,
+    pub input_type: ModelInputType,
+    pub metadata: ModelMetadata,
+}
+
+/// Version and build information
+#[derive(Serialize)]
+pub struct VersionResponse {
+    pub version: String,
+    pub git_sha: String,
+    pub build_timestamp: String,
+    pub rustc_version: String,
+    pub features: Vec<String>,
+}
+
+/// Diagnostics response
+#[derive(Serialize)]
+pub struct DiagnosticsResponse {
+    pub build: VersionResponse,
+    pub models: Vec<ModelInfo>,
+    pub device: Option<String>,
+    pub backends: Vec<String>,
+    pub cache: CacheStats,
+    pub config: serde_json::Value,
+    pub metrics: MetricsResponse,
+}
+
+/// Reject admin requests without the configured bearer token
+fn authorize_admin(engine: &InferenceEngine, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let expected = match &engine.config().server.admin_token {
+        Some(token) => token,
+        None => {
+            return Err((StatusCode::FORBIDDEN, "Admin endpoints are disabled: no admin token configured".to_string()));
+        }
+    };
+    
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    
+    if provided.is_some_and(|provided| token_matches(provided, expected)) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "Invalid or missing admin token".to_string()))
+    }
+}
+
+/// Whether a provided token matches the expected one
+///
+/// Compares SHA-256 digests rather than the tokens, so how long the comparison
+/// takes says nothing about how much of the token was right.
+fn token_matches(provided: &str, expected: &str) -> bool {
+    Sha256::digest(provided.as_bytes()) == Sha256::digest(expected.as_bytes())
+}
+
+/// Read build metadata emitted by the build script, defaulting to "unknown"
+fn build_metadata(value: Option<&'static str>) -> String {
+    value
+        .filter(|v| !v.is_empty() && *v != "VERGEN_IDEMPOTENT_OUTPUT")
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Collect version and build information
+fn build_info() -> VersionResponse {
+    VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: build_metadata(option_env!("VERGEN_GIT_SHA")),
+        build_timestamp: build_metadata(option_env!("VERGEN_BUILD_TIMESTAMP")),
+        rustc_version: build_metadata(option_env!("VERGEN_RUSTC_SEMVER")),
+        features: enabled_features(),
+    }
+}
+
+/// Unwrap a JSON body, explaining an oversized body in terms of `server.max_request_bytes`
+fn json_body<T>(engine: &InferenceEngine, payload: Result<Json<T>, JsonRejection>) -> Result<T, (StatusCode, String)> {
+    match payload {
+        Ok(Json(payload)) => Ok(payload),
+        Err(rejection) if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Request body exceeds the {}-byte limit set by server.max_request_bytes",
+                engine.config().server.max_request_bytes
+            ),
+        )),
+        Err(rejection) => Err((rejection.status(), rejection.body_text())),
+    }
+}
+
+/// Bound a prediction by `timeouts.request_ms`, reporting expiry as the model being unavailable
+async fn with_request_timeout<T>(
+    engine: &InferenceEngine,
+    prediction: impl std::future::Future<Output = Result<T, SynaptronError>>,
+) -> Result<T, SynaptronError> {
+    match engine.config().timeouts.request() {
+        Some(timeout) => tokio::time::timeout(timeout, prediction).await.unwrap_or_else(|_| {
+            Err(SynaptronError::ModelUnavailable(format!(
+                "Prediction timed out after {} ms", timeout.as_millis()
+            )))
+        }),
+        None => prediction.await,
+    }
+}
+
+/// Map an engine error to an HTTP status
+fn error_status(e: &SynaptronError) -> StatusCode {
+    match e.root() {
+        SynaptronError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        SynaptronError::ModelNotFound(_) => StatusCode::NOT_FOUND,
+        SynaptronError::ModelUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        SynaptronError::Multimodal(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        SynaptronError::GraphNode { source, .. } => error_status(source),
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Current metrics from the engine's collector
+fn live_metrics(engine: &InferenceEngine) -> MetricsResponse {
+    let metrics = engine.metrics();
+    
+    MetricsResponse {
+        total_requests: metrics.get_total_requests(),
+        avg_latency_ms: metrics.get_avg_latency_ms(),
+        latency_percentiles: metrics.get_latency_percentiles(),
+        throughput: metrics.get_throughput(engine.uptime().as_secs_f64()),
+        retry_budget: engine.retry_budget().available(),
+        unhealthy_models: engine.breaker().unhealthy_models(),
+        active_inferences: engine.active_inferences(),
+        queue_depth: engine.queue_depth(),
+        requests_by_model: metrics.labeled_stats(),
+        backend_pool: engine.backend_pool().stats(),
+        memory: engine.memory_monitor().stats(),
+    }
+}
+
+/// Compiled-in optional features
+fn enabled_features() -> Vec<String> {
+    let mut features = Vec::new();
+    
+    if cfg!(feature = "onnx") {
+        features.push("onnx".to_string());
+    }
+    if cfg!(feature = "openvino") {
+        features.push("openvino".to_string());
+    }
+    if cfg!(feature = "tensorrt") {
+        features.push("tensorrt".to_string());
+    }
+    if cfg!(feature = "cuda") {
+        features.push("cuda".to_string());
+    }
+    if cfg!(feature = "opencl") {
+        features.push("opencl".to_string());
+    }
+    
+    features
 }
 
 /// Health check handler
 #[debug_handler]
-pub async fn health_handler() -> Result<Json<HealthResponse>, StatusCode> {
+pub async fn health_handler(
+    State(engine): State<InferenceEngine>,
+) -> Result<Json<HealthResponse>, StatusCode> {
     info!("Health check requested");
     
     let response = HealthResponse {
         status: "healthy".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        uptime: 0, // In a real implementation, this would track uptime
+        uptime: engine.uptime().as_secs(),
     };
     
     Ok(Json(response))
 }
 
+/// Readiness handler
+///
+/// Unlike `/health`, answers `503` until the `model.preload` models (or the
+/// default model) are ready and again once shutdown starts, so load balancers
+/// hold traffic back.
+#[debug_handler]
+pub async fn ready_handler(
+    State(engine): State<InferenceEngine>,
+) -> (StatusCode, Json<Readiness>) {
+    let readiness = engine.readiness().await;
+    let status = if readiness.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    
+    (status, Json(readiness))
+}
+
+/// Version handler
+#[debug_handler]
+pub async fn version_handler() -> Json<VersionResponse> {
+    info!("Version requested");
+    Json(build_info())
+}
+
+/// Header flagging a degraded fallback response
+pub const DEGRADED_HEADER: &str = "x-synaptron-degraded";
+
 /// Predict handler
 #[debug_handler]
 pub async fn predict_handler(
     State(engine): State<InferenceEngine>,
-    Json(payload): Json<PredictRequest>,
-) -> Result<Json<PredictResponse>, (StatusCode, String)> {
-    info!("Predict requested for input: {}", &payload.input);
+    Extension(request_id): Extension<RequestId>,
+    identity: Option<Extension<Identity>>,
+    payload: Result<Json<PredictRequest>, JsonRejection>,
+) -> Result<Response, (StatusCode, String)> {
+    let payload = json_body(&engine, payload)?;
+    let input_bytes = payload.input_bytes()?;
+    if payload.input_base64.is_some() {
+        info!("Predict requested for {} bytes of binary input", input_bytes.len());
+    } else {
+        info!("Predict requested for input: {}", &payload.input);
+    }
+    
+    let scope = ModelScope {
+        model: payload.model.clone(),
+        identity: identity.map(|Extension(identity)| identity),
+        input_type: payload.input_type_hint(),
+    };
     
     // Start timing
     let start_time = Instant::now();
     
-    // Convert input to bytes for processing
-    let input_bytes = payload.input.as_bytes().to_vec();
+    // Named outputs requested, return them alongside the final prediction
+    if let Some(output_names) = &payload.outputs {
+        let result = with_request_timeout(&engine, engine.infer_outputs(input_bytes, output_names, &scope)).await;
+        engine.metrics().record_request(start_time.elapsed().as_secs_f64() * 1000.0, result.is_ok());
+        
+        return match result {
+            Ok(outputs) => {
+                let latency_ms = start_time.elapsed().as_millis();
+                info!("Prediction with {} named outputs completed in {} ms", outputs.len(), latency_ms);
+                
+                let response = PredictResponse {
+                    prediction: String::new(),
+                    latency_ms,
+                    outputs: Some(outputs),
+                    result: None,
+                };
+                Ok(Json(response).into_response())
+            }
+            Err(e) => {
+                error!("Prediction failed: {:?}", e);
+                Err((error_status(&e), format!("Prediction failed (request {}): {}", request_id.0, e)))
+            }
+        };
+    }
+    
+    // Run inference, bypassing the preprocessor for pre-tokenized input
+    if payload.pre_tokenized {
+        let token_ids = payload.token_ids.ok_or_else(|| {
+            (StatusCode::BAD_REQUEST, "pre_tokenized requires token_ids".to_string())
+        })?;
+        let result = with_request_timeout(&engine, engine.infer_tokens(token_ids, &scope)).await;
+        return prediction_response(&engine, &request_id, result, payload.top_k, start_time).await;
+    }
+    
+    let result = with_request_timeout(&engine, engine.infer_with_fallback(input_bytes, payload.cache_mode(), &scope)).await;
+    prediction_response(&engine, &request_id, result, payload.top_k, start_time).await
+}
+
+/// Binary predict handler
+///
+/// Runs inference on the raw request body, such as PNG or WAV bytes. The body's
+/// `Content-Type` is a hint for routing when the data's own signature is inconclusive.
+#[debug_handler]
+pub async fn predict_binary_handler(
+    State(engine): State<InferenceEngine>,
+    Extension(request_id): Extension<RequestId>,
+    identity: Option<Extension<Identity>>,
+    Query(params): Query<BinaryPredictParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, (StatusCode, String)> {
+    info!("Predict requested for {} bytes of binary input", body.len());
+    if body.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Request body is empty".to_string()));
+    }
+    
+    let input_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(multimodal::input_type_for_content_type);
+    let scope = ModelScope {
+        model: params.model,
+        identity: identity.map(|Extension(identity)| identity),
+        input_type,
+    };
+    let cache_mode = if params.no_cache { CacheMode::Bypass } else { CacheMode::Use };
+    
+    let start_time = Instant::now();
+    let result = with_request_timeout(&engine, engine.infer_with_fallback(body.to_vec(), cache_mode, &scope)).await;
+    prediction_response(&engine, &request_id, result, params.top_k, start_time).await
+}
+
+/// Decode a prediction by what its model produces
+///
+/// Output of a model since unloaded is returned as text.
+async fn decode_prediction(
+    engine: &InferenceEngine,
+    prediction: &Prediction,
+    top_k: Option<usize>,
+) -> Result<PredictionResult, SynaptronError> {
+    let text = || PredictionResult::Generation {
+        text: String::from_utf8_lossy(&prediction.output).to_string(),
+    };
+    
+    match engine.postprocessor(&prediction.model).await {
+        Some(postprocessor) => match postprocessor.decode(&prediction.output, top_k.unwrap_or(DEFAULT_TOP_K)) {
+            Ok(result) => Ok(result),
+            // A configured fallback response needn't match the model's output layout
+            Err(_) if prediction.degraded => Ok(text()),
+            Err(e) => Err(e),
+        },
+        None => Ok(text()),
+    }
+}
+
+/// Decode the result of one input of a batch prediction
+async fn batch_item(
+    engine: &InferenceEngine,
+    index: usize,
+    result: Result<Prediction, SynaptronError>,
+    top_k: Option<usize>,
+) -> BatchItemResult {
+    let decoded = match result {
+        Ok(prediction) => decode_prediction(engine, &prediction, top_k).await,
+        Err(e) => Err(e),
+    };
+    
+    match decoded {
+        Ok(result) => BatchItemResult {
+            index,
+            prediction: Some(result.summary()),
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => {
+            error!("Batch item {} failed: {:?}", index, e);
+            BatchItemResult {
+                index,
+                prediction: None,
+                result: None,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Record a finished prediction and decode it into a response
+async fn prediction_response(
+    engine: &InferenceEngine,
+    request_id: &RequestId,
+    result: Result<Prediction, SynaptronError>,
+    top_k: Option<usize>,
+    start_time: Instant,
+) -> Result<Response, (StatusCode, String)> {
+    engine.metrics().record_request(start_time.elapsed().as_secs_f64() * 1000.0, result.is_ok());
     
-    // Run inference
-    match engine.infer(input_bytes).await {
-        Ok(output_bytes) => {
-            // Convert output bytes back to string
-            let prediction = String::from_utf8_lossy(&output_bytes).to_string();
+    match result {
+        Ok(prediction) => {
+            let result = decode_prediction(engine, &prediction, top_k).await.map_err(|e| {
+                error!("Postprocessing failed: {:?}", e);
+                (error_status(&e), format!("Prediction failed (request {}): {}", request_id.0, e))
+            })?;
             
             // Calculate latency
             let latency_ms = start_time.elapsed().as_millis();
@@ -92,29 +614,196 @@ pub async fn predict_handler(
             info!("Prediction completed successfully in {} ms", latency_ms);
             
             let response = PredictResponse {
-                prediction,
+                prediction: result.summary(),
                 latency_ms,
+                outputs: None,
+                result: Some(result),
             };
             
-            Ok(Json(response))
+            if prediction.degraded {
+                Ok(([(DEGRADED_HEADER, "true")], Json(response)).into_response())
+            } else {
+                Ok(Json(response).into_response())
+            }
         }
         Err(e) => {
             error!("Prediction failed: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Prediction failed: {:?}", e)))
+            Err((error_status(&e), format!("Prediction failed (request {}): {}", request_id.0, e)))
+        }
+    }
+}
+
+/// Embed handler
+///
+/// Returns the model's pooled hidden state for text, or for binary input sent as `input_base64`.
+#[debug_handler]
+pub async fn embed_handler(
+    State(engine): State<InferenceEngine>,
+    Extension(request_id): Extension<RequestId>,
+    identity: Option<Extension<Identity>>,
+    payload: Result<Json<EmbedRequest>, JsonRejection>,
+) -> Result<Json<EmbedResponse>, (StatusCode, String)> {
+    let payload = json_body(&engine, payload)?;
+    let input_bytes = request_input(&payload.input, payload.input_base64.as_deref())?;
+    info!("Embedding requested for {} bytes of input with {:?} pooling", input_bytes.len(), payload.pooling);
+    
+    let scope = ModelScope {
+        model: payload.model.clone(),
+        identity: identity.map(|Extension(identity)| identity),
+        input_type: request_input_type(payload.input_type.as_ref(), payload.content_type.as_deref()),
+    };
+    
+    let start_time = Instant::now();
+    let result = with_request_timeout(&engine, engine.infer_embedding(input_bytes, payload.pooling, &scope)).await;
+    engine.metrics().record_request(start_time.elapsed().as_secs_f64() * 1000.0, result.is_ok());
+    
+    match result {
+        Ok(embedding) => Ok(Json(EmbedResponse {
+            model: embedding.model,
+            dim: embedding.vector.len(),
+            embedding: embedding.vector,
+            latency_ms: start_time.elapsed().as_millis(),
+        })),
+        Err(e) => {
+            error!("Embedding failed: {:?}", e);
+            Err((error_status(&e), format!("Embedding failed (request {}): {}", request_id.0, e)))
         }
     }
 }
 
+/// Terminal data sent on a prediction event stream
+pub const STREAM_DONE: &str = "[DONE]";
+
+/// Streaming predict handler
+///
+/// Sends each output chunk as a Server-Sent Event, an `error` event if inference
+/// fails part-way, and a final `[DONE]` event. Closing the connection stops inference.
+#[debug_handler]
+pub async fn predict_stream_handler(
+    State(engine): State<InferenceEngine>,
+    Extension(request_id): Extension<RequestId>,
+    identity: Option<Extension<Identity>>,
+    payload: Result<Json<PredictRequest>, JsonRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let payload = json_body(&engine, payload)?;
+    let input_bytes = payload.input_bytes()?;
+    info!("Streaming predict requested for {} bytes of input", input_bytes.len());
+    
+    let scope = ModelScope {
+        model: payload.model.clone(),
+        identity: identity.map(|Extension(identity)| identity),
+        input_type: payload.input_type_hint(),
+    };
+    
+    let chunks = engine.infer_stream(input_bytes, &scope).await.map_err(|e| {
+        error!("Streaming prediction failed: {:?}", e);
+        (error_status(&e), format!("Prediction failed (request {}): {}", request_id.0, e))
+    })?;
+    
+    let events = chunks
+        .map(|chunk| {
+            let event = match chunk {
+                Ok(bytes) => Event::default().data(String::from_utf8_lossy(&bytes)),
+                Err(e) => {
+                    error!("Streaming prediction failed mid-stream: {:?}", e);
+                    Event::default().event("error").data(e.to_string())
+                }
+            };
+            Ok(event)
+        })
+        .chain(stream::once(async { Ok(Event::default().data(STREAM_DONE)) }));
+    
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Batch predict handler
+///
+/// Runs every input through the batch processor and returns one result per
+/// input in input order; a failed input carries an `error` instead of failing the batch.
+#[debug_handler]
+pub async fn predict_batch_handler(
+    State(engine): State<InferenceEngine>,
+    Extension(request_id): Extension<RequestId>,
+    identity: Option<Extension<Identity>>,
+    payload: Result<Json<BatchPredictRequest>, JsonRejection>,
+) -> Result<Json<BatchPredictResponse>, (StatusCode, String)> {
+    let payload = json_body(&engine, payload)?;
+    info!("Batch predict requested for {} inputs", payload.inputs.len());
+    
+    let scope = payload.scope(identity);
+    let inputs = payload.inputs.into_iter().map(String::into_bytes).collect();
+    
+    let start_time = Instant::now();
+    let results = with_request_timeout(&engine, async { Ok(engine.batch_infer(inputs, &scope).await) }).await
+        .map_err(|e| {
+            error!("Batch prediction failed: {:?}", e);
+            (error_status(&e), format!("Prediction failed (request {}): {}", request_id.0, e))
+        })?;
+    
+    let mut items = Vec::with_capacity(results.len());
+    for (index, result) in results.into_iter().enumerate() {
+        items.push(batch_item(&engine, index, result, payload.top_k).await);
+    }
+    
+    info!("Batch prediction of {} inputs completed in {} ms", items.len(), start_time.elapsed().as_millis());
+    Ok(Json(BatchPredictResponse {
+        results: items,
+        latency_ms: start_time.elapsed().as_millis(),
+    }))
+}
+
+/// Streaming batch predict handler
+///
+/// Emits one `application/x-ndjson` line per input as it completes. Lines are in
+/// completion order, and each carries the `index` of its input so clients can reorder.
+#[debug_handler]
+pub async fn predict_batch_stream_handler(
+    State(engine): State<InferenceEngine>,
+    identity: Option<Extension<Identity>>,
+    payload: Result<Json<BatchPredictRequest>, JsonRejection>,
+) -> Response {
+    let payload = match json_body(&engine, payload) {
+        Ok(payload) => payload,
+        Err(rejection) => return rejection.into_response(),
+    };
+    info!("Streaming batch predict requested for {} inputs", payload.inputs.len());
+    
+    let scope = payload.scope(identity);
+    let top_k = payload.top_k;
+    let inputs = payload.inputs.into_iter().map(String::into_bytes).collect();
+    
+    let lines = engine.batch_infer_stream(inputs, scope).then(move |(index, result)| {
+        let engine = engine.clone();
+        async move {
+            let item = batch_item(&engine, index, result, top_k).await;
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok::<_, serde_json::Error>(line)
+        }
+    });
+    
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    ).into_response()
+}
+
 /// List models handler
 #[debug_handler]
 pub async fn list_models_handler(
     State(engine): State<InferenceEngine>,
+    identity: Option<Extension<Identity>>,
 ) -> Result<Json<ListModelsResponse>, (StatusCode, String)> {
     info!("List models requested");
     
-    // Get list of loaded models
+    let identity = identity.map(|Extension(identity)| identity);
+    
+    // Get list of loaded models visible to the caller
     let models_guard = engine.models.read().await;
-    let model_names: Vec<String> = models_guard.keys().cloned().collect();
+    let model_names: Vec<String> = models_guard.keys()
+        .filter(|name| engine.is_model_visible(name, identity.as_ref()))
+        .cloned()
+        .collect();
     
     let response = ListModelsResponse {
         models: model_names,
@@ -123,32 +812,284 @@ pub async fn list_models_handler(
     Ok(Json(response))
 }
 
+/// Model details handler
+#[debug_handler]
+pub async fn model_handler(
+    State(engine): State<InferenceEngine>,
+    identity: Option<Extension<Identity>>,
+    Path(name): Path<String>,
+) -> Result<Json<ModelResponse>, (StatusCode, String)> {
+    info!("Model details requested: {}", name);
+    
+    let identity = identity.map(|Extension(identity)| identity);
+    
+    let models_guard = engine.models.read().await;
+    let model = match models_guard.get(&name) {
+        Some(model) if engine.is_model_visible(&name, identity.as_ref()) => model,
+        _ => return Err((StatusCode::NOT_FOUND, format!("Model not loaded: {}", name))),
+    };
+    let active = engine.backends.read().await.get(&name).cloned();
+    
+    let response = ModelResponse {
+        health: engine.breaker().health(&name),
+        format: model.format.clone(),
+        input_type: model.input_type.clone(),
+        metadata: model.metadata.clone(),
+        loaded: active.is_some(),
+        device: active.as_ref().map(|active| active.device.clone()),
+        backend: active.map(|active| active.backend.name().to_string()),
+        name,
+    };
+    
+    Ok(Json(response))
+}
+
+/// Model stats handler
+///
+/// A loaded model that has served no requests reports zeroed stats.
+#[debug_handler]
+pub async fn model_stats_handler(
+    State(engine): State<InferenceEngine>,
+    identity: Option<Extension<Identity>>,
+    Path(name): Path<String>,
+) -> Result<Json<ModelStats>, (StatusCode, String)> {
+    info!("Model stats requested: {}", name);
+    
+    let identity = identity.map(|Extension(identity)| identity);
+    
+    let models_guard = engine.models.read().await;
+    if !models_guard.contains_key(&name) || !engine.is_model_visible(&name, identity.as_ref()) {
+        return Err((StatusCode::NOT_FOUND, format!("Model not loaded: {}", name)));
+    }
+    
+    Ok(Json(engine.metrics().model_stats(&name)))
+}
+
+/// Map a model activation error to an HTTP status
+fn activation_status(e: &SynaptronError) -> StatusCode {
+    match e.root() {
+        SynaptronError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        SynaptronError::ModelNotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 /// Activate model handler
+///
+/// Loads the model from `model.cache_dir`, downloading it first when auto-download allows.
 #[debug_handler]
 pub async fn activate_model_handler(
     State(engine): State<InferenceEngine>,
-    Json(payload): Json<ActivateModelRequest>,
+    headers: HeaderMap,
+    payload: Result<Json<ActivateModelRequest>, JsonRejection>,
 ) -> Result<StatusCode, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    let payload = json_body(&engine, payload)?;
     info!("Activate model requested: {}", payload.model_name);
     
-    // In a real implementation, this would load/activate the specified model
-    // For now, we'll just return OK
+    engine.activate_model(&payload.model_name).await.map_err(|e| {
+        error!("Activating model {} failed: {}", payload.model_name, e);
+        (activation_status(&e), format!("Activating model {} failed: {}", payload.model_name, e))
+    })?;
+    
+    Ok(StatusCode::OK)
+}
+
+/// Deactivate model handler
+#[debug_handler]
+pub async fn deactivate_model_handler(
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+    payload: Result<Json<DeactivateModelRequest>, JsonRejection>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    let payload = json_body(&engine, payload)?;
+    info!("Deactivate model requested: {}", payload.model_name);
+    
+    engine.unload_model(&payload.model_name).await.map_err(|e| {
+        error!("Deactivating model {} failed: {}", payload.model_name, e);
+        (activation_status(&e), format!("Deactivating model {} failed: {}", payload.model_name, e))
+    })?;
+    
     Ok(StatusCode::OK)
 }
 
+/// Model graph response
+#[derive(Serialize)]
+pub struct GraphResponse {
+    /// Nodes in execution order
+    pub nodes: Vec<GraphNode>,
+    
+    /// Node IDs in execution order
+    pub execution_order: Vec<String>,
+    
+    /// Timing of each node that has run since the graph was installed
+    pub node_timings: HashMap<String, NodeTiming>,
+}
+
+/// Model graph handler
+///
+/// Nodes running models hidden from the caller are left out.
+#[debug_handler]
+pub async fn graph_handler(
+    State(engine): State<InferenceEngine>,
+    identity: Option<Extension<Identity>>,
+) -> Result<Json<GraphResponse>, (StatusCode, String)> {
+    info!("Model graph requested");
+    
+    let identity = identity.map(|Extension(identity)| identity);
+    Ok(Json(graph_response(&engine, identity.as_ref()).await))
+}
+
+/// Current graph as seen by a caller
+async fn graph_response(engine: &InferenceEngine, identity: Option<&Identity>) -> GraphResponse {
+    let (spec, execution_order) = engine.graph().await;
+    let mut node_timings = engine.graph_node_timings().await;
+    
+    let nodes: Vec<GraphNode> = spec.nodes.into_iter()
+        .filter(|node| engine.is_model_visible(&node.model_name, identity))
+        .collect();
+    let execution_order = execution_order.into_iter()
+        .filter(|id| nodes.iter().any(|node| &node.id == id))
+        .collect();
+    node_timings.retain(|id, _| nodes.iter().any(|node| &node.id == id));
+    
+    GraphResponse { nodes, execution_order, node_timings }
+}
+
+/// Install model graph handler
+///
+/// Replaces the active graph; an invalid definition leaves the current one in place.
+#[debug_handler]
+pub async fn install_graph_handler(
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+    Json(spec): Json<GraphSpec>,
+) -> Result<Json<GraphResponse>, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    info!("Model graph install requested: {} nodes", spec.nodes.len());
+    
+    engine.install_graph(&spec).await.map_err(|e| {
+        error!("Model graph install failed: {}", e);
+        let status = match e.root() {
+            SynaptronError::GraphExecution(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, e.to_string())
+    })?;
+    
+    Ok(Json(graph_response(&engine, None).await))
+}
+
 /// Metrics handler
+///
+/// Prometheus scrapers asking for `text/plain` or OpenMetrics get the text
+/// exposition format; everything else gets JSON.
 #[debug_handler]
 pub async fn metrics_handler(
-    State(_engine): State<InferenceEngine>,
-) -> Result<Json<MetricsResponse>, (StatusCode, String)> {
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
     info!("Metrics requested");
     
-    // In a real implementation, this would return actual metrics
-    let response = MetricsResponse {
-        total_requests: 0,
-        avg_latency_ms: 0.0,
-        throughput: 0.0,
+    let wants_text = headers.get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/plain") || accept.contains("application/openmetrics-text"));
+    
+    if wants_text {
+        return Ok((
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            engine.metrics().format_prometheus()
+                + &engine.backend_pool().stats().format_prometheus()
+                + &engine.memory_monitor().stats().format_prometheus(),
+        ).into_response());
+    }
+    
+    Ok(Json(live_metrics(&engine)).into_response())
+}
+
+/// Diagnostics handler
+#[debug_handler]
+pub async fn diagnostics_handler(
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+) -> Result<Json<DiagnosticsResponse>, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    info!("Diagnostics requested");
+    
+    let models = {
+        let models_guard = engine.models.read().await;
+        models_guard.values().map(|model| ModelInfo {
+            name: model.name.clone(),
+            path: model.path.clone(),
+            format: model.format.clone(),
+            input_type: model.input_type.clone(),
+            metadata: model.metadata.clone(),
+        }).collect()
+    };
+    
+    let backends = {
+        let backends_guard = engine.backends.read().await;
+        backends_guard.iter()
+            .map(|(model, active)| format!("{} on {} ({})", model, active.device, active.backend.name()))
+            .collect()
+    };
+    
+    let device = engine.device_manager.select_device().await.ok();
+    
+    let config = engine.config().redacted()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize config: {}", e)))?;
+    
+    let response = DiagnosticsResponse {
+        build: build_info(),
+        models,
+        device,
+        backends,
+        cache: engine.model_cache.stats().await,
+        config,
+        metrics: live_metrics(&engine),
     };
     
     Ok(Json(response))
 }
+
+/// Effective configuration handler
+#[debug_handler]
+pub async fn config_handler(
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    info!("Effective configuration requested");
+    
+    let config = engine.effective_config().redacted()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize config: {}", e)))?;
+    
+    Ok(Json(config))
+}
+
+/// Config reload handler
+///
+/// Applies the hot-reloadable settings and returns the resulting configuration.
+#[debug_handler]
+pub async fn reload_config_handler(
+    State(engine): State<InferenceEngine>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    authorize_admin(&engine, &headers)?;
+    info!("Configuration reload requested");
+    
+    engine.reload_config().await.map_err(|e| {
+        error!("Configuration reload failed: {}", e);
+        let status = match e.root() {
+            SynaptronError::Config(_) | SynaptronError::RemoteConfig(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("Configuration reload failed: {}", e))
+    })?;
+    
+    let config = engine.effective_config().redacted()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize config: {}", e)))?;
+    
+    Ok(Json(config))
+}
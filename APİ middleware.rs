@@ -0,0 +1,228 @@
+//! HTTP middleware for the Synaptron inference engine
+
+use crate::{api::handlers::DEGRADED_HEADER, error::SynaptronError};
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info_span, Instrument};
+use uuid::Uuid;
+
+/// Request ID header read from requests and echoed on responses
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// W3C trace context header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Longest accepted inbound request ID
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Request ID, available to handlers via `Extension<RequestId>`
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Extract a usable request ID from `x-request-id`, falling back to the `traceparent` trace ID
+fn inbound_request_id(headers: &HeaderMap) -> Option<String> {
+    let explicit = headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| is_valid_request_id(id));
+    
+    if let Some(id) = explicit {
+        return Some(id.to_string());
+    }
+    
+    // traceparent is "version-traceid-parentid-flags"
+    headers
+        .get(TRACEPARENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split('-').nth(1))
+        .filter(|trace_id| trace_id.len() == 32 && trace_id.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_string)
+}
+
+/// Accept only short IDs of safe characters so clients can't inject into logs
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+/// Propagate or generate a request ID, record it on the request span and echo it on the response
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = inbound_request_id(request.headers())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+    
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    
+    let mut response = next.run(request).instrument(span).await;
+    
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    
+    response
+}
+
+/// CORS layer for `server.cors_allowed_origins`, or `None` when no origin is allowed
+///
+/// Preflight `OPTIONS` requests are answered by the layer itself, so they never
+/// reach authentication.
+pub fn cors_layer(allowed_origins: &[String]) -> Result<Option<CorsLayer>, SynaptronError> {
+    if allowed_origins.is_empty() {
+        return Ok(None);
+    }
+    
+    let allow_origin = if allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin.trim_end_matches('/')).map_err(|_| {
+                    SynaptronError::Config(config::ConfigError::Message(format!(
+                        "server.cors_allowed_origins: invalid origin {:?}", origin
+                    )))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+    
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+            .allow_headers([
+                header::AUTHORIZATION,
+                header::CONTENT_TYPE,
+                header::ACCEPT,
+                HeaderName::from_static(REQUEST_ID_HEADER),
+            ])
+            .expose_headers([
+                HeaderName::from_static(REQUEST_ID_HEADER),
+                HeaderName::from_static(DEGRADED_HEADER),
+            ]),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Extension, routing::get, Router};
+    use tower::ServiceExt;
+    
+    /// Router answering with the request ID its handler was given
+    fn app() -> Router {
+        Router::new()
+            .route("/", get(|Extension(id): Extension<RequestId>| async move { id.0 }))
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+    
+    /// Send a request with `headers` and return the echoed header and the ID the handler saw
+    async fn request_id(headers: &[(&str, &str)]) -> (String, String) {
+        let mut request = axum::http::Request::get("/");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        
+        let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let echoed = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (echoed, String::from_utf8(body.to_vec()).unwrap())
+    }
+    
+    #[tokio::test]
+    async fn inbound_request_id_is_propagated_and_echoed() {
+        let (echoed, seen) = request_id(&[(REQUEST_ID_HEADER, "req-42")]).await;
+        
+        assert_eq!(echoed, "req-42");
+        assert_eq!(seen, "req-42");
+    }
+    
+    #[tokio::test]
+    async fn oversized_or_unsafe_ids_are_replaced() {
+        let long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        
+        for id in [long.as_str(), "bad id\twith spaces", "<script>"] {
+            let (echoed, seen) = request_id(&[(REQUEST_ID_HEADER, id)]).await;
+            assert_ne!(echoed, id);
+            assert!(Uuid::parse_str(&echoed).is_ok(), "{:?} was not replaced by a generated ID", id);
+            assert_eq!(seen, echoed);
+        }
+    }
+    
+    #[tokio::test]
+    async fn trace_id_is_used_without_a_request_id() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        
+        let (echoed, _) = request_id(&[(TRACEPARENT_HEADER, traceparent)]).await;
+        
+        assert_eq!(echoed, "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+    
+    #[test]
+    fn longest_accepted_id_is_kept() {
+        assert!(is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN)));
+        assert!(!is_valid_request_id(""));
+    }
+
+    /// `Access-Control-Allow-Origin` of the response to a request from `origin`, through a layer allowing `allowed`
+    async fn allowed_origin(allowed: &[&str], method: Method, origin: &str) -> Option<String> {
+        let allowed: Vec<String> = allowed.iter().map(|origin| origin.to_string()).collect();
+        let app = Router::new()
+            .route("/predict", axum::routing::post(|| async { "ok" }))
+            .layer(cors_layer(&allowed).unwrap().expect("an origin is allowed"));
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri("/predict")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap();
+        
+        let response = app.oneshot(request).await.unwrap();
+        response.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+    
+    #[tokio::test]
+    async fn allowed_origins_get_the_cors_header() {
+        let allowed = ["https://dashboard.example.com/"];
+        
+        let echoed = allowed_origin(&allowed, Method::POST, "https://dashboard.example.com").await;
+        
+        assert_eq!(echoed.as_deref(), Some("https://dashboard.example.com"));
+        assert_eq!(allowed_origin(&allowed, Method::POST, "https://evil.example.com").await, None);
+    }
+    
+    #[tokio::test]
+    async fn preflight_requests_are_answered() {
+        let echoed = allowed_origin(&["https://dashboard.example.com"], Method::OPTIONS, "https://dashboard.example.com").await;
+        
+        assert_eq!(echoed.as_deref(), Some("https://dashboard.example.com"));
+    }
+    
+    #[tokio::test]
+    async fn wildcard_allows_any_origin() {
+        assert_eq!(allowed_origin(&["*"], Method::POST, "https://anywhere.example.com").await.as_deref(), Some("*"));
+    }
+    
+    #[test]
+    fn cors_is_off_without_allowed_origins() {
+        assert!(cors_layer(&[]).unwrap().is_none());
+        assert!(cors_layer(&["https://bad\norigin".to_string()]).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 //! API modules for the Synaptron inference engine
 
+pub mod auth;
 pub mod handlers;
-pub mod routes;
+pub mod middleware;
@@ -0,0 +1,146 @@
+//! CPU backend for the Synaptron inference engine
+
+use crate::{
+    backend::{Backend, DEFAULT_OUTPUT},
+    config::CpuPrecision,
+    error::SynaptronError,
+    model::{Model, OutputTensor},
+};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Model state held by the CPU backend
+struct LoadedModel {
+    /// Model name
+    name: String,
+
+    /// Output dimensions reported for inference results
+    output_shape: Vec<usize>,
+}
+
+/// CPU backend
+///
+/// Passes inputs through unchanged and reports them with the loaded model's
+/// output shape, so pipelines can run end to end without an accelerator.
+pub struct CPUBackend {
+    /// Compute precision
+    precision: CpuPrecision,
+
+    /// Currently loaded model
+    loaded: RwLock<Option<LoadedModel>>,
+}
+
+impl CPUBackend {
+    /// Create a new CPU backend
+    pub fn new() -> Result<Self, SynaptronError> {
+        Ok(Self {
+            precision: CpuPrecision::F32,
+            loaded: RwLock::new(None),
+        })
+    }
+    
+    /// Set the compute precision
+    pub fn with_precision(mut self, precision: CpuPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+    
+    /// Name of the loaded model, if any
+    pub fn loaded_model(&self) -> Option<String> {
+        self.loaded.read().as_ref().map(|loaded| loaded.name.clone())
+    }
+    
+    /// Element type name for the compute precision
+    fn dtype(&self) -> &'static str {
+        match self.precision {
+            CpuPrecision::Auto | CpuPrecision::F32 => "f32",
+            CpuPrecision::Bf16 => "bf16",
+            CpuPrecision::F16 => "f16",
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for CPUBackend {
+    fn name(&self) -> &str {
+        "cpu"
+    }
+    
+    async fn load_model(&self, model: &Model) -> Result<(), SynaptronError> {
+        info!("Loading model {} on CPU backend ({})", model.name, self.dtype());
+        
+        *self.loaded.write() = Some(LoadedModel {
+            name: model.name.clone(),
+            output_shape: model.metadata.output_shape.clone(),
+        });
+        
+        Ok(())
+    }
+    
+    fn new_session(&self) -> Option<Box<dyn Backend>> {
+        Some(Box::new(Self {
+            precision: self.precision,
+            loaded: RwLock::new(None),
+        }))
+    }
+    
+    async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        let loaded_guard = self.loaded.read();
+        let loaded = loaded_guard.as_ref()
+            .ok_or_else(|| SynaptronError::Inference("No model loaded on CPU backend".to_string()))?;
+        
+        debug!("Running {} on CPU backend with {} input bytes", loaded.name, input.len());
+        Ok(input)
+    }
+    
+    async fn infer_outputs(
+        &self,
+        input: Vec<u8>,
+        output_names: &[String],
+    ) -> Result<HashMap<String, OutputTensor>, SynaptronError> {
+        let output_shape = self.loaded.read().as_ref()
+            .map(|loaded| loaded.output_shape.clone())
+            .ok_or_else(|| SynaptronError::Inference("No model loaded on CPU backend".to_string()))?;
+        
+        let output = self.infer(input).await?;
+        
+        Ok(output_names.iter()
+            .filter(|name| name.as_str() == DEFAULT_OUTPUT)
+            .map(|name| (name.clone(), OutputTensor {
+                shape: output_shape.clone(),
+                dtype: self.dtype().to_string(),
+                data: output.clone(),
+            }))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn inference_needs_a_loaded_model() {
+        let backend = CPUBackend::new().unwrap();
+        
+        assert!(matches!(backend.infer(vec![1, 2, 3]).await, Err(SynaptronError::Inference(_))));
+        assert_eq!(backend.loaded_model(), None);
+    }
+
+    #[tokio::test]
+    async fn loaded_model_echoes_input_with_its_output_shape() {
+        let backend = CPUBackend::new().unwrap().with_precision(CpuPrecision::Bf16);
+        let mut model = Model::for_test("bert", crate::model::ModelInputType::Text, b"weights");
+        model.metadata.output_shape = vec![1, 4];
+        
+        backend.load_model(&model).await.unwrap();
+        
+        assert_eq!(backend.loaded_model().as_deref(), Some("bert"));
+        assert_eq!(backend.infer(vec![1, 2, 3, 4]).await.unwrap(), vec![1, 2, 3, 4]);
+        let outputs = backend.infer_outputs(vec![1, 2, 3, 4], &[DEFAULT_OUTPUT.to_string()]).await.unwrap();
+        assert_eq!(outputs[DEFAULT_OUTPUT].shape, vec![1, 4]);
+        assert_eq!(outputs[DEFAULT_OUTPUT].dtype, "bf16");
+    }
+}
@@ -0,0 +1,112 @@
+//! Hardware acceleration backends for the Synaptron inference engine
+
+use crate::{error::SynaptronError, model::{Model, OutputTensor}};
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
+
+pub mod cpu;
+pub mod pool;
+pub mod registry;
+
+#[cfg(feature = "onnx")]
+pub mod onnx;
+
+/// Output name used by backends that produce a single unnamed output
+pub const DEFAULT_OUTPUT: &str = "output";
+
+/// Error for inference abandoned because its caller went away
+pub fn cancelled() -> SynaptronError {
+    SynaptronError::Inference("cancelled".to_string())
+}
+
+/// Inference backend running models on a device
+///
+/// Backends are shared across requests, so methods take `&self` and
+/// implementations keep loaded state behind interior mutability.
+#[async_trait]
+pub trait Backend: Send + Sync {
+    /// Backend name for logging
+    fn name(&self) -> &str;
+    
+    /// Load a model, replacing any previously loaded one
+    async fn load_model(&self, model: &Model) -> Result<(), SynaptronError>;
+    
+    /// Start a session on this instance's device for a further model
+    ///
+    /// The engine initializes one instance per backend and device and loads
+    /// later models there into sessions of it, which keep it alive. The
+    /// default `None` shares nothing, so each model gets an instance of its own.
+    fn new_session(&self) -> Option<Box<dyn Backend>> {
+        None
+    }
+    
+    /// Run inference on preprocessed input
+    async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError>;
+    
+    /// Run one forward pass over a batch of inputs, returning one output per input
+    ///
+    /// The default runs `infer` on each input concurrently.
+    async fn infer_batch(&self, inputs: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, SynaptronError> {
+        futures::future::join_all(inputs.into_iter().map(|input| self.infer(input)))
+            .await
+            .into_iter()
+            .collect()
+    }
+    
+    /// Run inference, failing with `cancelled()` once `cancel` fires
+    ///
+    /// The default stops waiting on `infer` at its next await point; backends
+    /// running blocking work should check the token themselves.
+    async fn infer_cancellable(&self, input: Vec<u8>, cancel: CancellationToken) -> Result<Vec<u8>, SynaptronError> {
+        tokio::select! {
+            result = self.infer(input) => result,
+            _ = cancel.cancelled() => Err(cancelled()),
+        }
+    }
+    
+    /// Run one batched forward pass, failing with `cancelled()` once `cancel` fires
+    async fn infer_batch_cancellable(
+        &self,
+        inputs: Vec<Vec<u8>>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Vec<u8>>, SynaptronError> {
+        tokio::select! {
+            result = self.infer_batch(inputs) => result,
+            _ = cancel.cancelled() => Err(cancelled()),
+        }
+    }
+    
+    /// Names of the outputs the loaded model exposes
+    fn output_names(&self) -> Vec<String> {
+        vec![DEFAULT_OUTPUT.to_string()]
+    }
+    
+    /// Run inference, returning the requested named outputs
+    ///
+    /// The default runs `infer` and reports its result as a flat byte tensor.
+    async fn infer_outputs(
+        &self,
+        input: Vec<u8>,
+        output_names: &[String],
+    ) -> Result<HashMap<String, OutputTensor>, SynaptronError> {
+        let output = self.infer(input).await?;
+        
+        Ok(output_names.iter()
+            .filter(|name| name.as_str() == DEFAULT_OUTPUT)
+            .map(|name| (name.clone(), OutputTensor {
+                shape: vec![output.len()],
+                dtype: "u8".to_string(),
+                data: output.clone(),
+            }))
+            .collect())
+    }
+    
+    /// Run inference, yielding output chunks as they are produced
+    ///
+    /// The default yields the full `infer` result as a single chunk.
+    fn infer_stream(&self, input: Vec<u8>) -> BoxStream<'_, Result<Vec<u8>, SynaptronError>> {
+        stream::once(self.infer(input)).boxed()
+    }
+}
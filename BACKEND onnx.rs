@@ -0,0 +1,284 @@
+//! ONNX Runtime backend for the Synaptron inference engine
+
+use crate::{
+    backend::{cancelled, Backend},
+    config::CpuPrecision,
+    error::SynaptronError,
+    model::{Model, ModelMetadata, OutputTensor},
+    utils::tensor,
+};
+use async_trait::async_trait;
+use ort::{session::Session, value::Tensor};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// ORT session option letting MLAS run f32 GEMMs in bf16 on arm64
+const BF16_FASTMATH_KEY: &str = "mlas.enable_gemm_fastmath_arm64_bfloat16";
+
+/// Session and input layout for the loaded model
+#[derive(Clone)]
+struct LoadedSession {
+    /// Model name
+    name: String,
+
+    /// ONNX Runtime session
+    session: Arc<Session>,
+
+    /// Declared input dimensions
+    input_shape: Vec<usize>,
+
+    /// Declared input element type
+    data_type: String,
+}
+
+/// ONNX Runtime backend
+///
+/// Inputs are little-endian tensors of the model's declared `data_type`,
+/// shaped by its `input_shape`. Outputs are returned as little-endian f32.
+pub struct OrtBackend {
+    /// Compute precision applied to new sessions
+    precision: CpuPrecision,
+
+    /// Currently loaded session
+    loaded: RwLock<Option<LoadedSession>>,
+}
+
+impl OrtBackend {
+    /// Create a new ONNX Runtime backend
+    pub fn new() -> Result<Self, SynaptronError> {
+        Ok(Self {
+            precision: CpuPrecision::F32,
+            loaded: RwLock::new(None),
+        })
+    }
+    
+    /// Set the compute precision
+    pub fn with_precision(mut self, precision: CpuPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+    
+    /// Session options selecting the compute precision
+    ///
+    /// ORT has no f16 mode for f32 graphs on the CPU provider, so f16 runs in f32.
+    fn precision_entries(&self, model_name: &str) -> &'static [(&'static str, &'static str)] {
+        match self.precision {
+            CpuPrecision::Bf16 => &[(BF16_FASTMATH_KEY, "1")],
+            CpuPrecision::F16 => {
+                warn!("ONNX Runtime cannot run {} in f16 on the CPU, using f32 instead", model_name);
+                &[]
+            }
+            CpuPrecision::Auto | CpuPrecision::F32 => &[],
+        }
+    }
+    
+    /// The loaded session
+    fn loaded(&self) -> Result<LoadedSession, SynaptronError> {
+        self.loaded.read().clone()
+            .ok_or_else(|| SynaptronError::Inference("No model loaded on ONNX Runtime backend".to_string()))
+    }
+    
+    /// Run a forward pass, returning the requested outputs, or all outputs when none are named
+    ///
+    /// A run cancelled while it waits for a blocking thread is skipped.
+    async fn run(
+        &self,
+        input: Vec<u8>,
+        output_names: Vec<String>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<(String, OutputTensor)>, SynaptronError> {
+        let LoadedSession { name, session, input_shape, data_type } = self.loaded()?;
+        debug!("Running {} on ONNX Runtime with {} input bytes", name, input.len());
+        
+        // Session runs are CPU bound, keep them off the async workers
+        tokio::task::spawn_blocking(move || {
+            if cancel.is_cancelled() {
+                debug!("Skipping cancelled run of {}", name);
+                return Err(cancelled());
+            }
+            
+            let input_name = session.inputs.first()
+                .map(|input| input.name.clone())
+                .ok_or_else(|| SynaptronError::Inference("ONNX model declares no inputs".to_string()))?;
+            
+            let inputs = match data_type.as_str() {
+                "i64" | "int64" => {
+                    let values = tensor::bytes_to_i64(&input)?;
+                    let shape = input_dims(&input_shape, values.len())?;
+                    ort::inputs![input_name => Tensor::from_array((shape, values))?]
+                }
+                "f32" | "float32" => {
+                    let values = tensor::bytes_to_f32(&input)?;
+                    let shape = input_dims(&input_shape, values.len())?;
+                    ort::inputs![input_name => Tensor::from_array((shape, values))?]
+                }
+                other => {
+                    return Err(SynaptronError::UnsupportedFormat(format!(
+                        "ONNX Runtime backend does not support input type {}", other
+                    )));
+                }
+            }.map_err(ort_error)?;
+            
+            let outputs = session.run(inputs).map_err(ort_error)?;
+            
+            let names: Vec<String> = if output_names.is_empty() {
+                session.outputs.iter().map(|output| output.name.clone()).collect()
+            } else {
+                output_names
+            };
+            
+            names.into_iter()
+                .map(|name| {
+                    let value = outputs.get(name.as_str()).ok_or_else(|| SynaptronError::Inference(format!(
+                        "ONNX model produced no output '{}'", name
+                    )))?;
+                    let (shape, data) = value.try_extract_raw_tensor::<f32>().map_err(ort_error)?;
+                    
+                    let tensor = OutputTensor {
+                        shape: shape.iter().map(|dim| *dim as usize).collect(),
+                        dtype: "f32".to_string(),
+                        data: tensor::f32_to_bytes(data),
+                    };
+                    Ok((name, tensor))
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| SynaptronError::Inference(format!("ONNX Runtime task failed: {}", e)))?
+    }
+}
+
+/// Input dimensions for `len` elements
+///
+/// Uses the declared shape when it fits, otherwise treats the first
+/// dimension as the batch size.
+fn input_dims(declared: &[usize], len: usize) -> Result<Vec<i64>, SynaptronError> {
+    let to_dims = |dims: &[usize]| dims.iter().map(|dim| *dim as i64).collect();
+    
+    if declared.is_empty() {
+        return Ok(vec![len as i64]);
+    }
+    
+    if declared.iter().product::<usize>() == len {
+        return Ok(to_dims(declared));
+    }
+    
+    let per_item: usize = declared[1..].iter().product();
+    if per_item > 0 && len.is_multiple_of(per_item) {
+        let mut dims = declared.to_vec();
+        dims[0] = len / per_item;
+        return Ok(to_dims(&dims));
+    }
+    
+    Err(SynaptronError::InvalidInput(format!(
+        "Input of {} elements does not fit model input shape {:?}", len, declared
+    )))
+}
+
+/// Convert an ONNX Runtime error
+fn ort_error(e: ort::Error) -> SynaptronError {
+    SynaptronError::Inference(format!("ONNX Runtime: {}", e))
+}
+
+#[async_trait]
+impl Backend for OrtBackend {
+    fn name(&self) -> &str {
+        "onnx_runtime"
+    }
+    
+    async fn load_model(&self, model: &Model) -> Result<(), SynaptronError> {
+        info!("Creating ONNX Runtime session for model: {}", model.name);
+        
+        let entries = self.precision_entries(&model.name);
+        let session = Session::builder()
+            .and_then(|builder| entries.iter().try_fold(builder, |builder, (key, value)| {
+                builder.with_config_entry(*key, *value)
+            }))
+            .and_then(|builder| builder.commit_from_memory(&model.data))
+            .map_err(|e| SynaptronError::ModelLoad(format!("ONNX Runtime could not load {}: {}", model.name, e)))?;
+        
+        let ModelMetadata { input_shape, data_type, .. } = &model.metadata;
+        debug!("ONNX model {} expects {} input of shape {:?}", model.name, data_type, input_shape);
+        
+        *self.loaded.write() = Some(LoadedSession {
+            name: model.name.clone(),
+            session: Arc::new(session),
+            input_shape: input_shape.clone(),
+            data_type: data_type.to_lowercase(),
+        });
+        
+        Ok(())
+    }
+    
+    fn new_session(&self) -> Option<Box<dyn Backend>> {
+        Some(Box::new(Self {
+            precision: self.precision,
+            loaded: RwLock::new(None),
+        }))
+    }
+    
+    async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        self.infer_cancellable(input, CancellationToken::new()).await
+    }
+    
+    async fn infer_cancellable(&self, input: Vec<u8>, cancel: CancellationToken) -> Result<Vec<u8>, SynaptronError> {
+        let first_output = self.output_names().into_iter().next()
+            .ok_or_else(|| SynaptronError::Inference("ONNX model declares no outputs".to_string()))?;
+        
+        let mut outputs = self.run(input, vec![first_output], cancel).await?;
+        Ok(outputs.pop().map(|(_, tensor)| tensor.data).unwrap_or_default())
+    }
+    
+    fn output_names(&self) -> Vec<String> {
+        self.loaded.read().as_ref()
+            .map(|loaded| loaded.session.outputs.iter().map(|output| output.name.clone()).collect())
+            .unwrap_or_default()
+    }
+    
+    async fn infer_outputs(
+        &self,
+        input: Vec<u8>,
+        output_names: &[String],
+    ) -> Result<HashMap<String, OutputTensor>, SynaptronError> {
+        debug!("Running ONNX Runtime for outputs: {:?}", output_names);
+        
+        Ok(self.run(input, output_names.to_vec(), CancellationToken::new()).await?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declared_shapes_are_used_when_they_fit() {
+        assert_eq!(input_dims(&[1, 3], 3).unwrap(), vec![1, 3]);
+        assert_eq!(input_dims(&[], 5).unwrap(), vec![5]);
+    }
+
+    #[test]
+    fn first_dimension_stretches_to_the_batch() {
+        assert_eq!(input_dims(&[1, 3], 6).unwrap(), vec![2, 3]);
+        assert!(matches!(input_dims(&[1, 3], 4), Err(SynaptronError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn inference_needs_a_loaded_session() {
+        let backend = OrtBackend::new().unwrap();
+        
+        assert!(backend.output_names().is_empty());
+        assert!(backend.infer(vec![0; 4]).await.is_err());
+        assert!(backend.infer_outputs(vec![0; 4], &["logits".to_string()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid_onnx_bytes_fail_to_load() {
+        let backend = OrtBackend::new().unwrap();
+        let model = Model::for_test("broken", crate::model::ModelInputType::Text, b"not an onnx graph");
+        
+        assert!(matches!(backend.load_model(&model).await, Err(SynaptronError::ModelLoad(_))));
+    }
+}
@@ -0,0 +1,271 @@
+//! Per-device sharing of initialized backends for the Synaptron inference engine
+
+use crate::{backend::Backend, error::SynaptronError, model::{Model, OutputTensor}};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+/// Backend kind and device id an instance was initialized for
+type PoolKey = (String, String);
+
+/// Backend pool counters
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackendPoolStats {
+    /// Backend instances initialized from scratch
+    pub created: u64,
+
+    /// Loads served by a session on an existing instance instead of a new one
+    pub reused: u64,
+
+    /// Instances currently shared by the models loaded on their device
+    pub shared: usize,
+}
+
+impl BackendPoolStats {
+    /// Render the counters in the Prometheus text exposition format
+    pub fn format_prometheus(&self) -> String {
+        [
+            "# HELP synaptron_backend_pool_created_total Backend instances initialized from scratch".to_string(),
+            "# TYPE synaptron_backend_pool_created_total counter".to_string(),
+            format!("synaptron_backend_pool_created_total {}", self.created),
+            "# HELP synaptron_backend_pool_reused_total Model loads served by a session on an existing backend instance".to_string(),
+            "# TYPE synaptron_backend_pool_reused_total counter".to_string(),
+            format!("synaptron_backend_pool_reused_total {}", self.reused),
+            "# HELP synaptron_backend_pool_shared Backend instances shared by the models on their device".to_string(),
+            "# TYPE synaptron_backend_pool_shared gauge".to_string(),
+            format!("synaptron_backend_pool_shared {}", self.shared),
+        ].join("\n") + "\n"
+    }
+}
+
+/// Backend instances shared per backend kind and device
+///
+/// The first model on a device initializes the instance; later ones get a
+/// session of it through `Backend::new_session`. Every session holds the
+/// instance, so it is torn down once the last model and request using it let
+/// go. Backends that can't start sessions get a new instance per model.
+#[derive(Clone)]
+pub struct BackendPool {
+    /// Shared instances per backend kind and device, held only by their sessions
+    shared: Arc<Mutex<HashMap<PoolKey, Weak<dyn Backend>>>>,
+
+    /// Instances initialized from scratch
+    created: Arc<AtomicU64>,
+
+    /// Sessions started on an existing instance
+    reused: Arc<AtomicU64>,
+}
+
+impl BackendPool {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(HashMap::new())),
+            created: Arc::new(AtomicU64::new(0)),
+            reused: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Backend for one model, a session of the device's instance or a new one from `create`
+    ///
+    /// The lock is held across `create`, so concurrent loads on one device
+    /// initialize a single instance between them.
+    pub fn acquire<F>(&self, kind: &str, device: &str, create: F) -> Result<Arc<dyn Backend>, SynaptronError>
+    where
+        F: FnOnce() -> Result<Arc<dyn Backend>, SynaptronError>,
+    {
+        let key = (kind.to_string(), device.to_string());
+        let mut shared = self.shared.lock();
+        shared.retain(|_, instance| instance.strong_count() > 0);
+
+        if let Some(instance) = shared.get(&key).and_then(Weak::upgrade) {
+            if let Some(session) = instance.new_session() {
+                debug!("Sharing {} backend on {}", kind, device);
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                return Ok(Arc::new(SharedSession { session, _instance: instance }));
+            }
+        }
+
+        let instance = create()?;
+        self.created.fetch_add(1, Ordering::Relaxed);
+
+        match instance.new_session() {
+            Some(session) => {
+                shared.insert(key, Arc::downgrade(&instance));
+                Ok(Arc::new(SharedSession { session, _instance: instance }))
+            }
+            None => Ok(instance),
+        }
+    }
+
+    /// Forget every shared instance, leaving each to drop with its last session
+    pub fn clear(&self) {
+        self.shared.lock().clear();
+    }
+
+    /// Current pool counters
+    pub fn stats(&self) -> BackendPoolStats {
+        BackendPoolStats {
+            created: self.created.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            shared: self.shared.lock().values().filter(|instance| instance.strong_count() > 0).count(),
+        }
+    }
+}
+
+impl Default for BackendPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One model's session, keeping the instance it was started on alive
+struct SharedSession {
+    /// Session the model is loaded into
+    session: Box<dyn Backend>,
+
+    /// Device instance, dropped with its last session
+    _instance: Arc<dyn Backend>,
+}
+
+#[async_trait]
+impl Backend for SharedSession {
+    fn name(&self) -> &str {
+        self.session.name()
+    }
+
+    async fn load_model(&self, model: &Model) -> Result<(), SynaptronError> {
+        self.session.load_model(model).await
+    }
+
+    fn new_session(&self) -> Option<Box<dyn Backend>> {
+        self.session.new_session()
+    }
+
+    async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        self.session.infer(input).await
+    }
+
+    async fn infer_batch(&self, inputs: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, SynaptronError> {
+        self.session.infer_batch(inputs).await
+    }
+
+    async fn infer_cancellable(&self, input: Vec<u8>, cancel: CancellationToken) -> Result<Vec<u8>, SynaptronError> {
+        self.session.infer_cancellable(input, cancel).await
+    }
+
+    async fn infer_batch_cancellable(
+        &self,
+        inputs: Vec<Vec<u8>>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Vec<u8>>, SynaptronError> {
+        self.session.infer_batch_cancellable(inputs, cancel).await
+    }
+
+    fn output_names(&self) -> Vec<String> {
+        self.session.output_names()
+    }
+
+    async fn infer_outputs(
+        &self,
+        input: Vec<u8>,
+        output_names: &[String],
+    ) -> Result<HashMap<String, OutputTensor>, SynaptronError> {
+        self.session.infer_outputs(input, output_names).await
+    }
+
+    fn infer_stream(&self, input: Vec<u8>) -> BoxStream<'_, Result<Vec<u8>, SynaptronError>> {
+        self.session.infer_stream(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+
+    /// Backend that can't start sessions, like one registered without `new_session`
+    struct Standalone;
+
+    #[async_trait]
+    impl Backend for Standalone {
+        fn name(&self) -> &str {
+            "standalone"
+        }
+
+        async fn load_model(&self, _model: &Model) -> Result<(), SynaptronError> {
+            Ok(())
+        }
+
+        async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+            Ok(input)
+        }
+    }
+
+    #[test]
+    fn models_on_one_device_share_one_instance() {
+        let pool = BackendPool::new();
+        let constructed = AtomicU64::new(0);
+        let create = || -> Result<Arc<dyn Backend>, SynaptronError> {
+            constructed.fetch_add(1, Ordering::Relaxed);
+            Ok(Arc::new(CPUBackend::new()?))
+        };
+
+        let first = pool.acquire("cpu", "cpu", create).unwrap();
+        let second = pool.acquire("cpu", "cpu", create).unwrap();
+
+        assert_eq!(constructed.load(Ordering::Relaxed), 1);
+        assert!(!Arc::ptr_eq(&first, &second), "each model needs a session of its own");
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.reused, stats.shared), (1, 1, 1));
+    }
+
+    #[test]
+    fn devices_get_instances_of_their_own() {
+        let pool = BackendPool::new();
+        let create = || -> Result<Arc<dyn Backend>, SynaptronError> { Ok(Arc::new(CPUBackend::new()?)) };
+
+        let _first = pool.acquire("cpu", "cuda:0", create).unwrap();
+        let _second = pool.acquire("cpu", "cuda:1", create).unwrap();
+
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.reused, stats.shared), (2, 0, 2));
+    }
+
+    #[test]
+    fn instance_is_torn_down_with_its_last_model() {
+        let pool = BackendPool::new();
+        let instance: Arc<dyn Backend> = Arc::new(CPUBackend::new().unwrap());
+        let watched = Arc::downgrade(&instance);
+        let mut instance = Some(instance);
+
+        let first = pool.acquire("cpu", "cpu", || Ok(instance.take().unwrap())).unwrap();
+        let second = pool.acquire("cpu", "cpu", || unreachable!("the instance is shared")).unwrap();
+
+        drop(first);
+        assert!(watched.upgrade().is_some(), "a model still uses the instance");
+
+        drop(second);
+        assert!(watched.upgrade().is_none());
+        assert_eq!(pool.stats().shared, 0);
+    }
+
+    #[test]
+    fn backends_without_sessions_are_not_shared() {
+        let pool = BackendPool::new();
+        let create = || -> Result<Arc<dyn Backend>, SynaptronError> { Ok(Arc::new(Standalone)) };
+
+        let first = pool.acquire("standalone", "cpu", create).unwrap();
+        let second = pool.acquire("standalone", "cpu", create).unwrap();
+
+        assert!(!Arc::ptr_eq(&first, &second));
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.reused, stats.shared), (2, 0, 0));
+    }
+}
@@ -0,0 +1,113 @@
+//! Custom backend registration for the Synaptron inference engine
+
+use crate::{backend::Backend, error::SynaptronError};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Creates a new, empty instance of a registered backend
+pub type BackendFactory = Arc<dyn Fn() -> Result<Box<dyn Backend>, SynaptronError> + Send + Sync>;
+
+/// Backends registered by name, consulted before the built-in ones
+///
+/// This is how crates embedding the engine run models on hardware the
+/// engine doesn't know about.
+#[derive(Clone, Default)]
+pub struct BackendRegistry {
+    /// Factories keyed by backend name
+    factories: Arc<RwLock<HashMap<String, BackendFactory>>>,
+}
+
+impl BackendRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a factory for a backend name, replacing any earlier one
+    ///
+    /// A registered name shadows a built-in backend of the same name.
+    pub fn register<F>(&self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Result<Box<dyn Backend>, SynaptronError> + Send + Sync + 'static,
+    {
+        self.factories.write().insert(name.into(), Arc::new(factory));
+    }
+
+    /// Remove a registered backend, returning whether it was registered
+    pub fn unregister(&self, name: &str) -> bool {
+        self.factories.write().remove(name).is_some()
+    }
+
+    /// Whether a backend name is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.read().contains_key(name)
+    }
+
+    /// Registered backend names, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.factories.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Create an instance of a registered backend, `None` if the name isn't registered
+    pub fn create(&self, name: &str) -> Option<Result<Arc<dyn Backend>, SynaptronError>> {
+        // Call the factory outside the lock, so it may itself use the registry
+        let factory = self.factories.read().get(name).cloned()?;
+        Some(factory().map(Arc::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+
+    /// Registry with a CPU backend registered as `custom`
+    fn registry() -> BackendRegistry {
+        let registry = BackendRegistry::new();
+        registry.register("custom", || Ok(Box::new(CPUBackend::new()?)));
+        registry
+    }
+
+    #[test]
+    fn registered_backends_are_created_by_name() {
+        let registry = registry();
+
+        let backend = registry.create("custom").expect("custom is registered").unwrap();
+
+        assert_eq!(backend.name(), "cpu");
+        assert!(registry.create("missing").is_none());
+    }
+
+    #[test]
+    fn names_are_sorted_and_unregistering_forgets_them() {
+        let registry = registry();
+        registry.register("accelerator", || Ok(Box::new(CPUBackend::new()?)));
+
+        assert_eq!(registry.names(), vec!["accelerator".to_string(), "custom".to_string()]);
+        assert!(registry.unregister("custom"));
+        assert!(!registry.unregister("custom"));
+        assert!(!registry.contains("custom"));
+        assert!(registry.create("custom").is_none());
+    }
+
+    #[test]
+    fn clones_share_registrations() {
+        let registry = BackendRegistry::new();
+        let clone = registry.clone();
+
+        clone.register("custom", || Ok(Box::new(CPUBackend::new()?)));
+
+        assert!(registry.contains("custom"));
+    }
+
+    #[test]
+    fn factory_errors_are_returned() {
+        let registry = BackendRegistry::new();
+        registry.register("broken", || Err(SynaptronError::BackendInit("no device".to_string())));
+
+        assert!(matches!(registry.create("broken"), Some(Err(SynaptronError::BackendInit(_)))));
+    }
+}
@@ -1,52 +1,383 @@
-/! Batch processing implementation for the Synaptron inference engine
+//! Batch processing implementation for the Synaptron inference engine
 
 use crate::{config::BatchConfig, error::SynaptronError};
-use tracing::{info, debug};
+use tracing::{info, info_span, debug, warn, Instrument, Span};
+use std::collections::HashMap;
 use std::future::Future;
-use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::time::{timeout, Duration};
+use tokio::sync::{oneshot, Notify, RwLock};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::{timeout, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Submitted input waiting for the next flush
+struct PendingEntry {
+    /// Model the input is for
+    model: String,
+    
+    /// Input data
+    input: Vec<u8>,
+    
+    /// Cancellation token for the submitting request
+    cancel: CancellationToken,
+    
+    /// Span of the submitting request, so batch logs carry its request ID
+    span: Span,
+    
+    /// When the input was submitted
+    submitted: Instant,
+    
+    /// Channel the result is sent back on
+    responder: oneshot::Sender<Result<Vec<u8>, SynaptronError>>,
+}
 
 /// Batch processor
 pub struct BatchProcessor {
-    /// Batch configuration
-    config: BatchConfig,
+    /// Batch configuration, replaced on config reload
+    config: Arc<parking_lot::RwLock<BatchConfig>>,
     
     /// Current batch
-    current_batch: Arc<RwLock<Vec<Vec<u8>>>>,
+    current_batch: Arc<RwLock<Vec<PendingEntry>>>,
+    
+    /// Per-model maximum batch sizes, replaced on config reload
+    model_batch_sizes: Arc<parking_lot::RwLock<HashMap<String, usize>>>,
+    
+    /// Longest a submitted input waits for its forward pass to start, `None` for no limit
+    timeout: Arc<parking_lot::RwLock<Option<Duration>>>,
+    
+    /// Wakes the batcher when inputs are submitted
+    wakeup: Arc<Notify>,
+    
+    /// Stops the batcher
+    stop: CancellationToken,
 }
 
 impl BatchProcessor {
     /// Create a new batch processor
     pub fn new(config: &BatchConfig) -> Self {
         Self {
-            config: config.clone(),
+            config: Arc::new(parking_lot::RwLock::new(config.clone())),
             current_batch: Arc::new(RwLock::new(Vec::new())),
+            model_batch_sizes: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            timeout: Arc::new(parking_lot::RwLock::new(None)),
+            wakeup: Arc::new(Notify::new()),
+            stop: CancellationToken::new(),
+        }
+    }
+    
+    /// Current batch configuration
+    pub fn config(&self) -> BatchConfig {
+        self.config.read().clone()
+    }
+    
+    /// Apply a reloaded batch configuration, taking effect from the next batch
+    pub fn update_config(&self, config: &BatchConfig) {
+        *self.config.write() = config.clone();
+    }
+    
+    /// Apply reloaded per-model batch sizes and wait timeout, taking effect from the next batch
+    pub fn update_limits(&self, sizes: HashMap<String, usize>, timeout: Option<Duration>) {
+        *self.model_batch_sizes.write() = sizes;
+        *self.timeout.write() = timeout;
+    }
+    
+    /// How long the first pending input waits for others to join its batch
+    fn window(&self) -> Duration {
+        Duration::from_millis(self.config().window_ms)
+    }
+    
+    /// Set how long a submitted input may wait for its forward pass to start
+    ///
+    /// Inputs still waiting when their batch is flushed after this long fail
+    /// instead of joining it, and each input run through `process` fails alone
+    /// once it takes this long.
+    pub fn with_timeout(self, timeout: Option<Duration>) -> Self {
+        *self.timeout.write() = timeout;
+        self
+    }
+    
+    /// Set per-model maximum batch sizes
+    pub fn with_model_batch_sizes(self, sizes: HashMap<String, usize>) -> Self {
+        *self.model_batch_sizes.write() = sizes;
+        self
+    }
+    
+    /// Get the maximum batch size for a model, falling back to the global default
+    pub fn max_batch_size_for(&self, model_name: &str) -> usize {
+        self.model_batch_sizes.read()
+            .get(model_name)
+            .copied()
+            .unwrap_or(self.config().max_batch_size)
+    }
+    
+    /// Group inputs by model and split each group into batches capped at that model's limit
+    pub fn form_batches<T>(&self, inputs: Vec<(String, T)>) -> Vec<(String, Vec<T>)> {
+        // Group while preserving first-seen model order
+        let mut groups: Vec<(String, Vec<T>)> = Vec::new();
+        
+        for (model_name, input) in inputs {
+            match groups.iter_mut().find(|(name, _)| *name == model_name) {
+                Some((_, group)) => group.push(input),
+                None => groups.push((model_name, vec![input])),
+            }
+        }
+        
+        let mut batches = Vec::new();
+        
+        for (model_name, group) in groups {
+            let cap = self.max_batch_size_for(&model_name).max(1);
+            debug!("Forming batches for model {} with cap {}", model_name, cap);
+            
+            let mut group = group;
+            while !group.is_empty() {
+                let rest = group.split_off(cap.min(group.len()));
+                batches.push((model_name.clone(), std::mem::replace(&mut group, rest)));
+            }
+        }
+        
+        batches
+    }
+    
+    /// Submit an input for a model to the forming batch, returning a receiver for its result
+    pub async fn submit(
+        &self,
+        model_name: &str,
+        input: Vec<u8>,
+        cancel: CancellationToken,
+    ) -> oneshot::Receiver<Result<Vec<u8>, SynaptronError>> {
+        let (responder, receiver) = oneshot::channel();
+        
+        let mut batch_guard = self.current_batch.write().await;
+        batch_guard.push(PendingEntry {
+            model: model_name.to_string(),
+            input,
+            cancel,
+            span: Span::current(),
+            submitted: Instant::now(),
+            responder,
+        });
+        debug!("Submitted input for {} to forming batch ({} pending)", model_name, batch_guard.len());
+        drop(batch_guard);
+        
+        self.wakeup.notify_one();
+        receiver
+    }
+    
+    /// Whether some model's pending inputs already fill a batch
+    async fn has_full_batch(&self) -> bool {
+        let batch_guard = self.current_batch.read().await;
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        
+        batch_guard.iter().any(|entry| {
+            let count = counts.entry(entry.model.as_str()).or_insert(0);
+            *count += 1;
+            *count >= self.max_batch_size_for(&entry.model).max(1)
+        })
+    }
+    
+    /// Spawn the batcher, which dispatches a batch once a model's inputs fill it or the window closes
+    ///
+    /// `processor` runs one batched forward pass for a model and returns one
+    /// output per input, in order; its token fires once every caller waiting on
+    /// the batch has gone away. Each batch runs on a task of its own, so a slow
+    /// forward pass doesn't hold up batches for other models; `processor` bounds
+    /// how many run at once. The batcher runs until `stop` is called, then waits
+    /// for the batches it dispatched.
+    pub fn spawn_batcher<F, Fut>(&self, processor: F) -> JoinHandle<()>
+    where
+        F: Fn(String, Vec<Vec<u8>>, CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<Vec<u8>>, SynaptronError>> + Send + 'static,
+    {
+        let batcher = self.clone();
+        let processor = Arc::new(processor);
+        
+        tokio::spawn(async move {
+            info!("Batcher started");
+            let mut running = JoinSet::new();
+            
+            loop {
+                // Wait for the first input unless some are left over from the last flush;
+                // a stale wakeup can find the batch already taken, so check again
+                if batcher.current_batch.read().await.is_empty() {
+                    tokio::select! {
+                        _ = batcher.stop.cancelled() => break,
+                        _ = batcher.wakeup.notified() => continue,
+                    }
+                }
+                
+                // Accumulate until a batch is full or the window closes
+                let deadline = Instant::now() + batcher.window();
+                while !batcher.has_full_batch().await {
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(deadline) => break,
+                        _ = batcher.wakeup.notified() => {}
+                    }
+                }
+                
+                for (model_name, entries) in batcher.take_batches().await {
+                    let processor = processor.clone();
+                    running.spawn(async move { Self::run_batch(model_name, entries, &*processor).await });
+                }
+                
+                // Reap finished batches so the set doesn't grow
+                while let Some(finished) = running.try_join_next() {
+                    if let Err(e) = finished {
+                        warn!("Batch task failed: {}", e);
+                    }
+                }
+            }
+            
+            while let Some(finished) = running.join_next().await {
+                if let Err(e) = finished {
+                    warn!("Batch task failed: {}", e);
+                }
+            }
+            info!("Batcher stopped");
+        })
+    }
+    
+    /// Stop the batcher; inputs still pending are left for a final `flush`
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+    
+    /// Flush the forming batch, dropping cancelled and expired entries first
+    ///
+    /// Inputs are grouped by model into batches capped at that model's limit,
+    /// and each batch is run as one call to `processor`. Returns the number of
+    /// inputs actually processed.
+    pub async fn flush<F, Fut>(&self, processor: F) -> Result<usize, SynaptronError>
+    where
+        F: Fn(String, Vec<Vec<u8>>, CancellationToken) -> Fut,
+        Fut: Future<Output = Result<Vec<Vec<u8>>, SynaptronError>>,
+    {
+        let batches = self.take_batches().await;
+        if batches.is_empty() {
+            debug!("No live pending inputs, skipping flush");
+            return Ok(0);
+        }
+        
+        let count = batches.iter().map(|(_, entries)| entries.len()).sum();
+        
+        // Batches for different models run concurrently
+        futures::future::join_all(batches.into_iter().map(|(model_name, entries)| {
+            Self::run_batch(model_name, entries, &processor)
+        })).await;
+        
+        Ok(count)
+    }
+    
+    /// Take the forming batch as per-model batches, failing cancelled and expired entries
+    async fn take_batches(&self) -> Vec<(String, Vec<PendingEntry>)> {
+        let entries = std::mem::take(&mut *self.current_batch.write().await);
+        
+        let (cancelled, live): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| entry.cancel.is_cancelled());
+        
+        for entry in cancelled {
+            let _ = entry.responder.send(Err(SynaptronError::Batch(
+                "Request cancelled before batch flush".to_string()
+            )));
+        }
+        
+        let timeout = *self.timeout.read();
+        let (expired, live): (Vec<_>, Vec<_>) = live
+            .into_iter()
+            .partition(|entry| timeout.is_some_and(|limit| entry.submitted.elapsed() > limit));
+        
+        for entry in expired {
+            let waited = entry.submitted.elapsed().as_millis();
+            debug!(parent: &entry.span, "Input for {} waited {} ms for a batch, failing it", entry.model, waited);
+            let _ = entry.responder.send(Err(SynaptronError::Batch(format!(
+                "Input waited {} ms for a batch to start", waited
+            ))));
+        }
+        
+        self.form_batches(live.into_iter().map(|entry| (entry.model.clone(), entry)).collect())
+    }
+    
+    /// Run one batch through the processor and send each result to its caller
+    async fn run_batch<F, Fut>(model_name: String, entries: Vec<PendingEntry>, processor: &F)
+    where
+        F: Fn(String, Vec<Vec<u8>>, CancellationToken) -> Fut,
+        Fut: Future<Output = Result<Vec<Vec<u8>>, SynaptronError>>,
+    {
+        let count = entries.len();
+        let batch_span = info_span!("batch", model = %model_name, size = count);
+        
+        // Link the batch to each request it serves, and log the batch in each request's span
+        let mut inputs = Vec::with_capacity(count);
+        let mut responders = Vec::with_capacity(count);
+        let mut waiters = Vec::with_capacity(count);
+        for entry in entries {
+            batch_span.follows_from(&entry.span);
+            debug!(parent: &entry.span, "Joined batch of size {} for model {}", count, model_name);
+            inputs.push(entry.input);
+            responders.push(entry.responder);
+            waiters.push(entry.cancel);
+        }
+        
+        info!(parent: &batch_span, "Running batch of size {} for model {}", count, model_name);
+        
+        // Cancel the forward pass once every caller waiting on it has gone away
+        let cancel = CancellationToken::new();
+        let run = processor(model_name.clone(), inputs, cancel.clone()).instrument(batch_span);
+        let run = async {
+            tokio::pin!(run);
+            tokio::select! {
+                result = &mut run => result,
+                _ = futures::future::join_all(waiters.iter().map(CancellationToken::cancelled)) => {
+                    debug!("Every caller of the batch for {} went away, cancelling it", model_name);
+                    cancel.cancel();
+                    run.await
+                }
+            }
+        };
+        match run.await {
+            Ok(results) if results.len() == count => {
+                for (responder, result) in responders.into_iter().zip(results) {
+                    let _ = responder.send(Ok(result));
+                }
+            }
+            Ok(results) => {
+                let message = format!("Batch returned {} outputs for {} inputs", results.len(), count);
+                for responder in responders {
+                    let _ = responder.send(Err(SynaptronError::Batch(message.clone())));
+                }
+            }
+            Err(e) => {
+                let message = e.to_string();
+                for responder in responders {
+                    let _ = responder.send(Err(SynaptronError::Batch(message.clone())));
+                }
+            }
         }
     }
     
     /// Process inputs in batches
-    pub async fn process<F, Fut>(
+    ///
+    /// Each input gets its own result, so one failed or timed out input
+    /// doesn't discard the others.
+    pub async fn process<F, Fut, T>(
         &self,
         inputs: Vec<Vec<u8>>,
         processor: F,
-    ) -> Result<Vec<Vec<u8>>, SynaptronError>
+    ) -> Vec<Result<T, SynaptronError>>
     where
         F: Fn(Vec<u8>) -> Fut,
-        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+        Fut: Future<Output = Result<T, SynaptronError>>,
     {
-        if !self.config.enabled || inputs.len() < 2 {
+        if !self.config().enabled || inputs.len() < 2 {
             // Process individually if batching is disabled or only one input
             debug!("Processing inputs individually");
             let mut results = Vec::new();
             
             for input in inputs {
-                let result = processor(input).await?;
-                results.push(result);
+                results.push(self.process_item(processor(input)).await);
             }
             
-            return Ok(results);
+            return results;
         }
         
         debug!("Processing batch of {} inputs", inputs.len());
@@ -54,52 +385,341 @@ impl BatchProcessor {
         // Split inputs into batches
         let mut results = Vec::new();
         
-        for chunk in inputs.chunks(self.config.max_batch_size) {
-            let batch_results = self.process_batch(chunk.to_vec(), &processor).await?;
+        for chunk in inputs.chunks(self.config().max_batch_size.max(1)) {
+            let batch_results = self.process_batch(chunk.to_vec(), &processor).await;
             results.extend(batch_results);
         }
         
-        Ok(results)
+        results
     }
     
-    /// Process a single batch
-    async fn process_batch<F, Fut>(
+    /// Process a single batch, returning each input's result in order
+    async fn process_batch<F, Fut, T>(
         &self,
         batch: Vec<Vec<u8>>,
         processor: &F,
-    ) -> Result<Vec<Vec<u8>>, SynaptronError>
+    ) -> Vec<Result<T, SynaptronError>>
     where
         F: Fn(Vec<u8>) -> Fut,
-        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+        Fut: Future<Output = Result<T, SynaptronError>>,
     {
         info!("Processing batch of size {}", batch.len());
         
-        // Process all inputs in the batch concurrently
-        let mut futures = Vec::new();
+        // Process all inputs in the batch concurrently, each under its own timeout
+        let results = futures::future::join_all(
+            batch.into_iter().map(|input| self.process_item(processor(input)))
+        ).await;
         
-        for input in batch {
-            futures.push(processor(input));
+        let timed_out = results.iter()
+            .filter(|result| matches!(result, Err(SynaptronError::Batch(_))))
+            .count();
+        if timed_out > 0 {
+            warn!("{} of {} batch inputs timed out", timed_out, results.len());
+        }
+        
+        results
+    }
+    
+    /// Process a single input under the batch timeout
+    async fn process_item<Fut, T>(&self, item: Fut) -> Result<T, SynaptronError>
+    where
+        Fut: Future<Output = Result<T, SynaptronError>>,
+    {
+        let limit = *self.timeout.read();
+        match limit {
+            Some(timeout_duration) => timeout(timeout_duration, item).await
+                .unwrap_or_else(|_| Err(SynaptronError::Batch(format!(
+                    "Batch input timed out after {} ms", timeout_duration.as_millis()
+                )))),
+            None => item.await,
+        }
+    }
+}
+
+impl Clone for BatchProcessor {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            current_batch: self.current_batch.clone(),
+            model_batch_sizes: self.model_batch_sizes.clone(),
+            timeout: self.timeout.clone(),
+            wakeup: self.wakeup.clone(),
+            stop: self.stop.clone(),
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_are_capped_per_model() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 3, ..BatchConfig::default() })
+            .with_model_batch_sizes(HashMap::from([("bert".to_string(), 2)]));
+        let inputs = (0..5u8)
+            .map(|i| ("bert".to_string(), vec![i]))
+            .chain((0..4u8).map(|i| ("resnet".to_string(), vec![i])))
+            .collect();
         
-        // Wait for all results with timeout
-        let timeout_duration = Duration::from_millis(self.config.timeout_ms);
+        let sizes: Vec<(String, usize)> = processor.form_batches(inputs)
+            .into_iter()
+            .map(|(model, batch)| (model, batch.len()))
+            .collect();
         
-        let results = match timeout(timeout_duration, futures::future::join_all(futures)).await {
-            Ok(results) => results,
-            Err(_) => {
-                return Err(SynaptronError::Batch(
-                    "Batch processing timed out".to_string()
-                ));
-            }
+        assert_eq!(sizes, [("bert", 2), ("bert", 2), ("bert", 1), ("resnet", 3), ("resnet", 1)]
+            .map(|(model, size)| (model.to_string(), size)));
+    }
+
+    #[test]
+    fn reloaded_limits_replace_the_model_batch_sizes() {
+        let processor = BatchProcessor::new(&BatchConfig::default())
+            .with_model_batch_sizes(HashMap::from([("bert".to_string(), 2)]));
+        
+        processor.update_limits(HashMap::from([("resnet".to_string(), 4)]), None);
+        
+        assert_eq!(processor.max_batch_size_for("bert"), BatchConfig::default().max_batch_size);
+        assert_eq!(processor.max_batch_size_for("resnet"), 4);
+    }
+
+    #[tokio::test]
+    async fn dropping_every_caller_cancels_the_running_batch() {
+        let processor = BatchProcessor::new(&BatchConfig::default());
+        let started = Arc::new(Notify::new());
+        let observed = Arc::new(Notify::new());
+        
+        let batcher = {
+            let (started, observed) = (started.clone(), observed.clone());
+            processor.spawn_batcher(move |_, _, cancel| {
+                let (started, observed) = (started.clone(), observed.clone());
+                async move {
+                    started.notify_one();
+                    cancel.cancelled().await;
+                    observed.notify_one();
+                    Err(crate::backend::cancelled())
+                }
+            })
         };
         
-        // Collect results
-        let mut processed_results = Vec::new();
+        // A request that goes away, as axum drops the handler future when the client disconnects
+        let request = {
+            let processor = processor.clone();
+            tokio::spawn(async move {
+                let cancel = CancellationToken::new();
+                let _cancel_on_drop = cancel.clone().drop_guard();
+                processor.submit("bert", vec![1, 2, 3], cancel).await.await
+            })
+        };
+        
+        started.notified().await;
+        request.abort();
+        
+        tokio::time::timeout(Duration::from_secs(1), observed.notified()).await
+            .expect("the backend never observed cancellation");
+        processor.stop();
+        batcher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelled_inputs_are_left_out_of_the_batch() {
+        let processor = BatchProcessor::new(&BatchConfig::default());
+        let kept = processor.submit("bert", vec![1], CancellationToken::new()).await;
+        let cancel = CancellationToken::new();
+        let dropped = processor.submit("bert", vec![2], cancel.clone()).await;
+        cancel.cancel();
+        
+        let processed = processor.flush(|_, inputs, _| async move {
+            assert_eq!(inputs, vec![vec![1]]);
+            Ok(inputs)
+        }).await.unwrap();
+        
+        assert_eq!(processed, 1);
+        assert_eq!(kept.await.unwrap().unwrap(), vec![1]);
+        assert!(dropped.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn flush_of_only_cancelled_inputs_skips_the_processor() {
+        let processor = BatchProcessor::new(&BatchConfig::default());
+        let cancel = CancellationToken::new();
+        let dropped = processor.submit("bert", vec![1], cancel.clone()).await;
+        cancel.cancel();
+        
+        let processed = processor.flush(|_, _, _| async move {
+            Err(SynaptronError::Batch("processor ran for a cancelled input".to_string()))
+        }).await.unwrap();
         
-        for result in results {
-            processed_results.push(result?);
+        assert_eq!(processed, 0);
+        assert!(matches!(dropped.await.unwrap(), Err(SynaptronError::Batch(_))));
+    }
+
+    #[tokio::test]
+    async fn full_batch_runs_before_the_window_closes() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 2, window_ms: 60_000, ..BatchConfig::default() });
+        let batcher = processor.spawn_batcher(|_, inputs, _| async move { Ok(inputs) });
+        
+        let first = processor.submit("bert", vec![1], CancellationToken::new()).await;
+        let second = processor.submit("bert", vec![2], CancellationToken::new()).await;
+        
+        let results = tokio::time::timeout(Duration::from_secs(1), futures::future::join(first, second)).await
+            .expect("a full batch waited for the window");
+        assert_eq!(results.0.unwrap().unwrap(), vec![1]);
+        assert_eq!(results.1.unwrap().unwrap(), vec![2]);
+        processor.stop();
+        batcher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn partial_batch_runs_when_the_window_closes() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 8, window_ms: 20, ..BatchConfig::default() });
+        let sizes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let batcher = {
+            let sizes = sizes.clone();
+            processor.spawn_batcher(move |_, inputs, _| {
+                sizes.lock().push(inputs.len());
+                async move { Ok(inputs) }
+            })
+        };
+        
+        let only = processor.submit("bert", vec![1], CancellationToken::new()).await;
+        
+        let result = tokio::time::timeout(Duration::from_secs(1), only).await
+            .expect("a partial batch never ran");
+        assert_eq!(result.unwrap().unwrap(), vec![1]);
+        assert_eq!(*sizes.lock(), vec![1]);
+        processor.stop();
+        batcher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_grouped_into_batches() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 8, window_ms: 50, ..BatchConfig::default() });
+        let sizes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let batcher = {
+            let sizes = sizes.clone();
+            processor.spawn_batcher(move |_, inputs, _| {
+                sizes.lock().push(inputs.len());
+                async move { Ok(inputs) }
+            })
+        };
+        
+        let requests: Vec<_> = (0..50u8).map(|i| {
+            let processor = processor.clone();
+            tokio::spawn(async move {
+                processor.submit("bert", vec![i], CancellationToken::new()).await.await
+            })
+        }).collect();
+        
+        for (i, request) in requests.into_iter().enumerate() {
+            let result = tokio::time::timeout(Duration::from_secs(5), request).await
+                .expect("a request never got its result");
+            assert_eq!(result.unwrap().unwrap().unwrap(), vec![i as u8]);
         }
         
-        Ok(processed_results)
+        let sizes = sizes.lock().clone();
+        assert_eq!(sizes.iter().sum::<usize>(), 50);
+        assert!(sizes.iter().all(|&size| size <= 8));
+        assert!(sizes.len() < 50, "requests ran one at a time: {:?}", sizes);
+        processor.stop();
+        batcher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn slow_batch_does_not_hold_up_other_models() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 1, window_ms: 60_000, ..BatchConfig::default() });
+        let release = Arc::new(Notify::new());
+        let batcher = {
+            let release = release.clone();
+            processor.spawn_batcher(move |model_name, inputs, _| {
+                let release = release.clone();
+                async move {
+                    if model_name == "slow" {
+                        release.notified().await;
+                    }
+                    Ok(inputs)
+                }
+            })
+        };
+        
+        let slow = processor.submit("slow", vec![1], CancellationToken::new()).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let fast = processor.submit("fast", vec![2], CancellationToken::new()).await;
+        
+        let result = tokio::time::timeout(Duration::from_secs(1), fast).await
+            .expect("a slow batch held up another model");
+        assert_eq!(result.unwrap().unwrap(), vec![2]);
+        
+        release.notify_one();
+        assert_eq!(slow.await.unwrap().unwrap(), vec![1]);
+        processor.stop();
+        batcher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn inputs_past_the_timeout_fail_alone() {
+        let processor = BatchProcessor::new(&BatchConfig::default())
+            .with_timeout(Some(Duration::from_millis(10)));
+        let stale = processor.submit("bert", vec![1], CancellationToken::new()).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let fresh = processor.submit("bert", vec![2], CancellationToken::new()).await;
+        
+        let processed = processor.flush(|_, inputs, _| async move { Ok(inputs) }).await.unwrap();
+        
+        assert_eq!(processed, 1);
+        assert!(matches!(stale.await.unwrap(), Err(SynaptronError::Batch(_))));
+        assert_eq!(fresh.await.unwrap().unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn short_batch_output_fails_every_input() {
+        let processor = BatchProcessor::new(&BatchConfig::default());
+        let first = processor.submit("bert", vec![1], CancellationToken::new()).await;
+        let second = processor.submit("bert", vec![2], CancellationToken::new()).await;
+        
+        processor.flush(|_, mut inputs, _| async move {
+            inputs.pop();
+            Ok(inputs)
+        }).await.unwrap();
+        
+        assert!(first.await.unwrap().is_err());
+        assert!(second.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn one_failed_input_keeps_the_others() {
+        let processor = BatchProcessor::new(&BatchConfig { max_batch_size: 2, ..BatchConfig::default() });
+        
+        let results = processor.process(vec![vec![1], vec![2], vec![3]], |input| async move {
+            match input[0] {
+                2 => Err(SynaptronError::Inference("bad input".to_string())),
+                _ => Ok(input),
+            }
+        }).await;
+        
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &vec![1]);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &vec![3]);
+    }
+
+    #[tokio::test]
+    async fn slow_input_times_out_alone() {
+        let processor = BatchProcessor::new(&BatchConfig::default())
+            .with_timeout(Some(Duration::from_millis(100)));
+        let inputs = (0..8u8).map(|i| vec![i]).collect();
+        
+        let results = processor.process(inputs, |input| async move {
+            let delay = if input[0] == 3 { Duration::from_secs(60) } else { Duration::from_millis(1) };
+            tokio::time::sleep(delay).await;
+            Ok(input)
+        }).await;
+        
+        assert_eq!(results.len(), 8);
+        for (i, result) in results.iter().enumerate() {
+            match i {
+                3 => assert!(matches!(result, Err(SynaptronError::Batch(_)))),
+                _ => assert_eq!(result.as_ref().unwrap(), &vec![i as u8]),
+            }
+        }
     }
 }
@@ -0,0 +1,293 @@
+//! Per-model circuit breaker for the Synaptron inference engine
+
+use crate::{config::BreakerConfig, error::SynaptronError};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Model health as seen by the breaker
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelHealth {
+    /// Serving normally
+    Healthy,
+    
+    /// Rejecting requests after consecutive failures
+    Unhealthy,
+    
+    /// Letting a single probe request through to test recovery
+    Probing,
+}
+
+/// Breaker state for one model
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// Consecutive server-side failures
+    consecutive_failures: u32,
+    
+    /// When the breaker opened, if it is open
+    opened_at: Option<Instant>,
+    
+    /// Whether a recovery probe is in flight
+    probing: bool,
+}
+
+/// Per-model circuit breaker
+pub struct ModelBreaker {
+    /// Breaker configuration
+    config: BreakerConfig,
+    
+    /// State per model
+    states: Arc<Mutex<HashMap<String, BreakerState>>>,
+}
+
+impl ModelBreaker {
+    /// Create a new breaker
+    pub fn new(config: &BreakerConfig) -> Self {
+        Self {
+            config: config.clone(),
+            states: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    
+    /// Check whether a request may be sent to a model
+    ///
+    /// The returned permit records the request's outcome. A recovery probe
+    /// whose permit is dropped without an outcome frees the probe slot again.
+    pub fn check(&self, model_name: &str) -> Result<BreakerPermit, SynaptronError> {
+        let mut states = self.states.lock();
+        let state = states.entry(model_name.to_string()).or_default();
+        
+        let opened_at = match state.opened_at {
+            Some(opened_at) => opened_at,
+            None => return Ok(self.permit(model_name, false)),
+        };
+        
+        let recovery = Duration::from_secs(self.config.recovery_secs);
+        if !state.probing && opened_at.elapsed() >= recovery {
+            info!("Probing recovery of model {}", model_name);
+            state.probing = true;
+            return Ok(self.permit(model_name, true));
+        }
+        
+        Err(SynaptronError::ModelUnavailable(format!(
+            "Model {} is unhealthy after {} consecutive failures",
+            model_name, state.consecutive_failures
+        )))
+    }
+    
+    /// Permit for one request to a model
+    fn permit(&self, model_name: &str, probe: bool) -> BreakerPermit {
+        BreakerPermit {
+            breaker: self.clone(),
+            model_name: model_name.to_string(),
+            probe,
+        }
+    }
+    
+    /// Record a successful request, closing the breaker
+    pub fn record_success(&self, model_name: &str) {
+        let mut states = self.states.lock();
+        if let Some(state) = states.get_mut(model_name) {
+            if state.opened_at.is_some() {
+                info!("Model {} recovered", model_name);
+            }
+            *state = BreakerState::default();
+        }
+    }
+    
+    /// Record a failed request; only server-side failures count towards opening the breaker
+    pub fn record_failure(&self, model_name: &str, error: &SynaptronError) {
+        if !Self::is_server_failure(error) {
+            // Bad input says nothing about recovery, let another request probe
+            self.end_probe(model_name);
+            return;
+        }
+        
+        let mut states = self.states.lock();
+        let state = states.entry(model_name.to_string()).or_default();
+        state.consecutive_failures += 1;
+        
+        if state.probing {
+            // Failed probe, stay open for another recovery period
+            state.probing = false;
+            state.opened_at = Some(Instant::now());
+        } else if state.opened_at.is_none() && state.consecutive_failures >= self.config.failure_threshold {
+            warn!("Marking model {} unhealthy after {} consecutive failures", model_name, state.consecutive_failures);
+            state.opened_at = Some(Instant::now());
+        }
+    }
+    
+    /// Free the probe slot of a model without recording an outcome
+    fn end_probe(&self, model_name: &str) {
+        if let Some(state) = self.states.lock().get_mut(model_name) {
+            state.probing = false;
+        }
+    }
+    
+    /// Drop a model's breaker state, so it starts healthy if loaded again
+    pub fn forget(&self, model_name: &str) {
+        self.states.lock().remove(model_name);
+    }
+    
+    /// Current health of a model
+    pub fn health(&self, model_name: &str) -> ModelHealth {
+        let states = self.states.lock();
+        match states.get(model_name) {
+            Some(state) if state.probing => ModelHealth::Probing,
+            Some(state) if state.opened_at.is_some() => ModelHealth::Unhealthy,
+            _ => ModelHealth::Healthy,
+        }
+    }
+    
+    /// Names of models that are not healthy
+    pub fn unhealthy_models(&self) -> Vec<String> {
+        let states = self.states.lock();
+        let mut names: Vec<String> = states.iter()
+            .filter(|(_, state)| state.opened_at.is_some())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+    
+    /// Whether an error reflects a server-side failure rather than bad input
+    fn is_server_failure(error: &SynaptronError) -> bool {
+        !matches!(
+            error.root(),
+            SynaptronError::InvalidInput(_)
+                | SynaptronError::UnsupportedFormat(_)
+                | SynaptronError::Tokenization(_)
+                | SynaptronError::ModelUnavailable(_)
+                | SynaptronError::ModelNotFound(_)
+        )
+    }
+}
+
+impl Clone for ModelBreaker {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            states: self.states.clone(),
+        }
+    }
+}
+
+/// Permission for one request to a model, returned by [`ModelBreaker::check`]
+///
+/// Recording an outcome consumes the permit. Dropping it without one, as
+/// when the request future is cancelled, ends a recovery probe unresolved so
+/// the next request can probe instead.
+pub struct BreakerPermit {
+    /// Breaker the permit was issued by
+    breaker: ModelBreaker,
+    
+    /// Model the request is for
+    model_name: String,
+    
+    /// Whether this request is the recovery probe
+    probe: bool,
+}
+
+impl BreakerPermit {
+    /// Record that the request succeeded
+    pub fn record_success(mut self) {
+        self.probe = false;
+        self.breaker.record_success(&self.model_name);
+    }
+    
+    /// Record that the request failed
+    pub fn record_failure(mut self, error: &SynaptronError) {
+        self.probe = false;
+        self.breaker.record_failure(&self.model_name, error);
+    }
+}
+
+impl Drop for BreakerPermit {
+    fn drop(&mut self) {
+        if self.probe {
+            self.breaker.end_probe(&self.model_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Breaker opening after two failures that probes as soon as it opens
+    fn breaker() -> ModelBreaker {
+        ModelBreaker::new(&BreakerConfig { failure_threshold: 2, recovery_secs: 0 })
+    }
+    
+    /// Server-side failure
+    fn backend_error() -> SynaptronError {
+        SynaptronError::Inference("backend crashed".to_string())
+    }
+    
+    #[test]
+    fn consecutive_server_failures_open_the_breaker() {
+        let breaker = ModelBreaker::new(&BreakerConfig { failure_threshold: 2, recovery_secs: 60 });
+        
+        breaker.check("bert").unwrap().record_failure(&backend_error());
+        assert_eq!(breaker.health("bert"), ModelHealth::Healthy);
+        breaker.check("bert").unwrap().record_failure(&backend_error());
+        
+        assert_eq!(breaker.health("bert"), ModelHealth::Unhealthy);
+        assert!(matches!(breaker.check("bert"), Err(SynaptronError::ModelUnavailable(_))));
+        assert_eq!(breaker.unhealthy_models(), ["bert"]);
+        assert!(breaker.check("gpt").is_ok());
+    }
+    
+    #[test]
+    fn bad_input_does_not_count_as_a_failure() {
+        let breaker = breaker();
+        
+        for _ in 0..5 {
+            breaker.check("bert").unwrap().record_failure(&SynaptronError::InvalidInput("empty".to_string()));
+        }
+        
+        assert_eq!(breaker.health("bert"), ModelHealth::Healthy);
+    }
+    
+    #[test]
+    fn one_probe_at_a_time_closes_the_breaker_on_success() {
+        let breaker = breaker();
+        breaker.record_failure("bert", &backend_error());
+        breaker.record_failure("bert", &backend_error());
+        
+        let probe = breaker.check("bert").unwrap();
+        assert_eq!(breaker.health("bert"), ModelHealth::Probing);
+        assert!(breaker.check("bert").is_err(), "a probe is already in flight");
+        probe.record_success();
+        
+        assert_eq!(breaker.health("bert"), ModelHealth::Healthy);
+        assert!(breaker.check("bert").is_ok());
+    }
+    
+    #[test]
+    fn dropped_probes_free_the_probe_slot() {
+        let breaker = breaker();
+        breaker.record_failure("bert", &backend_error());
+        breaker.record_failure("bert", &backend_error());
+        
+        drop(breaker.check("bert").unwrap());
+        
+        assert_eq!(breaker.health("bert"), ModelHealth::Unhealthy);
+        assert!(breaker.check("bert").is_ok());
+    }
+    
+    #[test]
+    fn failed_probes_keep_the_breaker_open() {
+        let breaker = breaker();
+        breaker.record_failure("bert", &backend_error());
+        breaker.record_failure("bert", &backend_error());
+        
+        breaker.check("bert").unwrap().record_failure(&backend_error());
+        
+        assert_eq!(breaker.health("bert"), ModelHealth::Unhealthy);
+    }
+}
@@ -1,11 +1,151 @@
 //! Model cache implementation for the Synaptron inference engine
 
-use crate::{config::CacheConfig, model::Model, error::SynaptronError};
-use tracing::{info, debug};
+use crate::{config::{CacheConfig, ResponseCacheConfig}, model::Model, error::SynaptronError};
+use tracing::{info, debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::fs;
+use tokio::sync::{Mutex, OnceCell, RwLock};
+use tokio_util::sync::CancellationToken;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// File name of the persistent cache index inside the cache directory
+const INDEX_FILE_NAME: &str = "cache_index.json";
+
+/// Persistent cache index entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Path of the cached model file
+    pub cache_file: String,
+    
+    /// Size in bytes
+    pub size: u64,
+    
+    /// SHA256 of the model data, hex encoded
+    pub checksum: String,
+    
+    /// Timestamp when cached
+    pub timestamp: u64,
+    
+    /// Timestamp of the last read or write, used for LRU eviction
+    #[serde(default)]
+    pub last_access: u64,
+    
+    /// Number of reads and writes while in memory
+    #[serde(default)]
+    pub access_count: usize,
+}
+
+/// Persistent on-disk index of cached model files, keyed by model path
+struct DiskIndex {
+    /// Cache directory
+    dir: String,
+    
+    /// Index file path
+    path: PathBuf,
+    
+    /// Index entries
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl DiskIndex {
+    /// Load the index from a cache directory, purging entries whose files are gone
+    async fn load(dir: &str) -> Result<Self, SynaptronError> {
+        let path = Path::new(dir).join(INDEX_FILE_NAME);
+        
+        let mut entries: HashMap<String, IndexEntry> = if path.exists() {
+            match serde_json::from_str(&fs::read_to_string(&path).await?) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("Ignoring unreadable cache index {}: {}", path.display(), e);
+                    HashMap::new()
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+        
+        let before = entries.len();
+        entries.retain(|_, entry| Path::new(&entry.cache_file).exists());
+        
+        let index = Self {
+            dir: dir.to_string(),
+            path,
+            entries,
+        };
+        
+        if index.entries.len() != before {
+            info!("Purged {} stale cache index entries", before - index.entries.len());
+            index.save().await?;
+        }
+        
+        Ok(index)
+    }
+    
+    /// Persist the index
+    async fn save(&self) -> Result<(), SynaptronError> {
+        let data = serde_json::to_vec_pretty(&self.entries)?;
+        Model::write_atomic(&self.path.to_string_lossy(), &data).await
+    }
+    
+    /// Total bytes of indexed cache files
+    fn total_bytes(&self) -> u64 {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+    
+    /// Evict least recently used files until `incoming` bytes fit under `max_bytes`
+    ///
+    /// The entry for `key` is about to be replaced, so its current size is not
+    /// counted. Errors without evicting anything if `incoming` alone exceeds the quota.
+    async fn make_room(&mut self, key: &str, incoming: u64, max_bytes: u64) -> Result<(), SynaptronError> {
+        if max_bytes == 0 {
+            return Ok(());
+        }
+        
+        if incoming > max_bytes {
+            return Err(SynaptronError::Cache(format!(
+                "Model of {} bytes exceeds the disk cache quota of {} bytes", incoming, max_bytes
+            )));
+        }
+        
+        let mut total = self.total_bytes() - self.entries.get(key).map_or(0, |entry| entry.size);
+        
+        let mut candidates: Vec<(String, u64)> = self.entries.iter()
+            .filter(|(path, _)| path.as_str() != key)
+            .map(|(path, entry)| (path.clone(), entry.last_access.max(entry.timestamp)))
+            .collect();
+        candidates.sort_by_key(|(_, last_access)| *last_access);
+        
+        for (path, _) in candidates {
+            if total + incoming <= max_bytes {
+                break;
+            }
+            
+            if let Some(entry) = self.entries.remove(&path) {
+                if let Err(e) = fs::remove_file(&entry.cache_file).await {
+                    warn!("Failed to remove evicted cache file {}: {}", entry.cache_file, e);
+                }
+                total -= entry.size;
+                info!("Evicted {} ({} bytes) from disk cache", path, entry.size);
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Current UNIX timestamp in seconds
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
 
 /// Cached model entry
 struct CachedModel {
@@ -17,29 +157,111 @@ struct CachedModel {
     
     /// Access count
     access_count: usize,
+    
+    /// When the entry was last read or written, used for LRU eviction
+    last_access: Instant,
+}
+
+/// Cache statistics
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    /// Whether the cache is enabled
+    pub enabled: bool,
+    
+    /// Number of cached entries
+    pub entries: usize,
+    
+    /// Maximum number of entries, 0 for no limit
+    pub max_size: usize,
+    
+    /// Bytes of models held in memory
+    pub bytes: u64,
+    
+    /// Memory budget in bytes, 0 for no limit
+    pub max_bytes: u64,
+    
+    /// Entry TTL in seconds
+    pub ttl_seconds: u64,
+    
+    /// Bytes of cached model files on disk
+    pub disk_bytes: u64,
+    
+    /// Disk quota in bytes, 0 for no limit
+    pub max_disk_bytes: u64,
+}
+
+/// Handle to a running TTL sweeper; stops it when dropped
+pub struct SweeperGuard {
+    /// Cancels the sweeper task
+    cancel: CancellationToken,
+}
+
+impl SweeperGuard {
+    /// Stop the sweeper
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for SweeperGuard {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
 }
 
 /// Model Cache
 pub struct ModelCache {
-    /// Cache configuration
-    config: CacheConfig,
+    /// Cache configuration, replaced on config reload
+    config: Arc<parking_lot::RwLock<CacheConfig>>,
     
     /// Cached models
     cache: Arc<RwLock<HashMap<String, CachedModel>>>,
+    
+    /// Summed data size of cached models
+    bytes: Arc<AtomicU64>,
+    
+    /// Persistent on-disk index, if enabled
+    index: Option<Arc<RwLock<DiskIndex>>>,
 }
 
 impl ModelCache {
+    /// Current cache configuration
+    fn config(&self) -> CacheConfig {
+        self.config.read().clone()
+    }
+    
+    /// Apply a reloaded cache configuration
+    ///
+    /// Entries over a lowered limit are evicted as new ones are stored.
+    pub fn update_config(&self, config: &CacheConfig) {
+        *self.config.write() = config.clone();
+    }
+    
     /// Create a new model cache
     pub fn new(config: &CacheConfig) -> Self {
         Self {
-            config: config.clone(),
+            config: Arc::new(parking_lot::RwLock::new(config.clone())),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            bytes: Arc::new(AtomicU64::new(0)),
+            index: None,
         }
     }
     
+    /// Create a model cache backed by a persistent index under `cache_dir`
+    pub async fn open(config: &CacheConfig, cache_dir: &str) -> Result<Self, SynaptronError> {
+        fs::create_dir_all(cache_dir).await?;
+        let index = DiskIndex::load(cache_dir).await?;
+        info!("Loaded cache index with {} entries from {}", index.entries.len(), cache_dir);
+        
+        Ok(Self {
+            index: Some(Arc::new(RwLock::new(index))),
+            ..Self::new(config)
+        })
+    }
+    
     /// Get a model from cache
     pub async fn get(&self, model_path: &str) -> Option<Model> {
-        if !self.config.enabled {
+        if !self.config().enabled {
             return None;
         }
         
@@ -48,72 +270,369 @@ impl ModelCache {
         let mut cache_guard = self.cache.write().await;
         
         // Check if model exists in cache
-        if let Some(mut cached_model) = cache_guard.get_mut(model_path) {
+        if let Some(cached_model) = cache_guard.get_mut(model_path) {
             // Check if cache entry is still valid
             let current_time = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
                 
-            if current_time - cached_model.timestamp < self.config.ttl_seconds {
-                // Update access count
+            if current_time - cached_model.timestamp < self.config().ttl_seconds {
+                // Update access count and mark as most recently used
                 cached_model.access_count += 1;
+                cached_model.last_access = Instant::now();
                 info!("Model found in cache: {}", model_path);
                 return Some(cached_model.model.clone());
             } else {
                 // Remove expired entry
-                cache_guard.remove(model_path);
+                self.remove_entry(&mut cache_guard, model_path);
                 info!("Expired model removed from cache: {}", model_path);
             }
         }
         
-        None
+        drop(cache_guard);
+        self.get_from_disk(model_path).await
+    }
+    
+    /// Look up a model in the persistent index and load it from disk
+    async fn get_from_disk(&self, model_path: &str) -> Option<Model> {
+        let index = self.index.as_ref()?;
+        let entry = index.read().await.entries.get(model_path).cloned()?;
+        
+        if now_secs().saturating_sub(entry.timestamp) >= self.config().ttl_seconds {
+            return None;
+        }
+        
+        match Model::load_from_cache(&entry.cache_file).await {
+            Ok(mut model) => {
+                info!("Model found in disk cache: {}", model_path);
+                model.path = model_path.to_string();
+                
+                {
+                    let mut index_guard = index.write().await;
+                    if let Some(entry) = index_guard.entries.get_mut(model_path) {
+                        entry.last_access = now_secs();
+                    }
+                    if let Err(e) = index_guard.save().await {
+                        warn!("Failed to record cache access for {}: {}", model_path, e);
+                    }
+                }
+                
+                let mut cache_guard = self.cache.write().await;
+                self.insert_entry(&mut cache_guard, model_path, model.clone(), entry.timestamp);
+                
+                Some(model)
+            }
+            Err(e) => {
+                warn!("Dropping unreadable disk cache entry for {}: {}", model_path, e);
+                let mut index_guard = index.write().await;
+                index_guard.entries.remove(model_path);
+                let _ = index_guard.save().await;
+                None
+            }
+        }
     }
     
     /// Put a model in cache
     pub async fn put(&self, model: Model) -> Result<(), SynaptronError> {
-        if !self.config.enabled {
+        if !self.config().enabled {
             return Ok(());
         }
         
         debug!("Putting model in cache: {}", model.path);
         
+        let timestamp = now_secs();
+        
         let mut cache_guard = self.cache.write().await;
+        self.insert_entry(&mut cache_guard, &model.path, model.clone(), timestamp);
+        drop(cache_guard);
         
-        // Check cache size and evict if necessary
-        if cache_guard.len() >= self.config.max_size {
-            self.evict_lru(&mut cache_guard).await;
+        // Persist the model file and record it in the on-disk index
+        if let Some(index) = &self.index {
+            let mut index_guard = index.write().await;
+            let size = model.data.len() as u64;
+            index_guard.make_room(&model.path, size, self.config().max_disk_bytes).await?;
+            model.save_to_cache(&index_guard.dir).await?;
+            
+            let entry = IndexEntry {
+                cache_file: Model::cache_file_path(&index_guard.dir, &model.path),
+                size,
+                checksum: format!("{:x}", Sha256::digest(&model.data)),
+                timestamp,
+                last_access: timestamp,
+                access_count: 1,
+            };
+            index_guard.entries.insert(model.path.clone(), entry);
+            index_guard.save().await?;
         }
         
-        // Add model to cache
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-            
-        cache_guard.insert(
-            model.path.clone(),
+        info!("Model cached: {}", model.path);
+        Ok(())
+    }
+    
+    /// Insert a model, evicting least recently used entries until it fits
+    ///
+    /// A model larger than the whole memory budget is not cached in memory.
+    fn insert_entry(&self, cache: &mut HashMap<String, CachedModel>, key: &str, model: Model, timestamp: u64) {
+        let size = model.data.len() as u64;
+        let max_bytes = self.config().max_bytes;
+        
+        if max_bytes > 0 && size > max_bytes {
+            warn!(
+                "Model {} ({} bytes) exceeds the cache budget of {} bytes, not caching it in memory",
+                key, size, max_bytes
+            );
+            return;
+        }
+        
+        // A replaced entry frees its own room
+        self.remove_entry(cache, key);
+        
+        // Check cache size and bytes and evict if necessary
+        while self.over_budget(cache.len(), size) && self.evict_lru_entry(cache) {}
+        
+        cache.insert(
+            key.to_string(),
             CachedModel {
-                model: model.clone(),
+                model,
                 timestamp,
                 access_count: 1,
+                last_access: Instant::now(),
             },
         );
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+    }
+    
+    /// Remove an entry, releasing its bytes
+    fn remove_entry(&self, cache: &mut HashMap<String, CachedModel>, key: &str) {
+        if let Some(entry) = cache.remove(key) {
+            self.bytes.fetch_sub(entry.model.data.len() as u64, Ordering::Relaxed);
+        }
+    }
+    
+    /// Whether adding `incoming` bytes as one more entry would exceed a limit
+    fn over_budget(&self, entries: usize, incoming: u64) -> bool {
+        let config = self.config();
+        let over_entries = config.max_size > 0 && entries >= config.max_size;
+        let over_bytes = config.max_bytes > 0
+            && self.current_bytes() + incoming > config.max_bytes;
+        over_entries || over_bytes
+    }
+    
+    /// Evict the least recently used in-memory model, returning whether one was evicted
+    ///
+    /// Used to shed memory under pressure; the on-disk copy, if any, is kept.
+    pub async fn evict_lru(&self) -> bool {
+        let mut cache_guard = self.cache.write().await;
+        self.evict_lru_entry(&mut cache_guard)
+    }
+    
+    /// Evict least recently used model from cache, returning whether one was evicted
+    fn evict_lru_entry(&self, cache: &mut HashMap<String, CachedModel>) -> bool {
+        let key = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_access)
+            .map(|(key, _)| key.clone());
         
-        info!("Model cached: {}", model.path);
+        match key {
+            Some(key) => {
+                self.remove_entry(cache, &key);
+                info!("Evicted LRU model from cache: {}", key);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Bytes of models currently held in memory
+    pub fn current_bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+    
+    /// Drop in-memory entries past their TTL, returning how many were removed
+    pub async fn sweep_expired(&self) -> usize {
+        let now = now_secs();
+        let mut cache_guard = self.cache.write().await;
+        
+        let expired: Vec<String> = cache_guard.iter()
+            .filter(|(_, entry)| now.saturating_sub(entry.timestamp) >= self.config().ttl_seconds)
+            .map(|(key, _)| key.clone())
+            .collect();
+        
+        for key in &expired {
+            self.remove_entry(&mut cache_guard, key);
+        }
+        
+        if !expired.is_empty() {
+            info!("Swept {} expired models from cache", expired.len());
+        }
+        expired.len()
+    }
+    
+    /// Spawn a background task dropping expired entries every quarter TTL
+    ///
+    /// Returns `None` when the cache is disabled. The period follows reloaded
+    /// TTLs, and the task runs until the returned guard is stopped or dropped.
+    pub fn start_sweeper(&self) -> Option<SweeperGuard> {
+        if !self.config().enabled {
+            return None;
+        }
+        
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let cache = self.clone();
+        
+        debug!("Starting cache TTL sweeper");
+        
+        tokio::spawn(async move {
+            loop {
+                let period = Duration::from_secs((cache.config().ttl_seconds / 4).max(1));
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(period) => {
+                        cache.sweep_expired().await;
+                    }
+                }
+            }
+            
+            debug!("Cache TTL sweeper stopped");
+        });
+        
+        Some(SweeperGuard { cancel })
+    }
+    
+    /// Get cache statistics
+    pub async fn stats(&self) -> CacheStats {
+        let cache_guard = self.cache.read().await;
+        
+        let disk_bytes = match &self.index {
+            Some(index) => index.read().await.total_bytes(),
+            None => 0,
+        };
+        
+        let config = self.config();
+        
+        CacheStats {
+            enabled: config.enabled,
+            entries: cache_guard.len(),
+            max_size: config.max_size,
+            bytes: self.current_bytes(),
+            max_bytes: config.max_bytes,
+            ttl_seconds: config.ttl_seconds,
+            disk_bytes,
+            max_disk_bytes: config.max_disk_bytes,
+        }
+    }
+    
+    /// Write every in-memory model and an index of their timestamps and access counts to `dir`
+    ///
+    /// Model files already on disk at their current size are not rewritten.
+    pub async fn persist(&self, dir: &str) -> Result<(), SynaptronError> {
+        if !self.config().enabled {
+            return Ok(());
+        }
+        
+        fs::create_dir_all(dir).await?;
+        
+        // Persisting to the live index's own directory updates it in place
+        let live = match &self.index {
+            Some(index) if index.read().await.dir == dir => Some(index.clone()),
+            _ => None,
+        };
+        let index = match live {
+            Some(index) => index,
+            None => Arc::new(RwLock::new(DiskIndex::load(dir).await?)),
+        };
+        
+        let cache_guard = self.cache.read().await;
+        let mut index_guard = index.write().await;
+        
+        for (key, cached) in cache_guard.iter() {
+            let cache_file = Model::cache_file_path(dir, &cached.model.path);
+            let size = cached.model.data.len() as u64;
+            
+            let existing = index_guard.entries.get(key)
+                .filter(|entry| entry.size == size && entry.cache_file == cache_file)
+                .map(|entry| entry.checksum.clone());
+            
+            let checksum = match existing {
+                Some(checksum) => checksum,
+                None => {
+                    let written = match index_guard.make_room(key, size, self.config().max_disk_bytes).await {
+                        Ok(()) => cached.model.save_to_cache(dir).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = written {
+                        warn!("Failed to persist cached model {}: {}", key, e);
+                        continue;
+                    }
+                    format!("{:x}", Sha256::digest(&cached.model.data))
+                }
+            };
+            
+            index_guard.entries.insert(key.clone(), IndexEntry {
+                cache_file,
+                size,
+                checksum,
+                timestamp: cached.timestamp,
+                last_access: now_secs().saturating_sub(cached.last_access.elapsed().as_secs()),
+                access_count: cached.access_count,
+            });
+        }
+        
+        index_guard.save().await?;
+        info!("Persisted {} cached models to {}", cache_guard.len(), dir);
         Ok(())
     }
     
-    /// Evict least recently used model from cache
-    async fn evict_lru(&self, cache: &mut HashMap<String, CachedModel>) {
-        if let Some((key, _)) = cache
-            .iter()
-            .min_by_key(|(_, entry)| entry.access_count)
-        {
-            let key = key.clone();
-            cache.remove(&key);
-            info!("Evicted LRU model from cache: {}", key);
+    /// Rebuild the in-memory cache from models persisted to `dir`
+    ///
+    /// Corrupt or partially written files are skipped with a warning rather than
+    /// failing. Returns the number of models restored.
+    pub async fn restore(&self, dir: &str) -> usize {
+        if !self.config().enabled {
+            return 0;
+        }
+        
+        let index = match DiskIndex::load(dir).await {
+            Ok(index) => index,
+            Err(e) => {
+                warn!("Not restoring model cache from {}: {}", dir, e);
+                return 0;
+            }
+        };
+        
+        // Oldest first, so the most recently used models survive any eviction
+        let mut entries: Vec<(String, IndexEntry)> = index.entries.into_iter().collect();
+        entries.sort_by_key(|(_, entry)| entry.last_access.max(entry.timestamp));
+        
+        let mut restored = 0;
+        
+        for (key, entry) in entries {
+            if now_secs().saturating_sub(entry.timestamp) >= self.config().ttl_seconds {
+                debug!("Not restoring expired cache entry: {}", key);
+                continue;
+            }
+            
+            let mut model = match Model::load_from_cache(&entry.cache_file).await {
+                Ok(model) => model,
+                Err(e) => {
+                    warn!("Skipping unreadable cache file {}: {}", entry.cache_file, e);
+                    continue;
+                }
+            };
+            model.path = key.clone();
+            
+            let mut cache_guard = self.cache.write().await;
+            self.insert_entry(&mut cache_guard, &key, model, entry.timestamp);
+            if let Some(cached) = cache_guard.get_mut(&key) {
+                cached.access_count = entry.access_count.max(1);
+            }
+            restored += 1;
         }
+        
+        info!("Restored {} cached models from {}", restored, dir);
+        restored
     }
     
     /// Clear cache
@@ -122,8 +641,494 @@ impl ModelCache {
         
         let mut cache_guard = self.cache.write().await;
         cache_guard.clear();
+        self.bytes.store(0, Ordering::Relaxed);
         
         info!("Model cache cleared");
         Ok(())
     }
 }
+
+impl Clone for ModelCache {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            cache: self.cache.clone(),
+            bytes: self.bytes.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+
+/// Inference response cache key
+///
+/// Includes the model's version, size and precision so a re-quantized or
+/// replaced model never serves results computed by its previous form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResponseKey {
+    /// Model name
+    model: String,
+    
+    /// Model version
+    version: String,
+    
+    /// Model runtime precision
+    precision: String,
+    
+    /// Model size in bytes
+    model_size: usize,
+    
+    /// SHA256 of the raw input
+    input_digest: [u8; 32],
+}
+
+impl ResponseKey {
+    /// Build the cache key for an input to a model
+    pub fn new(model: &Model, input: &[u8]) -> Self {
+        Self {
+            model: model.name.clone(),
+            version: model.metadata.version.clone(),
+            precision: model.metadata.data_type.clone(),
+            model_size: model.data.len(),
+            input_digest: Sha256::digest(input).into(),
+        }
+    }
+    
+    /// Name of the model the response belongs to
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+/// How a request uses the response cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serve cached responses and store fresh ones
+    Use,
+    
+    /// Skip the cache read but store the fresh response, replacing any stale entry
+    Refresh,
+    
+    /// Neither read nor write the cache
+    Bypass,
+}
+
+/// Shared result of an in-flight refresh
+type InFlight = Arc<OnceCell<Result<Vec<u8>, Arc<SynaptronError>>>>;
+
+/// Cached response bytes with the timestamp they were stored
+type StoredResponse = (Vec<u8>, u64);
+
+/// Inference response cache
+pub struct ResponseCache {
+    /// Response cache configuration, replaced on config reload
+    config: Arc<parking_lot::RwLock<ResponseCacheConfig>>,
+    
+    /// Cached responses with the timestamp they were stored
+    entries: Arc<RwLock<HashMap<ResponseKey, StoredResponse>>>,
+    
+    /// Refreshes in flight, so concurrent refreshes of a key share one computation
+    in_flight: Arc<Mutex<HashMap<ResponseKey, InFlight>>>,
+}
+
+impl ResponseCache {
+    /// Current response cache configuration
+    fn config(&self) -> ResponseCacheConfig {
+        self.config.read().clone()
+    }
+    
+    /// Apply a reloaded response cache configuration
+    pub fn update_config(&self, config: &ResponseCacheConfig) {
+        *self.config.write() = config.clone();
+    }
+    
+    /// Create a new response cache
+    pub fn new(config: &ResponseCacheConfig) -> Self {
+        Self {
+            config: Arc::new(parking_lot::RwLock::new(config.clone())),
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    
+    /// Get a cached response
+    pub async fn get(&self, key: &ResponseKey) -> Option<Vec<u8>> {
+        if !self.config().enabled {
+            return None;
+        }
+        
+        let entries_guard = self.entries.read().await;
+        let (response, timestamp) = entries_guard.get(key)?;
+        
+        if now_secs().saturating_sub(*timestamp) < self.config().ttl_seconds {
+            debug!("Response cache hit for model: {}", key.model);
+            Some(response.clone())
+        } else {
+            None
+        }
+    }
+    
+    /// Store a response
+    pub async fn put(&self, key: ResponseKey, response: Vec<u8>) {
+        if !self.config().enabled {
+            return;
+        }
+        
+        let mut entries_guard = self.entries.write().await;
+        
+        if entries_guard.len() >= self.config().max_size && !entries_guard.contains_key(&key) {
+            let oldest = entries_guard.iter()
+                .min_by_key(|(_, (_, timestamp))| *timestamp)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries_guard.remove(&oldest);
+            }
+        }
+        
+        entries_guard.insert(key, (response, now_secs()));
+    }
+    
+    /// Recompute a response, coalescing concurrent refreshes of the same key
+    ///
+    /// Only the first caller runs `compute`, which is expected to store its result;
+    /// callers arriving while it runs wait for and share that result.
+    pub async fn refresh<F, Fut>(&self, key: ResponseKey, compute: F) -> Result<Vec<u8>, SynaptronError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+    {
+        let cell = {
+            let mut in_flight_guard = self.in_flight.lock().await;
+            in_flight_guard.entry(key.clone())
+                .or_insert_with(|| Arc::new(OnceCell::new()))
+                .clone()
+        };
+        
+        let result = cell
+            .get_or_init(|| async { compute().await.map_err(Arc::new) })
+            .await
+            .clone();
+        
+        {
+            let mut in_flight_guard = self.in_flight.lock().await;
+            if in_flight_guard.get(&key).is_some_and(|current| Arc::ptr_eq(current, &cell)) {
+                in_flight_guard.remove(&key);
+            }
+        }
+        
+        result.map_err(SynaptronError::Shared)
+    }
+    
+    /// Remove a model's cached responses, returning how many were removed
+    pub async fn invalidate_model(&self, model_name: &str) -> usize {
+        let mut entries_guard = self.entries.write().await;
+        let before = entries_guard.len();
+        entries_guard.retain(|key, _| key.model != model_name);
+        before - entries_guard.len()
+    }
+    
+    /// Clear all cached responses
+    pub async fn clear(&self) {
+        self.entries.write().await.clear();
+    }
+}
+
+impl Clone for ResponseCache {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            entries: self.entries.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelInputType;
+    
+    /// Model named `name` holding `size` bytes
+    fn sized_model(name: &str, size: usize) -> Model {
+        Model::for_test(name, ModelInputType::Text, &vec![7u8; size])
+    }
+    
+    #[tokio::test]
+    async fn reopened_caches_find_models_through_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let model = sized_model("bert", 64);
+        
+        ModelCache::open(&CacheConfig::default(), cache_dir).await.unwrap().put(model.clone()).await.unwrap();
+        let reopened = ModelCache::open(&CacheConfig::default(), cache_dir).await.unwrap();
+        
+        let cached = reopened.get(&model.path).await.expect("model is found on disk");
+        assert_eq!(cached.path, model.path);
+        assert_eq!(cached.data.len(), 64);
+        assert_eq!(reopened.stats().await.disk_bytes, 64);
+    }
+    
+    #[tokio::test]
+    async fn index_entries_without_files_are_purged() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let model = sized_model("bert", 64);
+        ModelCache::open(&CacheConfig::default(), cache_dir).await.unwrap().put(model.clone()).await.unwrap();
+        
+        std::fs::remove_file(Model::cache_file_path(cache_dir, &model.path)).unwrap();
+        let reopened = ModelCache::open(&CacheConfig::default(), cache_dir).await.unwrap();
+        
+        assert_eq!(reopened.stats().await.disk_bytes, 0);
+        assert!(reopened.get(&model.path).await.is_none());
+    }
+    
+    #[test]
+    fn models_with_one_file_name_get_separate_cache_files() {
+        let first = Model::cache_file_path("cache", "a/model.onnx");
+        let second = Model::cache_file_path("cache", "b/model.onnx");
+        
+        assert_ne!(first, second);
+    }
+
+    /// Response cache that is switched on, holding at most `max_size` responses
+    fn response_cache(max_size: usize) -> ResponseCache {
+        ResponseCache::new(&ResponseCacheConfig { enabled: true, max_size, ..ResponseCacheConfig::default() })
+    }
+    
+    #[tokio::test]
+    async fn responses_are_keyed_on_model_version_and_precision() {
+        let cache = response_cache(10);
+        let model = sized_model("bert", 8);
+        cache.put(ResponseKey::new(&model, b"hello"), b"positive".to_vec()).await;
+        
+        let mut quantized = model.clone();
+        quantized.metadata.data_type = "int8".to_string();
+        let mut upgraded = model.clone();
+        upgraded.metadata.version = "2.0".to_string();
+        
+        assert_eq!(cache.get(&ResponseKey::new(&model, b"hello")).await, Some(b"positive".to_vec()));
+        assert_eq!(cache.get(&ResponseKey::new(&model, b"hi")).await, None);
+        assert_eq!(cache.get(&ResponseKey::new(&quantized, b"hello")).await, None);
+        assert_eq!(cache.get(&ResponseKey::new(&upgraded, b"hello")).await, None);
+    }
+    
+    #[tokio::test]
+    async fn response_cache_is_off_by_default() {
+        let cache = ResponseCache::new(&ResponseCacheConfig::default());
+        let key = ResponseKey::new(&sized_model("bert", 8), b"hello");
+        
+        cache.put(key.clone(), b"positive".to_vec()).await;
+        
+        assert_eq!(cache.get(&key).await, None);
+    }
+    
+    #[tokio::test]
+    async fn full_response_caches_make_room_and_forget_unloaded_models() {
+        let cache = response_cache(2);
+        let bert = sized_model("bert", 8);
+        let gpt = sized_model("gpt", 8);
+        
+        cache.put(ResponseKey::new(&bert, b"a"), b"1".to_vec()).await;
+        cache.put(ResponseKey::new(&bert, b"b"), b"2".to_vec()).await;
+        cache.put(ResponseKey::new(&gpt, b"c"), b"3".to_vec()).await;
+        
+        assert_eq!(cache.entries.read().await.len(), 2);
+        assert_eq!(cache.invalidate_model("gpt").await, 1);
+        assert_eq!(cache.get(&ResponseKey::new(&gpt, b"c")).await, None);
+    }
+
+    #[tokio::test]
+    async fn disk_cache_evicts_least_recently_used_files_past_its_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let config = CacheConfig { max_disk_bytes: 200, ..CacheConfig::default() };
+        let cache = ModelCache::open(&config, cache_dir).await.unwrap();
+        let (first, second, third) = (sized_model("first", 80), sized_model("second", 80), sized_model("third", 80));
+        
+        cache.put(first.clone()).await.unwrap();
+        cache.put(second.clone()).await.unwrap();
+        cache.index.as_ref().unwrap().write().await.entries.get_mut(&first.path).unwrap().last_access += 10;
+        cache.put(third.clone()).await.unwrap();
+        
+        let index = cache.index.as_ref().unwrap().read().await;
+        assert!(index.entries.contains_key(&first.path));
+        assert!(!index.entries.contains_key(&second.path));
+        assert!(!Path::new(&Model::cache_file_path(cache_dir, &second.path)).exists());
+        assert_eq!(index.total_bytes(), 160);
+    }
+    
+    #[tokio::test]
+    async fn models_larger_than_the_disk_quota_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CacheConfig { max_disk_bytes: 50, ..CacheConfig::default() };
+        let cache = ModelCache::open(&config, dir.path().to_str().unwrap()).await.unwrap();
+        
+        let err = cache.put(sized_model("large", 80)).await.unwrap_err();
+        
+        assert!(matches!(err, SynaptronError::Cache(_)));
+        assert_eq!(cache.stats().await.disk_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn concurrent_refreshes_compute_once() {
+        let cache = response_cache(10);
+        let key = ResponseKey::new(&sized_model("bert", 8), b"hello");
+        let computed = AtomicU64::new(0);
+        let compute = || async {
+            computed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(b"fresh".to_vec())
+        };
+        
+        let (first, second) = tokio::join!(cache.refresh(key.clone(), compute), cache.refresh(key.clone(), compute));
+        
+        assert_eq!(first.unwrap(), b"fresh");
+        assert_eq!(second.unwrap(), b"fresh");
+        assert_eq!(computed.load(Ordering::Relaxed), 1);
+        assert!(cache.in_flight.lock().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn coalesced_refreshes_share_the_original_error() {
+        let cache = response_cache(10);
+        let key = ResponseKey::new(&sized_model("bert", 8), b"hello");
+        let compute = || async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Err(SynaptronError::Inference("backend failed".to_string()))
+        };
+        
+        let (first, second) = tokio::join!(cache.refresh(key.clone(), compute), cache.refresh(key.clone(), compute));
+        
+        for result in [first, second] {
+            assert!(matches!(result.unwrap_err().root(), SynaptronError::Inference(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_model_is_evicted() {
+        let cache = ModelCache::new(&CacheConfig { max_size: 2, ..CacheConfig::default() });
+        let (first, second, third) = (sized_model("first", 8), sized_model("second", 8), sized_model("third", 8));
+        
+        cache.put(first.clone()).await.unwrap();
+        cache.put(second.clone()).await.unwrap();
+        cache.get(&first.path).await.unwrap();
+        cache.put(third.clone()).await.unwrap();
+        
+        assert!(cache.get(&first.path).await.is_some());
+        assert!(cache.get(&second.path).await.is_none());
+        assert!(cache.get(&third.path).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn memory_cache_stays_within_its_byte_budget() {
+        let cache = ModelCache::new(&CacheConfig { max_bytes: 100, ..CacheConfig::default() });
+        
+        cache.put(sized_model("first", 60)).await.unwrap();
+        cache.put(sized_model("second", 30)).await.unwrap();
+        cache.put(sized_model("third", 50)).await.unwrap();
+        
+        let stats = cache.stats().await;
+        assert_eq!((stats.entries, stats.bytes), (2, 80));
+    }
+    
+    #[tokio::test]
+    async fn models_larger_than_the_budget_stay_out_of_memory() {
+        let cache = ModelCache::new(&CacheConfig { max_bytes: 100, ..CacheConfig::default() });
+        cache.put(sized_model("small", 40)).await.unwrap();
+        
+        cache.put(sized_model("huge", 120)).await.unwrap();
+        
+        let stats = cache.stats().await;
+        assert_eq!((stats.entries, stats.bytes), (1, 40));
+    }
+    
+    #[tokio::test]
+    async fn replacing_a_model_releases_its_old_bytes() {
+        let cache = ModelCache::new(&CacheConfig { max_bytes: 100, ..CacheConfig::default() });
+        
+        cache.put(sized_model("bert", 60)).await.unwrap();
+        cache.put(sized_model("bert", 70)).await.unwrap();
+        
+        assert_eq!(cache.current_bytes(), 70);
+        cache.clear().await.unwrap();
+        assert_eq!(cache.current_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn persisted_models_are_restored_with_their_access_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let persist_dir = dir.path().to_str().unwrap();
+        let cache = ModelCache::new(&CacheConfig::default());
+        let model = sized_model("bert", 32);
+        cache.put(model.clone()).await.unwrap();
+        cache.get(&model.path).await.unwrap();
+        
+        cache.persist(persist_dir).await.unwrap();
+        let restored = ModelCache::new(&CacheConfig::default());
+        
+        assert_eq!(restored.restore(persist_dir).await, 1);
+        let entries = restored.cache.read().await;
+        let entry = &entries[&model.path];
+        assert_eq!(entry.model.name, "bert");
+        assert_eq!(entry.access_count, 2);
+    }
+    
+    #[tokio::test]
+    async fn corrupt_persisted_models_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let persist_dir = dir.path().to_str().unwrap();
+        let cache = ModelCache::new(&CacheConfig::default());
+        let (intact, corrupt) = (sized_model("intact", 32), sized_model("corrupt", 32));
+        cache.put(intact.clone()).await.unwrap();
+        cache.put(corrupt.clone()).await.unwrap();
+        cache.persist(persist_dir).await.unwrap();
+        
+        std::fs::write(Model::cache_file_path(persist_dir, &corrupt.path), b"garbage").unwrap();
+        let restored = ModelCache::new(&CacheConfig::default());
+        
+        assert_eq!(restored.restore(persist_dir).await, 1);
+        assert!(restored.get(&intact.path).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn sweeping_drops_only_expired_models() {
+        let cache = ModelCache::new(&CacheConfig::default());
+        let (fresh, stale) = (sized_model("fresh", 8), sized_model("stale", 8));
+        cache.put(fresh.clone()).await.unwrap();
+        cache.put(stale.clone()).await.unwrap();
+        cache.cache.write().await.get_mut(&stale.path).unwrap().timestamp -= 3600;
+        
+        assert_eq!(cache.sweep_expired().await, 1);
+        
+        let stats = cache.stats().await;
+        assert_eq!((stats.entries, stats.bytes), (1, 8));
+        assert!(cache.get(&fresh.path).await.is_some());
+    }
+    
+    #[tokio::test]
+    async fn sweeper_only_runs_for_enabled_caches() {
+        let disabled = ModelCache::new(&CacheConfig { enabled: false, ..CacheConfig::default() });
+        assert!(disabled.start_sweeper().is_none());
+        
+        let cache = ModelCache::new(&CacheConfig { ttl_seconds: 1, ..CacheConfig::default() });
+        cache.put(sized_model("bert", 8)).await.unwrap();
+        let sweeper = cache.start_sweeper().expect("enabled caches are swept");
+        
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert_eq!(cache.stats().await.entries, 0);
+        sweeper.stop();
+    }
+
+    #[tokio::test]
+    async fn pressure_eviction_keeps_the_disk_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModelCache::open(&CacheConfig::default(), dir.path().to_str().unwrap()).await.unwrap();
+        let model = sized_model("bert", 16);
+        cache.put(model.clone()).await.unwrap();
+        
+        assert!(cache.evict_lru().await);
+        assert!(!cache.evict_lru().await, "nothing is left in memory");
+        
+        assert_eq!(cache.current_bytes(), 0);
+        assert!(cache.get(&model.path).await.is_some());
+    }
+}
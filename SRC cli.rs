@@ -0,0 +1,140 @@
+//! Command line interface for the Synaptron server
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Synaptron command line
+#[derive(Debug, Parser)]
+#[command(name = "synaptron", version, about)]
+pub struct Cli {
+    /// Config file, instead of `config.yaml` in the working directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    
+    /// Increase log verbosity (-v debug, -vv trace); `RUST_LOG` takes precedence
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    
+    /// Print command output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    
+    /// Subcommand, defaults to `serve`
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the HTTP server
+    Serve,
+    
+    /// Load a model and run inference on one input
+    Infer(InferArgs),
+    
+    /// Inspect models
+    Models {
+        #[command(subcommand)]
+        command: ModelsCommand,
+    },
+    
+    /// Download a model file from Hugging Face
+    Download(DownloadArgs),
+    
+    /// Measure inference latency and throughput of a model
+    Bench(BenchArgs),
+    
+    /// Fire concurrent requests at an endpoint and report throughput and latency
+    Loadtest(LoadtestArgs),
+}
+
+/// Arguments for `loadtest`
+#[derive(Debug, Args)]
+pub struct LoadtestArgs {
+    /// Endpoint URL, e.g. http://127.0.0.1:8080/predict
+    #[arg(long)]
+    pub url: String,
+    
+    /// Number of concurrent workers
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+    
+    /// Measured duration in seconds
+    #[arg(long, default_value_t = 30)]
+    pub duration: u64,
+    
+    /// File containing the JSON request body
+    #[arg(long)]
+    pub input: PathBuf,
+    
+    /// Warmup seconds excluded from stats
+    #[arg(long, default_value_t = 0)]
+    pub warmup: u64,
+    
+    /// Maximum requests per second across all workers
+    #[arg(long)]
+    pub rate: Option<u32>,
+}
+
+/// Arguments for `infer`
+#[derive(Debug, Args)]
+pub struct InferArgs {
+    /// Model file to load
+    #[arg(long)]
+    pub model: String,
+    
+    /// File containing the raw input
+    #[arg(long)]
+    pub input: PathBuf,
+    
+    /// Number of ranked labels printed for classifiers
+    #[arg(long)]
+    pub top_k: Option<usize>,
+}
+
+/// `models` subcommands
+#[derive(Debug, Subcommand)]
+pub enum ModelsCommand {
+    /// List model files in the model cache directory
+    List,
+}
+
+/// Arguments for `download`
+#[derive(Debug, Args)]
+pub struct DownloadArgs {
+    /// Hugging Face repository, e.g. sentence-transformers/all-MiniLM-L6-v2
+    pub repo: String,
+    
+    /// Branch, tag or commit
+    #[arg(long, default_value = "main")]
+    pub revision: String,
+    
+    /// File within the repository
+    #[arg(long, default_value = "model.safetensors")]
+    pub file: String,
+    
+    /// Destination path, defaults to the model cache directory
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `bench`
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Model file to load
+    #[arg(long)]
+    pub model: String,
+    
+    /// File containing the raw input, defaults to a short text
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+    
+    /// Inference runs
+    #[arg(long, default_value_t = 100)]
+    pub iterations: usize,
+    
+    /// Concurrent runs
+    #[arg(long, default_value_t = 1)]
+    pub concurrency: usize,
+}
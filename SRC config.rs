@@ -1,11 +1,29 @@
 //! Configuration management for the Synaptron inference engine
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use config::{Config as ConfigLoader, Environment, File};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use config::{Config as ConfigLoader, Environment, File, FileFormat};
+use tracing::{info, warn};
 
 use crate::error::SynaptronError;
+use crate::preprocessing::TextStep;
+use crate::quantization::QuantMode;
+
+/// Environment variable holding the remote configuration URL
+pub const CONFIG_URL_ENV: &str = "SYNAPTRON_CONFIG_URL";
+
+/// Environment variable holding the `Authorization` header sent to the remote configuration URL
+pub const CONFIG_AUTH_ENV: &str = "SYNAPTRON_CONFIG_AUTH";
+
+/// Environment variable making remote configuration fetch failures fatal
+pub const CONFIG_URL_REQUIRED_ENV: &str = "SYNAPTRON_CONFIG_URL_REQUIRED";
+
+/// Environment variable holding comma-separated API keys added to `auth.api_keys`
+pub const API_KEYS_ENV: &str = "SYNAPTRON_API_KEYS";
 
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,8 +34,30 @@ pub struct ServerConfig {
     /// Port number
     pub port: u16,
 
-    /// Number of worker threads
+    /// Maximum concurrent inference calls; further requests queue for a worker
     pub workers: usize,
+
+    /// Bearer token required for `/admin` endpoints; admin endpoints are disabled when unset
+    pub admin_token: Option<String>,
+
+    /// Largest accepted request body in bytes, 0 for unlimited
+    pub max_request_bytes: usize,
+
+    /// Origins allowed to call the API from a browser, `*` for any; none allows same-origin only
+    pub cors_allowed_origins: Vec<String>,
+
+    /// Log a span for every HTTP request and response
+    pub request_tracing: bool,
+
+    /// Milliseconds a request may take to produce a response before a 408, 0 for none
+    pub http_timeout_ms: u64,
+}
+
+impl ServerConfig {
+    /// Timeout for producing a response
+    pub fn http_timeout(&self) -> Option<Duration> {
+        TimeoutsConfig::to_duration(self.http_timeout_ms)
+    }
 }
 
 impl Default for ServerConfig {
@@ -26,17 +66,133 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 8080,
             workers: num_cpus::get(),
+            admin_token: None,
+            max_request_bytes: 10 * 1024 * 1024,
+            cors_allowed_origins: Vec::new(),
+            request_tracing: false,
+            http_timeout_ms: 0,
+        }
+    }
+}
+
+/// Authentication configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Require authentication on inference and model endpoints
+    pub enabled: bool,
+
+    /// Authenticator to use: "static" or "jwt"
+    pub method: String,
+
+    /// Accepted API keys for the static authenticator
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+
+    /// JWT validation settings for the jwt authenticator
+    #[serde(default)]
+    pub jwt: JwtConfig,
+
+    /// Other tenants' models each tenant may also use, keyed by tenant
+    #[serde(default)]
+    pub tenants: HashMap<String, Vec<String>>,
+}
+
+impl AuthConfig {
+    /// API keys listed in a comma-separated `SYNAPTRON_API_KEYS` value, numbered as subjects
+    pub fn env_api_keys(value: &str) -> Vec<ApiKeyConfig> {
+        value.split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .enumerate()
+            .map(|(index, key)| ApiKeyConfig {
+                key: key.to_string(),
+                subject: format!("env-key-{}", index + 1),
+                tenant: None,
+            })
+            .collect()
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: "static".to_string(),
+            api_keys: Vec::new(),
+            jwt: JwtConfig::default(),
+            tenants: HashMap::new(),
+        }
+    }
+}
+
+/// Static API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    /// The key, sent as `Authorization: Bearer <key>`
+    pub key: String,
+
+    /// Subject the key authenticates as
+    pub subject: String,
+
+    /// Tenant the key belongs to
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+/// JWT validation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    /// Signing algorithm, e.g. "HS256" or "RS256"
+    pub algorithm: String,
+
+    /// Shared secret for HMAC algorithms
+    pub secret: Option<String>,
+
+    /// PEM public key for RSA, EC and EdDSA algorithms
+    pub public_key_pem: Option<String>,
+
+    /// Required `aud` claim
+    pub audience: Option<String>,
+
+    /// Required `iss` claim
+    pub issuer: Option<String>,
+
+    /// Claim holding the caller's tenant
+    pub tenant_claim: String,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: "HS256".to_string(),
+            secret: None,
+            public_key_pem: None,
+            audience: None,
+            issuer: None,
+            tenant_claim: "tenant".to_string(),
         }
     }
 }
 
+/// All model formats recognized by the loader
+pub const ALL_MODEL_FORMATS: &[&str] = &[
+    "onnx",
+    "pytorch",
+    "savedmodel",
+    "torchscript",
+    "gguf",
+    "safetensors",
+    "unknown",
+];
+
 /// Model configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
     /// Model cache directory
     pub cache_dir: String,
 
-    /// Default model name
+    /// Model serving requests that name none and match no routing rule, loaded on demand; empty for none
     pub default_model: String,
 
     /// Maximum input length
@@ -44,6 +200,31 @@ pub struct ModelConfig {
 
     /// Enable auto-download
     pub auto_download: bool,
+
+    /// Attempts at a download before giving up, resuming partial files between attempts
+    pub download_max_attempts: u32,
+
+    /// Delay before the first download retry in milliseconds, doubling for each further retry
+    pub download_retry_base_ms: u64,
+
+    /// Model formats allowed to load; pickle-based formats can be excluded here
+    pub allowed_formats: Vec<String>,
+
+    /// Per-model overrides keyed by model name
+    #[serde(default)]
+    pub models: HashMap<String, PerModelConfig>,
+
+    /// Snapshot optimized models into `cache_dir` and reload them on startup
+    #[serde(default)]
+    pub warm_snapshots: bool,
+
+    /// JSON or YAML model graph definition installed on startup
+    #[serde(default)]
+    pub graph_file: Option<String>,
+
+    /// Models loaded and warmed up on startup, by name
+    #[serde(default)]
+    pub preload: Vec<String>,
 }
 
 impl Default for ModelConfig {
@@ -53,10 +234,86 @@ impl Default for ModelConfig {
             default_model: "bert-base-uncased".to_string(),
             max_input_length: 512,
             auto_download: true,
+            download_max_attempts: 4,
+            download_retry_base_ms: 1_000,
+            allowed_formats: ALL_MODEL_FORMATS.iter().map(|f| f.to_string()).collect(),
+            models: HashMap::new(),
+            warm_snapshots: false,
+            graph_file: None,
+            preload: Vec::new(),
         }
     }
 }
 
+impl ModelConfig {
+    /// Get the per-model overrides for a model, if any
+    pub fn for_model(&self, name: &str) -> Option<&PerModelConfig> {
+        self.models.get(name)
+    }
+
+    /// Whether a model format is allowed to load
+    pub fn is_format_allowed(&self, format: &str) -> bool {
+        self.allowed_formats.iter().any(|allowed| allowed.eq_ignore_ascii_case(format))
+    }
+
+    /// Whether a model may be auto-downloaded, honoring its per-model override
+    pub fn auto_download_for(&self, name: &str) -> bool {
+        self.for_model(name)
+            .and_then(|overrides| overrides.auto_download)
+            .unwrap_or(self.auto_download)
+    }
+}
+
+/// Per-model configuration overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PerModelConfig {
+    /// Maximum batch size for this model, overriding `batch.max_batch_size`
+    pub max_batch_size: Option<usize>,
+
+    /// Auto-download override for this model, taking precedence over `model.auto_download`
+    pub auto_download: Option<bool>,
+
+    /// Ordered text cleaning steps for this model
+    pub text_steps: Option<Vec<TextStep>>,
+
+    /// Response served when inference on this model fails
+    pub fallback_on_error: Option<FallbackPolicy>,
+
+    /// Tenant owning this model; models without an owner are shared with all tenants
+    pub tenant: Option<String>,
+
+    /// Hugging Face repository to auto-download from, defaulting to the model name
+    pub repo: Option<String>,
+
+    /// Repository revision to download, defaulting to `main`
+    pub revision: Option<String>,
+
+    /// File within the repository, defaulting to the model file name
+    pub file: Option<String>,
+
+    /// Device id to pin this model to, such as `cuda:1`, instead of auto-selecting
+    pub device: Option<String>,
+
+    /// Expected hex SHA256 of the model file, overriding a `<file>.sha256` beside it
+    pub sha256: Option<String>,
+
+    /// Backend to run this model on, such as one registered with `register_backend`, instead of auto-selecting
+    pub backend: Option<String>,
+}
+
+/// Response policy when inference fails
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FallbackPolicy {
+    /// Return the error
+    Error,
+
+    /// Serve the model's last successful output, or the error if there is none
+    LastKnown,
+
+    /// Serve a fixed payload
+    Default(Vec<u8>),
+}
+
 /// Device configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceConfig {
@@ -90,6 +347,37 @@ pub struct BackendConfig {
 
     /// Enable auto backend selection
     pub auto_select: bool,
+
+    /// Fail model loading when an optimization pass fails instead of keeping the original model
+    pub strict_optimization: bool,
+
+    /// Compute precision for the CPU backend
+    pub cpu_precision: CpuPrecision,
+
+    /// Weight quantization applied when optimizing models, none when unset
+    #[serde(default)]
+    pub quantization: Option<QuantMode>,
+
+    /// Directory of calibration inputs for static int8 quantization
+    #[serde(default)]
+    pub calibration_dir: Option<String>,
+}
+
+/// CPU backend compute precision
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuPrecision {
+    /// Fastest precision the CPU accelerates, falling back to f32
+    Auto,
+
+    /// 32-bit float
+    F32,
+
+    /// bfloat16, accelerated by AVX-512 BF16 and AMX
+    Bf16,
+
+    /// IEEE half precision, accelerated by AVX-512 FP16
+    F16,
 }
 
 impl Default for BackendConfig {
@@ -99,6 +387,10 @@ impl Default for BackendConfig {
             tensorrt: false,
             onnx_runtime: true,
             auto_select: true,
+            strict_optimization: false,
+            cpu_precision: CpuPrecision::Auto,
+            quantization: None,
+            calibration_dir: None,
         }
     }
 }
@@ -109,11 +401,17 @@ pub struct CacheConfig {
     /// Enable LRU cache
     pub enabled: bool,
 
-    /// Maximum cache size
+    /// Maximum number of cached entries, 0 for no limit
     pub max_size: usize,
 
+    /// Maximum total bytes of models held in memory, 0 for no limit
+    pub max_bytes: u64,
+
     /// Cache TTL in seconds
     pub ttl_seconds: u64,
+
+    /// Maximum total bytes of cached model files on disk, 0 for no limit
+    pub max_disk_bytes: u64,
 }
 
 impl Default for CacheConfig {
@@ -121,7 +419,35 @@ impl Default for CacheConfig {
         Self {
             enabled: true,
             max_size: 1000,
+            max_bytes: 0,
             ttl_seconds: 3600,
+            max_disk_bytes: 0,
+        }
+    }
+}
+
+/// Inference response cache configuration
+///
+/// Off by default: caching is only safe for models whose output is a pure
+/// function of the input, which excludes sampled generations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseCacheConfig {
+    /// Enable the response cache
+    pub enabled: bool,
+
+    /// Maximum number of cached responses
+    pub max_size: usize,
+
+    /// Response TTL in seconds
+    pub ttl_seconds: u64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_size: 1000,
+            ttl_seconds: 300,
         }
     }
 }
@@ -135,8 +461,8 @@ pub struct BatchConfig {
     /// Maximum batch size
     pub max_batch_size: usize,
 
-    /// Batch timeout in milliseconds
-    pub timeout_ms: u64,
+    /// How long in milliseconds the first queued input waits for others to join its batch
+    pub window_ms: u64,
 }
 
 impl Default for BatchConfig {
@@ -144,7 +470,256 @@ impl Default for BatchConfig {
         Self {
             enabled: true,
             max_batch_size: 32,
-            timeout_ms: 100,
+            window_ms: 5,
+        }
+    }
+}
+
+/// Timeout configuration
+///
+/// All values are in milliseconds, and zero means "no timeout".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeoutsConfig {
+    /// End-to-end request timeout
+    pub request_ms: u64,
+
+    /// Backend inference timeout, must not exceed `request_ms`
+    pub inference_ms: u64,
+
+    /// Model download timeout
+    pub download_ms: u64,
+
+    /// Overall graceful shutdown deadline, bounding every shutdown phase
+    pub shutdown_ms: u64,
+
+    /// Longest a submitted input waits for its batch's forward pass to start, and longest each
+    /// input of a batch request runs; must not exceed `request_ms`
+    pub batch_ms: u64,
+}
+
+impl Default for TimeoutsConfig {
+    fn default() -> Self {
+        Self {
+            request_ms: 30_000,
+            inference_ms: 10_000,
+            download_ms: 600_000,
+            shutdown_ms: 30_000,
+            batch_ms: 100,
+        }
+    }
+}
+
+impl TimeoutsConfig {
+    /// Convert a millisecond timeout to a duration, with zero meaning no timeout
+    pub fn to_duration(ms: u64) -> Option<Duration> {
+        if ms == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(ms))
+        }
+    }
+
+    /// End-to-end request timeout
+    pub fn request(&self) -> Option<Duration> {
+        Self::to_duration(self.request_ms)
+    }
+
+    /// Backend inference timeout
+    pub fn inference(&self) -> Option<Duration> {
+        Self::to_duration(self.inference_ms)
+    }
+
+    /// Model download timeout
+    pub fn download(&self) -> Option<Duration> {
+        Self::to_duration(self.download_ms)
+    }
+
+    /// Graceful shutdown timeout
+    pub fn shutdown(&self) -> Option<Duration> {
+        Self::to_duration(self.shutdown_ms)
+    }
+
+    /// Batch wait and per-input timeout
+    pub fn batch(&self) -> Option<Duration> {
+        Self::to_duration(self.batch_ms)
+    }
+
+    /// Check that inner timeouts don't exceed outer ones, returning a warning per violation
+    pub fn validate(&self) -> Vec<String> {
+        // Zero is unbounded, so it compares as larger than any finite timeout
+        let effective = |ms: u64| if ms == 0 { u64::MAX } else { ms };
+        let mut warnings = Vec::new();
+
+        for (name, inner) in [("inference_ms", self.inference_ms), ("batch_ms", self.batch_ms)] {
+            if effective(inner) > effective(self.request_ms) {
+                warnings.push(format!(
+                    "timeouts.{} ({}) exceeds timeouts.request_ms ({}); the request will time out first",
+                    name, inner, self.request_ms
+                ));
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Input-dependent model routing configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Rules evaluated in order; the first match wins
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// Route inputs matching a condition to a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingRule {
+    /// Condition on the input
+    pub condition: RoutingCondition,
+
+    /// Model serving matching inputs
+    pub target_model: String,
+}
+
+/// Condition on the input text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingCondition {
+    /// Input has at least this many characters
+    MinLength(usize),
+
+    /// Input has at most this many characters
+    MaxLength(usize),
+
+    /// Input matches this regular expression
+    Regex(String),
+}
+
+/// Shutdown phase timeouts
+///
+/// All values are in milliseconds, and zero means "no timeout". Every phase is
+/// additionally bounded by `timeouts.shutdown_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// Time to wait for in-flight requests to finish
+    pub drain_ms: u64,
+
+    /// Time to process inputs left in the forming batch
+    pub flush_ms: u64,
+
+    /// Time to persist cache state and final metrics
+    pub persist_ms: u64,
+
+    /// Time to unload models and release backends
+    pub unload_ms: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            drain_ms: 20_000,
+            flush_ms: 5_000,
+            persist_ms: 5_000,
+            unload_ms: 5_000,
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// Drain phase timeout
+    pub fn drain(&self) -> Option<Duration> {
+        TimeoutsConfig::to_duration(self.drain_ms)
+    }
+
+    /// Batch flush phase timeout
+    pub fn flush(&self) -> Option<Duration> {
+        TimeoutsConfig::to_duration(self.flush_ms)
+    }
+
+    /// Persist phase timeout
+    pub fn persist(&self) -> Option<Duration> {
+        TimeoutsConfig::to_duration(self.persist_ms)
+    }
+
+    /// Backend unload phase timeout
+    pub fn unload(&self) -> Option<Duration> {
+        TimeoutsConfig::to_duration(self.unload_ms)
+    }
+}
+
+/// Retry configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Maximum retries per inference request
+    pub max_retries: u32,
+
+    /// Retry tokens deposited per request (0.1 caps retries at 10% of requests)
+    pub budget_ratio: f64,
+
+    /// Retry tokens refilled per second regardless of traffic
+    pub budget_min_per_second: f64,
+
+    /// Maximum retry tokens the budget can hold
+    pub budget_max_tokens: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            budget_ratio: 0.1,
+            budget_min_per_second: 1.0,
+            budget_max_tokens: 10.0,
+        }
+    }
+}
+
+/// Per-model circuit breaker configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerConfig {
+    /// Consecutive server-side failures before a model is marked unhealthy
+    pub failure_threshold: u32,
+
+    /// Seconds before an unhealthy model receives a recovery probe
+    pub recovery_secs: u64,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            recovery_secs: 30,
+        }
+    }
+}
+
+/// Memory pressure monitor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// Process resident memory in bytes above which cached models are evicted, 0 for no limit
+    pub high_water_bytes: u64,
+
+    /// GPU memory used by this process in bytes above which cached models are evicted, 0 for no limit
+    pub gpu_high_water_bytes: u64,
+
+    /// Sampling interval in milliseconds, 0 disables the monitor
+    pub interval_ms: u64,
+
+    /// Unload idle models once the model cache has nothing left to evict
+    pub unload_idle: bool,
+
+    /// Seconds without requests before a model counts as idle
+    pub idle_secs: u64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            high_water_bytes: 0,
+            gpu_high_water_bytes: 0,
+            interval_ms: 5_000,
+            unload_idle: false,
+            idle_secs: 300,
         }
     }
 }
@@ -160,6 +735,18 @@ pub struct MonitoringConfig {
 
     /// Metrics endpoint
     pub metrics_endpoint: String,
+
+    /// StatsD/DogStatsD server (`host:port`) to push metrics to over UDP
+    pub statsd_endpoint: Option<String>,
+
+    /// StatsD push interval in milliseconds
+    pub statsd_interval_ms: u64,
+
+    /// Log line format
+    pub log_format: LogFormat,
+
+    /// Log filter, such as `info` or `synaptron=debug`; `RUST_LOG` takes precedence
+    pub log_level: String,
 }
 
 impl Default for MonitoringConfig {
@@ -168,12 +755,33 @@ impl Default for MonitoringConfig {
             tracing: true,
             metrics: true,
             metrics_endpoint: "/metrics".to_string(),
+            statsd_endpoint: None,
+            statsd_interval_ms: 10_000,
+            log_format: LogFormat::Pretty,
+            log_level: "info".to_string(),
         }
     }
 }
 
+/// Log line format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
+/// Dotted paths of secret fields, with `[]` standing for any array element
+const SECRET_FIELDS: &[&str] = &["server.admin_token", "auth.api_keys[].key", "auth.jwt.secret"];
+
+/// Placeholder written in place of redacted values
+pub const REDACTED: &str = "***REDACTED***";
+
 /// Main configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     /// Server configuration
     pub server: ServerConfig,
@@ -190,69 +798,296 @@ pub struct Config {
     /// Cache configuration
     pub cache: CacheConfig,
 
+    /// Inference response cache configuration
+    pub response_cache: ResponseCacheConfig,
+
     /// Batch configuration
     pub batch: BatchConfig,
 
+    /// Authentication configuration
+    pub auth: AuthConfig,
+
+    /// Retry configuration
+    pub retry: RetryConfig,
+
+    /// Timeout configuration
+    pub timeouts: TimeoutsConfig,
+
+    /// Shutdown phase timeouts
+    pub shutdown: ShutdownConfig,
+
+    /// Input-dependent model routing
+    #[serde(default)]
+    pub routing: RoutingConfig,
+
+    /// Per-model circuit breaker configuration
+    pub breaker: BreakerConfig,
+
+    /// Memory pressure monitor configuration
+    pub memory: MemoryConfig,
+
     /// Monitoring configuration
     pub monitoring: MonitoringConfig,
-}
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            server: ServerConfig::default(),
-            model: ModelConfig::default(),
-            device: DeviceConfig::default(),
-            backend: BackendConfig::default(),
-            cache: CacheConfig::default(),
-            batch: BatchConfig::default(),
-            monitoring: MonitoringConfig::default(),
-        }
-    }
+    /// Config file this configuration was loaded from, re-read on reload
+    #[serde(skip)]
+    pub source_file: Option<PathBuf>,
 }
 
 impl Config {
-    /// Load configuration from file and environment variables
+    /// Load configuration from `config.yaml` in the working directory and environment variables
     pub fn load() -> Result<Self, SynaptronError> {
+        Self::load_from(None)
+    }
+
+    /// Load configuration from a config file, or `config.yaml` in the working directory, and environment variables
+    pub fn load_from(path: Option<&Path>) -> Result<Self, SynaptronError> {
         let mut config_builder = ConfigLoader::builder()
             .set_default("server.host", "127.0.0.1")?
             .set_default("server.port", 8080)?
-            .set_default("server.workers", num_cpus::get())?
+            .set_default("server.workers", num_cpus::get() as u64)?
+            .set_default("server.max_request_bytes", 10 * 1024 * 1024)?
+            .set_default("server.cors_allowed_origins", Vec::<String>::new())?
+            .set_default("server.request_tracing", false)?
+            .set_default("server.http_timeout_ms", 0)?
             .set_default("model.cache_dir", "./models_cache")?
             .set_default("model.default_model", "bert-base-uncased")?
             .set_default("model.max_input_length", 512)?
             .set_default("model.auto_download", true)?
+            .set_default("model.download_max_attempts", 4)?
+            .set_default("model.download_retry_base_ms", 1_000)?
+            .set_default("model.allowed_formats", ALL_MODEL_FORMATS.to_vec())?
             .set_default("device.preferred", "cpu")?
             .set_default("device.auto_select", true)?
             .set_default("backend.openvino", false)?
             .set_default("backend.tensorrt", false)?
             .set_default("backend.onnx_runtime", true)?
             .set_default("backend.auto_select", true)?
+            .set_default("backend.strict_optimization", false)?
+            .set_default("backend.cpu_precision", "auto")?
             .set_default("cache.enabled", true)?
             .set_default("cache.max_size", 1000)?
+            .set_default("cache.max_bytes", 0)?
             .set_default("cache.ttl_seconds", 3600)?
+            .set_default("cache.max_disk_bytes", 0)?
+            .set_default("response_cache.enabled", false)?
+            .set_default("response_cache.max_size", 1000)?
+            .set_default("response_cache.ttl_seconds", 300)?
             .set_default("batch.enabled", true)?
             .set_default("batch.max_batch_size", 32)?
-            .set_default("batch.timeout_ms", 100)?
+            .set_default("batch.window_ms", 5)?
+            .set_default("auth.enabled", false)?
+            .set_default("auth.method", "static")?
+            .set_default("timeouts.request_ms", 30_000)?
+            .set_default("timeouts.inference_ms", 10_000)?
+            .set_default("timeouts.download_ms", 600_000)?
+            .set_default("timeouts.shutdown_ms", 30_000)?
+            .set_default("timeouts.batch_ms", 100)?
+            .set_default("shutdown.drain_ms", 20_000)?
+            .set_default("shutdown.flush_ms", 5_000)?
+            .set_default("shutdown.persist_ms", 5_000)?
+            .set_default("shutdown.unload_ms", 5_000)?
+            .set_default("breaker.failure_threshold", 5)?
+            .set_default("breaker.recovery_secs", 30)?
+            .set_default("memory.high_water_bytes", 0)?
+            .set_default("memory.gpu_high_water_bytes", 0)?
+            .set_default("memory.interval_ms", 5_000)?
+            .set_default("memory.unload_idle", false)?
+            .set_default("memory.idle_secs", 300)?
+            .set_default("retry.max_retries", 2)?
+            .set_default("retry.budget_ratio", 0.1)?
+            .set_default("retry.budget_min_per_second", 1.0)?
+            .set_default("retry.budget_max_tokens", 10.0)?
             .set_default("monitoring.tracing", true)?
             .set_default("monitoring.metrics", true)?
             .set_default("monitoring.metrics_endpoint", "/metrics")?
+            .set_default("monitoring.statsd_interval_ms", 10_000)?
+            .set_default("monitoring.log_format", "pretty")?
+            .set_default("monitoring.log_level", "info")?
             .add_source(Environment::with_prefix("SYNAPTRON"));
 
-        // Try to load from config file
-        if let Ok(current_dir) = env::current_dir() {
-            let config_path = current_dir.join("config.yaml");
-            if config_path.exists() {
-                config_builder = config_builder.add_source(File::from(config_path));
+        // An explicit config file must exist; the default one is optional
+        let config_path = match path {
+            Some(path) if !path.exists() => {
+                return Err(SynaptronError::Config(config::ConfigError::Message(format!(
+                    "Config file not found: {}", path.display()
+                ))));
+            }
+            Some(path) => Some(path.to_path_buf()),
+            None => env::current_dir().ok()
+                .map(|current_dir| current_dir.join("config.yaml"))
+                .filter(|config_path| config_path.exists()),
+        };
+        if let Some(config_path) = &config_path {
+            config_builder = config_builder.add_source(File::from(config_path.clone()));
+        }
+
+        // Layer the remote config on top of the local file if configured
+        if let Ok(url) = env::var(CONFIG_URL_ENV) {
+            let auth = env::var(CONFIG_AUTH_ENV).ok();
+            let required = env::var(CONFIG_URL_REQUIRED_ENV)
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false);
+
+            match Self::fetch_remote(&url, auth.as_deref()) {
+                Ok((body, format)) => {
+                    info!("Loaded remote configuration from: {}", url);
+                    config_builder = config_builder.add_source(File::from_str(&body, format));
+                }
+                Err(SynaptronError::RemoteConfig(msg)) if !required => {
+                    warn!("{}; falling back to local configuration", msg);
+                }
+                Err(e) => return Err(e),
             }
         }
 
         let config = config_builder.build()?;
-        let synaptron_config: Config = config.try_deserialize()?;
+        let mut synaptron_config: Config = config.try_deserialize()?;
+        synaptron_config.source_file = path.map(Path::to_path_buf);
+
+        // Keys from the environment keep secrets out of config files
+        if let Ok(keys) = env::var(API_KEYS_ENV) {
+            synaptron_config.auth.api_keys.extend(AuthConfig::env_api_keys(&keys));
+        }
+
+        for warning in synaptron_config.validate() {
+            warn!("Contradictory configuration: {}", warning);
+        }
 
         Ok(synaptron_config)
     }
 
+    /// Fetch a YAML or JSON configuration document over HTTP
+    fn fetch_remote(url: &str, auth: Option<&str>) -> Result<(String, FileFormat), SynaptronError> {
+        let mut request = ureq::get(url);
+        if let Some(auth) = auth {
+            request = request.set("Authorization", auth);
+        }
+
+        let response = request.call()
+            .map_err(|e| SynaptronError::RemoteConfig(format!("Failed to fetch config from {}: {}", url, e)))?;
+
+        let format = if response.content_type().contains("json") || url.ends_with(".json") {
+            FileFormat::Json
+        } else {
+            FileFormat::Yaml
+        };
+
+        let body = response.into_string()
+            .map_err(|e| SynaptronError::RemoteConfig(format!("Failed to read config from {}: {}", url, e)))?;
+
+        // Validate up front so a malformed document is reported against its URL.
+        // Malformed remote config is always fatal, regardless of the required flag.
+        let parsed = match format {
+            FileFormat::Json => serde_json::from_str::<serde_json::Value>(&body).map(|_| ()).map_err(|e| e.to_string()),
+            _ => serde_yaml::from_str::<serde_yaml::Value>(&body).map(|_| ()).map_err(|e| e.to_string()),
+        };
+        if let Err(e) = parsed {
+            return Err(SynaptronError::Config(config::ConfigError::Message(
+                format!("Malformed config from {}: {}", url, e)
+            )));
+        }
+
+        Ok((body, format))
+    }
+
+    /// Validate the configuration, returning a warning per contradictory setting
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = self.timeouts.validate();
+
+        for rule in &self.routing.rules {
+            let known = rule.target_model == self.model.default_model
+                || self.model.models.contains_key(&rule.target_model);
+            if !known {
+                warnings.push(format!(
+                    "routing rule targets {}, which is neither model.default_model nor in model.models; \
+                     the rule is skipped until that model is loaded",
+                    rule.target_model
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// Paths of settings that differ from `reloaded` and can't change without a restart
+    ///
+    /// Batch, both caches and the StatsD side of monitoring are hot-reloadable;
+    /// everything else, such as `server.port`, is fixed at startup.
+    pub fn restart_required_changes(&self, reloaded: &Config) -> Result<Vec<String>, SynaptronError> {
+        // Carry the hot-reloadable settings over so only the fixed ones can differ
+        let mut fixed = reloaded.clone();
+        fixed.batch = self.batch.clone();
+        fixed.timeouts.batch_ms = self.timeouts.batch_ms;
+        for (name, overrides) in fixed.model.models.iter_mut() {
+            overrides.max_batch_size = self.model.models.get(name).and_then(|current| current.max_batch_size);
+        }
+        fixed.cache = self.cache.clone();
+        fixed.response_cache = self.response_cache.clone();
+        fixed.monitoring.metrics = self.monitoring.metrics;
+        fixed.monitoring.statsd_endpoint = self.monitoring.statsd_endpoint.clone();
+        fixed.monitoring.statsd_interval_ms = self.monitoring.statsd_interval_ms;
+
+        let mut changes = Vec::new();
+        Self::diff_paths("", &serde_json::to_value(self)?, &serde_json::to_value(&fixed)?, &mut changes);
+        Ok(changes)
+    }
+
+    /// Collect the dotted paths at which two JSON values differ
+    fn diff_paths(path: &str, current: &serde_json::Value, reloaded: &serde_json::Value, changes: &mut Vec<String>) {
+        match (current, reloaded) {
+            (serde_json::Value::Object(current), serde_json::Value::Object(reloaded)) => {
+                let mut fields: Vec<&String> = current.keys().chain(reloaded.keys()).collect();
+                fields.sort();
+                fields.dedup();
+
+                for field in fields {
+                    let inner_path = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+                    let null = serde_json::Value::Null;
+                    Self::diff_paths(
+                        &inner_path,
+                        current.get(field).unwrap_or(&null),
+                        reloaded.get(field).unwrap_or(&null),
+                        changes,
+                    );
+                }
+            }
+            (current, reloaded) if current != reloaded => changes.push(path.to_string()),
+            _ => {}
+        }
+    }
+
+    /// Serialize the configuration to JSON with secret fields redacted
+    pub fn redacted(&self) -> Result<serde_json::Value, SynaptronError> {
+        let mut value = serde_json::to_value(self)?;
+        Self::redact_value("", &mut value);
+        Ok(value)
+    }
+
+    /// Recursively replace the `SECRET_FIELDS` of a JSON value found at `path`
+    fn redact_value(path: &str, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (field, inner) in map.iter_mut() {
+                    let inner_path = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+
+                    if SECRET_FIELDS.contains(&inner_path.as_str()) && !inner.is_null() {
+                        *inner = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        Self::redact_value(&inner_path, inner);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                let item_path = format!("{}[]", path);
+                for item in items {
+                    Self::redact_value(&item_path, item);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Save configuration to file
     pub fn save(&self, path: &str) -> Result<(), SynaptronError> {
         let yaml = serde_yaml::to_string(self)?;
@@ -260,3 +1095,199 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one HTTP response with the given content type and body, returning its URL
+    fn serve_once(content_type: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/config", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                content_type, body.len(), body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        url
+    }
+
+    #[test]
+    fn remote_json_config_is_fetched() {
+        let body = r#"{"server": {"port": 9090}}"#;
+        let (fetched, format) = Config::fetch_remote(&serve_once("application/json", body), None).unwrap();
+
+        assert_eq!(fetched, body);
+        assert!(matches!(format, FileFormat::Json));
+    }
+
+    #[test]
+    fn remote_yaml_config_is_fetched() {
+        let body = "server:\n  port: 9090\n";
+        let (fetched, format) = Config::fetch_remote(&serve_once("text/yaml", body), None).unwrap();
+
+        assert_eq!(fetched, body);
+        assert!(matches!(format, FileFormat::Yaml));
+    }
+
+    #[test]
+    fn malformed_remote_config_is_a_config_error() {
+        let result = Config::fetch_remote(&serve_once("application/json", "{\"server\": "), None);
+
+        assert!(matches!(result, Err(SynaptronError::Config(_))));
+    }
+
+    #[test]
+    fn unreachable_remote_config_is_a_remote_config_error() {
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let result = Config::fetch_remote(&format!("http://{}/config", address), None);
+
+        assert!(matches!(result, Err(SynaptronError::RemoteConfig(_))));
+    }
+
+    #[test]
+    fn missing_explicit_config_file_is_an_error() {
+        assert!(Config::load_from(Some(Path::new("does-not-exist.yaml"))).is_err());
+    }
+
+    #[test]
+    fn effective_config_redacts_secrets() {
+        let mut config = Config::default();
+        config.server.admin_token = Some("admin-secret".to_string());
+        config.auth.jwt.secret = Some("jwt-secret".to_string());
+        config.auth.api_keys.push(ApiKeyConfig {
+            key: "key-secret".to_string(),
+            subject: "ci".to_string(),
+            tenant: None,
+        });
+
+        let redacted = config.redacted().unwrap();
+
+        assert_eq!(redacted["server"]["admin_token"], REDACTED);
+        assert_eq!(redacted["auth"]["jwt"]["secret"], REDACTED);
+        assert_eq!(redacted["auth"]["api_keys"][0]["key"], REDACTED);
+        assert_eq!(redacted["auth"]["api_keys"][0]["subject"], "ci");
+        assert_eq!(redacted["server"]["port"], 8080);
+        let text = redacted.to_string();
+        for secret in ["admin-secret", "jwt-secret", "key-secret"] {
+            assert!(!text.contains(secret), "{} leaked", secret);
+        }
+    }
+
+    #[test]
+    fn unset_secrets_stay_null() {
+        let redacted = Config::default().redacted().unwrap();
+
+        assert!(redacted["server"]["admin_token"].is_null());
+    }
+
+    #[test]
+    fn per_model_auto_download_overrides_the_global_setting() {
+        let mut config = ModelConfig::default();
+        config.models.insert("private".to_string(), PerModelConfig {
+            auto_download: Some(false),
+            ..PerModelConfig::default()
+        });
+        config.models.insert("tuned".to_string(), PerModelConfig::default());
+
+        assert!(!config.auto_download_for("private"));
+        assert!(config.auto_download_for("tuned"));
+        assert!(config.auto_download_for("unconfigured"));
+
+        config.auto_download = false;
+        assert!(!config.auto_download_for("unconfigured"));
+    }
+
+    #[test]
+    fn only_allowed_formats_may_load() {
+        let config = ModelConfig {
+            allowed_formats: vec!["safetensors".to_string(), "ONNX".to_string()],
+            ..ModelConfig::default()
+        };
+
+        assert!(config.is_format_allowed("safetensors"));
+        assert!(config.is_format_allowed("onnx"));
+        assert!(!config.is_format_allowed("pytorch"));
+        assert!(ALL_MODEL_FORMATS.iter().all(|format| ModelConfig::default().is_format_allowed(format)));
+    }
+
+    #[test]
+    fn zero_timeouts_mean_no_timeout() {
+        let timeouts = TimeoutsConfig { inference_ms: 0, ..TimeoutsConfig::default() };
+
+        assert_eq!(timeouts.inference(), None);
+        assert_eq!(timeouts.request(), Some(Duration::from_millis(30_000)));
+    }
+
+    #[test]
+    fn inner_timeouts_longer_than_the_request_are_flagged() {
+        let timeouts = TimeoutsConfig { request_ms: 1_000, inference_ms: 5_000, ..TimeoutsConfig::default() };
+        let warnings = timeouts.validate();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("timeouts.inference_ms (5000)"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn unbounded_requests_allow_any_inner_timeout() {
+        let timeouts = TimeoutsConfig { request_ms: 0, ..TimeoutsConfig::default() };
+
+        assert!(timeouts.validate().is_empty());
+        assert!(TimeoutsConfig::default().validate().is_empty());
+    }
+
+    #[test]
+    fn hot_reloadable_settings_need_no_restart() {
+        let current = Config::default();
+        let mut reloaded = current.clone();
+        reloaded.batch.window_ms = 20;
+        reloaded.timeouts.batch_ms = 250;
+        reloaded.cache.enabled = !current.cache.enabled;
+        reloaded.monitoring.statsd_interval_ms = 1_000;
+
+        assert!(current.restart_required_changes(&reloaded).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fixed_settings_are_reported_by_path() {
+        let current = Config::default();
+        let mut reloaded = current.clone();
+        reloaded.server.port = 9090;
+        reloaded.timeouts.inference_ms = 1_000;
+
+        assert_eq!(
+            current.restart_required_changes(&reloaded).unwrap(),
+            ["server.port", "timeouts.inference_ms"],
+        );
+    }
+
+    #[test]
+    fn model_batch_sizes_reload_but_other_overrides_do_not() {
+        let mut current = Config::default();
+        current.model.models.insert("bert".to_string(), PerModelConfig::default());
+        let mut reloaded = current.clone();
+        reloaded.model.models.get_mut("bert").unwrap().max_batch_size = Some(8);
+        assert!(current.restart_required_changes(&reloaded).unwrap().is_empty());
+
+        reloaded.model.models.get_mut("bert").unwrap().device = Some("cuda:1".to_string());
+        assert_eq!(current.restart_required_changes(&reloaded).unwrap(), ["model.models.bert.device"]);
+    }
+
+    #[test]
+    fn environment_api_keys_are_numbered_subjects() {
+        let keys = AuthConfig::env_api_keys(" k1, ,k2,");
+
+        let parsed: Vec<(&str, &str)> = keys.iter().map(|key| (key.key.as_str(), key.subject.as_str())).collect();
+        assert_eq!(parsed, [("k1", "env-key-1"), ("k2", "env-key-2")]);
+        assert!(keys.iter().all(|key| key.tenant.is_none()));
+    }
+}
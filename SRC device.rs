@@ -1,133 +1,298 @@
 //! Device management for the Synaptron inference engine
 
-use crate::{config::DeviceConfig, error::SynaptronError};
-use tracing::{info, debug};
+use crate::{config::{CpuPrecision, DeviceConfig}, error::SynaptronError};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::{info, debug, warn};
 
-/// Device manager
-pub struct DeviceManager {
-    /// Device configuration
-    config: DeviceConfig,
+/// PCI vendor id of Intel devices, as reported by sysfs
+const INTEL_VENDOR_ID: &str = "0x8086";
+
+/// Kind of inference device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceKind {
+    /// Host CPU
+    Cpu,
+    
+    /// NVIDIA GPU
+    Cuda,
+    
+    /// Intel GPU, through OpenVINO
+    Gpu,
+    
+    /// Intel VPU/NPU, through OpenVINO
+    Vpu,
 }
 
-impl DeviceManager {
-    /// Create a new device manager
-    pub fn new(config: &DeviceConfig) -> Self {
+impl DeviceKind {
+    /// Device string used for backend selection
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceKind::Cpu => "cpu",
+            DeviceKind::Cuda => "cuda",
+            DeviceKind::Gpu => "gpu",
+            DeviceKind::Vpu => "vpu",
+        }
+    }
+    
+    /// Relative compute throughput, used as the primary ranking key
+    pub fn compute_score(&self) -> f64 {
+        match self {
+            DeviceKind::Cuda => 100.0,
+            DeviceKind::Gpu => 40.0,
+            DeviceKind::Vpu => 20.0,
+            DeviceKind::Cpu => 10.0,
+        }
+    }
+    
+    /// Whether this build has a backend for the device
+    fn supported(&self) -> bool {
+        match self {
+            DeviceKind::Cpu => true,
+            DeviceKind::Cuda => cfg!(feature = "tensorrt"),
+            DeviceKind::Gpu | DeviceKind::Vpu => cfg!(feature = "openvino"),
+        }
+    }
+}
+
+/// Inference device found on the system
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeviceInfo {
+    /// Device id, e.g. `cpu` or `cuda:1`
+    pub id: String,
+    
+    /// Device kind
+    pub kind: DeviceKind,
+    
+    /// Total memory in bytes, 0 when unknown
+    pub total_memory: u64,
+    
+    /// Free memory in bytes, 0 when unknown
+    pub free_memory: u64,
+    
+    /// Relative compute throughput
+    pub compute_score: f64,
+}
+
+impl DeviceInfo {
+    /// Describe a device, scoring it by kind
+    pub fn new(kind: DeviceKind, index: Option<usize>, total_memory: u64, free_memory: u64) -> Self {
+        let id = match index {
+            Some(index) => format!("{}:{}", kind.as_str(), index),
+            None => kind.as_str().to_string(),
+        };
+        
         Self {
-            config: config.clone(),
+            id,
+            kind,
+            total_memory,
+            free_memory,
+            compute_score: kind.compute_score(),
         }
     }
+}
+
+/// Hardware probe used for device detection, replaceable for testing
+pub trait DeviceProbe: Send + Sync {
+    /// Devices present on the system
+    fn devices(&self) -> Vec<DeviceInfo>;
+}
+
+/// Probe reading the local system through the driver files it exposes
+pub struct SystemProbe;
+
+impl SystemProbe {
+    /// Host memory as (total, available) bytes from `/proc/meminfo`
+    fn host_memory() -> (u64, u64) {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        let field = |name: &str| meminfo.lines()
+            .find(|line| line.starts_with(name))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map_or(0, |kb| kb * 1024);
+        
+        (field("MemTotal:"), field("MemAvailable:"))
+    }
     
-    /// Select the best device for inference
-    pub async fn select_device(&self) -> Result<String, SynaptronError> {
-        info!("Selecting best device for inference");
+    /// CUDA devices visible to this process
+    fn cuda_devices() -> Vec<DeviceInfo> {
+        // The driver exposes its version file only when the kernel module is loaded
+        if !Path::new("/proc/driver/nvidia/version").exists() {
+            return Vec::new();
+        }
         
-        if self.config.auto_select {
-            // In a real implementation, this would check available hardware
-            // and select the best device based on performance characteristics
-            
-            // For now, we'll use a simple selection:
-            #[cfg(feature = "cuda")]
-            {
-                // Check if CUDA is available
-                if self.is_cuda_available().await {
-                    info!("Selected CUDA device");
-                    return Ok("cuda".to_string());
-                }
-            }
-            
-            #[cfg(feature = "openvino")]
-            {
-                // Check if Intel hardware is available
-                if self.is_intel_hardware_available().await {
-                    info!("Selected Intel GPU/VPU device");
-                    return Ok("gpu".to_string()); // or "vpu"
-                }
-            }
-            
-            // Default to CPU
-            info!("Selected CPU device");
-            Ok("cpu".to_string())
-        } else {
-            // Use preferred device from config
-            info!("Using preferred device: {}", self.config.preferred);
-            Ok(self.config.preferred.clone())
+        // (physical index, total, free) per GPU; nvidia-smi reports memory in MiB
+        let mut gpus: Vec<(usize, u64, u64)> = std::process::Command::new("nvidia-smi")
+            .args(["--query-gpu=index,memory.total,memory.free", "--format=csv,noheader,nounits"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.split(',').map(|field| field.trim().parse::<u64>().ok());
+                    Some((fields.next()?? as usize, fields.next()?? << 20, fields.next()?? << 20))
+                })
+                .collect())
+            .unwrap_or_default();
+        
+        if gpus.is_empty() {
+            let installed = std::fs::read_dir("/proc/driver/nvidia/gpus")
+                .map(|entries| entries.filter_map(|entry| entry.ok()).count())
+                .unwrap_or(0);
+            gpus = (0..installed).map(|index| (index, 0, 0)).collect();
         }
+        
+        // CUDA_VISIBLE_DEVICES selects and renumbers the devices this process may use
+        if let Ok(visible) = std::env::var("CUDA_VISIBLE_DEVICES") {
+            gpus = visible.split(',')
+                .map(str::trim)
+                .map_while(|id| id.parse::<usize>().ok())
+                .filter_map(|physical| gpus.iter().find(|(index, _, _)| *index == physical).copied())
+                .collect();
+        }
+        
+        gpus.into_iter()
+            .enumerate()
+            .map(|(index, (_, total, free))| DeviceInfo::new(DeviceKind::Cuda, Some(index), total, free))
+            .collect()
     }
     
-    /// Check if CUDA is available
-    async fn is_cuda_available(&self) -> bool {
-        debug!("Checking CUDA availability");
+    /// Whether the OpenVINO runtime is installed
+    fn openvino_runtime() -> bool {
+        if std::env::var_os("INTEL_OPENVINO_DIR").is_some() {
+            return true;
+        }
         
-        // In a real implementation, this would check for CUDA devices
-        // For now, we'll return false
-        false
+        ["/opt/intel/openvino", "/usr/lib/x86_64-linux-gnu/libopenvino.so", "/usr/local/lib/libopenvino.so"]
+            .iter()
+            .any(|path| Path::new(path).exists())
     }
     
-    /// Check if Intel hardware is available
-    async fn is_intel_hardware_available(&self) -> bool {
-        debug!("Checking Intel hardware availability");
+    /// Count `/sys/class/{class}` devices whose PCI vendor is Intel
+    fn count_intel(class: &str, prefix: &str) -> usize {
+        let entries = match std::fs::read_dir(Path::new("/sys/class").join(class)) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
         
-        // In a real implementation, this would check for Intel devices
-        // For now, we'll return false
-        false
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+            .filter(|entry| {
+                std::fs::read_to_string(entry.path().join("device/vendor"))
+                    .is_ok_and(|vendor| vendor.trim() == INTEL_VENDOR_ID)
+            })
+            .count()
     }
 }
 
-impl Clone for DeviceManager {
-    fn clone(&self) -> Self {
-        Self {
-            config: self.config.clone(),
+impl DeviceProbe for SystemProbe {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        let (total, free) = Self::host_memory();
+        let mut devices = vec![DeviceInfo::new(DeviceKind::Cpu, None, total, free)];
+        
+        devices.extend(Self::cuda_devices());
+        
+        if Self::openvino_runtime() {
+            devices.extend((0..Self::count_intel("drm", "renderD"))
+                .map(|index| DeviceInfo::new(DeviceKind::Gpu, Some(index), 0, 0)));
+            devices.extend((0..Self::count_intel("accel", "accel"))
+                .map(|index| DeviceInfo::new(DeviceKind::Vpu, Some(index), 0, 0)));
         }
+        
+        devices
     }
-}//! Device management for the Synaptron inference engine
-
-use crate::{config::DeviceConfig, error::SynaptronError};
-use tracing::{info, debug};
+}
 
 /// Device manager
 pub struct DeviceManager {
     /// Device configuration
     config: DeviceConfig,
+    
+    /// Hardware probe
+    probe: Arc<dyn DeviceProbe>,
+    
+    /// Probed devices, gathered once
+    devices: Arc<OnceCell<Vec<DeviceInfo>>>,
 }
 
 impl DeviceManager {
     /// Create a new device manager
     pub fn new(config: &DeviceConfig) -> Self {
+        Self::with_probe(config, Arc::new(SystemProbe))
+    }
+    
+    /// Create a device manager detecting hardware with a custom probe
+    pub fn with_probe(config: &DeviceConfig, probe: Arc<dyn DeviceProbe>) -> Self {
         Self {
             config: config.clone(),
+            probe,
+            devices: Arc::new(OnceCell::new()),
         }
     }
     
+    /// Devices on the system, probing on first use
+    pub async fn list_devices(&self) -> Vec<DeviceInfo> {
+        self.devices.get_or_init(|| async {
+            let probe = self.probe.clone();
+            let devices = tokio::task::spawn_blocking(move || probe.devices())
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Device probe failed, assuming CPU only: {}", e);
+                    Vec::new()
+                });
+            
+            info!("Detected devices: {:?}", devices);
+            devices
+        }).await.clone()
+    }
+    
+    /// Best device this build can run on, by compute score and then free memory
+    pub async fn select_best(&self) -> DeviceInfo {
+        let mut devices: Vec<DeviceInfo> = self.list_devices().await
+            .into_iter()
+            .filter(|device| device.kind.supported())
+            .collect();
+        
+        devices.sort_by(|a, b| {
+            b.compute_score.total_cmp(&a.compute_score)
+                .then(b.free_memory.cmp(&a.free_memory))
+        });
+        
+        devices.into_iter()
+            .next()
+            .unwrap_or_else(|| DeviceInfo::new(DeviceKind::Cpu, None, 0, 0))
+    }
+    
+    /// Look up a device by id
+    pub async fn device(&self, id: &str) -> Result<DeviceInfo, SynaptronError> {
+        let device = self.list_devices().await
+            .into_iter()
+            .find(|device| device.id == id)
+            .or_else(|| (id == "cpu").then(|| DeviceInfo::new(DeviceKind::Cpu, None, 0, 0)))
+            .ok_or_else(|| SynaptronError::DeviceSelection(format!("Device not found: {}", id)))?;
+        
+        if !device.kind.supported() {
+            return Err(SynaptronError::DeviceSelection(format!(
+                "Device {} is not supported by this build", id
+            )));
+        }
+        
+        Ok(device)
+    }
+    
     /// Select the best device for inference
     pub async fn select_device(&self) -> Result<String, SynaptronError> {
         info!("Selecting best device for inference");
         
         if self.config.auto_select {
-            // In a real implementation, this would check available hardware
-            // and select the best device based on performance characteristics
-            
-            // For now, we'll use a simple selection:
-            #[cfg(feature = "cuda")]
-            {
-                // Check if CUDA is available
-                if self.is_cuda_available().await {
-                    info!("Selected CUDA device");
-                    return Ok("cuda".to_string());
-                }
-            }
-            
-            #[cfg(feature = "openvino")]
-            {
-                // Check if Intel hardware is available
-                if self.is_intel_hardware_available().await {
-                    info!("Selected Intel GPU/VPU device");
-                    return Ok("gpu".to_string()); // or "vpu"
-                }
-            }
-            
-            // Default to CPU
-            info!("Selected CPU device");
-            Ok("cpu".to_string())
+            let device = self.select_best().await;
+            info!("Selected device: {}", device.id);
+            Ok(device.kind.as_str().to_string())
         } else {
             // Use preferred device from config
             info!("Using preferred device: {}", self.config.preferred);
@@ -136,21 +301,9 @@ impl DeviceManager {
     }
     
     /// Check if CUDA is available
-    async fn is_cuda_available(&self) -> bool {
+    pub async fn is_cuda_available(&self) -> bool {
         debug!("Checking CUDA availability");
-        
-        // In a real implementation, this would check for CUDA devices
-        // For now, we'll return false
-        false
-    }
-    
-    /// Check if Intel hardware is available
-    async fn is_intel_hardware_available(&self) -> bool {
-        debug!("Checking Intel hardware availability");
-        
-        // In a real implementation, this would check for Intel devices
-        // For now, we'll return false
-        false
+        self.list_devices().await.iter().any(|device| device.kind == DeviceKind::Cuda)
     }
 }
 
@@ -158,6 +311,193 @@ impl Clone for DeviceManager {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
+            probe: self.probe.clone(),
+            devices: self.devices.clone(),
+        }
+    }
+}
+
+/// Resolve the configured CPU precision to one this CPU accelerates
+///
+/// `auto` picks bf16, then f16, then f32. An explicit reduced precision the CPU
+/// can't accelerate warns and falls back to f32 rather than running slowly.
+pub fn resolve_cpu_precision(requested: CpuPrecision) -> CpuPrecision {
+    let resolved = match requested {
+        CpuPrecision::Auto if cpu_accelerates(CpuPrecision::Bf16) => CpuPrecision::Bf16,
+        CpuPrecision::Auto if cpu_accelerates(CpuPrecision::F16) => CpuPrecision::F16,
+        CpuPrecision::Auto | CpuPrecision::F32 => CpuPrecision::F32,
+        precision if cpu_accelerates(precision) => precision,
+        precision => {
+            warn!("CPU does not accelerate {:?} inference, using f32 instead", precision);
+            CpuPrecision::F32
+        }
+    };
+    
+    info!("CPU backend precision: {:?} (requested {:?})", resolved, requested);
+    resolved
+}
+
+/// Whether the CPU has instructions accelerating a precision
+fn cpu_accelerates(precision: CpuPrecision) -> bool {
+    match precision {
+        CpuPrecision::Auto | CpuPrecision::F32 => true,
+        CpuPrecision::Bf16 => has_cpu_flag("avx512_bf16") || has_cpu_flag("amx_bf16") || has_cpu_flag("bf16"),
+        CpuPrecision::F16 => has_cpu_flag("avx512_fp16"),
+    }
+}
+
+/// Check a CPU feature flag, using `/proc/cpuinfo` naming (`Features` on arm64)
+fn has_cpu_flag(flag: &str) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let detected = match flag {
+            "avx512_bf16" => std::arch::is_x86_feature_detected!("avx512bf16"),
+            "avx512_fp16" => std::arch::is_x86_feature_detected!("avx512fp16"),
+            _ => false,
+        };
+        if detected {
+            return true;
+        }
+    }
+    
+    // AMX isn't covered by std feature detection, read the kernel's flags instead
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(cpuinfo) = std::fs::read_to_string("/proc/cpuinfo") {
+            return cpuinfo.lines()
+                .filter(|line| line.starts_with("flags") || line.starts_with("Features"))
+                .flat_map(|line| line.split_whitespace())
+                .any(|f| f == flag);
         }
     }
+    
+    debug!("CPU flag {} not detected", flag);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    /// Probe reporting a fixed set of devices and counting how often it runs
+    struct FixedProbe {
+        devices: Vec<DeviceInfo>,
+        probes: AtomicUsize,
+    }
+    
+    impl DeviceProbe for FixedProbe {
+        fn devices(&self) -> Vec<DeviceInfo> {
+            self.probes.fetch_add(1, Ordering::SeqCst);
+            self.devices.clone()
+        }
+    }
+    
+    /// Manager over a fixed probe, returned with the probe to inspect it
+    fn manager(devices: Vec<DeviceInfo>) -> (DeviceManager, Arc<FixedProbe>) {
+        let probe = Arc::new(FixedProbe { devices, probes: AtomicUsize::new(0) });
+        (DeviceManager::with_probe(&DeviceConfig::default(), probe.clone()), probe)
+    }
+    
+    #[test]
+    fn device_ids_carry_their_index() {
+        assert_eq!(DeviceInfo::new(DeviceKind::Cpu, None, 0, 0).id, "cpu");
+        assert_eq!(DeviceInfo::new(DeviceKind::Cuda, Some(1), 0, 0).id, "cuda:1");
+        assert_eq!(DeviceInfo::new(DeviceKind::Vpu, Some(0), 0, 0).compute_score, 20.0);
+    }
+    
+    #[tokio::test]
+    async fn devices_are_probed_once() {
+        let (devices, probe) = manager(vec![
+            DeviceInfo::new(DeviceKind::Cpu, None, 16, 8),
+            DeviceInfo::new(DeviceKind::Cuda, Some(0), 24, 20),
+        ]);
+        let clone = devices.clone();
+        
+        assert_eq!(devices.list_devices().await.len(), 2);
+        assert!(clone.is_cuda_available().await);
+        assert_eq!(clone.list_devices().await, devices.list_devices().await);
+        assert_eq!(probe.probes.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test]
+    async fn cpu_only_systems_have_no_cuda() {
+        let (devices, _) = manager(vec![DeviceInfo::new(DeviceKind::Cpu, None, 16, 8)]);
+        
+        assert!(!devices.is_cuda_available().await);
+    }
+
+    #[tokio::test]
+    async fn best_device_ranks_by_score_then_free_memory() {
+        let (devices, _) = manager(vec![
+            DeviceInfo::new(DeviceKind::Cpu, None, 64, 32),
+            DeviceInfo::new(DeviceKind::Cuda, Some(0), 24, 4),
+            DeviceInfo::new(DeviceKind::Cuda, Some(1), 24, 20),
+        ]);
+        
+        let best = devices.select_best().await;
+        
+        let expected = if cfg!(feature = "tensorrt") { "cuda:1" } else { "cpu" };
+        assert_eq!(best.id, expected);
+    }
+    
+    #[tokio::test]
+    async fn devices_are_looked_up_by_id() {
+        let (devices, _) = manager(vec![
+            DeviceInfo::new(DeviceKind::Cpu, None, 64, 32),
+            DeviceInfo::new(DeviceKind::Cuda, Some(0), 24, 20),
+        ]);
+        
+        assert_eq!(devices.device("cpu").await.unwrap().free_memory, 32);
+        assert_eq!(devices.device("cuda:0").await.is_ok(), cfg!(feature = "tensorrt"));
+        assert!(matches!(devices.device("cuda:3").await, Err(SynaptronError::DeviceSelection(_))));
+    }
+    
+    #[tokio::test]
+    async fn cpu_is_always_available() {
+        let (devices, _) = manager(Vec::new());
+        
+        assert_eq!(devices.select_best().await.id, "cpu");
+        assert_eq!(devices.device("cpu").await.unwrap().kind, DeviceKind::Cpu);
+    }
+    
+    #[tokio::test]
+    async fn preferred_device_is_used_without_auto_select() {
+        let config = DeviceConfig { preferred: "cuda:1".to_string(), auto_select: false };
+        let devices = DeviceManager::with_probe(&config, Arc::new(FixedProbe { devices: Vec::new(), probes: AtomicUsize::new(0) }));
+        
+        assert_eq!(devices.select_device().await.unwrap(), "cuda:1");
+    }
+
+    #[test]
+    fn auto_precision_picks_what_the_cpu_accelerates() {
+        let expected = if cpu_accelerates(CpuPrecision::Bf16) {
+            CpuPrecision::Bf16
+        } else if cpu_accelerates(CpuPrecision::F16) {
+            CpuPrecision::F16
+        } else {
+            CpuPrecision::F32
+        };
+        
+        assert_eq!(resolve_cpu_precision(CpuPrecision::Auto), expected);
+        assert_eq!(resolve_cpu_precision(CpuPrecision::F32), CpuPrecision::F32);
+    }
+    
+    #[test]
+    fn unaccelerated_precision_falls_back_to_f32() {
+        for precision in [CpuPrecision::Bf16, CpuPrecision::F16] {
+            let expected = if cpu_accelerates(precision) { precision } else { CpuPrecision::F32 };
+            assert_eq!(resolve_cpu_precision(precision), expected);
+        }
+    }
+    
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn avx512_bf16_cpus_select_bf16() {
+        if !std::arch::is_x86_feature_detected!("avx512bf16") {
+            return;
+        }
+        
+        assert_eq!(resolve_cpu_precision(CpuPrecision::Auto), CpuPrecision::Bf16);
+    }
 }
@@ -1,49 +1,319 @@
 //! Core inference engine implementation for Synaptron
 
 use crate::{
-    config::Config, 
+    api::auth::Identity,
+    config::{Config, FallbackPolicy, MonitoringConfig}, 
     error::SynaptronError, 
-    model::Model, 
-    backend::Backend, 
+    model::{Model, ModelInputType, OutputTensor}, 
+    backend::{pool::BackendPool, registry::BackendRegistry, Backend}, 
     device::DeviceManager,
     batch::BatchProcessor,
-    cache::ModelCache,
-    graph::ModelGraph,
-    optimizer::AutoOptimizer
+    breaker::ModelBreaker,
+    cache::{CacheMode, ModelCache, ResponseCache, ResponseKey, SweeperGuard},
+    graph::{GraphSpec, ModelGraph, NodeTiming},
+    memory::{MemoryMonitor, MonitorGuard},
+    metrics::{BenchmarkReport, MetricsCollector, STATUS_OK},
+    multimodal::MultimodalProcessor,
+    optimizer::AutoOptimizer,
+    postprocessing::{self, Pooling, Postprocessor},
+    preprocessing::{Preprocessor, PreprocessorRegistry},
+    retry::RetryBudget,
+    routing::RoutingRules,
+    shutdown::{Shutdown, ShutdownPhase},
 };
-use tracing::{info, error, debug};
+use tracing::{info, error, debug, warn, Instrument};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use futures::stream::{self, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use serde::Serialize;
 use axum::{
     routing::{get, post},
     Router,
 };
 
+/// Input used for the smoke inference run before promoting a staged model
+const SMOKE_INPUT: &[u8] = b"smoke test";
+
+/// Chunks buffered between a streaming backend task and its client
+const STREAM_BUFFER: usize = 16;
+
+/// Aborts a spawned task when dropped, so abandoned streams stop their backend work
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Shared result of an in-flight model load
+type LoadInFlight = Arc<tokio::sync::OnceCell<Result<(), Arc<SynaptronError>>>>;
+
+/// Model path and requested device id of a load, `None` for the best available device
+type LoadKey = (String, Option<String>);
+
+/// In-flight loads keyed by model path and device
+type LoadingMap = Arc<parking_lot::Mutex<std::collections::HashMap<LoadKey, LoadInFlight>>>;
+
+/// Removes a finished load from the in-flight map when dropped, including on error or cancellation
+struct LoadingGuard {
+    /// In-flight loads keyed by model path and device
+    loading: LoadingMap,
+    
+    /// Path and device of the load
+    key: LoadKey,
+    
+    /// The load's result cell
+    cell: LoadInFlight,
+}
+
+impl Drop for LoadingGuard {
+    fn drop(&mut self) {
+        let mut loading_guard = self.loading.lock();
+        if loading_guard.get(&self.key).is_some_and(|current| Arc::ptr_eq(current, &self.cell)) {
+            loading_guard.remove(&self.key);
+        }
+    }
+}
+
+/// Run `load` unless a load with the same key is in flight, sharing one result between callers
+async fn coalesce_load<F, Fut>(loading: &LoadingMap, key: LoadKey, load: F) -> Result<(), SynaptronError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<(), SynaptronError>>,
+{
+    let cell = loading.lock()
+        .entry(key.clone())
+        .or_insert_with(|| Arc::new(tokio::sync::OnceCell::new()))
+        .clone();
+    let _guard = LoadingGuard {
+        loading: loading.clone(),
+        key,
+        cell: cell.clone(),
+    };
+    
+    let result = cell
+        .get_or_init(|| async { load().await.map_err(Arc::new) })
+        .await
+        .clone();
+    
+    result.map_err(SynaptronError::Shared)
+}
+
+/// Counts a request waiting for an inference worker until dropped
+struct QueuedGuard(Arc<AtomicUsize>);
+
+impl QueuedGuard {
+    /// Count a waiting request
+    fn enter(queued: &Arc<AtomicUsize>) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        Self(queued.clone())
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Caller-specific constraints on which model serves a request
+#[derive(Debug, Clone, Default)]
+pub struct ModelScope {
+    /// Model requested by the caller; the engine picks one when unset
+    pub model: Option<String>,
+
+    /// Authenticated caller; unrestricted when authentication is disabled
+    pub identity: Option<Identity>,
+
+    /// Declared input type, used when it can't be detected from the input
+    pub input_type: Option<ModelInputType>,
+}
+
+/// Inference output with the model that produced it
+#[derive(Debug, Clone)]
+pub struct Prediction {
+    /// Model that served the request
+    pub model: String,
+
+    /// Raw backend output
+    pub output: Vec<u8>,
+
+    /// Whether the output is a degraded fallback response
+    pub degraded: bool,
+}
+
+/// Whether the engine can serve traffic
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    /// Ready for traffic
+    pub ready: bool,
+
+    /// Draining for shutdown
+    pub shutting_down: bool,
+
+    /// Loaded models with an initialized backend
+    pub models: Vec<String>,
+
+    /// Required models that aren't ready
+    pub missing: Vec<String>,
+}
+
+/// Embedding vector with the model that produced it
+#[derive(Debug, Clone)]
+pub struct Embedding {
+    /// Model that served the request
+    pub model: String,
+
+    /// Pooled embedding
+    pub vector: Vec<f32>,
+}
+
+/// Model file found in the model cache directory
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailableModel {
+    /// Model name, the file stem
+    pub name: String,
+
+    /// Path of the model file
+    pub path: String,
+
+    /// Whether the model is loaded
+    pub loaded: bool,
+}
+
+/// Model loaded into its own backend outside the active set
+struct StandbyModel {
+    /// The model
+    model: Model,
+
+    /// Device the backend runs on
+    device: String,
+
+    /// Backend holding the model
+    backend: Arc<dyn Backend>,
+}
+
+/// Backend instance holding an active model
+#[derive(Clone)]
+pub(crate) struct ActiveBackend {
+    /// Device id the backend runs on
+    pub(crate) device: String,
+
+    /// Backend holding the model
+    pub(crate) backend: Arc<dyn Backend>,
+
+    /// Unix time in seconds the model became active, its idle baseline until used
+    pub(crate) activated_at: u64,
+}
+
+impl ActiveBackend {
+    /// Track a model becoming active now
+    pub(crate) fn new(device: String, backend: Arc<dyn Backend>) -> Self {
+        let activated_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self { device, backend, activated_at }
+    }
+}
+
 /// Inference Engine
 pub struct InferenceEngine {
     /// Configuration
-    config: Config,
+    pub(crate) config: Config,
 
     /// Active models
-    models: Arc<RwLock<std::collections::HashMap<String, Model>>>,
+    pub(crate) models: Arc<RwLock<std::collections::HashMap<String, Model>>>,
+
+    /// Backend holding each active model, keyed by model name
+    pub(crate) backends: Arc<RwLock<std::collections::HashMap<String, ActiveBackend>>>,
 
-    /// Backend manager
-    backends: Arc<RwLock<std::collections::HashMap<String, Box<dyn Backend>>>>,
+    /// Live backend instances per backend and device, which later models on the device load into sessions of
+    backend_pool: BackendPool,
+
+    /// Custom backends, consulted before the built-in ones
+    backend_registry: BackendRegistry,
 
     /// Device manager
-    device_manager: DeviceManager,
+    pub(crate) device_manager: DeviceManager,
 
     /// Batch processor
     batch_processor: BatchProcessor,
 
     /// Model cache
-    model_cache: ModelCache,
+    pub(crate) model_cache: ModelCache,
+
+    /// Background expiry of cached models while the cache is enabled, stopped on shutdown
+    cache_sweeper: Arc<parking_lot::Mutex<Option<SweeperGuard>>>,
+
+    /// Sheds cached and idle models under memory pressure
+    memory_monitor: MemoryMonitor,
+
+    /// Running memory monitor task, stopped on shutdown
+    memory_guard: Arc<parking_lot::Mutex<Option<MonitorGuard>>>,
 
-    /// Model graph for chaining
-    model_graph: ModelGraph,
+    /// Model graph for chaining, replaced when a pipeline is installed
+    model_graph: Arc<RwLock<ModelGraph>>,
 
     /// Auto optimizer
     auto_optimizer: AutoOptimizer,
+
+    /// Metrics collector
+    metrics: MetricsCollector,
+
+    /// Engine-wide retry budget
+    retry_budget: RetryBudget,
+
+    /// Preprocessors keyed by model input type
+    preprocessors: Arc<PreprocessorRegistry>,
+
+    /// Routes inputs to a model of the matching input type
+    multimodal: Arc<MultimodalProcessor>,
+
+    /// Models staged for promotion
+    staged: Arc<RwLock<std::collections::HashMap<String, StandbyModel>>>,
+
+    /// Previously active models kept for rollback
+    previous: Arc<RwLock<std::collections::HashMap<String, StandbyModel>>>,
+
+    /// Inference response cache
+    response_cache: ResponseCache,
+
+    /// Per-model circuit breaker
+    breaker: ModelBreaker,
+
+    /// Last successful output per model, for the `LastKnown` fallback policy
+    last_known: Arc<RwLock<std::collections::HashMap<String, Vec<u8>>>>,
+
+    /// Shutdown coordinator
+    shutdown: Shutdown,
+
+    /// Input-dependent routing rules
+    routing: RoutingRules,
+
+    /// When the engine started, for uptime
+    start_time: std::time::Instant,
+
+    /// Permits bounding concurrent inference to `server.workers`
+    workers: Arc<Semaphore>,
+
+    /// Requests waiting for an inference permit
+    queued: Arc<AtomicUsize>,
+
+    /// Latest configuration, including reloaded settings
+    live_config: Arc<parking_lot::RwLock<Config>>,
+
+    /// Serializes config reloads
+    reloading: Arc<tokio::sync::Mutex<()>>,
+
+    /// Running StatsD exporter, replaced when its settings are reloaded
+    statsd_exporter: Arc<parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    
+    /// Model loads in flight keyed by path and device, so concurrent loads of one model onto one device share a single load
+    loading: LoadingMap,
 }
 
 impl InferenceEngine {
@@ -52,177 +322,2612 @@ impl InferenceEngine {
         info!("Initializing Synaptron inference engine");
         
         let device_manager = DeviceManager::new(&config.device);
-        let batch_processor = BatchProcessor::new(&config.batch);
-        let model_cache = ModelCache::new(&config.cache);
-        let model_graph = ModelGraph::new();
+        let batch_processor = BatchProcessor::new(&config.batch)
+            .with_model_batch_sizes(Self::model_batch_sizes(&config))
+            .with_timeout(config.timeouts.batch());
+        let model_cache = ModelCache::open(&config.cache, &config.model.cache_dir).await?;
+        model_cache.restore(&config.model.cache_dir).await;
+        let cache_sweeper = Arc::new(parking_lot::Mutex::new(model_cache.start_sweeper()));
+        let memory_monitor = MemoryMonitor::new(&config.memory, model_cache.clone());
+        let model_graph = Arc::new(RwLock::new(ModelGraph::new()));
         let auto_optimizer = AutoOptimizer::new(&config.backend);
         
         // Create cache directory if it doesn't exist
         tokio::fs::create_dir_all(&config.model.cache_dir).await?;
         
-        Ok(Self {
+        let retry_budget = RetryBudget::new(&config.retry);
+        let metrics = MetricsCollector::new();
+        let response_cache = ResponseCache::new(&config.response_cache);
+        let breaker = ModelBreaker::new(&config.breaker);
+        let backend_pool = BackendPool::new();
+        let shutdown = Shutdown::new(&config.shutdown, config.timeouts.shutdown());
+        let routing = RoutingRules::new(&config.routing)?;
+        let workers = config.server.workers.max(1);
+        let live_config = Arc::new(parking_lot::RwLock::new(config.clone()));
+        
+        let engine = Self {
             config,
             models: Arc::new(RwLock::new(std::collections::HashMap::new())),
             backends: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            backend_pool,
+            backend_registry: BackendRegistry::new(),
             device_manager,
             batch_processor,
             model_cache,
+            cache_sweeper,
+            memory_monitor,
+            memory_guard: Arc::new(parking_lot::Mutex::new(None)),
             model_graph,
             auto_optimizer,
-        })
+            metrics,
+            retry_budget,
+            preprocessors: Arc::new(PreprocessorRegistry::with_defaults()),
+            multimodal: Arc::new(MultimodalProcessor::new()),
+            staged: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            previous: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            response_cache,
+            breaker,
+            last_known: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            shutdown,
+            routing,
+            start_time: std::time::Instant::now(),
+            workers: Arc::new(Semaphore::new(workers)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            live_config,
+            reloading: Arc::new(tokio::sync::Mutex::new(())),
+            statsd_exporter: Arc::new(parking_lot::Mutex::new(None)),
+            loading: Arc::new(parking_lot::Mutex::new(std::collections::HashMap::new())),
+        };
+        
+        engine.restart_statsd_exporter(&engine.config.monitoring);
+        
+        if let Some(graph_file) = &engine.config.model.graph_file {
+            engine.install_graph(&GraphSpec::from_file(graph_file)?).await?;
+        }
+        
+        // Runs even while batching is disabled, so a config reload can enable it
+        let batcher = engine.clone();
+        engine.batch_processor.spawn_batcher(move |model_name, inputs, cancel| {
+            let engine = batcher.clone();
+            async move { engine.infer_batch_on(&model_name, inputs, cancel).await }
+        });
+        
+        let unloader = engine.clone();
+        *engine.memory_guard.lock() = engine.memory_monitor.start(move || {
+            let engine = unloader.clone();
+            async move { engine.unload_idle_model().await }
+        });
+        
+        engine.preload_models().await;
+        
+        Ok(engine)
     }
 
-    /// Load a model
-    pub async fn load_model(&self, model_path: &str) -> Result<(), SynaptronError> {
-        info!("Loading model from: {}", model_path);
+    /// Load and warm up the `model.preload` models
+    ///
+    /// A model that fails to load is logged and left out, so the server still
+    /// starts and `/ready` reports it missing.
+    async fn preload_models(&self) {
+        for name in &self.config.model.preload {
+            let started = std::time::Instant::now();
+            if let Err(e) = self.activate_model(name).await {
+                error!("Failed to preload model {}: {}", name, e);
+                continue;
+            }
+            
+            match self.warm_up(name).await {
+                Ok(()) => info!("Preloaded and warmed up model {} in {:?}", name, started.elapsed()),
+                Err(e) => warn!("Preloaded model {}, but its warm-up inference failed: {}", name, e),
+            }
+        }
+    }
+
+    /// Run one throwaway inference so backends compile and allocate before the first request
+    ///
+    /// Bypasses the response cache, metrics and circuit breaker.
+    pub async fn warm_up(&self, name: &str) -> Result<(), SynaptronError> {
+        let input_type = {
+            let models_guard = self.models.read().await;
+            models_guard.get(name)
+                .map(|model| model.input_type.clone())
+                .ok_or_else(|| SynaptronError::ModelNotFound(name.to_string()))?
+        };
         
-        // Check cache first
-        if let Some(cached_model) = self.model_cache.get(model_path).await {
-            info!("Model found in cache");
-            let mut models_guard = self.models.write().await;
-            models_guard.insert(cached_model.name.clone(), cached_model);
-            return Ok(());
+        let input = crate::preprocessing::warmup_input(&input_type)?;
+        self.infer_on_unchecked(name, input, CacheMode::Bypass).await?;
+        Ok(())
+    }
+
+    /// Models that must be ready before the engine takes traffic: `model.preload`, or else `model.default_model`
+    fn required_models(&self) -> Vec<String> {
+        if self.config.model.preload.is_empty() {
+            vec![self.config.model.default_model.clone()]
+        } else {
+            self.config.model.preload.clone()
         }
+    }
+
+    /// Whether the engine can serve traffic
+    ///
+    /// Ready once every required model is loaded with an initialized backend,
+    /// until shutdown starts.
+    pub async fn readiness(&self) -> Readiness {
+        let models_guard = self.models.read().await;
+        let backends_guard = self.backends.read().await;
         
-        // Create model instance
-        let model = Model::load(model_path, &self.config.model).await?;
+        let mut models: Vec<String> = models_guard.keys()
+            .filter(|name| backends_guard.contains_key(*name))
+            .cloned()
+            .collect();
+        models.sort();
         
-        // Select optimal device
-        let device = self.device_manager.select_device().await?;
-        info!("Selected device: {:?}", device);
+        let missing: Vec<String> = self.required_models().into_iter()
+            .filter(|name| !models.contains(name))
+            .collect();
+        let shutting_down = self.shutdown.is_shutting_down();
         
-        // Initialize backend
-        let backend = self.initialize_backend(&device).await?;
+        Readiness {
+            ready: missing.is_empty() && !shutting_down,
+            shutting_down,
+            models,
+            missing,
+        }
+    }
+
+    /// Get the engine configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Latest configuration, including settings applied by `reload_config`
+    pub fn effective_config(&self) -> Config {
+        self.live_config.read().clone()
+    }
+
+    /// Re-read the configuration and apply its hot-reloadable settings
+    ///
+    /// Batch settings, including per-model batch sizes and `timeouts.batch_ms`, and cache,
+    /// response cache and StatsD settings are applied together. A reload that
+    /// changes any other setting, such as `server.port`, is rejected whole.
+    pub async fn reload_config(&self) -> Result<(), SynaptronError> {
+        let _reloading = self.reloading.lock().await;
         
-        // Optimize model
-        let optimized_model = self.auto_optimizer.optimize(model, &device).await?;
+        let source_file = self.effective_config().source_file;
+        let reloaded = tokio::task::spawn_blocking(move || Config::load_from(source_file.as_deref())).await
+            .map_err(|e| SynaptronError::Other(format!("Config reload failed: {}", e)))??;
         
-        // Load model to backend
-        backend.load_model(&optimized_model).await?;
+        let current = self.effective_config();
+        let fixed = current.restart_required_changes(&reloaded)?;
+        if !fixed.is_empty() {
+            return Err(SynaptronError::Config(::config::ConfigError::Message(format!(
+                "Reloaded configuration changes settings that require a restart: {}", fixed.join(", ")
+            ))));
+        }
         
-        // Store model and backend
+        self.batch_processor.update_config(&reloaded.batch);
+        self.batch_processor.update_limits(Self::model_batch_sizes(&reloaded), reloaded.timeouts.batch());
+        self.model_cache.update_config(&reloaded.cache);
+        
+        // The sweeper only runs while the cache is enabled
         {
-            let mut models_guard = self.models.write().await;
-            models_guard.insert(optimized_model.name.clone(), optimized_model);
+            let mut sweeper_guard = self.cache_sweeper.lock();
+            if !reloaded.cache.enabled {
+                *sweeper_guard = None;
+            } else if sweeper_guard.is_none() {
+                *sweeper_guard = self.model_cache.start_sweeper();
+            }
+        }
+        self.response_cache.update_config(&reloaded.response_cache);
+        
+        let statsd_changed = current.monitoring.metrics != reloaded.monitoring.metrics
+            || current.monitoring.statsd_endpoint != reloaded.monitoring.statsd_endpoint
+            || current.monitoring.statsd_interval_ms != reloaded.monitoring.statsd_interval_ms;
+        if statsd_changed {
+            self.restart_statsd_exporter(&reloaded.monitoring);
+        }
+        
+        *self.live_config.write() = reloaded;
+        info!("Configuration reloaded");
+        Ok(())
+    }
+
+    /// Per-model maximum batch sizes from the model overrides
+    fn model_batch_sizes(config: &Config) -> std::collections::HashMap<String, usize> {
+        config.model.models.iter()
+            .filter_map(|(name, overrides)| overrides.max_batch_size.map(|size| (name.clone(), size)))
+            .collect()
+    }
+
+    /// Start the StatsD exporter for monitoring settings, stopping any running one
+    fn restart_statsd_exporter(&self, monitoring: &MonitoringConfig) {
+        let mut exporter_guard = self.statsd_exporter.lock();
+        if let Some(exporter) = exporter_guard.take() {
+            exporter.abort();
+        }
+        
+        if monitoring.metrics {
+            if let Some(endpoint) = &monitoring.statsd_endpoint {
+                *exporter_guard = Some(crate::metrics::spawn_statsd_exporter(
+                    self.metrics.clone(),
+                    endpoint.clone(),
+                    std::time::Duration::from_millis(monitoring.statsd_interval_ms.max(1)),
+                ));
+            }
+        }
+    }
+
+    /// Get the metrics collector
+    pub fn metrics(&self) -> &MetricsCollector {
+        &self.metrics
+    }
+
+    /// Time since the engine started
+    pub fn uptime(&self) -> std::time::Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Get the per-model circuit breaker
+    pub fn breaker(&self) -> &ModelBreaker {
+        &self.breaker
+    }
+
+    /// Get the retry budget
+    pub fn retry_budget(&self) -> &RetryBudget {
+        &self.retry_budget
+    }
+
+    /// Get the pool of backend instances shared per device
+    pub fn backend_pool(&self) -> &BackendPool {
+        &self.backend_pool
+    }
+
+    /// Get the memory pressure monitor
+    pub fn memory_monitor(&self) -> &MemoryMonitor {
+        &self.memory_monitor
+    }
+
+    /// Register a custom backend, used for models whose `backend` override names it
+    ///
+    /// Registered backends are consulted before the built-in ones, so a name
+    /// such as `cpu` replaces that backend. The backend's `name()` should return
+    /// the registered name, under which its instances are shared per device.
+    pub fn register_backend<F>(&self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Result<Box<dyn Backend>, SynaptronError> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        info!("Registering backend: {}", name);
+        self.backend_registry.register(name, factory);
+    }
+
+    /// Load a model, on the device pinned in its configuration or the best available one
+    ///
+    /// Fails like `load_model_on`.
+    pub async fn load_model(&self, model_path: &str) -> Result<(), SynaptronError> {
+        let name = std::path::Path::new(model_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+        let pinned = self.config.model.for_model(name).and_then(|overrides| overrides.device.clone());
+        
+        self.load_model_on(model_path, pinned.as_deref()).await
+    }
+
+    /// Resolve a model name to the file it loads from
+    ///
+    /// Prefers a model file in `model.cache_dir`; otherwise, when the model may be
+    /// auto-downloaded, the path it will be downloaded to.
+    pub async fn resolve_model_path(&self, name: &str) -> Result<String, SynaptronError> {
+        // Names are file stems in the cache directory, never paths
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            return Err(SynaptronError::InvalidInput(format!("Invalid model name: {:?}", name)));
+        }
+        
+        if let Some(model) = self.available_models().await?.into_iter().find(|model| model.name == name) {
+            return Ok(model.path);
         }
         
+        if self.config.model.auto_download_for(name) {
+            let extension = self.config.model.for_model(name)
+                .and_then(|overrides| overrides.file.as_deref())
+                .and_then(|file| std::path::Path::new(file).extension())
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("safetensors");
+            return Ok(format!("{}/{}.{}", self.config.model.cache_dir, name, extension));
+        }
+        
+        Err(SynaptronError::ModelNotFound(name.to_string()))
+    }
+
+    /// Load a model by name, resolving its file through `resolve_model_path`
+    pub async fn activate_model(&self, name: &str) -> Result<(), SynaptronError> {
+        let model_path = self.resolve_model_path(name).await?;
+        self.load_model(&model_path).await
+    }
+
+    /// Unload an active model, freeing its memory and per-model state
+    ///
+    /// Requests already running on the model's backend finish first; the
+    /// device's backend instance goes once no other model uses it. Errors with
+    /// `ModelNotFound` if the model isn't loaded.
+    pub async fn unload_model(&self, name: &str) -> Result<(), SynaptronError> {
         {
+            let mut models_guard = self.models.write().await;
             let mut backends_guard = self.backends.write().await;
-            backends_guard.insert(device.clone(), backend);
+            
+            if models_guard.remove(name).is_none() {
+                debug!("Unload requested for model {}, which is not loaded", name);
+                return Err(SynaptronError::ModelNotFound(name.to_string()));
+            }
+            
+            if let Some(active) = backends_guard.remove(name) {
+                debug!("Dropping backend of {} on {}", name, active.device);
+            }
         }
         
-        info!("Model loaded successfully");
+        self.last_known.write().await.remove(name);
+        let evicted = self.response_cache.invalidate_model(name).await;
+        self.breaker.forget(name);
+        self.metrics.forget_model(name);
+        
+        if self.model_graph.read().await.uses_model(name) {
+            warn!("Unloaded model {} is used by the model graph, which will fail until it is loaded again", name);
+        }
+        
+        info!("Model unloaded: {} ({} cached responses evicted)", name, evicted);
         Ok(())
     }
 
-    /// Initialize backend based on device
-    async fn initialize_backend(&self, device: &str) -> Result<Box<dyn Backend>, SynaptronError> {
-        match device {
-            #[cfg(feature = "openvino")]
-            "cpu" | "gpu" | "vpu" => {
-                debug!("Initializing OpenVINO backend for device: {}", device);
-                Ok(Box::new(crate::backend::openvino::OpenVINOBackend::new(device)?))
-            },
-            #[cfg(feature = "tensorrt")]
-            "cuda" => {
-                debug!("Initializing TensorRT backend for CUDA device");
-                Ok(Box::new(crate::backend::tensorrt::TensorRTBackend::new()?))
-            },
-            "cpu" => {
-                debug!("Initializing CPU backend");
-                Ok(Box::new(crate::backend::cpu::CPUBackend::new()?))
-            },
-            _ => {
-                error!("Unsupported device: {}", device);
-                Err(SynaptronError::DeviceSelection(format!("Unsupported device: {}", device)))
+    /// Load a model on a specific device id such as `cuda:1`, or the best available device
+    ///
+    /// Concurrent loads of the same path onto the same device share one load, so
+    /// the model is downloaded and optimized once; callers that joined get its
+    /// result, failures included. Failures are `SynaptronError::Shared`, so match
+    /// on their `root()`.
+    pub async fn load_model_on(&self, model_path: &str, device_id: Option<&str>) -> Result<(), SynaptronError> {
+        let key = (model_path.to_string(), device_id.map(str::to_string));
+        coalesce_load(&self.loading, key, || self.load_model_once(model_path, device_id)).await
+    }
+
+    /// Load a model on a device, without coalescing concurrent loads
+    async fn load_model_once(&self, model_path: &str, device_id: Option<&str>) -> Result<(), SynaptronError> {
+        info!("Loading model from: {}", model_path);
+        
+        let (device, device_id) = self.resolve_device(device_id).await?;
+        
+        let snapshot_target = self.snapshot_target(&device, model_path)?;
+        let snapshot_path = crate::snapshot::path_for(&self.config.model.cache_dir, model_path);
+        let snapshot = if self.config.model.warm_snapshots {
+            crate::snapshot::load(&snapshot_path, model_path, &snapshot_target, &self.config.model).await
+        } else {
+            None
+        };
+        
+        let mut optimized_model = match snapshot {
+            Some(model) => model,
+            None => {
+                // Check cache first, otherwise create model instance
+                let model = match self.model_cache.get(model_path).await {
+                    Some(cached_model) => {
+                        info!("Model found in cache");
+                        cached_model
+                    }
+                    None => Model::load(model_path, &self.config.model).await?,
+                };
+                
+                // Optimize model
+                let optimized_model = self.auto_optimizer.optimize(model, &device).await?;
+                
+                if self.config.model.warm_snapshots {
+                    if let Err(e) = crate::snapshot::save(&optimized_model, &snapshot_path, model_path, &snapshot_target).await {
+                        warn!("Failed to write warm snapshot for {}: {}", model_path, e);
+                    }
+                }
+                
+                optimized_model
             }
+        };
+        
+        self.attach_tokenizer(&mut optimized_model, model_path).await?;
+        
+        // Initialize backend
+        let backend = self.initialize_backend(&device, &device_id, &optimized_model).await?;
+        
+        // Load model to backend
+        backend.load_model(&optimized_model).await?;
+        
+        // Store model and the backend holding it together, so no request sees one without the other
+        let name = optimized_model.name.clone();
+        let mut models_guard = self.models.write().await;
+        let mut backends_guard = self.backends.write().await;
+        
+        models_guard.insert(name.clone(), optimized_model);
+        backends_guard.insert(name, ActiveBackend::new(device_id, backend));
+        
+        info!("Model loaded successfully");
+        Ok(())
+    }
+
+    /// Load the tokenizer shipped next to a text model's file
+    async fn attach_tokenizer(&self, model: &mut Model, model_path: &str) -> Result<(), SynaptronError> {
+        if model.input_type != ModelInputType::Text {
+            return Ok(());
         }
+        
+        let model_dir = std::path::Path::new(model_path)
+            .parent()
+            .map(|dir| dir.to_path_buf())
+            .unwrap_or_default();
+        let tokenizer = tokio::task::spawn_blocking(move || Preprocessor::load_tokenizer(model_dir)).await
+            .map_err(|e| SynaptronError::Tokenization(format!("Tokenizer loading failed: {}", e)))??;
+        
+        model.tokenizer = tokenizer.map(Arc::new);
+        Ok(())
     }
 
-    /// Run inference
-    pub async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
-        debug!("Running inference");
+    /// Device, precision, quantization and backend a model is optimized for on a device
+    fn snapshot_target(&self, device: &str, model_path: &str) -> Result<crate::snapshot::SnapshotTarget, SynaptronError> {
+        let precision = if device == "cpu" {
+            format!("{:?}", crate::device::resolve_cpu_precision(self.config.backend.cpu_precision))
+        } else {
+            "F32".to_string()
+        };
         
-        // For now, we'll use a simple approach
-        // In a real implementation, this would be more complex with model selection, etc.
+        let format = Model::detect_format(model_path)?;
         
-        // Get the first available model
-        let models_guard = self.models.read().await;
-        let model = models_guard.values().next()
-            .ok_or_else(|| SynaptronError::Inference("No model loaded".to_string()))?;
+        Ok(crate::snapshot::SnapshotTarget {
+            device: device.to_string(),
+            precision: precision.to_lowercase(),
+            quantization: self.config.backend.quantization,
+            optimized_backend: self.auto_optimizer.backend_for_format(&format, device).ok(),
+        })
+    }
+
+    /// Device kind and id for a pinned device id, or for the best available device
+    async fn resolve_device(&self, device_id: Option<&str>) -> Result<(String, String), SynaptronError> {
+        match device_id {
+            Some(id) => {
+                let pinned = self.device_manager.device(id).await?;
+                info!("Using pinned device: {}", pinned.id);
+                Ok((pinned.kind.as_str().to_string(), pinned.id))
+            }
+            None => {
+                let device = self.device_manager.select_device().await?;
+                info!("Selected device: {:?}", device);
+                Ok((device.clone(), device))
+            }
+        }
+    }
+
+    /// Load a new version of a model into a staging slot alongside the active one
+    ///
+    /// The new version goes on the device pinned in the model's configuration,
+    /// or the best available one, like `load_model`.
+    pub async fn stage_model(&self, name: &str, model_path: &str) -> Result<(), SynaptronError> {
+        info!("Staging model {} from: {}", name, model_path);
         
-        // Get backend
-        let backends_guard = self.backends.read().await;
-        let backend = backends_guard.values().next()
-            .ok_or_else(|| SynaptronError::Inference("No backend available".to_string()))?;
+        let mut model = Model::load(model_path, &self.config.model).await?;
+        model.name = name.to_string();
+        
+        let pinned = self.config.model.for_model(name).and_then(|overrides| overrides.device.clone());
+        let (device, device_id) = self.resolve_device(pinned.as_deref()).await?;
+        let mut model = self.auto_optimizer.optimize(model, &device).await?;
+        self.attach_tokenizer(&mut model, model_path).await?;
+        let backend = self.initialize_backend(&device, &device_id, &model).await?;
+        backend.load_model(&model).await?;
         
-        // Run inference
-        let result = backend.infer(input).await?;
+        let mut staged_guard = self.staged.write().await;
+        staged_guard.insert(name.to_string(), StandbyModel { model, device: device_id, backend });
         
-        Ok(result)
+        info!("Model {} staged", name);
+        Ok(())
     }
 
-    /// Run batch inference
-    pub async fn batch_infer(&self, inputs: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, SynaptronError> {
-        debug!("Running batch inference with {} inputs", inputs.len());
+    /// Promote a staged model to active after a successful smoke inference
+    ///
+    /// If the smoke inference fails the staged model stays staged and the
+    /// active version keeps serving.
+    pub async fn promote_model(&self, name: &str) -> Result<(), SynaptronError> {
+        info!("Promoting staged model: {}", name);
         
-        // Use batch processor
-        let results = self.batch_processor.process(inputs, |input| {
-            // This is a simplified implementation
-            // In a real implementation, this would properly handle async
-            Box::pin(async move {
-                // For now, we'll just return the input as output
-                // In a real implementation, this would call the actual inference
-                Ok(input)
-            })
-        }).await?;
+        let mut staged_guard = self.staged.write().await;
+        let staged = staged_guard.get(name)
+            .ok_or_else(|| SynaptronError::ModelLoad(format!("No staged model named {}", name)))?;
+        
+        let smoke_input = self.preprocessors.get(&staged.model, &self.config.model)?.preprocess(SMOKE_INPUT)?;
+        if let Err(e) = staged.backend.infer(smoke_input).await {
+            error!("Smoke inference failed for staged model {}: {}", name, e);
+            return Err(SynaptronError::Inference(format!(
+                "Promotion of {} rejected, smoke inference failed: {}", name, e
+            )));
+        }
+        
+        let staged = match staged_guard.remove(name) {
+            Some(staged) => staged,
+            None => return Err(SynaptronError::ModelLoad(format!("No staged model named {}", name))),
+        };
+        drop(staged_guard);
         
-        Ok(results)
+        // Swap under both write locks so no request sees a half-promoted state
+        let mut models_guard = self.models.write().await;
+        let mut backends_guard = self.backends.write().await;
+        
+        let old_model = models_guard.insert(name.to_string(), staged.model);
+        let old_backend = backends_guard.insert(
+            name.to_string(),
+            ActiveBackend::new(staged.device, staged.backend),
+        );
+        
+        if let (Some(model), Some(old)) = (old_model, old_backend) {
+            let mut previous_guard = self.previous.write().await;
+            previous_guard.insert(name.to_string(), StandbyModel { model, device: old.device, backend: old.backend });
+        }
+        
+        info!("Model {} promoted", name);
+        Ok(())
     }
 
-    /// Start HTTP server
-    pub async fn start_server(&self) -> Result<(), SynaptronError> {
-        info!("Starting HTTP server on {}:{}", self.config.server.host, self.config.server.port);
+    /// Revert a promoted model to the version it replaced
+    pub async fn rollback_model(&self, name: &str) -> Result<(), SynaptronError> {
+        info!("Rolling back model: {}", name);
         
-        // Create router
-        let app = self.create_router()?;
+        let previous = {
+            let mut previous_guard = self.previous.write().await;
+            previous_guard.remove(name)
+                .ok_or_else(|| SynaptronError::ModelLoad(format!("No previous version of {} to roll back to", name)))?
+        };
         
-        // Bind and start server
-        let addr = format!("{}:{}", self.config.server.host, self.config.server.port)
-            .parse()
-            .map_err(|e| SynaptronError::HttpServer(axum::http::Error::from(e)))?;
-            
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
+        let mut models_guard = self.models.write().await;
+        let mut backends_guard = self.backends.write().await;
         
+        models_guard.insert(name.to_string(), previous.model);
+        backends_guard.insert(name.to_string(), ActiveBackend::new(previous.device, previous.backend));
+        
+        info!("Model {} rolled back", name);
         Ok(())
     }
 
-    /// Create HTTP router
-    fn create_router(&self) -> Result<Router, SynaptronError> {
-        let app = Router::new()
-            .route("/predict", post(crate::api::handlers::predict_handler))
-            .route("/models", get(crate::api::handlers::list_models_handler))
-            .route("/models/activate", post(crate::api::handlers::activate_model_handler))
-            .route("/health", get(crate::api::handlers::health_handler))
-            .route("/metrics", get(crate::api::handlers::metrics_handler))
-            .with_state(self.clone());
-            
-        Ok(app)
+    /// Backend for a model on a device, sharing the device's instance when it has one
+    async fn initialize_backend(&self, device: &str, device_id: &str, model: &Model) -> Result<Arc<dyn Backend>, SynaptronError> {
+        // A configured backend wins, then the optimizer's choice; models that skipped optimization get one now
+        let configured = self.config.model.for_model(&model.name).and_then(|overrides| overrides.backend.clone());
+        let backend = match configured.or_else(|| model.optimized_backend.clone()) {
+            Some(backend) => backend,
+            None => self.auto_optimizer.select_backend(model, device)?,
+        };
+        
+        self.backend_pool.acquire(&backend, device_id, || self.create_backend(&backend, device, device_id, model))
     }
-}
 
-impl Clone for InferenceEngine {
-    fn clone(&self) -> Self {
-        Self {
-            config: self.config.clone(),
-            models: self.models.clone(),
-            backends: self.backends.clone(),
-            device_manager: self.device_manager.clone(),
-            batch_processor: self.batch_processor.clone(),
-            model_cache: self.model_cache.clone(),
-            model_graph: self.model_graph.clone(),
-            auto_optimizer: self.auto_optimizer.clone(),
+    /// Initialize a new instance of a backend on a device
+    ///
+    /// `device` is the device kind, such as `cuda`, and `device_id` the specific
+    /// device, such as `cuda:1`; only the OpenVINO and TensorRT backends take it.
+    #[cfg_attr(not(any(feature = "openvino", feature = "tensorrt")), allow(unused_variables))]
+    fn create_backend(&self, backend: &str, device: &str, device_id: &str, model: &Model) -> Result<Arc<dyn Backend>, SynaptronError> {
+        if let Some(created) = self.backend_registry.create(backend) {
+            debug!("Initializing registered backend {} for model: {}", backend, model.name);
+            return created;
         }
+        
+        match backend {
+            #[cfg(feature = "openvino")]
+            "openvino" => {
+                debug!("Initializing OpenVINO backend for device: {}", device_id);
+                Ok(Arc::new(crate::backend::openvino::OpenVINOBackend::new(device_id)?))
+            },
+            #[cfg(feature = "tensorrt")]
+            "tensorrt" => {
+                debug!("Initializing TensorRT backend for CUDA device: {}", device_id);
+                Ok(Arc::new(crate::backend::tensorrt::TensorRTBackend::new(device_id)?))
+            },
+            #[cfg(feature = "onnx")]
+            "onnx_runtime" => {
+                debug!("Initializing ONNX Runtime backend for model: {}", model.name);
+                let precision = crate::device::resolve_cpu_precision(self.config.backend.cpu_precision);
+                Ok(Arc::new(crate::backend::onnx::OrtBackend::new()?.with_precision(precision)))
+            },
+            "cpu" if device == "cpu" => {
+                debug!("Initializing CPU backend for model: {}", model.name);
+                let precision = crate::device::resolve_cpu_precision(self.config.backend.cpu_precision);
+                Ok(Arc::new(crate::backend::cpu::CPUBackend::new()?.with_precision(precision)))
+            },
+            _ => {
+                error!("Backend {} is not available for device: {}", backend, device);
+                Err(SynaptronError::DeviceSelection(format!(
+                    "Backend {} is not available for device: {}", backend, device
+                )))
+            }
+        }
+    }
+
+    /// Wait for an inference worker, bounding concurrent inference to `server.workers`
+    async fn worker_permit(&self) -> Result<OwnedSemaphorePermit, SynaptronError> {
+        let _queued = QueuedGuard::enter(&self.queued);
+        self.workers.clone().acquire_owned().await
+            .map_err(|_| SynaptronError::ModelUnavailable("Inference workers are closed".to_string()))
+    }
+
+    /// Requests waiting for an inference worker
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// Inference calls currently running
+    pub fn active_inferences(&self) -> usize {
+        self.config.server.workers.max(1) - self.workers.available_permits()
+    }
+
+    /// Backend holding an active model
+    async fn backend_for(&self, model_name: &str) -> Result<Arc<dyn Backend>, SynaptronError> {
+        self.backends.read().await
+            .get(model_name)
+            .map(|active| active.backend.clone())
+            .ok_or_else(|| SynaptronError::Inference(format!("No backend holds model: {}", model_name)))
+    }
+
+    /// Run inference
+    pub async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        debug!("Running inference");
+        
+        let model_name = self.select_model(&input, &ModelScope::default()).await?;
+        self.infer_on(&model_name, input, CacheMode::Use).await
+    }
+
+    /// Run inference on a named loaded model
+    ///
+    /// Errors with `ModelNotFound` if no model of that name is loaded.
+    pub async fn infer_with(&self, model_name: &str, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        debug!("Running inference on {}", model_name);
+        
+        let scope = ModelScope { model: Some(model_name.to_string()), ..ModelScope::default() };
+        let model_name = self.select_model(&input, &scope).await?;
+        self.infer_on(&model_name, input, CacheMode::Use).await
+    }
+
+    /// Run inference, applying the selected model's fallback policy on failure
+    pub async fn infer_with_fallback(
+        &self,
+        input: Vec<u8>,
+        cache_mode: CacheMode,
+        scope: &ModelScope,
+    ) -> Result<Prediction, SynaptronError> {
+        let model_name = self.select_model(&input, scope).await?;
+        let result = self.infer_on(&model_name, input, cache_mode).await;
+        self.apply_fallback(model_name, result).await
+    }
+
+    /// Turn a model's inference result into a prediction under its fallback policy
+    async fn apply_fallback(
+        &self,
+        model_name: String,
+        result: Result<Vec<u8>, SynaptronError>,
+    ) -> Result<Prediction, SynaptronError> {
+        let policy = self.config.model.for_model(&model_name)
+            .and_then(|overrides| overrides.fallback_on_error.clone())
+            .unwrap_or(FallbackPolicy::Error);
+        
+        match result {
+            Ok(output) => {
+                if policy == FallbackPolicy::LastKnown {
+                    let mut last_known_guard = self.last_known.write().await;
+                    last_known_guard.insert(model_name.clone(), output.clone());
+                }
+                Ok(Prediction { model: model_name, output, degraded: false })
+            }
+            Err(e) => match policy {
+                FallbackPolicy::Error => Err(e),
+                FallbackPolicy::LastKnown => {
+                    let last_known_guard = self.last_known.read().await;
+                    match last_known_guard.get(&model_name) {
+                        Some(output) => {
+                            warn!("Inference on {} failed, serving last known response: {}", model_name, e);
+                            Ok(Prediction { model: model_name, output: output.clone(), degraded: true })
+                        }
+                        None => Err(e),
+                    }
+                }
+                FallbackPolicy::Default(output) => {
+                    warn!("Inference on {} failed, serving default response: {}", model_name, e);
+                    Ok(Prediction { model: model_name, output, degraded: true })
+                }
+            },
+        }
+    }
+
+    /// Names of the loaded models, sorted
+    pub async fn loaded_models(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.models.read().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Model files in `model.cache_dir`, flagged when loaded
+    pub async fn available_models(&self) -> Result<Vec<AvailableModel>, SynaptronError> {
+        let paths = Model::discover(&self.config.model.cache_dir).await?;
+        let models_guard = self.models.read().await;
+        
+        Ok(paths.into_iter()
+            .map(|path| {
+                let name = std::path::Path::new(&path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let loaded = models_guard.contains_key(&name);
+                AvailableModel { name, path, loaded }
+            })
+            .collect())
+    }
+
+    /// Run inference on a loaded model repeatedly, bypassing the response cache
+    pub async fn benchmark(
+        &self,
+        model_name: &str,
+        input: Vec<u8>,
+        iterations: usize,
+        concurrency: usize,
+    ) -> Result<BenchmarkReport, SynaptronError> {
+        if !self.models.read().await.contains_key(model_name) {
+            return Err(SynaptronError::ModelNotFound(model_name.to_string()));
+        }
+        let concurrency = concurrency.max(1);
+        
+        info!("Benchmarking {} with {} runs, {} concurrent", model_name, iterations, concurrency);
+        
+        let start = std::time::Instant::now();
+        let runs: Vec<(f64, bool)> = stream::iter(0..iterations)
+            .map(|_| {
+                let input = input.clone();
+                async move {
+                    let sent_at = std::time::Instant::now();
+                    let result = self.infer_on(model_name, input, CacheMode::Bypass).await;
+                    if let Err(e) = &result {
+                        debug!("Benchmark run on {} failed: {}", model_name, e);
+                    }
+                    (sent_at.elapsed().as_secs_f64() * 1000.0, result.is_ok())
+                }
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        let elapsed = start.elapsed();
+        
+        let errors = runs.iter().filter(|(_, ok)| !ok).count();
+        let latencies_ms = runs.into_iter().map(|(latency_ms, _)| latency_ms).collect();
+        
+        Ok(BenchmarkReport::new(model_name, concurrency, errors, latencies_ms, elapsed))
+    }
+
+    /// Input types of the loaded models
+    async fn model_types(&self) -> std::collections::HashMap<String, ModelInputType> {
+        self.models.read().await
+            .iter()
+            .map(|(name, model)| (name.clone(), model.input_type.clone()))
+            .collect()
+    }
+
+    /// Validate a graph definition and make it the active model graph
+    ///
+    /// Nodes may use loaded models, models in `model.cache_dir` and models with
+    /// per-model overrides.
+    pub async fn install_graph(&self, spec: &GraphSpec) -> Result<(), SynaptronError> {
+        let model_types = self.model_types().await;
+        let available: std::collections::HashSet<String> = self.available_models().await?
+            .into_iter()
+            .map(|model| model.name)
+            .collect();
+        
+        let graph = ModelGraph::from_config(spec, |model_name| {
+            model_types.contains_key(model_name)
+                || available.contains(model_name)
+                || self.config.model.for_model(model_name).is_some()
+        }, &model_types)?;
+        
+        *self.model_graph.write().await = graph;
+        info!("Installed model graph with {} nodes", spec.nodes.len());
+        Ok(())
+    }
+
+    /// Active model graph definition and its execution order
+    pub async fn graph(&self) -> (GraphSpec, Vec<String>) {
+        let graph = self.model_graph.read().await;
+        (graph.spec(), graph.execution_order().to_vec())
+    }
+
+    /// Timing of each node of the active model graph that has run
+    pub async fn graph_node_timings(&self) -> std::collections::HashMap<String, NodeTiming> {
+        self.model_graph.read().await.node_timings()
+    }
+
+    /// Run the model graph on an input, returning its terminal node's output
+    pub async fn infer_graph(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        let model_types = self.model_types().await;
+        let graph = self.model_graph.read().await.clone();
+        graph
+            .execute(&model_types, input, |model_name, input| async move {
+                self.infer_on(&model_name, input, CacheMode::Use).await
+            })
+            .await
+    }
+
+    /// Run the model graph on an input, returning every terminal node's output
+    pub async fn infer_graph_all(&self, input: Vec<u8>) -> Result<std::collections::HashMap<String, Vec<u8>>, SynaptronError> {
+        let model_types = self.model_types().await;
+        let graph = self.model_graph.read().await.clone();
+        graph
+            .execute_all(&model_types, input, |model_name, input| async move {
+                self.infer_on(&model_name, input, CacheMode::Use).await
+            })
+            .await
+    }
+
+    /// Postprocessor decoding a loaded model's output
+    pub async fn postprocessor(&self, model_name: &str) -> Option<Postprocessor> {
+        self.models.read().await
+            .get(model_name)
+            .map(Postprocessor::for_model)
+    }
+
+    /// Whether a model is visible to a caller
+    ///
+    /// Every model is visible when authentication is disabled. Otherwise a caller
+    /// sees shared models, its own tenant's models and those granted to its tenant.
+    pub fn is_model_visible(&self, model_name: &str, identity: Option<&Identity>) -> bool {
+        let identity = match identity {
+            Some(identity) => identity,
+            None => return true,
+        };
+        
+        let owner = self.config.model.for_model(model_name)
+            .and_then(|overrides| overrides.tenant.as_deref());
+        
+        match (owner, identity.tenant.as_deref()) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(owner), Some(tenant)) => {
+                owner == tenant || self.config.auth.tenants.get(tenant)
+                    .is_some_and(|granted| granted.iter().any(|name| name == model_name))
+            }
+        }
+    }
+
+    /// Select the model to serve an input
+    ///
+    /// Models hidden from the caller are reported as not found so their existence isn't leaked.
+    async fn select_model(&self, input: &[u8], scope: &ModelScope) -> Result<String, SynaptronError> {
+        let models_guard = self.models.read().await;
+        let identity = scope.identity.as_ref();
+        
+        if let Some(name) = &scope.model {
+            if models_guard.contains_key(name) && self.is_model_visible(name, identity) {
+                return Ok(name.clone());
+            }
+            return Err(SynaptronError::ModelNotFound(name.clone()));
+        }
+        
+        // First matching routing rule wins
+        let routed = self.routing.route(input, |target| {
+            models_guard.contains_key(target) && self.is_model_visible(target, identity)
+        });
+        if let Some(target) = routed {
+            return Ok(target.to_string());
+        }
+        
+        // The default model serves any input of its type, so single-model deployments are deterministic
+        let default = &self.config.model.default_model;
+        let use_default = !default.is_empty() && self.is_model_visible(default, identity);
+        let input_type = self.multimodal.input_type_of(input, scope.input_type.clone());
+        if use_default {
+            if let Some(model) = models_guard.get(default) {
+                if model.input_type == input_type {
+                    return Ok(default.clone());
+                }
+            }
+        }
+        
+        let accepted = models_guard.iter()
+            .any(|(name, model)| model.input_type == input_type && self.is_model_visible(name, identity));
+        if accepted || !use_default || models_guard.contains_key(default) {
+            if models_guard.is_empty() {
+                return Err(SynaptronError::Inference("No model loaded".to_string()));
+            }
+            
+            // Otherwise pick a model accepting the input's type
+            return self.multimodal.route_input(input, &models_guard, Some(input_type), |name| {
+                self.is_model_visible(name, identity)
+            }).await;
+        }
+        drop(models_guard);
+        
+        // No loaded model takes the input, so load the default on demand
+        self.load_default_model().await?;
+        match self.models.read().await.get(default) {
+            Some(model) if model.input_type == input_type => Ok(default.clone()),
+            Some(model) => Err(SynaptronError::Multimodal(format!(
+                "Default model {} takes {:?} input, not {:?}", default, model.input_type, input_type
+            ))),
+            None => Err(SynaptronError::ModelUnavailable(format!("Default model {} was unloaded", default))),
+        }
+    }
+
+    /// Load `model.default_model` for a request that no loaded model serves
+    async fn load_default_model(&self) -> Result<(), SynaptronError> {
+        let default = &self.config.model.default_model;
+        info!("Loading default model {} on demand", default);
+        
+        self.activate_model(default).await.map_err(|e| {
+            error!("Failed to load default model {}: {}", default, e);
+            SynaptronError::ModelUnavailable(format!("Default model {} could not be loaded: {}", default, e))
+        })
+    }
+
+    /// Run inference on a specific loaded model, tracking its health
+    async fn infer_on(
+        &self,
+        model_name: &str,
+        input: Vec<u8>,
+        cache_mode: CacheMode,
+    ) -> Result<Vec<u8>, SynaptronError> {
+        self.run_tracked(model_name, self.infer_on_unchecked(model_name, input, cache_mode)).await
+    }
+
+    /// Run work on a specific loaded model behind its breaker, recording its metrics
+    ///
+    /// `run` yields its result and whether it was served from the response cache.
+    /// The work counts as in flight, so shutdown waits for it.
+    async fn run_tracked<T>(
+        &self,
+        model_name: &str,
+        run: impl std::future::Future<Output = Result<(T, bool), SynaptronError>>,
+    ) -> Result<T, SynaptronError> {
+        if self.shutdown.is_shutting_down() {
+            return Err(SynaptronError::ModelUnavailable("Engine is shutting down".to_string()));
+        }
+        let _in_flight = self.shutdown.in_flight().enter();
+        
+        let breaker_permit = self.breaker.check(model_name)?;
+        
+        let start_time = std::time::Instant::now();
+        let result = run.await;
+        let latency_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+        
+        match &result {
+            Ok((_, cache_hit)) => {
+                breaker_permit.record_success();
+                self.metrics.record_model_request(model_name, latency_ms, true, *cache_hit);
+                self.metrics.record_labeled_request(model_name, STATUS_OK, latency_ms);
+            }
+            Err(e) => {
+                breaker_permit.record_failure(e);
+                self.metrics.record_model_request(model_name, latency_ms, false, false);
+                self.metrics.record_labeled_request(model_name, e.kind(), latency_ms);
+            }
+        }
+        
+        result.map(|(output, _)| output)
+    }
+
+    /// Run inference on a specific loaded model without consulting the breaker
+    ///
+    /// Returns the output and whether it was served from the response cache.
+    async fn infer_on_unchecked(
+        &self,
+        model_name: &str,
+        input: Vec<u8>,
+        cache_mode: CacheMode,
+    ) -> Result<(Vec<u8>, bool), SynaptronError> {
+        // The models lock is released before the backend runs so loads and unloads aren't held up
+        let (cache_key, input) = {
+            let models_guard = self.models.read().await;
+            let model = models_guard.get(model_name)
+                .ok_or_else(|| SynaptronError::Inference(format!("Model not loaded: {}", model_name)))?;
+            
+            let cache_key = ResponseKey::new(model, &input);
+            if cache_mode == CacheMode::Use {
+                if let Some(cached) = self.response_cache.get(&cache_key).await {
+                    return Ok((cached, true));
+                }
+            }
+            
+            // Preprocess for the model's modality
+            (cache_key, self.preprocessors.get(model, &self.config.model)?.preprocess(&input)?)
+        };
+        
+        let output = match cache_mode {
+            CacheMode::Use => self.run_backend(cache_key, input, true).await?,
+            CacheMode::Bypass => self.run_backend(cache_key, input, false).await?,
+            CacheMode::Refresh => {
+                let key = cache_key.clone();
+                self.response_cache
+                    .refresh(key, || self.run_backend(cache_key, input, true))
+                    .await?
+            }
+        };
+        
+        Ok((output, false))
+    }
+
+    /// Run inference, yielding output chunks as the backend produces them
+    ///
+    /// The backend runs in its own task; dropping the returned stream (for
+    /// example when the client disconnects) aborts it. Streamed output is not cached.
+    pub async fn infer_stream(
+        &self,
+        input: Vec<u8>,
+        scope: &ModelScope,
+    ) -> Result<impl Stream<Item = Result<Vec<u8>, SynaptronError>> + Send + 'static, SynaptronError> {
+        debug!("Running streaming inference");
+        
+        if self.shutdown.is_shutting_down() {
+            return Err(SynaptronError::ModelUnavailable("Engine is shutting down".to_string()));
+        }
+        
+        let model_name = self.select_model(&input, scope).await?;
+        let breaker_permit = self.breaker.check(&model_name)?;
+        
+        let input = {
+            let models_guard = self.models.read().await;
+            let model = models_guard.get(&model_name)
+                .ok_or_else(|| SynaptronError::Inference(format!("Model not loaded: {}", model_name)))?;
+            self.preprocessors.get(model, &self.config.model)?.preprocess(&input)?
+        };
+        
+        let backend = self.backend_for(&model_name).await?;
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let engine = self.clone();
+        
+        let task = tokio::spawn(async move {
+            let _permit = match engine.worker_permit().await {
+                Ok(permit) => permit,
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+            
+            let mut chunks = backend.infer_stream(input);
+            let mut breaker_permit = Some(breaker_permit);
+            
+            while let Some(chunk) = chunks.next().await {
+                if let (Err(e), Some(permit)) = (&chunk, breaker_permit.take()) {
+                    permit.record_failure(e);
+                }
+                if tx.send(chunk).await.is_err() {
+                    debug!("Stream consumer for {} went away, stopping inference", model_name);
+                    return;
+                }
+            }
+            
+            if let Some(permit) = breaker_permit {
+                permit.record_success();
+            }
+        }.instrument(tracing::Span::current()));
+        
+        // The stream owns the task and an in-flight guard, so shutdown drains open streams
+        let state = (rx, AbortOnDrop(task), self.shutdown.in_flight().enter());
+        
+        Ok(stream::unfold(state, |(mut rx, task, in_flight)| async move {
+            rx.recv().await.map(|chunk| (chunk, (rx, task, in_flight)))
+        }))
+    }
+
+    /// Run inference and pool the model's hidden states into one embedding vector
+    ///
+    /// The hidden width is the last dimension of the model's output shape; output
+    /// of exactly that width is taken to be pooled by the model already.
+    pub async fn infer_embedding(
+        &self,
+        input: Vec<u8>,
+        pooling: Pooling,
+        scope: &ModelScope,
+    ) -> Result<Embedding, SynaptronError> {
+        let model_name = self.select_model(&input, scope).await?;
+        let output = self.infer_on(&model_name, input, CacheMode::Use).await?;
+        
+        let hidden_size = {
+            let models_guard = self.models.read().await;
+            models_guard.get(&model_name).and_then(|model| model.metadata.output_shape.last().copied())
+        };
+        let vector = postprocessing::pool(&output, hidden_size, pooling)?;
+        
+        Ok(Embedding { model: model_name, vector })
+    }
+
+    /// Run inference, returning the requested named outputs (e.g. `logits`, `attentions.layer_0`)
+    ///
+    /// Requesting an output the loaded session doesn't expose is an input error
+    /// listing the outputs that are available. Named outputs bypass the response
+    /// cache and get no fallback response, which stands in for a single output.
+    pub async fn infer_outputs(
+        &self,
+        input: Vec<u8>,
+        output_names: &[String],
+        scope: &ModelScope,
+    ) -> Result<std::collections::HashMap<String, OutputTensor>, SynaptronError> {
+        debug!("Running inference for outputs: {:?}", output_names);
+        
+        let model_name = self.select_model(&input, scope).await?;
+        self.run_tracked(&model_name, async {
+            let outputs = self.infer_outputs_on(&model_name, input, output_names).await?;
+            Ok((outputs, false))
+        }).await
+    }
+
+    /// Run inference for named outputs on a specific loaded model
+    async fn infer_outputs_on(
+        &self,
+        model_name: &str,
+        input: Vec<u8>,
+        output_names: &[String],
+    ) -> Result<std::collections::HashMap<String, OutputTensor>, SynaptronError> {
+        let input = {
+            let models_guard = self.models.read().await;
+            let model = models_guard.get(model_name)
+                .ok_or_else(|| SynaptronError::Inference(format!("Model not loaded: {}", model_name)))?;
+            self.preprocessors.get(model, &self.config.model)?.preprocess(&input)?
+        };
+        
+        let backend = self.backend_for(model_name).await?;
+        
+        let available = backend.output_names();
+        if let Some(missing) = output_names.iter().find(|name| !available.contains(name)) {
+            return Err(SynaptronError::InvalidInput(format!(
+                "Model {} does not expose output '{}'; available outputs: {}",
+                model_name, missing, available.join(", ")
+            )));
+        }
+        
+        let _permit = self.worker_permit().await?;
+        match self.config.timeouts.inference() {
+            Some(duration) => tokio::time::timeout(duration, backend.infer_outputs(input, output_names)).await
+                .unwrap_or_else(|_| Err(SynaptronError::Inference(format!(
+                    "Inference timed out after {} ms", duration.as_millis()
+                )))),
+            None => backend.infer_outputs(input, output_names).await,
+        }
+    }
+
+    /// Run inference on pre-tokenized ids, bypassing the preprocessor
+    ///
+    /// Runs behind the model's breaker with its fallback policy, like a prediction on raw input.
+    pub async fn infer_tokens(&self, token_ids: Vec<u32>, scope: &ModelScope) -> Result<Prediction, SynaptronError> {
+        debug!("Running inference on {} pre-tokenized ids", token_ids.len());
+        
+        let model_name = self.select_model(&[], scope).await?;
+        let (cache_key, input) = self.token_input(&model_name, &token_ids).await?;
+        
+        let result = self.run_tracked(&model_name, async move {
+            if let Some(cached) = self.response_cache.get(&cache_key).await {
+                return Ok((cached, true));
+            }
+            Ok((self.run_backend(cache_key, input, true).await?, false))
+        }).await;
+        
+        self.apply_fallback(model_name, result).await
+    }
+
+    /// Check token ids against a loaded text model, returning their response cache key and backend input
+    async fn token_input(&self, model_name: &str, token_ids: &[u32]) -> Result<(ResponseKey, Vec<u8>), SynaptronError> {
+        let models_guard = self.models.read().await;
+        let model = models_guard.get(model_name)
+            .ok_or_else(|| SynaptronError::Inference(format!("Model not loaded: {}", model_name)))?;
+        
+        if model.input_type != ModelInputType::Text {
+            return Err(SynaptronError::InvalidInput(format!(
+                "Model {} does not accept token ids", model_name
+            )));
+        }
+        if token_ids.is_empty() || token_ids.len() > self.config.model.max_input_length {
+            return Err(SynaptronError::InvalidInput(format!(
+                "Expected between 1 and {} token ids, got {}",
+                self.config.model.max_input_length, token_ids.len()
+            )));
+        }
+        if let Some(vocab_size) = model.metadata.vocab_size {
+            if let Some(id) = token_ids.iter().find(|id| **id as usize >= vocab_size) {
+                return Err(SynaptronError::InvalidInput(format!(
+                    "Token id {} is outside the vocabulary of {} (size {})", id, model_name, vocab_size
+                )));
+            }
+        }
+        
+        // Same layout the text preprocessor produces
+        let input: Vec<u8> = token_ids.iter().flat_map(|id| id.to_le_bytes()).collect();
+        Ok((ResponseKey::new(model, &input), input))
+    }
+
+    /// Run preprocessed input through the backend with retries, optionally caching the result
+    async fn run_backend(
+        &self,
+        cache_key: ResponseKey,
+        input: Vec<u8>,
+        store: bool,
+    ) -> Result<Vec<u8>, SynaptronError> {
+        // Get the backend holding the model
+        let backend = self.backend_for(cache_key.model()).await?;
+        
+        // Run inference, retrying only while the engine-wide budget allows
+        self.retry_budget.deposit();
+        let mut attempt = 0;
+        
+        let inference_timeout = self.config.timeouts.inference();
+        
+        // Dropping the request, as axum does when the client disconnects, tells the backend to stop
+        let cancel = CancellationToken::new();
+        let _cancel_on_drop = cancel.clone().drop_guard();
+        
+        loop {
+            let attempt_result = if self.batch_processor.config().enabled {
+                self.infer_batched(cache_key.model(), input.clone()).await
+            } else {
+                let _permit = self.worker_permit().await?;
+                let run = backend.infer_cancellable(input.clone(), cancel.clone());
+                match inference_timeout {
+                    Some(duration) => tokio::time::timeout(duration, run).await
+                        .unwrap_or_else(|_| Err(SynaptronError::Inference(format!(
+                            "Inference timed out after {} ms", duration.as_millis()
+                        )))),
+                    None => run.await,
+                }
+            };
+            
+            match attempt_result {
+                Ok(result) => {
+                    if store {
+                        self.response_cache.put(cache_key, result.clone()).await;
+                    }
+                    return Ok(result);
+                }
+                Err(e) if attempt < self.retry_budget.max_retries() && self.retry_budget.try_withdraw() => {
+                    attempt += 1;
+                    warn!("Inference failed, retrying (attempt {}): {}", attempt, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Queue preprocessed input for the model's next batch and wait for its output
+    async fn infer_batched(&self, model_name: &str, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        // Dropping the request before its batch runs leaves it out of the batch, and
+        // once every request of a running batch is dropped the batch is cancelled
+        let cancel = CancellationToken::new();
+        let _cancel_on_drop = cancel.clone().drop_guard();
+        
+        self.batch_processor.submit(model_name, input, cancel).await.await
+            .unwrap_or_else(|_| Err(SynaptronError::Batch("Batch dropped before completing".to_string())))
+    }
+
+    /// Run one batched forward pass on a loaded model
+    ///
+    /// A batch takes a single inference worker however many inputs it holds,
+    /// and gives up once `cancel` fires.
+    async fn infer_batch_on(
+        &self,
+        model_name: &str,
+        inputs: Vec<Vec<u8>>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Vec<u8>>, SynaptronError> {
+        let backend = self.backend_for(model_name).await?;
+        let _permit = self.worker_permit().await?;
+        if cancel.is_cancelled() {
+            return Err(crate::backend::cancelled());
+        }
+        
+        match self.config.timeouts.inference() {
+            Some(duration) => tokio::time::timeout(duration, backend.infer_batch_cancellable(inputs, cancel)).await
+                .unwrap_or_else(|_| Err(SynaptronError::Inference(format!(
+                    "Inference timed out after {} ms", duration.as_millis()
+                )))),
+            None => backend.infer_batch_cancellable(inputs, cancel).await,
+        }
+    }
+
+    /// Run batch inference, returning each input's result in order
+    ///
+    /// Inputs run concurrently in groups of `batch.max_batch_size`, each through
+    /// the same model selection, fallback and batching as a single prediction,
+    /// so concurrent inputs for one model share forward passes. Each input is
+    /// recorded as a request with its own latency.
+    pub async fn batch_infer(&self, inputs: Vec<Vec<u8>>, scope: &ModelScope) -> Vec<Result<Prediction, SynaptronError>> {
+        debug!("Running batch inference with {} inputs", inputs.len());
+        
+        self.batch_processor.process(inputs, |input| async move {
+            let started = std::time::Instant::now();
+            let result = self.infer_with_fallback(input, CacheMode::Use, scope).await;
+            self.metrics.record_request(started.elapsed().as_secs_f64() * 1000.0, result.is_ok());
+            result
+        }).await
+    }
+
+    /// Run batch inference, yielding each result with its input index as soon as it completes
+    ///
+    /// Results arrive in completion order rather than input order, so callers
+    /// reorder by index when they need to.
+    pub fn batch_infer_stream(
+        &self,
+        inputs: Vec<Vec<u8>>,
+        scope: ModelScope,
+    ) -> impl Stream<Item = (usize, Result<Prediction, SynaptronError>)> + Send + 'static {
+        debug!("Streaming batch inference with {} inputs", inputs.len());
+        
+        let engine = self.clone();
+        let scope = Arc::new(scope);
+        let concurrency = self.batch_processor.config().max_batch_size.max(1);
+        
+        stream::iter(inputs.into_iter().enumerate())
+            .map(move |(index, input)| {
+                let engine = engine.clone();
+                let scope = scope.clone();
+                async move { (index, engine.infer_with_fallback(input, CacheMode::Use, &scope).await) }
+            })
+            .buffer_unordered(concurrency)
+    }
+
+    /// Start HTTP server
+    pub async fn start_server(&self) -> Result<(), SynaptronError> {
+        info!("Starting HTTP server on {}:{}", self.config.server.host, self.config.server.port);
+        
+        // Create router
+        let app = self.create_router()?;
+        
+        // Bind and start server
+        let addr = format!("{}:{}", self.config.server.host, self.config.server.port)
+            .parse::<std::net::SocketAddr>()
+            .map_err(|e| SynaptronError::Config(::config::ConfigError::Message(format!(
+                "Invalid server address {}:{}: {}", self.config.server.host, self.config.server.port, e
+            ))))?;
+            
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        
+        // A signal runs the full shutdown sequence, whose first phase stops the listener
+        let engine = self.clone();
+        let mut shutdown_task = tokio::spawn(async move {
+            let token = engine.shutdown.token();
+            tokio::select! {
+                _ = crate::shutdown::shutdown_signal() => engine.shutdown().await,
+                _ = token.cancelled() => {}
+            }
+        });
+        
+        let stop_accepting = self.shutdown.token();
+        let server = axum::serve(listener, app)
+            .with_graceful_shutdown(async move { stop_accepting.cancelled().await });
+        
+        tokio::select! {
+            served = server => {
+                // Let the shutdown sequence finish persisting before returning
+                match &served {
+                    Ok(()) => { let _ = shutdown_task.await; }
+                    Err(_) => shutdown_task.abort(),
+                }
+                served?;
+            }
+            _ = &mut shutdown_task => {
+                warn!("Shutdown finished with connections still open, closing them");
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Unload the model idle longest, returning whether one was unloaded
+    ///
+    /// Only models without requests for `memory.idle_secs` since they were
+    /// last used, or activated if never used, qualify; required models and
+    /// models the model graph uses are kept.
+    async fn unload_idle_model(&self) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let required = self.required_models();
+        
+        let idlest = {
+            let graph = self.model_graph.read().await;
+            self.backends.read().await.iter()
+                .filter(|(name, _)| !required.contains(*name) && !graph.uses_model(name))
+                .map(|(name, active)| {
+                    let last_used = self.metrics.model_stats(name).last_used.unwrap_or(0);
+                    (last_used.max(active.activated_at), name.clone())
+                })
+                .filter(|(last_used, _)| now.saturating_sub(*last_used) >= self.config.memory.idle_secs)
+                .min()
+        };
+        
+        match idlest {
+            Some((_, name)) => {
+                info!("Unloading idle model {} to relieve memory pressure", name);
+                self.unload_model(&name).await.is_ok()
+            }
+            None => false,
+        }
+    }
+
+    /// Shut the engine down in order: stop accepting requests, drain in-flight
+    /// requests, flush the forming batch, persist state, then unload backends
+    ///
+    /// Each phase runs under its own timeout; a phase that times out is
+    /// abandoned and shutdown moves on to the next one.
+    pub async fn shutdown(&self) {
+        info!("Shutting down inference engine");
+        let started = std::time::Instant::now();
+        
+        self.shutdown.run_phase(ShutdownPhase::StopAccepting, started, async {
+            self.shutdown.token().cancel();
+        }).await;
+        
+        let in_flight = self.shutdown.in_flight();
+        if self.shutdown.run_phase(ShutdownPhase::Drain, started, in_flight.wait_idle()).await.is_none() {
+            warn!("Abandoning {} in-flight requests", in_flight.count());
+        }
+        
+        let flushed = self.shutdown.run_phase(ShutdownPhase::FlushBatch, started, async {
+            // Stop the batcher, then run whatever it left pending
+            self.batch_processor.stop();
+            self.batch_processor.flush(|model_name, inputs, cancel| {
+                let engine = self.clone();
+                async move { engine.infer_batch_on(&model_name, inputs, cancel).await }
+            }).await
+        }).await;
+        if let Some(Err(e)) = flushed {
+            error!("Failed to flush pending batch: {}", e);
+        }
+        
+        let persisted = self.shutdown.run_phase(ShutdownPhase::Persist, started, async {
+            info!(
+                "Final metrics: {} requests, {:.2} ms average latency, {:.2}% success",
+                self.metrics.get_total_requests(),
+                self.metrics.get_avg_latency_ms(),
+                self.metrics.get_success_rate(),
+            );
+            self.model_cache.persist(&self.config.model.cache_dir).await
+        }).await;
+        if let Some(Err(e)) = persisted {
+            error!("Failed to persist model cache: {}", e);
+        }
+        
+        if let Some(sweeper) = self.cache_sweeper.lock().take() {
+            sweeper.stop();
+        }
+        if let Some(monitor) = self.memory_guard.lock().take() {
+            monitor.stop();
+        }
+        if let Some(exporter) = self.statsd_exporter.lock().take() {
+            exporter.abort();
+        }
+        
+        self.shutdown.run_phase(ShutdownPhase::UnloadBackends, started, async {
+            self.backends.write().await.clear();
+            self.models.write().await.clear();
+            self.backend_pool.clear();
+        }).await;
+        
+        info!("Inference engine shut down in {} ms", started.elapsed().as_millis());
+    }
+
+    /// Create HTTP router
+    fn create_router(&self) -> Result<Router, SynaptronError> {
+        let mut app = Router::new()
+            .route("/predict", post(crate::api::handlers::predict_handler))
+            .route("/predict/binary", post(crate::api::handlers::predict_binary_handler))
+            .route("/predict/stream", post(crate::api::handlers::predict_stream_handler))
+            .route("/predict/batch", post(crate::api::handlers::predict_batch_handler))
+            .route("/predict/batch/stream", post(crate::api::handlers::predict_batch_stream_handler))
+            .route("/embed", post(crate::api::handlers::embed_handler))
+            .route("/models", get(crate::api::handlers::list_models_handler))
+            .route("/models/activate", post(crate::api::handlers::activate_model_handler))
+            .route("/models/deactivate", post(crate::api::handlers::deactivate_model_handler))
+            .route("/models/:name", get(crate::api::handlers::model_handler))
+            .route("/models/:name/stats", get(crate::api::handlers::model_stats_handler))
+            .route("/graph", get(crate::api::handlers::graph_handler).post(crate::api::handlers::install_graph_handler))
+            .route("/health", get(crate::api::handlers::health_handler))
+            .route("/ready", get(crate::api::handlers::ready_handler))
+            .route("/version", get(crate::api::handlers::version_handler))
+            .route("/metrics", get(crate::api::handlers::metrics_handler))
+            .route("/admin/diagnostics", get(crate::api::handlers::diagnostics_handler))
+            .route("/admin/config", get(crate::api::handlers::config_handler))
+            .route("/admin/reload", post(crate::api::handlers::reload_config_handler));
+        
+        if let Some(authenticator) = crate::api::auth::from_config(&self.config.auth)? {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                authenticator,
+                crate::api::auth::auth_middleware,
+            ));
+        }
+        
+        let body_limit = match self.config.server.max_request_bytes {
+            0 => axum::extract::DefaultBodyLimit::disable(),
+            max_bytes => axum::extract::DefaultBodyLimit::max(max_bytes),
+        };
+        
+        app = app
+            .layer(body_limit)
+            .layer(axum::middleware::from_fn(crate::api::middleware::request_id_middleware));
+        
+        if let Some(timeout) = self.config.server.http_timeout() {
+            app = app.layer(tower_http::timeout::TimeoutLayer::new(timeout));
+        }
+        
+        if self.config.server.request_tracing {
+            app = app.layer(tower_http::trace::TraceLayer::new_for_http());
+        }
+        
+        // Outermost, so preflight requests and error responses carry CORS headers
+        if let Some(cors) = crate::api::middleware::cors_layer(&self.config.server.cors_allowed_origins)? {
+            app = app.layer(cors);
+        }
+        
+        let app = app.with_state(self.clone());
+        
+        Ok(app)
+    }
+}
+
+impl Clone for InferenceEngine {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            models: self.models.clone(),
+            backends: self.backends.clone(),
+            backend_pool: self.backend_pool.clone(),
+            backend_registry: self.backend_registry.clone(),
+            device_manager: self.device_manager.clone(),
+            batch_processor: self.batch_processor.clone(),
+            model_cache: self.model_cache.clone(),
+            cache_sweeper: self.cache_sweeper.clone(),
+            memory_monitor: self.memory_monitor.clone(),
+            memory_guard: self.memory_guard.clone(),
+            model_graph: self.model_graph.clone(),
+            auto_optimizer: self.auto_optimizer.clone(),
+            metrics: self.metrics.clone(),
+            retry_budget: self.retry_budget.clone(),
+            preprocessors: self.preprocessors.clone(),
+            multimodal: self.multimodal.clone(),
+            staged: self.staged.clone(),
+            previous: self.previous.clone(),
+            response_cache: self.response_cache.clone(),
+            breaker: self.breaker.clone(),
+            last_known: self.last_known.clone(),
+            shutdown: self.shutdown.clone(),
+            routing: self.routing.clone(),
+            start_time: self.start_time,
+            workers: self.workers.clone(),
+            queued: self.queued.clone(),
+            live_config: self.live_config.clone(),
+            reloading: self.reloading.clone(),
+            statsd_exporter: self.statsd_exporter.clone(),
+            loading: self.loading.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Load key for a model path on the best available device
+    fn key(path: &str) -> LoadKey {
+        (path.to_string(), None)
+    }
+
+    #[tokio::test]
+    async fn concurrent_loads_of_one_model_run_the_loader_once() {
+        let loading = LoadingMap::default();
+        let runs = AtomicUsize::new(0);
+        let runs = &runs;
+        let load = || async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(())
+        };
+
+        let (first, second) = tokio::join!(
+            coalesce_load(&loading, key("models/bert.onnx"), load),
+            coalesce_load(&loading, key("models/bert.onnx"), load),
+        );
+
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(loading.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn joined_loads_get_the_original_error() {
+        let loading = LoadingMap::default();
+        let load = || async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Err(SynaptronError::ModelNotFound("bert".to_string()))
+        };
+
+        let (first, second) = tokio::join!(
+            coalesce_load(&loading, key("models/bert.onnx"), load),
+            coalesce_load(&loading, key("models/bert.onnx"), load),
+        );
+
+        for result in [first, second] {
+            let error = result.expect_err("load should fail");
+            assert!(matches!(error.root(), SynaptronError::ModelNotFound(name) if name == "bert"));
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_on_different_devices_run_separately() {
+        let loading = LoadingMap::default();
+        let runs = AtomicUsize::new(0);
+        let runs = &runs;
+        let load = || async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(())
+        };
+
+        let (first, second) = tokio::join!(
+            coalesce_load(&loading, ("models/bert.onnx".to_string(), Some("cuda:0".to_string())), load),
+            coalesce_load(&loading, ("models/bert.onnx".to_string(), Some("cuda:1".to_string())), load),
+        );
+
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn finished_loads_are_not_reused() {
+        let loading = LoadingMap::default();
+        let runs = AtomicUsize::new(0);
+        let runs = &runs;
+        let load = || async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        };
+
+        coalesce_load(&loading, key("models/bert.onnx"), load).await.unwrap();
+        coalesce_load(&loading, key("models/bert.onnx"), load).await.unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    /// Engine over an empty model directory, kept alive by the returned guard
+    async fn test_engine(mut config: Config) -> (InferenceEngine, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        config.model.cache_dir = dir.path().to_str().unwrap().to_string();
+        (InferenceEngine::new(config).await.unwrap(), dir)
+    }
+
+    /// Caller belonging to `tenant`
+    fn caller(tenant: Option<&str>) -> Identity {
+        Identity { subject: "caller".to_string(), tenant: tenant.map(str::to_string) }
+    }
+
+    #[tokio::test]
+    async fn models_are_visible_to_their_tenant_and_its_grants() {
+        let mut config = Config::default();
+        config.model.models.insert("acme-model".to_string(), crate::config::PerModelConfig {
+            tenant: Some("acme".to_string()),
+            ..Default::default()
+        });
+        config.auth.tenants.insert("globex".to_string(), vec!["acme-model".to_string()]);
+        let (engine, _dir) = test_engine(config).await;
+
+        assert!(engine.is_model_visible("acme-model", Some(&caller(Some("acme")))));
+        assert!(engine.is_model_visible("acme-model", Some(&caller(Some("globex")))));
+        assert!(!engine.is_model_visible("acme-model", Some(&caller(Some("initech")))));
+        assert!(!engine.is_model_visible("acme-model", Some(&caller(None))));
+        assert!(engine.is_model_visible("acme-model", None), "every model is visible without auth");
+        assert!(engine.is_model_visible("shared-model", Some(&caller(Some("initech")))));
+    }
+
+    #[tokio::test]
+    async fn hidden_models_are_reported_as_not_found() {
+        let mut config = Config::default();
+        config.model.models.insert("acme-model".to_string(), crate::config::PerModelConfig {
+            tenant: Some("acme".to_string()),
+            ..Default::default()
+        });
+        let (engine, _dir) = test_engine(config).await;
+        let model = Model::for_test("acme-model", ModelInputType::Text, b"weights");
+        engine.models.write().await.insert(model.name.clone(), model);
+        let scope_for = |tenant| ModelScope {
+            model: Some("acme-model".to_string()),
+            identity: Some(caller(Some(tenant))),
+            ..ModelScope::default()
+        };
+
+        let err = engine.select_model(b"input", &scope_for("initech")).await.unwrap_err();
+
+        assert!(matches!(err, SynaptronError::ModelNotFound(_)));
+        assert_eq!(engine.select_model(b"input", &scope_for("acme")).await.unwrap(), "acme-model");
+    }
+
+    #[tokio::test]
+    async fn path_like_model_names_are_rejected() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+
+        for name in ["", "../secrets", "models/bert", "models\\bert", ".."] {
+            let err = engine.resolve_model_path(name).await.unwrap_err();
+            assert!(matches!(err, SynaptronError::InvalidInput(_)), "{:?} was not rejected", name);
+        }
+    }
+
+    /// Write a small safetensors model file named `name` into `dir`, returning its path
+    fn write_model(dir: &std::path::Path, name: &str) -> String {
+        let header = br#"{"weight":{"dtype":"F32","shape":[2],"data_offsets":[0,8]}}"#;
+        let path = dir.join(format!("{}.safetensors", name));
+        std::fs::write(&path, [&(header.len() as u64).to_le_bytes()[..], &header[..], &[0u8; 8][..]].concat()).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// Backend counting the 4-byte token ids it is given, naming the file its model came from
+    ///
+    /// Inference fails for models loaded from a file whose name contains `broken`.
+    #[derive(Default)]
+    struct TokenCounter {
+        loaded: parking_lot::Mutex<Option<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Backend for TokenCounter {
+        fn name(&self) -> &str {
+            "token_counter"
+        }
+
+        async fn load_model(&self, model: &Model) -> Result<(), SynaptronError> {
+            let file = std::path::Path::new(&model.path).file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            *self.loaded.lock() = Some(file.to_string());
+            Ok(())
+        }
+
+        async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+            let file = self.loaded.lock().clone()
+                .ok_or_else(|| SynaptronError::Inference("No model loaded".to_string()))?;
+            if file.contains("broken") {
+                return Err(SynaptronError::Inference(format!("{} is broken", file)));
+            }
+            Ok(format!("{} tokens on {}", input.len() / 4, file).into_bytes())
+        }
+
+        fn output_names(&self) -> Vec<String> {
+            vec!["logits".to_string(), "attentions.layer_0".to_string()]
+        }
+
+        async fn infer_outputs(
+            &self,
+            input: Vec<u8>,
+            output_names: &[String],
+        ) -> Result<std::collections::HashMap<String, OutputTensor>, SynaptronError> {
+            Ok(output_names.iter()
+                .map(|name| (name.clone(), OutputTensor { shape: vec![1, input.len() / 4], dtype: "u32".to_string(), data: input.clone() }))
+                .collect())
+        }
+    }
+
+    /// Engine with model files for `names` loaded on a `TokenCounter` backend
+    async fn counting_engine(names: &[&str], mut config: Config) -> (InferenceEngine, tempfile::TempDir) {
+        config.model.auto_download = false;
+        for name in names {
+            config.model.models.entry(name.to_string()).or_default().backend = Some("token_counter".to_string());
+        }
+        let (engine, dir) = test_engine(config).await;
+        engine.register_backend("token_counter", || Ok(Box::new(TokenCounter::default())));
+
+        for name in names {
+            engine.load_model(&write_model(dir.path(), name)).await.unwrap();
+        }
+        (engine, dir)
+    }
+
+    #[tokio::test]
+    async fn promoted_version_serves_until_rolled_back() {
+        let (engine, dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let v2 = write_model(dir.path(), "bert-tiny-v2");
+
+        engine.stage_model("bert-tiny", &v2).await.unwrap();
+        assert_eq!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny");
+
+        engine.promote_model("bert-tiny").await.unwrap();
+        assert_eq!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny-v2");
+
+        engine.rollback_model("bert-tiny").await.unwrap();
+        assert_eq!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny");
+    }
+
+    #[tokio::test]
+    async fn failed_smoke_inference_keeps_the_active_version() {
+        let (engine, dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        engine.stage_model("bert-tiny", &write_model(dir.path(), "bert-tiny-broken")).await.unwrap();
+
+        assert!(matches!(engine.promote_model("bert-tiny").await, Err(SynaptronError::Inference(_))));
+        assert_eq!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny");
+        assert!(engine.rollback_model("bert-tiny").await.is_err(), "nothing was promoted");
+    }
+
+    #[tokio::test]
+    async fn staged_versions_go_on_the_pinned_device() {
+        let (mut engine, dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let v2 = write_model(dir.path(), "bert-tiny-v2");
+        engine.config.model.models.get_mut("bert-tiny").unwrap().device = Some("cuda:7".to_string());
+
+        let err = engine.stage_model("bert-tiny", &v2).await.unwrap_err();
+
+        assert!(matches!(err, SynaptronError::DeviceSelection(_)), "{:?}", err);
+        assert!(engine.staged.read().await.is_empty());
+    }
+
+    /// Config whose model `name` falls back by `policy` when inference fails
+    fn fallback_config(name: &str, policy: FallbackPolicy) -> Config {
+        let mut config = Config::default();
+        config.model.models.insert(name.to_string(), crate::config::PerModelConfig {
+            fallback_on_error: Some(policy),
+            ..Default::default()
+        });
+        config
+    }
+
+    /// Scope naming one model
+    fn scope(model: &str) -> ModelScope {
+        ModelScope { model: Some(model.to_string()), ..ModelScope::default() }
+    }
+
+    #[tokio::test]
+    async fn failed_inference_serves_the_default_fallback() {
+        let config = fallback_config("bert-broken", FallbackPolicy::Default(b"unavailable".to_vec()));
+        let (engine, _dir) = counting_engine(&["bert-broken"], config).await;
+
+        let prediction = engine.infer_with_fallback(b"abc".to_vec(), CacheMode::Use, &scope("bert-broken")).await.unwrap();
+
+        assert!(prediction.degraded);
+        assert_eq!(prediction.output, b"unavailable");
+    }
+
+    #[tokio::test]
+    async fn last_known_without_a_success_is_the_error() {
+        let (engine, _dir) = counting_engine(&["bert-broken"], fallback_config("bert-broken", FallbackPolicy::LastKnown)).await;
+
+        let result = engine.infer_with_fallback(b"abc".to_vec(), CacheMode::Use, &scope("bert-broken")).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn successful_inference_is_not_degraded() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], fallback_config("bert-tiny", FallbackPolicy::LastKnown)).await;
+
+        let prediction = engine.infer_with_fallback(b"abc".to_vec(), CacheMode::Use, &scope("bert-tiny")).await.unwrap();
+
+        assert!(!prediction.degraded);
+        assert_eq!(prediction.output, b"3 tokens on bert-tiny");
+    }
+
+    #[tokio::test]
+    async fn token_ids_bypass_the_preprocessor() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+
+        let prediction = engine.infer_tokens(vec![101, 7592, 2088, 999, 102], &scope("bert-tiny")).await.unwrap();
+
+        assert_eq!(prediction.output, b"5 tokens on bert-tiny");
+    }
+
+    #[tokio::test]
+    async fn token_ids_outside_the_vocabulary_are_rejected() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        engine.models.write().await.get_mut("bert-tiny").unwrap().metadata.vocab_size = Some(1000);
+
+        assert!(engine.infer_tokens(vec![5, 999], &scope("bert-tiny")).await.is_ok());
+        let err = engine.infer_tokens(vec![5, 1000], &scope("bert-tiny")).await.unwrap_err();
+        assert!(matches!(err, SynaptronError::InvalidInput(_)));
+        assert!(matches!(engine.infer_tokens(vec![], &scope("bert-tiny")).await, Err(SynaptronError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn failed_token_inference_serves_the_fallback() {
+        let config = fallback_config("bert-broken", FallbackPolicy::Default(b"unavailable".to_vec()));
+        let (engine, _dir) = counting_engine(&["bert-broken"], config).await;
+
+        let prediction = engine.infer_tokens(vec![101, 102], &scope("bert-broken")).await.unwrap();
+
+        assert!(prediction.degraded);
+        assert_eq!(prediction.output, b"unavailable");
+        assert_eq!(engine.metrics.model_stats("bert-broken").request_count, 1);
+    }
+
+    #[tokio::test]
+    async fn token_ids_are_refused_once_shutdown_starts() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        engine.shutdown.token().cancel();
+
+        let err = engine.infer_tokens(vec![101, 102], &scope("bert-tiny")).await.unwrap_err();
+
+        assert!(matches!(err, SynaptronError::ModelUnavailable(_)));
+    }
+
+    #[tokio::test]
+    async fn named_intermediate_outputs_are_returned() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let names = vec!["attentions.layer_0".to_string()];
+
+        let outputs = engine.infer_outputs(b"abc".to_vec(), &names, &scope("bert-tiny")).await.unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs["attentions.layer_0"].shape, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn unknown_outputs_list_the_available_ones() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let names = vec!["logits".to_string(), "hidden_states".to_string()];
+
+        let err = engine.infer_outputs(b"abc".to_vec(), &names, &scope("bert-tiny")).await.unwrap_err();
+
+        match err {
+            SynaptronError::InvalidInput(message) => {
+                assert!(message.contains("hidden_states"));
+                assert!(message.contains("logits, attentions.layer_0"));
+            }
+            other => panic!("expected an input error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn named_outputs_are_recorded_and_refused_once_shutdown_starts() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let names = vec!["logits".to_string()];
+
+        engine.infer_outputs(b"abc".to_vec(), &names, &scope("bert-tiny")).await.unwrap();
+        assert_eq!(engine.metrics.model_stats("bert-tiny").request_count, 1);
+
+        engine.shutdown.token().cancel();
+        let err = engine.infer_outputs(b"abc".to_vec(), &names, &scope("bert-tiny")).await.unwrap_err();
+        assert!(matches!(err, SynaptronError::ModelUnavailable(_)));
+    }
+
+    #[tokio::test]
+    async fn inputs_route_to_a_model_of_their_type() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+        for (name, input_type) in [("resnet", ModelInputType::Image), ("bert", ModelInputType::Text)] {
+            engine.models.write().await.insert(name.to_string(), Model::for_test(name, input_type, b"weights"));
+        }
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+        assert_eq!(engine.select_model(&png, &ModelScope::default()).await.unwrap(), "resnet");
+        assert_eq!(engine.select_model(b"hello", &ModelScope::default()).await.unwrap(), "bert");
+    }
+
+    #[tokio::test]
+    async fn ambiguous_input_follows_the_hint() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+        for (name, input_type) in [("resnet", ModelInputType::Image), ("bert", ModelInputType::Text)] {
+            engine.models.write().await.insert(name.to_string(), Model::for_test(name, input_type, b"weights"));
+        }
+        let hinted = ModelScope { input_type: Some(ModelInputType::Image), ..ModelScope::default() };
+
+        assert_eq!(engine.select_model(&[0x00, 0x01, 0x02], &hinted).await.unwrap(), "resnet");
+    }
+
+    #[tokio::test]
+    async fn models_sharing_a_device_both_stay_inferable() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        let (engine, dir) = test_engine(config).await;
+
+        for name in ["bert-a", "bert-b"] {
+            engine.load_model_on(&write_model(dir.path(), name), Some("cpu")).await.unwrap();
+        }
+
+        for name in ["bert-a", "bert-b"] {
+            assert!(engine.infer_with(name, b"abc".to_vec()).await.is_ok(), "{} lost its backend", name);
+        }
+        let backends = engine.backends.read().await;
+        assert!(!Arc::ptr_eq(&backends["bert-a"].backend, &backends["bert-b"].backend));
+        assert_eq!(engine.backend_pool().stats().created, 1);
+    }
+
+    /// Backend holding each inference for a while, recording the most that ran at once
+    struct Busy {
+        running: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Backend for Busy {
+        fn name(&self) -> &str {
+            "busy"
+        }
+
+        async fn load_model(&self, _model: &Model) -> Result<(), SynaptronError> {
+            Ok(())
+        }
+
+        async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn inference_is_bounded_by_the_worker_count() {
+        let mut config = Config::default();
+        config.server.workers = 2;
+        config.batch.enabled = false;
+        config.model.auto_download = false;
+        config.model.models.insert("bert-busy".to_string(), crate::config::PerModelConfig {
+            backend: Some("busy".to_string()),
+            ..Default::default()
+        });
+        let (engine, dir) = test_engine(config).await;
+        let (running, peak) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        {
+            let (running, peak) = (running.clone(), peak.clone());
+            engine.register_backend("busy", move || Ok(Box::new(Busy { running: running.clone(), peak: peak.clone() })));
+        }
+        engine.load_model(&write_model(dir.path(), "bert-busy")).await.unwrap();
+
+        let requests = (0..6u8).map(|i| engine.infer_with("bert-busy", vec![b'a' + i]));
+        let results = futures::future::join_all(requests).await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!((engine.active_inferences(), engine.queue_depth()), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn unloaded_models_leave_the_engine() {
+        let (engine, _dir) = counting_engine(&["bert-a", "bert-b"], Config::default()).await;
+
+        engine.unload_model("bert-a").await.unwrap();
+
+        assert_eq!(engine.loaded_models().await, vec!["bert-b".to_string()]);
+        assert!(matches!(engine.infer_with("bert-a", b"abc".to_vec()).await, Err(SynaptronError::ModelNotFound(_))));
+        assert!(matches!(engine.unload_model("bert-a").await, Err(SynaptronError::ModelNotFound(_))));
+        assert_eq!(engine.infer_with("bert-b", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-b");
+    }
+
+    #[tokio::test]
+    async fn preloaded_models_are_loaded_by_construction() {
+        let dir = tempfile::tempdir().unwrap();
+        write_model(dir.path(), "bert-tiny");
+        let mut config = Config::default();
+        config.model.cache_dir = dir.path().to_str().unwrap().to_string();
+        config.model.auto_download = false;
+        config.model.preload = vec!["bert-tiny".to_string()];
+
+        let engine = InferenceEngine::new(config).await.unwrap();
+
+        assert_eq!(engine.loaded_models().await, vec!["bert-tiny".to_string()]);
+        assert!(engine.readiness().await.ready);
+    }
+
+    #[tokio::test]
+    async fn preload_failures_leave_the_engine_unready() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        config.model.preload = vec!["bert-missing".to_string()];
+
+        let (engine, _dir) = test_engine(config).await;
+
+        let readiness = engine.readiness().await;
+        assert!(!readiness.ready);
+        assert_eq!(readiness.missing, vec!["bert-missing".to_string()]);
+    }
+
+    /// Send `request` through the engine's HTTP router
+    async fn send(engine: &InferenceEngine, request: axum::http::Request<axum::body::Body>) -> axum::response::Response {
+        use tower::ServiceExt;
+        engine.create_router().unwrap().oneshot(request).await.unwrap()
+    }
+
+    /// GET request for `uri`
+    fn get_request(uri: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap()
+    }
+
+    /// Response body parsed as JSON
+    async fn json_body(response: axum::response::Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn ready_waits_for_the_default_model() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        config.model.default_model = "bert-tiny".to_string();
+        let (engine, dir) = test_engine(config).await;
+
+        assert_eq!(send(&engine, get_request("/health")).await.status(), axum::http::StatusCode::OK);
+        let response = send(&engine, get_request("/ready")).await;
+        assert_eq!(response.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json_body(response).await["missing"], serde_json::json!(["bert-tiny"]));
+
+        engine.load_model(&write_model(dir.path(), "bert-tiny")).await.unwrap();
+
+        let response = send(&engine, get_request("/ready")).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(json_body(response).await["models"], serde_json::json!(["bert-tiny"]));
+    }
+
+    #[tokio::test]
+    async fn engine_runs_a_full_cycle_without_a_server() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        let (engine, dir) = test_engine(config).await;
+
+        engine.load_model(&write_model(dir.path(), "bert-tiny")).await.unwrap();
+        assert!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.is_ok());
+        assert_eq!(engine.metrics().model_stats("bert-tiny").request_count, 1);
+
+        engine.unload_model("bert-tiny").await.unwrap();
+        assert!(engine.loaded_models().await.is_empty());
+        assert_eq!(engine.metrics().model_stats("bert-tiny").request_count, 0);
+
+        engine.shutdown().await;
+        assert!(engine.readiness().await.shutting_down);
+    }
+
+    /// POST request for `uri` with a JSON body
+    fn post_json(uri: &str, body: serde_json::Value) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::post(uri)
+            .header(axum::http::header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn batch_inputs_run_through_the_backend_in_order() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let inputs = vec![b"a".to_vec(), b"abcd".to_vec(), b"ab".to_vec()];
+
+        let results = engine.batch_infer(inputs.clone(), &scope("bert-tiny")).await;
+
+        let outputs: Vec<Vec<u8>> = results.into_iter().map(|result| result.unwrap().output).collect();
+        assert_eq!(outputs, vec![
+            b"1 tokens on bert-tiny".to_vec(),
+            b"4 tokens on bert-tiny".to_vec(),
+            b"2 tokens on bert-tiny".to_vec(),
+        ]);
+        assert_ne!(outputs, inputs);
+    }
+
+    #[tokio::test]
+    async fn batch_route_returns_results_in_input_order() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let request = post_json("/predict/batch", serde_json::json!({ "inputs": ["a", "abcd"], "model": "bert-tiny" }));
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["results"][0]["index"], 0);
+        assert_eq!(body["results"][0]["prediction"], "1 tokens on bert-tiny");
+        assert_eq!(body["results"][1]["index"], 1);
+        assert_eq!(body["results"][1]["prediction"], "4 tokens on bert-tiny");
+    }
+
+    /// Config serving `name` when a request names no model
+    fn default_model_config(name: &str) -> Config {
+        let mut config = Config::default();
+        config.model.default_model = name.to_string();
+        config.model.models.entry(name.to_string()).or_default().backend = Some("token_counter".to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn unnamed_requests_go_to_the_default_model() {
+        let (engine, _dir) = counting_engine(&["bert-a", "bert-b"], default_model_config("bert-b")).await;
+
+        for _ in 0..3 {
+            assert_eq!(engine.infer(b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-b");
+        }
+    }
+
+    #[tokio::test]
+    async fn default_model_is_loaded_on_demand() {
+        let (engine, dir) = counting_engine(&["resnet-tiny"], default_model_config("bert-tiny")).await;
+        write_model(dir.path(), "bert-tiny");
+
+        assert_eq!(engine.infer(b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny");
+        assert_eq!(engine.loaded_models().await, vec!["bert-tiny".to_string(), "resnet-tiny".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn unloadable_default_model_is_unavailable() {
+        let (engine, _dir) = counting_engine(&["resnet-tiny"], default_model_config("bert-missing")).await;
+
+        assert!(matches!(engine.infer(b"abc".to_vec()).await, Err(SynaptronError::ModelUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn models_run_on_registered_backends() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        config.model.models.entry("bert-tiny".to_string()).or_default().backend = Some("custom_npu".to_string());
+        let (engine, dir) = test_engine(config).await;
+        let path = write_model(dir.path(), "bert-tiny");
+
+        let err = engine.load_model(&path).await.unwrap_err();
+        assert!(matches!(err.root(), SynaptronError::DeviceSelection(_)));
+
+        engine.register_backend("custom_npu", || Ok(Box::new(TokenCounter::default())));
+        engine.load_model(&path).await.unwrap();
+
+        assert_eq!(engine.infer_with("bert-tiny", b"abc".to_vec()).await.unwrap(), b"3 tokens on bert-tiny");
+    }
+
+    #[tokio::test]
+    async fn diagnostics_list_loaded_models_and_redact_secrets() {
+        let mut config = Config::default();
+        config.server.admin_token = Some("admin-secret".to_string());
+        let (engine, _dir) = counting_engine(&["bert-tiny"], config).await;
+        let request = axum::http::Request::get("/admin/diagnostics")
+            .header(axum::http::header::AUTHORIZATION, "Bearer admin-secret")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["models"][0]["name"], "bert-tiny");
+        assert_eq!(body["config"]["server"]["admin_token"], crate::config::REDACTED);
+        assert!(!body.to_string().contains("admin-secret"));
+        assert_eq!(body["build"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[tokio::test]
+    async fn diagnostics_need_the_admin_token() {
+        let mut config = Config::default();
+        config.server.admin_token = Some("admin-secret".to_string());
+        let (engine, _dir) = test_engine(config).await;
+
+        let response = send(&engine, get_request("/admin/diagnostics")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn streamed_batch_has_one_line_per_input() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let request = post_json("/predict/batch/stream", serde_json::json!({ "inputs": ["a", "ab", "abc"], "model": "bert-tiny" }));
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.headers()[axum::http::header::CONTENT_TYPE], "application/x-ndjson");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut lines: Vec<serde_json::Value> = std::str::from_utf8(&body).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        lines.sort_by_key(|line| line["index"].as_u64());
+        let indices: Vec<u64> = lines.iter().filter_map(|line| line["index"].as_u64()).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        let predictions: Vec<&serde_json::Value> = lines.iter().map(|line| &line["prediction"]).collect();
+        assert_eq!(predictions, ["1 tokens on bert-tiny", "2 tokens on bert-tiny", "3 tokens on bert-tiny"]);
+    }
+
+    #[tokio::test]
+    async fn version_reports_the_crate_version() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+
+        let response = send(&engine, get_request("/version")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        for field in ["git_sha", "build_timestamp", "rustc_version"] {
+            assert!(!body[field].as_str().unwrap().is_empty(), "{} is empty", field);
+        }
+        let onnx = serde_json::Value::from("onnx");
+        assert_eq!(body["features"].as_array().unwrap().contains(&onnx), cfg!(feature = "onnx"));
+    }
+
+    /// Admin POST request for `uri` naming `model`
+    fn admin_model_request(uri: &str, model: &str) -> axum::http::Request<axum::body::Body> {
+        let mut request = post_json(uri, serde_json::json!({ "model_name": model }));
+        request.headers_mut().insert(axum::http::header::AUTHORIZATION, "Bearer admin-secret".parse().unwrap());
+        request
+    }
+
+    /// Engine taking admin requests, with no model downloads
+    async fn admin_engine() -> (InferenceEngine, tempfile::TempDir) {
+        let mut config = Config::default();
+        config.server.admin_token = Some("admin-secret".to_string());
+        config.model.auto_download = false;
+        test_engine(config).await
+    }
+
+    #[tokio::test]
+    async fn activated_models_are_listed_until_deactivated() {
+        let (engine, dir) = admin_engine().await;
+        write_model(dir.path(), "bert-tiny");
+
+        let response = send(&engine, admin_model_request("/models/activate", "bert-tiny")).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let listed = json_body(send(&engine, get_request("/models")).await).await;
+        assert_eq!(listed["models"], serde_json::json!(["bert-tiny"]));
+
+        let response = send(&engine, admin_model_request("/models/deactivate", "bert-tiny")).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let listed = json_body(send(&engine, get_request("/models")).await).await;
+        assert_eq!(listed["models"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn activating_a_missing_model_is_not_found() {
+        let (engine, _dir) = admin_engine().await;
+
+        let response = send(&engine, admin_model_request("/models/activate", "bert-missing")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+        assert!(engine.loaded_models().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deactivating_an_unloaded_model_is_not_found() {
+        let (engine, _dir) = admin_engine().await;
+
+        let response = send(&engine, admin_model_request("/models/deactivate", "bert-tiny")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn admin_requests_need_the_whole_token() {
+        let (engine, _dir) = admin_engine().await;
+        let mut request = admin_model_request("/models/activate", "bert-tiny");
+        request.headers_mut().insert(axum::http::header::AUTHORIZATION, "Bearer admin-secre".parse().unwrap());
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn oversized_activation_bodies_name_the_limit() {
+        let mut config = Config::default();
+        config.server.admin_token = Some("admin-secret".to_string());
+        config.server.max_request_bytes = 64;
+        let (engine, _dir) = test_engine(config).await;
+
+        let response = send(&engine, admin_model_request("/models/activate", &"a".repeat(1024))).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("server.max_request_bytes"));
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected() {
+        let mut config = Config::default();
+        config.server.max_request_bytes = 64;
+        let (engine, _dir) = counting_engine(&["bert-tiny"], config).await;
+        let request = post_json("/predict", serde_json::json!({ "input": "a".repeat(1024), "model": "bert-tiny" }));
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("server.max_request_bytes"));
+    }
+
+    /// Backend whose inference never finishes in time
+    struct Stall;
+
+    #[async_trait::async_trait]
+    impl Backend for Stall {
+        fn name(&self) -> &str {
+            "stall"
+        }
+
+        async fn load_model(&self, _model: &Model) -> Result<(), SynaptronError> {
+            Ok(())
+        }
+
+        async fn infer(&self, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn hanging_inference_times_out() {
+        let mut config = Config::default();
+        config.timeouts.request_ms = 50;
+        config.timeouts.inference_ms = 0;
+        config.batch.enabled = false;
+        config.model.auto_download = false;
+        config.model.models.entry("bert-tiny".to_string()).or_default().backend = Some("stall".to_string());
+        let (engine, dir) = test_engine(config).await;
+        engine.register_backend("stall", || Ok(Box::new(Stall)));
+        engine.load_model(&write_model(dir.path(), "bert-tiny")).await.unwrap();
+        let request = post_json("/predict", serde_json::json!({ "input": "abc", "model": "bert-tiny" }));
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn binary_images_route_to_the_image_model() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        let (engine, dir) = test_engine(config).await;
+        for name in ["resnet-tiny", "bert-tiny"] {
+            engine.load_model(&write_model(dir.path(), name)).await.unwrap();
+        }
+        engine.models.write().await.get_mut("resnet-tiny").unwrap().metadata.input_shape = vec![1, 3, 4, 4];
+        let png = crate::preprocessing::warmup_input(&ModelInputType::Image).unwrap();
+        let request = axum::http::Request::post("/predict/binary?top_k=1")
+            .header(axum::http::header::CONTENT_TYPE, "application/octet-stream")
+            .body(axum::body::Body::from(png))
+            .unwrap();
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["type"], "Classification");
+        assert_eq!(body["labels"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn empty_binary_bodies_are_rejected() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+        let request = axum::http::Request::post("/predict/binary").body(axum::body::Body::empty()).unwrap();
+
+        assert_eq!(send(&engine, request).await.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn responses_echo_the_inbound_request_id() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let mut request = post_json("/predict", serde_json::json!({ "input": "abc", "model": "bert-missing" }));
+        request.headers_mut().insert(crate::api::middleware::REQUEST_ID_HEADER, "trace-1234".parse().unwrap());
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.headers()[crate::api::middleware::REQUEST_ID_HEADER], "trace-1234");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("request trace-1234"));
+    }
+
+    #[tokio::test]
+    async fn responses_without_a_request_id_get_one() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+
+        let response = send(&engine, get_request("/health")).await;
+
+        assert!(response.headers().contains_key(crate::api::middleware::REQUEST_ID_HEADER));
+    }
+
+    #[tokio::test]
+    async fn model_details_round_trip_the_metadata() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+        let metadata = {
+            let mut models = engine.models.write().await;
+            let model = models.get_mut("bert-tiny").unwrap();
+            model.metadata.architecture = "BertForMaskedLM".to_string();
+            model.metadata.input_shape = vec![1, 128];
+            serde_json::to_value(&model.metadata).unwrap()
+        };
+
+        let response = send(&engine, get_request("/models/bert-tiny")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["metadata"], metadata);
+        assert_eq!(body["metadata"]["architecture"], "BertForMaskedLM");
+        assert_eq!(body["input_type"], serde_json::to_value(ModelInputType::Text).unwrap());
+        assert_eq!(body["loaded"], true);
+        assert_eq!(body["backend"], "token_counter");
+    }
+
+    #[tokio::test]
+    async fn details_of_unknown_models_are_not_found() {
+        let (engine, _dir) = test_engine(Config::default()).await;
+
+        let response = send(&engine, get_request("/models/bert-missing")).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn degraded_responses_carry_the_degraded_header() {
+        let config = fallback_config("bert-broken", FallbackPolicy::Default(b"unavailable".to_vec()));
+        let (engine, _dir) = counting_engine(&["bert-broken"], config).await;
+        let request = post_json("/predict", serde_json::json!({ "input": "abc", "model": "bert-broken" }));
+
+        let response = send(&engine, request).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(response.headers()[crate::api::handlers::DEGRADED_HEADER], "true");
+        assert_eq!(json_body(response).await["prediction"], "unavailable");
+    }
+
+    #[tokio::test]
+    async fn cancelling_shutdown_stops_the_server() {
+        let mut config = Config::default();
+        config.server.port = 0;
+        let (engine, _dir) = test_engine(config).await;
+        let server = {
+            let engine = engine.clone();
+            tokio::spawn(async move { engine.start_server().await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        engine.shutdown.token().cancel();
+
+        let served = tokio::time::timeout(Duration::from_secs(5), server).await
+            .expect("the server should stop once shutdown is cancelled");
+        assert!(served.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn metrics_count_served_predictions() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+
+        let response = send(&engine, post_json("/predict", serde_json::json!({ "input": "abc", "model": "bert-tiny" }))).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        let metrics = json_body(send(&engine, get_request("/metrics")).await).await;
+        assert_eq!(metrics["total_requests"], 1);
+        assert!(metrics["avg_latency_ms"].as_f64().unwrap() > 0.0);
+        assert!(metrics["throughput"].as_f64().unwrap() > 0.0);
+
+        let health = json_body(send(&engine, get_request("/health")).await).await;
+        assert_eq!(health["status"], "healthy");
+        assert!(health["uptime"].as_u64().unwrap() <= engine.uptime().as_secs());
+    }
+
+    #[tokio::test]
+    async fn streamed_predictions_end_with_done() {
+        let (engine, _dir) = counting_engine(&["bert-tiny"], Config::default()).await;
+
+        let response = send(&engine, post_json("/predict/stream", serde_json::json!({ "input": "abc", "model": "bert-tiny" }))).await;
+
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(response.headers()[axum::http::header::CONTENT_TYPE], "text/event-stream");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("data: 3 tokens on bert-tiny\n\n"), "{}", body);
+        assert!(body.ends_with("data: [DONE]\n\n"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn dropping_a_stream_stops_its_inference() {
+        let mut config = Config::default();
+        config.model.auto_download = false;
+        config.model.models.entry("bert-tiny".to_string()).or_default().backend = Some("stall".to_string());
+        let (engine, dir) = test_engine(config).await;
+        engine.register_backend("stall", || Ok(Box::new(Stall)));
+        engine.load_model(&write_model(dir.path(), "bert-tiny")).await.unwrap();
+
+        let mut chunks = Box::pin(engine.infer_stream(b"abc".to_vec(), &scope("bert-tiny")).await.unwrap());
+        assert!(tokio::time::timeout(Duration::from_millis(50), chunks.next()).await.is_err());
+        assert_eq!(engine.active_inferences(), 1);
+        assert_eq!(engine.shutdown.in_flight().count(), 1);
+
+        drop(chunks);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(engine.active_inferences(), 0);
+        assert_eq!(engine.shutdown.in_flight().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_the_statsd_exporter() {
+        let mut config = Config::default();
+        config.monitoring.metrics = true;
+        config.monitoring.statsd_endpoint = Some("127.0.0.1:9".to_string());
+        let (engine, _dir) = test_engine(config).await;
+        let exporter = engine.statsd_exporter.lock().as_ref().expect("exporter not started").abort_handle();
+
+        engine.shutdown().await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(exporter.is_finished());
+        assert!(engine.statsd_exporter.lock().is_none());
     }
 }
@@ -1,5 +1,6 @@
 //! Error types for the Synaptron inference engine
 
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Synaptron error types
@@ -21,14 +22,34 @@ pub enum SynaptronError {
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
 
+    /// Remote configuration fetch error
+    #[error("Remote configuration error: {0}")]
+    RemoteConfig(String),
+
     /// HTTP server error
     #[error("HTTP server error: {0}")]
     HttpServer(#[from] axum::http::Error),
 
+    /// Invalid request input
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Model not loaded or not visible to the caller
+    #[error("Model not found: {0}")]
+    ModelNotFound(String),
+
+    /// Model temporarily unavailable
+    #[error("Model unavailable: {0}")]
+    ModelUnavailable(String),
+
     /// Model loading error
     #[error("Model loading error: {0}")]
     ModelLoad(String),
 
+    /// Model format rejected by configuration
+    #[error("Unsupported model format: {0}")]
+    UnsupportedFormat(String),
+
     /// Device selection error
     #[error("Device selection error: {0}")]
     DeviceSelection(String),
@@ -49,6 +70,16 @@ pub enum SynaptronError {
     #[error("Graph execution error: {0}")]
     GraphExecution(String),
 
+    /// A graph node failed or timed out, failing the execution
+    #[error("Graph node {node} failed: {source}")]
+    GraphNode {
+        /// ID of the failed node
+        node: String,
+
+        /// Why the node failed
+        source: Box<SynaptronError>,
+    },
+
     /// Optimization error
     #[error("Optimization error: {0}")]
     Optimization(String),
@@ -68,4 +99,53 @@ pub enum SynaptronError {
     /// Any other error
     #[error("Other error: {0}")]
     Other(String),
+
+    /// One error handed to every caller of a coalesced operation
+    ///
+    /// Model loads and response cache refreshes fail with this, so match on
+    /// `root()` rather than on the variant returned.
+    #[error(transparent)]
+    Shared(Arc<SynaptronError>),
+}
+
+impl SynaptronError {
+    /// Short snake_case name of the error kind, used as a metrics label
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SynaptronError::Io(_) => "io",
+            SynaptronError::Json(_) => "json",
+            SynaptronError::Yaml(_) => "yaml",
+            SynaptronError::Config(_) => "config",
+            SynaptronError::RemoteConfig(_) => "remote_config",
+            SynaptronError::HttpServer(_) => "http_server",
+            SynaptronError::InvalidInput(_) => "invalid_input",
+            SynaptronError::ModelNotFound(_) => "model_not_found",
+            SynaptronError::ModelUnavailable(_) => "model_unavailable",
+            SynaptronError::ModelLoad(_) => "model_load",
+            SynaptronError::UnsupportedFormat(_) => "unsupported_format",
+            SynaptronError::DeviceSelection(_) => "device_selection",
+            SynaptronError::Inference(_) => "inference",
+            SynaptronError::BackendInit(_) => "backend_init",
+            SynaptronError::Tokenization(_) => "tokenization",
+            SynaptronError::GraphExecution(_) => "graph_execution",
+            SynaptronError::GraphNode { .. } => "graph_node",
+            SynaptronError::Optimization(_) => "optimization",
+            SynaptronError::Cache(_) => "cache",
+            SynaptronError::Batch(_) => "batch",
+            SynaptronError::Multimodal(_) => "multimodal",
+            SynaptronError::Other(_) => "other",
+            SynaptronError::Shared(source) => source.kind(),
+        }
+    }
+
+    /// The error itself, or the error it shares
+    ///
+    /// Callers telling errors apart should match on this, since coalesced
+    /// operations wrap their error in `Shared`.
+    pub fn root(&self) -> &SynaptronError {
+        match self {
+            SynaptronError::Shared(source) => source.root(),
+            _ => self,
+        }
+    }
 }
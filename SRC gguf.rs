@@ -0,0 +1,421 @@
+//! GGUF model file parsing for the Synaptron inference engine
+
+use crate::error::SynaptronError;
+use std::collections::HashMap;
+
+/// Leading bytes of a GGUF file
+pub const GGUF_MAGIC: &[u8] = b"GGUF";
+
+/// Oldest supported GGUF version; version 1 used 32-bit counts
+const MIN_VERSION: u32 = 2;
+
+/// Deepest nesting of arrays accepted, so a corrupt header can't exhaust the stack
+const MAX_ARRAY_DEPTH: usize = 8;
+
+/// Metadata value of a GGUF key
+#[derive(Debug, Clone, PartialEq)]
+pub enum GgufValue {
+    /// Any unsigned integer type
+    Uint(u64),
+
+    /// Any signed integer type
+    Int(i64),
+
+    /// Any floating point type
+    Float(f64),
+
+    /// Boolean
+    Bool(bool),
+
+    /// UTF-8 string
+    String(String),
+
+    /// Array, kept only as its element count since token lists run to 100k+ entries
+    Array(u64),
+}
+
+impl GgufValue {
+    /// Value as an unsigned integer, if it is a non-negative integer
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            GgufValue::Uint(v) => Some(*v),
+            GgufValue::Int(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Value as a string slice, if it is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GgufValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Tensor entry of a GGUF header
+#[derive(Debug, Clone)]
+pub struct GgufTensor {
+    /// Tensor name
+    pub name: String,
+
+    /// Dimensions, innermost first as GGUF stores them
+    pub dims: Vec<u64>,
+
+    /// ggml element type id
+    pub ggml_type: u32,
+}
+
+/// Parsed GGUF header: version, key-value metadata and tensor descriptions
+#[derive(Debug, Clone)]
+pub struct Gguf {
+    /// Format version
+    pub version: u32,
+
+    /// Key-value metadata, such as `general.architecture`
+    pub metadata: HashMap<String, GgufValue>,
+
+    /// Tensor descriptions in file order
+    pub tensors: Vec<GgufTensor>,
+}
+
+/// Bounds-checked little-endian reader over a GGUF header
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Take the next `len` bytes
+    fn take(&mut self, len: u64) -> Result<&'a [u8], SynaptronError> {
+        let end = usize::try_from(len).ok()
+            .and_then(|len| self.pos.checked_add(len))
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("truncated header"))?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    /// Take the next `N` bytes as an array
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SynaptronError> {
+        Ok(self.take(N as u64)?.try_into().expect("N bytes"))
+    }
+
+    /// Read a little-endian u32
+    fn u32(&mut self) -> Result<u32, SynaptronError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    /// Read a little-endian u64
+    fn u64(&mut self) -> Result<u64, SynaptronError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    /// Read a u64-length-prefixed UTF-8 string
+    fn string(&mut self) -> Result<String, SynaptronError> {
+        let len = self.u64()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid("string is not UTF-8"))
+    }
+
+    /// Read a count that can't exceed the bytes left, so corrupt counts can't force huge allocations
+    fn count(&mut self, what: &str) -> Result<u64, SynaptronError> {
+        let count = self.u64()?;
+        if count > (self.bytes.len() - self.pos) as u64 {
+            return Err(invalid(&format!("{} count {} exceeds the file size", what, count)));
+        }
+        Ok(count)
+    }
+
+    /// Read a value of a GGUF value type id
+    fn value(&mut self, value_type: u32) -> Result<GgufValue, SynaptronError> {
+        self.nested_value(value_type, 0)
+    }
+
+    /// Read a value found inside `depth` enclosing arrays
+    fn nested_value(&mut self, value_type: u32, depth: usize) -> Result<GgufValue, SynaptronError> {
+        Ok(match value_type {
+            0 => GgufValue::Uint(self.array::<1>()?[0] as u64),
+            1 => GgufValue::Int(i8::from_le_bytes(self.array()?) as i64),
+            2 => GgufValue::Uint(u16::from_le_bytes(self.array()?) as u64),
+            3 => GgufValue::Int(i16::from_le_bytes(self.array()?) as i64),
+            4 => GgufValue::Uint(self.u32()? as u64),
+            5 => GgufValue::Int(i32::from_le_bytes(self.array()?) as i64),
+            6 => GgufValue::Float(f32::from_le_bytes(self.array()?) as f64),
+            7 => GgufValue::Bool(self.array::<1>()?[0] != 0),
+            8 => GgufValue::String(self.string()?),
+            9 => {
+                if depth >= MAX_ARRAY_DEPTH {
+                    return Err(invalid(&format!("arrays nested deeper than {}", MAX_ARRAY_DEPTH)));
+                }
+                let element_type = self.u32()?;
+                let len = self.count("array")?;
+                for _ in 0..len {
+                    self.nested_value(element_type, depth + 1)?;
+                }
+                GgufValue::Array(len)
+            }
+            10 => GgufValue::Uint(self.u64()?),
+            11 => GgufValue::Int(i64::from_le_bytes(self.array()?)),
+            12 => GgufValue::Float(f64::from_le_bytes(self.array()?)),
+            other => return Err(invalid(&format!("unknown value type {}", other))),
+        })
+    }
+}
+
+/// Error for a malformed GGUF file
+fn invalid(reason: &str) -> SynaptronError {
+    SynaptronError::UnsupportedFormat(format!("Invalid GGUF file: {}", reason))
+}
+
+/// Name of a ggml tensor element type
+pub fn ggml_type_name(ggml_type: u32) -> String {
+    let name = match ggml_type {
+        0 => "f32",
+        1 => "f16",
+        2 => "q4_0",
+        3 => "q4_1",
+        6 => "q5_0",
+        7 => "q5_1",
+        8 => "q8_0",
+        9 => "q8_1",
+        10 => "q2_k",
+        11 => "q3_k",
+        12 => "q4_k",
+        13 => "q5_k",
+        14 => "q6_k",
+        15 => "q8_k",
+        24 => "i8",
+        25 => "i16",
+        26 => "i32",
+        27 => "i64",
+        28 => "f64",
+        30 => "bf16",
+        other => return format!("ggml_type_{}", other),
+    };
+    name.to_string()
+}
+
+/// Name of a llama.cpp `general.file_type`, the quantization the file was produced with
+pub fn file_type_name(file_type: u64) -> Option<&'static str> {
+    Some(match file_type {
+        0 => "f32",
+        1 => "f16",
+        2 => "q4_0",
+        3 => "q4_1",
+        7 => "q8_0",
+        8 => "q5_0",
+        9 => "q5_1",
+        10 => "q2_k",
+        11 => "q3_k_s",
+        12 => "q3_k_m",
+        13 => "q3_k_l",
+        14 => "q4_k_s",
+        15 => "q4_k_m",
+        16 => "q5_k_s",
+        17 => "q5_k_m",
+        18 => "q6_k",
+        32 => "bf16",
+        _ => return None,
+    })
+}
+
+impl Gguf {
+    /// Parse the header of a GGUF file: magic, version, counts, metadata, then tensor descriptions
+    pub fn parse(bytes: &[u8]) -> Result<Self, SynaptronError> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        if reader.take(GGUF_MAGIC.len() as u64)? != GGUF_MAGIC {
+            return Err(invalid("missing GGUF magic"));
+        }
+        let version = reader.u32()?;
+        if version < MIN_VERSION {
+            return Err(invalid(&format!("version {} is not supported", version)));
+        }
+
+        let tensor_count = reader.count("tensor")?;
+        let kv_count = reader.count("metadata")?;
+
+        let mut metadata = HashMap::new();
+        for _ in 0..kv_count {
+            let key = reader.string()?;
+            let value_type = reader.u32()?;
+            let value = reader.value(value_type)?;
+            metadata.insert(key, value);
+        }
+
+        let mut tensors = Vec::new();
+        for _ in 0..tensor_count {
+            let name = reader.string()?;
+            let n_dims = reader.u32()?;
+            let dims = (0..n_dims).map(|_| reader.u64()).collect::<Result<Vec<_>, _>>()?;
+            let ggml_type = reader.u32()?;
+            let _offset = reader.u64()?;
+            tensors.push(GgufTensor { name, dims, ggml_type });
+        }
+
+        Ok(Self { version, metadata, tensors })
+    }
+
+    /// Metadata value of a key
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+
+    /// Model architecture from `general.architecture`, such as `llama`
+    pub fn architecture(&self) -> Option<&str> {
+        self.get("general.architecture").and_then(GgufValue::as_str)
+    }
+
+    /// Architecture-scoped integer, such as `llama.context_length`
+    fn arch_u64(&self, key: &str) -> Option<u64> {
+        let architecture = self.architecture()?;
+        self.get(&format!("{}.{}", architecture, key)).and_then(GgufValue::as_u64)
+    }
+
+    /// Maximum context length in tokens
+    pub fn context_length(&self) -> Option<usize> {
+        self.arch_u64("context_length").map(|v| v as usize)
+    }
+
+    /// Hidden state width
+    pub fn embedding_length(&self) -> Option<usize> {
+        self.arch_u64("embedding_length").map(|v| v as usize)
+    }
+
+    /// Vocabulary size, from the tokenizer's token list or the architecture's `vocab_size`
+    pub fn vocab_size(&self) -> Option<usize> {
+        match self.get("tokenizer.ggml.tokens") {
+            Some(GgufValue::Array(len)) => Some(*len as usize),
+            _ => self.arch_u64("vocab_size").map(|v| v as usize),
+        }
+    }
+
+    /// Quantization type, from `general.file_type` or else the most common type of the matrix tensors
+    pub fn quantization(&self) -> Option<String> {
+        if let Some(name) = self.get("general.file_type").and_then(GgufValue::as_u64).and_then(file_type_name) {
+            return Some(name.to_string());
+        }
+
+        // Norms and biases stay f32 in quantized files, so count only matrices
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for tensor in self.tensors.iter().filter(|tensor| tensor.dims.len() >= 2) {
+            *counts.entry(tensor.ggml_type).or_default() += 1;
+        }
+        counts.into_iter()
+            .max_by_key(|(ggml_type, count)| (*count, *ggml_type))
+            .map(|(ggml_type, _)| ggml_type_name(ggml_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GGUF string: u64 length, then UTF-8 bytes
+    fn string(value: &str) -> Vec<u8> {
+        [&(value.len() as u64).to_le_bytes()[..], value.as_bytes()].concat()
+    }
+
+    /// Metadata entry of a value type id and its encoded value
+    fn entry(key: &str, value_type: u32, value: &[u8]) -> Vec<u8> {
+        [&string(key)[..], &value_type.to_le_bytes()[..], value].concat()
+    }
+
+    /// Tensor description of a ggml type
+    fn tensor(name: &str, dims: &[u64], ggml_type: u32) -> Vec<u8> {
+        let mut bytes = string(name);
+        bytes.extend((dims.len() as u32).to_le_bytes());
+        for dim in dims {
+            bytes.extend(dim.to_le_bytes());
+        }
+        bytes.extend(ggml_type.to_le_bytes());
+        bytes.extend(0u64.to_le_bytes());
+        bytes
+    }
+
+    /// GGUF file of a version with metadata entries and tensor descriptions
+    fn file(version: u32, entries: &[Vec<u8>], tensors: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = GGUF_MAGIC.to_vec();
+        bytes.extend(version.to_le_bytes());
+        bytes.extend((tensors.len() as u64).to_le_bytes());
+        bytes.extend((entries.len() as u64).to_le_bytes());
+        bytes.extend(entries.concat());
+        bytes.extend(tensors.concat());
+        bytes
+    }
+
+    /// Array of `len` strings
+    fn string_array(len: u64) -> Vec<u8> {
+        let mut bytes = 8u32.to_le_bytes().to_vec();
+        bytes.extend(len.to_le_bytes());
+        for i in 0..len {
+            bytes.extend(string(&format!("token{}", i)));
+        }
+        bytes
+    }
+
+    #[test]
+    fn metadata_gives_architecture_shapes_and_quantization() {
+        let data = file(3, &[
+            entry("general.architecture", 8, &string("llama")),
+            entry("llama.context_length", 4, &4096u32.to_le_bytes()),
+            entry("llama.embedding_length", 10, &4096u64.to_le_bytes()),
+            entry("general.file_type", 4, &15u32.to_le_bytes()),
+            entry("tokenizer.ggml.tokens", 9, &string_array(3)),
+        ], &[]);
+
+        let gguf = Gguf::parse(&data).unwrap();
+
+        assert_eq!(gguf.version, 3);
+        assert_eq!(gguf.architecture(), Some("llama"));
+        assert_eq!(gguf.context_length(), Some(4096));
+        assert_eq!(gguf.embedding_length(), Some(4096));
+        assert_eq!(gguf.vocab_size(), Some(3));
+        assert_eq!(gguf.quantization().as_deref(), Some("q4_k_m"));
+    }
+
+    #[test]
+    fn quantization_falls_back_to_the_matrix_tensor_types() {
+        let data = file(3, &[], &[
+            tensor("blk.0.attn_q.weight", &[4096, 4096], 8),
+            tensor("blk.0.attn_k.weight", &[4096, 4096], 8),
+            tensor("blk.0.attn_norm.weight", &[4096], 0),
+            tensor("output_norm.weight", &[4096], 0),
+        ]);
+
+        let gguf = Gguf::parse(&data).unwrap();
+
+        assert_eq!(gguf.tensors.len(), 4);
+        assert_eq!(gguf.tensors[0].dims, [4096, 4096]);
+        assert_eq!(gguf.quantization().as_deref(), Some("q8_0"));
+    }
+
+    #[test]
+    fn deeply_nested_arrays_are_rejected() {
+        let mut nested = Vec::new();
+        for _ in 0..MAX_ARRAY_DEPTH + 1 {
+            nested.extend(9u32.to_le_bytes());
+            nested.extend(1u64.to_le_bytes());
+        }
+        nested.extend([0u8; 64]);
+        let data = file(3, &[entry("nested", 9, &nested)], &[]);
+
+        let err = Gguf::parse(&data).unwrap_err();
+
+        assert!(err.to_string().contains("nested deeper"), "{}", err);
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let valid = file(3, &[entry("general.architecture", 8, &string("llama"))], &[]);
+        let mut huge_count = valid.clone();
+        huge_count[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        assert!(Gguf::parse(&file(1, &[], &[])).is_err(), "version 1 is unsupported");
+        assert!(Gguf::parse(b"GGML\x03\x00\x00\x00").is_err());
+        assert!(Gguf::parse(&valid[..valid.len() - 2]).is_err());
+        assert!(Gguf::parse(&huge_count).is_err());
+    }
+}
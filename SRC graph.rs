@@ -1,10 +1,128 @@
 //! Dynamic model graph implementation for the Synaptron inference engine
 
-use crate::{model::Model, error::SynaptronError};
-use tracing::{info, debug};
-use std::collections::HashMap;
+use crate::{model::ModelInputType, error::SynaptronError, utils::tensor};
+use tracing::{info, info_span, debug, warn, Instrument};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
+/// Transformation applied to data flowing along a graph edge
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EdgeAdapter {
+    /// Pass bytes through unchanged
+    Passthrough,
+    
+    /// Decode producer output into clean UTF-8 text for a text consumer,
+    /// which tokenizes it during its own preprocessing
+    DecodeText,
+}
+
+impl EdgeAdapter {
+    /// Select the adapter for a producer output modality and consumer input modality
+    pub fn select(produced: &ModelInputType, consumed: &ModelInputType) -> Option<Self> {
+        match (produced, consumed) {
+            (ModelInputType::Text, ModelInputType::Text) => Some(EdgeAdapter::DecodeText),
+            (produced, consumed) if produced == consumed => Some(EdgeAdapter::Passthrough),
+            _ => None,
+        }
+    }
+    
+    /// Apply the adapter to producer output
+    pub fn apply(&self, output: Vec<u8>) -> Vec<u8> {
+        match self {
+            EdgeAdapter::Passthrough => output,
+            EdgeAdapter::DecodeText => String::from_utf8_lossy(&output)
+                .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+                .as_bytes()
+                .to_vec(),
+        }
+    }
+}
+
+/// Modality a model produces
+///
+/// Speech models transcribe to text and text models generate text, while
+/// image models produce image features.
+pub fn output_modality(input_type: &ModelInputType) -> ModelInputType {
+    match input_type {
+        ModelInputType::Audio | ModelInputType::Text => ModelInputType::Text,
+        ModelInputType::Image => ModelInputType::Image,
+    }
+}
+
+/// Custom merge function combining a node's inputs, in declared order
+pub type MergeFn = Arc<dyn Fn(&[Vec<u8>]) -> Result<Vec<u8>, SynaptronError> + Send + Sync>;
+
+/// How a node with several inputs combines them before inference
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum MergeStrategy {
+    /// Concatenate inputs in declared order
+    Concat,
+    
+    /// Use only the first input
+    #[default]
+    First,
+    
+    /// Element-wise sum of equally sized little-endian f32 tensors
+    Sum,
+    
+    /// Custom merge function; only available when building graphs in code
+    #[serde(skip)]
+    Custom(MergeFn),
+}
+
+impl fmt::Debug for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeStrategy::Concat => write!(f, "Concat"),
+            MergeStrategy::First => write!(f, "First"),
+            MergeStrategy::Sum => write!(f, "Sum"),
+            MergeStrategy::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl MergeStrategy {
+    /// Merge a node's inputs
+    pub fn merge(&self, inputs: Vec<Vec<u8>>) -> Result<Vec<u8>, SynaptronError> {
+        match self {
+            MergeStrategy::Concat => Ok(inputs.concat()),
+            MergeStrategy::First => inputs.into_iter().next().ok_or_else(|| {
+                SynaptronError::GraphExecution("No inputs to merge".to_string())
+            }),
+            MergeStrategy::Sum => Self::sum_f32(&inputs),
+            MergeStrategy::Custom(merge) => merge(&inputs),
+        }
+    }
+    
+    /// Element-wise sum of little-endian f32 tensors
+    fn sum_f32(inputs: &[Vec<u8>]) -> Result<Vec<u8>, SynaptronError> {
+        let len = inputs.first()
+            .ok_or_else(|| SynaptronError::GraphExecution("No inputs to merge".to_string()))?
+            .len();
+        
+        if len % 4 != 0 || inputs.iter().any(|input| input.len() != len) {
+            return Err(SynaptronError::GraphExecution(format!(
+                "Sum merge needs f32 inputs of equal length, got lengths {:?}",
+                inputs.iter().map(Vec::len).collect::<Vec<_>>()
+            )));
+        }
+        
+        let mut sums = vec![0f32; len / 4];
+        for input in inputs {
+            for (sum, value) in sums.iter_mut().zip(tensor::bytes_to_f32(input)?) {
+                *sum += value;
+            }
+        }
+        
+        Ok(tensor::f32_to_bytes(&sums))
+    }
+}
+
 /// Graph node
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphNode {
@@ -14,11 +132,132 @@ pub struct GraphNode {
     /// Model name
     pub model_name: String,
     
-    /// Input node IDs
+    /// Input node IDs; `input` is the graph's initial input
+    #[serde(default)]
     pub inputs: Vec<String>,
     
     /// Output node IDs
+    #[serde(default)]
     pub outputs: Vec<String>,
+    
+    /// Adapter applied to this node's inputs, overriding automatic selection
+    #[serde(default)]
+    pub adapter: Option<EdgeAdapter>,
+    
+    /// How inputs from several upstream nodes are combined
+    #[serde(default)]
+    pub merge: MergeStrategy,
+    
+    /// Time limit for this node's inference in milliseconds, 0 for no limit
+    #[serde(default)]
+    pub timeout_ms: u64,
+    
+    /// What happens to the execution when this node fails or times out
+    #[serde(default)]
+    pub on_error: OnError,
+}
+
+impl GraphNode {
+    /// Time limit for this node's inference, `None` for no limit
+    pub fn timeout(&self) -> Option<Duration> {
+        crate::config::TimeoutsConfig::to_duration(self.timeout_ms)
+    }
+}
+
+/// Policy for a graph node that fails or times out
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum OnError {
+    /// Fail the whole execution with an error naming the node
+    #[default]
+    Fail,
+    
+    /// Pass the node's input on as its output
+    SkipNode,
+    
+    /// Use the output of an upstream node, or `input` for the graph input
+    UseFallback(String),
+}
+
+/// Timing of a graph node across executions
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeTiming {
+    /// Executions of the node, including failed ones
+    pub runs: u64,
+    
+    /// Executions that failed, including timeouts
+    pub failures: u64,
+    
+    /// Executions cut off by the node's timeout
+    pub timeouts: u64,
+    
+    /// Average latency in milliseconds
+    pub avg_latency_ms: f64,
+    
+    /// Slowest latency in milliseconds
+    pub max_latency_ms: f64,
+    
+    /// Latency of the latest execution in milliseconds
+    pub last_latency_ms: f64,
+}
+
+impl NodeTiming {
+    /// Record one execution
+    fn record(&mut self, elapsed: Duration, failed: bool, timed_out: bool) {
+        let latency_ms = elapsed.as_secs_f64() * 1000.0;
+        
+        self.runs += 1;
+        self.failures += failed as u64;
+        self.timeouts += timed_out as u64;
+        self.avg_latency_ms += (latency_ms - self.avg_latency_ms) / self.runs as f64;
+        self.max_latency_ms = self.max_latency_ms.max(latency_ms);
+        self.last_latency_ms = latency_ms;
+    }
+}
+
+/// Whether `target` feeds `node`, directly or through other nodes
+fn is_upstream(nodes: &HashMap<&str, &GraphNode>, node: &GraphNode, target: &str) -> bool {
+    let mut pending: Vec<&str> = node.inputs.iter().map(String::as_str).collect();
+    let mut seen = HashSet::new();
+    
+    while let Some(id) = pending.pop() {
+        if id == target {
+            return true;
+        }
+        if seen.insert(id) {
+            if let Some(producer) = nodes.get(id) {
+                pending.extend(producer.inputs.iter().map(String::as_str));
+            }
+        }
+    }
+    
+    false
+}
+
+/// Node ID under which the graph's initial input is available
+pub const GRAPH_INPUT: &str = "input";
+
+/// Declarative graph definition, read from JSON or YAML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphSpec {
+    /// Graph nodes; each node's `inputs` are its incoming edges
+    pub nodes: Vec<GraphNode>,
+}
+
+impl GraphSpec {
+    /// Read a graph definition from a `.json`, `.yaml` or `.yml` file
+    pub fn from_file(path: &str) -> Result<Self, SynaptronError> {
+        let contents = std::fs::read_to_string(path)?;
+        
+        if path.ends_with(".json") {
+            serde_json::from_str(&contents).map_err(|e| {
+                SynaptronError::GraphExecution(format!("Invalid graph file {}: {}", path, e))
+            })
+        } else {
+            serde_yaml::from_str(&contents).map_err(|e| {
+                SynaptronError::GraphExecution(format!("Invalid graph file {}: {}", path, e))
+            })
+        }
+    }
 }
 
 /// Model graph
@@ -28,6 +267,12 @@ pub struct ModelGraph {
     
     /// Execution order
     execution_order: Vec<String>,
+    
+    /// Input types of known models, used to validate edges at build time
+    model_types: HashMap<String, ModelInputType>,
+    
+    /// Per-node timing, shared by clones of the graph
+    timings: Arc<Mutex<HashMap<String, NodeTiming>>>,
 }
 
 impl ModelGraph {
@@ -36,15 +281,165 @@ impl ModelGraph {
         Self {
             nodes: HashMap::new(),
             execution_order: Vec::new(),
+            model_types: HashMap::new(),
+            timings: Arc::new(Mutex::new(HashMap::new())),
         }
     }
     
+    /// Build a graph from a definition
+    ///
+    /// Every node's model must satisfy `is_loadable`, and every input and output
+    /// ID must name another node (or `input` for the initial input). Edges are
+    /// validated against `model_types`, the input types of loaded models.
+    pub fn from_config(
+        spec: &GraphSpec,
+        is_loadable: impl Fn(&str) -> bool,
+        model_types: &HashMap<String, ModelInputType>,
+    ) -> Result<Self, SynaptronError> {
+        let mut node_ids = HashSet::new();
+        for node in &spec.nodes {
+            if node.id == GRAPH_INPUT {
+                return Err(SynaptronError::GraphExecution(format!(
+                    "Node ID '{}' is reserved for the graph input", GRAPH_INPUT
+                )));
+            }
+            if !node_ids.insert(node.id.as_str()) {
+                return Err(SynaptronError::GraphExecution(format!("Duplicate node ID: {}", node.id)));
+            }
+        }
+        
+        for node in &spec.nodes {
+            if !is_loadable(&node.model_name) {
+                return Err(SynaptronError::GraphExecution(format!(
+                    "Node {} references unknown model: {}", node.id, node.model_name
+                )));
+            }
+            if let Some(input_id) = node.inputs.iter().find(|id| *id != GRAPH_INPUT && !node_ids.contains(id.as_str())) {
+                return Err(SynaptronError::GraphExecution(format!(
+                    "Node {} references unknown input node: {}", node.id, input_id
+                )));
+            }
+            if let Some(output_id) = node.outputs.iter().find(|id| !node_ids.contains(id.as_str())) {
+                return Err(SynaptronError::GraphExecution(format!(
+                    "Node {} references unknown output node: {}", node.id, output_id
+                )));
+            }
+        }
+        
+        // A fallback's output must exist by the time its node runs
+        let by_id: HashMap<&str, &GraphNode> = spec.nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+        for node in &spec.nodes {
+            if let OnError::UseFallback(fallback) = &node.on_error {
+                if fallback != GRAPH_INPUT && !is_upstream(&by_id, node, fallback) {
+                    return Err(SynaptronError::GraphExecution(format!(
+                        "Node {} falls back to {}, which is not upstream of it", node.id, fallback
+                    )));
+                }
+            }
+        }
+        
+        let mut graph = Self::new();
+        for (model_name, input_type) in model_types {
+            graph.set_model_type(model_name, input_type.clone());
+        }
+        for node in &spec.nodes {
+            graph.add_node(node.clone())?;
+        }
+        
+        info!("Built model graph with {} nodes", spec.nodes.len());
+        Ok(graph)
+    }
+    
+    /// Graph definition, nodes in execution order
+    pub fn spec(&self) -> GraphSpec {
+        GraphSpec {
+            nodes: self.execution_order.iter()
+                .filter_map(|id| self.nodes.get(id))
+                .cloned()
+                .collect(),
+        }
+    }
+    
+    /// Whether any node runs a model
+    pub fn uses_model(&self, model_name: &str) -> bool {
+        self.nodes.values().any(|node| node.model_name == model_name)
+    }
+    
+    /// Node IDs in execution order
+    pub fn execution_order(&self) -> &[String] {
+        &self.execution_order
+    }
+    
+    /// Timing of each node that has run, keyed by node ID
+    pub fn node_timings(&self) -> HashMap<String, NodeTiming> {
+        self.timings.lock().clone()
+    }
+    
+    /// Record a model's input type so edges using it can be validated when added
+    pub fn set_model_type(&mut self, model_name: &str, input_type: ModelInputType) {
+        self.model_types.insert(model_name.to_string(), input_type);
+    }
+    
     /// Add a node to the graph
+    ///
+    /// Edges between models with known input types are validated immediately;
+    /// edges involving unknown models are checked at execution instead.
     pub fn add_node(&mut self, node: GraphNode) -> Result<(), SynaptronError> {
         info!("Adding node to graph: {}", node.id);
         
-        self.nodes.insert(node.id.clone(), node);
-        self.update_execution_order()?;
+        let node_id = node.id.clone();
+        let previous = self.nodes.insert(node_id.clone(), node);
+        
+        let result = match self.validate_node_edges(&node_id) {
+            Ok(()) => self.update_execution_order(),
+            Err(e) => Err(e),
+        };
+        
+        if let Err(e) = result {
+            // Roll back so the graph stays valid
+            match previous {
+                Some(previous) => self.nodes.insert(node_id, previous),
+                None => self.nodes.remove(&node_id),
+            };
+            return Err(e);
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate every edge into and out of a node against the known model types
+    fn validate_node_edges(&self, node_id: &str) -> Result<(), SynaptronError> {
+        let node = match self.nodes.get(node_id) {
+            Some(node) => node,
+            None => return Ok(()),
+        };
+        
+        let incoming = node.inputs.iter()
+            .filter_map(|input_id| self.nodes.get(input_id))
+            .map(|producer| (producer, node));
+        let outgoing = self.nodes.values()
+            .filter(|consumer| consumer.inputs.iter().any(|id| id == node_id))
+            .map(|consumer| (node, consumer));
+        
+        for (producer, consumer) in incoming.chain(outgoing) {
+            let types = (
+                self.model_types.get(&producer.model_name),
+                self.model_types.get(&consumer.model_name),
+            );
+            
+            match types {
+                (Some(producer_type), Some(consumer_type)) => {
+                    Self::resolve_adapter(producer, consumer, producer_type, consumer_type)?;
+                }
+                _ if consumer.adapter.is_none() => {
+                    warn!(
+                        "Cannot validate edge {} -> {} yet, model not loaded; deferring to execution",
+                        producer.id, consumer.id
+                    );
+                }
+                _ => {}
+            }
+        }
         
         Ok(())
     }
@@ -59,17 +454,75 @@ impl ModelGraph {
         Ok(())
     }
     
+    /// Resolve the adapter for the edge from `producer` into `consumer`
+    fn edge_adapter(
+        &self,
+        producer: &GraphNode,
+        consumer: &GraphNode,
+        model_types: &HashMap<String, ModelInputType>,
+    ) -> Result<EdgeAdapter, SynaptronError> {
+        match (model_types.get(&producer.model_name), model_types.get(&consumer.model_name)) {
+            (Some(producer_type), Some(consumer_type)) => {
+                Self::resolve_adapter(producer, consumer, producer_type, consumer_type)
+            }
+            _ => Ok(consumer.adapter.unwrap_or(EdgeAdapter::Passthrough)),
+        }
+    }
+    
+    /// Pick the configured or automatic adapter for an edge between model input types
+    fn resolve_adapter(
+        producer: &GraphNode,
+        consumer: &GraphNode,
+        producer_type: &ModelInputType,
+        consumer_type: &ModelInputType,
+    ) -> Result<EdgeAdapter, SynaptronError> {
+        if let Some(adapter) = consumer.adapter {
+            return Ok(adapter);
+        }
+        
+        let produced = output_modality(producer_type);
+        EdgeAdapter::select(&produced, consumer_type).ok_or_else(|| {
+            SynaptronError::GraphExecution(format!(
+                "Incompatible edge {} -> {}: {:?} output cannot feed {:?} input without an adapter",
+                producer.id, consumer.id, produced, consumer_type
+            ))
+        })
+    }
+    
+    /// Validate that every edge connects compatible modalities or has an adapter
+    ///
+    /// `model_types` holds the input type of each loaded model.
+    pub fn validate(&self, model_types: &HashMap<String, ModelInputType>) -> Result<(), SynaptronError> {
+        debug!("Validating graph edges");
+        
+        for consumer in self.nodes.values() {
+            for input_id in &consumer.inputs {
+                if let Some(producer) = self.nodes.get(input_id) {
+                    self.edge_adapter(producer, consumer, model_types)?;
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
     /// Update execution order based on dependencies
+    ///
+    /// Errors without changing the current order if the graph contains a cycle.
     fn update_execution_order(&mut self) -> Result<(), SynaptronError> {
         debug!("Updating execution order");
         
-        // Simple topological sort implementation
-        let mut visited = HashMap::new();
+        // Depth-first topological sort, visiting nodes in a stable order
+        let mut visited = HashSet::new();
+        let mut path = Vec::new();
         let mut order = Vec::new();
         
-        for (node_id, _) in &self.nodes {
-            if !visited.contains_key(node_id) {
-                self.topological_sort(node_id, &mut visited, &mut order)?;
+        let mut node_ids: Vec<&String> = self.nodes.keys().collect();
+        node_ids.sort();
+        
+        for node_id in node_ids {
+            if !visited.contains(node_id) {
+                self.topological_sort(node_id, &mut visited, &mut path, &mut order)?;
             }
         }
         
@@ -78,39 +531,132 @@ impl ModelGraph {
     }
     
     /// Topological sort helper
+    ///
+    /// `path` holds the nodes on the current recursion stack, so reaching one of
+    /// them again means a cycle.
     fn topological_sort(
         &self,
         node_id: &str,
-        visited: &mut HashMap<String, bool>,
+        visited: &mut HashSet<String>,
+        path: &mut Vec<String>,
         order: &mut Vec<String>,
     ) -> Result<(), SynaptronError> {
-        // Mark as visited
-        visited.insert(node_id.to_string(), true);
+        if let Some(start) = path.iter().position(|id| id == node_id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(node_id.to_string());
+            return Err(SynaptronError::GraphExecution(format!(
+                "cycle detected: {}", cycle.join(" -> ")
+            )));
+        }
+        
+        if visited.contains(node_id) {
+            return Ok(());
+        }
+        
+        path.push(node_id.to_string());
         
         // Visit all dependencies first
         if let Some(node) = self.nodes.get(node_id) {
             for input_id in &node.inputs {
-                if self.nodes.contains_key(input_id) && !visited.contains_key(input_id) {
-                    self.topological_sort(input_id, visited, order)?;
+                if self.nodes.contains_key(input_id) {
+                    self.topological_sort(input_id, visited, path, order)?;
                 }
             }
         }
         
+        path.pop();
+        visited.insert(node_id.to_string());
+        
         // Add to order
         order.push(node_id.to_string());
         Ok(())
     }
     
-    /// Execute the graph
-    pub async fn execute(
+    /// Terminal node IDs (nodes no other node consumes), sorted for determinism
+    pub fn leaf_nodes(&self) -> Vec<String> {
+        let mut leaves: Vec<String> = self.nodes.keys()
+            .filter(|id| !self.nodes.values().any(|node| node.inputs.contains(id)))
+            .cloned()
+            .collect();
+        leaves.sort();
+        leaves
+    }
+    
+    /// Execute the graph, returning the output of its single terminal node
+    ///
+    /// `model_types` holds the input type of each loaded model and `infer` runs
+    /// a node's model on its input. Errors if the graph has more than one
+    /// terminal node; use `execute_all` for those.
+    pub async fn execute<F, Fut>(
         &self,
-        models: &HashMap<String, Model>,
+        model_types: &HashMap<String, ModelInputType>,
         initial_input: Vec<u8>,
-    ) -> Result<Vec<u8>, SynaptronError> {
+        infer: F,
+    ) -> Result<Vec<u8>, SynaptronError>
+    where
+        F: Fn(String, Vec<u8>) -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+    {
+        let leaves = self.leaf_nodes();
+        if leaves.len() > 1 {
+            return Err(SynaptronError::GraphExecution(format!(
+                "Graph has {} terminal nodes ({}); use execute_all",
+                leaves.len(), leaves.join(", ")
+            )));
+        }
+        
+        let outputs = self.run(model_types, initial_input, infer).await?;
+        
+        match leaves.first() {
+            Some(leaf_id) => outputs.get(leaf_id).cloned().ok_or_else(|| {
+                SynaptronError::GraphExecution("No output from graph execution".to_string())
+            }),
+            // Return initial input if no nodes
+            None => Ok(outputs.get(GRAPH_INPUT).unwrap().clone()),
+        }
+    }
+    
+    /// Execute the graph, returning every terminal node's output keyed by node ID
+    pub async fn execute_all<F, Fut>(
+        &self,
+        model_types: &HashMap<String, ModelInputType>,
+        initial_input: Vec<u8>,
+        infer: F,
+    ) -> Result<HashMap<String, Vec<u8>>, SynaptronError>
+    where
+        F: Fn(String, Vec<u8>) -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+    {
+        let mut outputs = self.run(model_types, initial_input, infer).await?;
+        
+        let mut results = HashMap::new();
+        for leaf_id in self.leaf_nodes() {
+            let output = outputs.remove(&leaf_id).ok_or_else(|| {
+                SynaptronError::GraphExecution(format!("No output from terminal node: {}", leaf_id))
+            })?;
+            results.insert(leaf_id, output);
+        }
+        
+        Ok(results)
+    }
+    
+    /// Run every node in execution order, returning all node outputs
+    ///
+    /// Every node's output is kept, so a node feeding several consumers runs once.
+    async fn run<F, Fut>(
+        &self,
+        model_types: &HashMap<String, ModelInputType>,
+        initial_input: Vec<u8>,
+        infer: F,
+    ) -> Result<HashMap<String, Vec<u8>>, SynaptronError>
+    where
+        F: Fn(String, Vec<u8>) -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, SynaptronError>>,
+    {
         info!("Executing model graph");
         
         let mut outputs: HashMap<String, Vec<u8>> = HashMap::new();
-        outputs.insert("input".to_string(), initial_input);
+        outputs.insert(GRAPH_INPUT.to_string(), initial_input);
         
         // Execute nodes in order
         for node_id in &self.execution_order {
@@ -120,61 +666,473 @@ impl ModelGraph {
                 
                 if node.inputs.is_empty() {
                     // Use initial input if no specific inputs
-                    node_inputs.push(outputs.get("input").unwrap().clone());
+                    node_inputs.push(outputs.get(GRAPH_INPUT).unwrap().clone());
                 } else {
-                    // Collect from previous node outputs
+                    // Collect from previous node outputs, adapting across modalities
                     for input_id in &node.inputs {
-                        if let Some(output) = outputs.get(input_id) {
-                            node_inputs.push(output.clone());
-                        }
+                        let output = outputs.get(input_id).ok_or_else(|| {
+                            SynaptronError::GraphExecution(format!(
+                                "Input {} of node {} produced no output", input_id, node_id
+                            ))
+                        })?;
+                        let adapter = match self.nodes.get(input_id) {
+                            Some(producer) => self.edge_adapter(producer, node, model_types)?,
+                            None => EdgeAdapter::Passthrough,
+                        };
+                        node_inputs.push(adapter.apply(output.clone()));
                     }
                 }
                 
-                // For simplicity, we'll use the first input
-                // In a real implementation, this would be more complex
-                let input = if !node_inputs.is_empty() {
-                    node_inputs[0].clone()
-                } else {
-                    return Err(SynaptronError::GraphExecution(
-                        format!("No input available for node: {}", node_id)
-                    ));
-                };
+                // Combine fan-in inputs according to the node's merge strategy
+                let input = node.merge.merge(node_inputs)?;
                 
                 // Run inference with the model
-                if let Some(model) = models.get(&node.model_name) {
-                    // In a real implementation, this would run the actual inference
-                    // For now, we'll just pass the input through
-                    let output = input;
-                    outputs.insert(node_id.clone(), output);
-                } else {
+                if !model_types.contains_key(&node.model_name) {
                     return Err(SynaptronError::GraphExecution(
                         format!("Model not found: {}", node.model_name)
                     ));
                 }
+                
+                debug!("Running graph node {} on model {}", node_id, node.model_name);
+                let skipped_input = (node.on_error == OnError::SkipNode).then(|| input.clone());
+                let node_span = info_span!("graph_node", node = %node_id, model = %node.model_name);
+                let run = infer(node.model_name.clone(), input).instrument(node_span);
+                
+                let started = Instant::now();
+                let (result, timed_out) = match node.timeout() {
+                    Some(limit) => match tokio::time::timeout(limit, run).await {
+                        Ok(result) => (result, false),
+                        Err(_) => (Err(SynaptronError::Inference(format!(
+                            "Timed out after {} ms", limit.as_millis()
+                        ))), true),
+                    },
+                    None => (run.await, false),
+                };
+                let elapsed = started.elapsed();
+                self.timings.lock().entry(node_id.clone()).or_default().record(elapsed, result.is_err(), timed_out);
+                debug!("Graph node {} finished in {:?}", node_id, elapsed);
+                
+                let output = match result {
+                    Ok(output) => output,
+                    Err(e) => {
+                        warn!("Graph node {} failed: {}", node_id, e);
+                        self.recover(node_id, node, e, skipped_input, &outputs)?
+                    }
+                };
+                outputs.insert(node_id.clone(), output);
             }
         }
         
-        // Return the output of the last node
-        if let Some(last_node_id) = self.execution_order.last() {
-            if let Some(output) = outputs.get(last_node_id) {
-                Ok(output.clone())
-            } else {
-                Err(SynaptronError::GraphExecution(
-                    "No output from graph execution".to_string()
-                ))
+        Ok(outputs)
+    }
+    
+    /// Output standing in for a failed node under its `on_error` policy
+    fn recover(
+        &self,
+        node_id: &str,
+        node: &GraphNode,
+        error: SynaptronError,
+        skipped_input: Option<Vec<u8>>,
+        outputs: &HashMap<String, Vec<u8>>,
+    ) -> Result<Vec<u8>, SynaptronError> {
+        let failed = |error| SynaptronError::GraphNode { node: node_id.to_string(), source: Box::new(error) };
+        
+        match (&node.on_error, skipped_input) {
+            (OnError::SkipNode, Some(input)) => {
+                info!("Skipping failed graph node {}", node_id);
+                Ok(input)
             }
-        } else {
-            // Return initial input if no nodes
-            Ok(outputs.get("input").unwrap().clone())
+            (OnError::UseFallback(fallback), _) => match outputs.get(fallback) {
+                Some(output) => {
+                    info!("Using output of {} for failed graph node {}", fallback, node_id);
+                    Ok(output.clone())
+                }
+                None => Err(failed(SynaptronError::GraphExecution(format!(
+                    "{} (fallback {} produced no output)", error, fallback
+                )))),
+            },
+            _ => Err(failed(error)),
         }
     }
 }
 
+impl Default for ModelGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Clone for ModelGraph {
     fn clone(&self) -> Self {
         Self {
             nodes: self.nodes.clone(),
             execution_order: self.execution_order.clone(),
+            model_types: self.model_types.clone(),
+            timings: self.timings.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Node running `model_name` on the outputs of `inputs`
+    fn node(id: &str, model_name: &str, inputs: &[&str]) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            model_name: model_name.to_string(),
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            outputs: Vec::new(),
+            adapter: None,
+            merge: MergeStrategy::default(),
+            timeout_ms: 0,
+            on_error: OnError::default(),
+        }
+    }
+    
+    #[test]
+    fn text_edges_decode_and_matching_edges_pass_through() {
+        let text = output_modality(&ModelInputType::Audio);
+        assert_eq!(text, ModelInputType::Text);
+        
+        assert_eq!(EdgeAdapter::select(&text, &ModelInputType::Text), Some(EdgeAdapter::DecodeText));
+        assert_eq!(
+            EdgeAdapter::select(&ModelInputType::Image, &ModelInputType::Image),
+            Some(EdgeAdapter::Passthrough)
+        );
+        assert_eq!(EdgeAdapter::select(&ModelInputType::Image, &ModelInputType::Text), None);
+    }
+    
+    #[test]
+    fn decoded_text_is_trimmed_of_padding() {
+        let output = b"  a transcript\0\0\n".to_vec();
+        
+        assert_eq!(EdgeAdapter::DecodeText.apply(output.clone()), b"a transcript");
+        assert_eq!(EdgeAdapter::Passthrough.apply(output.clone()), output);
+    }
+
+    #[test]
+    fn configured_adapters_override_selection() {
+        let model_types = HashMap::from([
+            ("vision".to_string(), ModelInputType::Image),
+            ("summarizer".to_string(), ModelInputType::Text),
+        ]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("features", "vision", &[])).unwrap();
+        graph.add_node(node("summary", "summarizer", &["features"])).unwrap();
+        assert!(graph.validate(&model_types).is_err());
+        
+        let mut adapted = node("summary", "summarizer", &["features"]);
+        adapted.adapter = Some(EdgeAdapter::Passthrough);
+        graph.add_node(adapted).unwrap();
+        assert!(graph.validate(&model_types).is_ok());
+    }
+
+    /// Inference stand-in recording which model ran on which input
+    async fn tag(model_name: String, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        Ok(format!("{}({})", model_name, String::from_utf8_lossy(&input)).into_bytes())
+    }
+    
+    /// Input types of image models with the given names
+    fn image_models(names: &[&str]) -> HashMap<String, ModelInputType> {
+        names.iter().map(|name| (name.to_string(), ModelInputType::Image)).collect()
+    }
+    
+    #[tokio::test]
+    async fn execute_all_returns_every_terminal_output() {
+        let model_types = image_models(&["encoder", "classifier", "captioner"]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("encode", "encoder", &[GRAPH_INPUT])).unwrap();
+        graph.add_node(node("classify", "classifier", &["encode"])).unwrap();
+        graph.add_node(node("caption", "captioner", &["encode"])).unwrap();
+        
+        let outputs = graph.execute_all(&model_types, b"x".to_vec(), tag).await.unwrap();
+        
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs["classify"], b"classifier(encoder(x))");
+        assert_eq!(outputs["caption"], b"captioner(encoder(x))");
+    }
+    
+    #[tokio::test]
+    async fn execute_rejects_graphs_with_several_terminals() {
+        let model_types = image_models(&["encoder", "classifier", "captioner"]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("encode", "encoder", &[])).unwrap();
+        graph.add_node(node("classify", "classifier", &["encode"])).unwrap();
+        graph.add_node(node("caption", "captioner", &["encode"])).unwrap();
+        
+        assert_eq!(graph.leaf_nodes(), ["caption", "classify"]);
+        assert!(graph.execute(&model_types, b"x".to_vec(), tag).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn shared_upstream_nodes_run_once() {
+        let model_types = image_models(&["encoder", "classifier", "captioner"]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("encode", "encoder", &[])).unwrap();
+        graph.add_node(node("classify", "classifier", &["encode"])).unwrap();
+        graph.add_node(node("caption", "captioner", &["encode"])).unwrap();
+        let runs = Mutex::new(Vec::new());
+        
+        graph.execute_all(&model_types, b"x".to_vec(), |model_name, input| {
+            runs.lock().push(model_name.clone());
+            tag(model_name, input)
+        }).await.unwrap();
+        
+        assert_eq!(runs.lock().iter().filter(|model_name| *model_name == "encoder").count(), 1);
+    }
+
+    #[test]
+    fn incompatible_edges_are_rejected_when_added() {
+        let mut graph = ModelGraph::new();
+        graph.set_model_type("vision", ModelInputType::Image);
+        graph.set_model_type("summarizer", ModelInputType::Text);
+        graph.add_node(node("features", "vision", &[])).unwrap();
+        
+        assert!(graph.add_node(node("summary", "summarizer", &["features"])).is_err());
+        assert_eq!(graph.execution_order(), ["features"], "the rejected node is rolled back");
+    }
+    
+    #[test]
+    fn edges_to_unknown_models_are_deferred() {
+        let mut graph = ModelGraph::new();
+        graph.set_model_type("vision", ModelInputType::Image);
+        graph.add_node(node("features", "vision", &[])).unwrap();
+        
+        assert!(graph.add_node(node("summary", "not-loaded", &["features"])).is_ok());
+        
+        let model_types = HashMap::from([
+            ("vision".to_string(), ModelInputType::Image),
+            ("not-loaded".to_string(), ModelInputType::Text),
+        ]);
+        assert!(graph.validate(&model_types).is_err());
+    }
+
+    #[test]
+    fn nodes_closing_a_cycle_are_rejected() {
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("a", "model", &["c"])).unwrap();
+        graph.add_node(node("b", "model", &["a"])).unwrap();
+        
+        let err = graph.add_node(node("c", "model", &["b"])).unwrap_err();
+        
+        assert!(err.to_string().contains("cycle detected"), "{}", err);
+        assert_eq!(graph.execution_order(), ["a", "b"]);
+    }
+    
+    #[test]
+    fn replacing_a_node_with_a_cycle_keeps_the_original() {
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("a", "model", &[])).unwrap();
+        graph.add_node(node("b", "model", &["a"])).unwrap();
+        
+        assert!(graph.add_node(node("a", "model", &["b"])).is_err());
+        
+        assert_eq!(graph.execution_order(), ["a", "b"]);
+        assert!(graph.spec().nodes[0].inputs.is_empty());
+    }
+    
+    #[test]
+    fn self_loops_are_cycles() {
+        let mut graph = ModelGraph::new();
+        
+        assert!(graph.add_node(node("a", "model", &["a"])).is_err());
+        assert!(graph.execution_order().is_empty());
+    }
+
+    #[test]
+    fn merge_strategies_combine_inputs_in_declared_order() {
+        let inputs = vec![b"ab".to_vec(), b"cd".to_vec()];
+        
+        assert_eq!(MergeStrategy::Concat.merge(inputs.clone()).unwrap(), b"abcd");
+        assert_eq!(MergeStrategy::First.merge(inputs.clone()).unwrap(), b"ab");
+        
+        let reversed = MergeStrategy::Custom(Arc::new(|inputs: &[Vec<u8>]| {
+            Ok::<_, SynaptronError>(inputs.iter().rev().flatten().copied().collect::<Vec<u8>>())
+        }));
+        assert_eq!(reversed.merge(inputs).unwrap(), b"cdab");
+    }
+    
+    #[test]
+    fn sum_merge_adds_f32_tensors() {
+        let inputs = vec![tensor::f32_to_bytes(&[1.0, 2.0]), tensor::f32_to_bytes(&[0.5, -2.0])];
+        
+        let sum = MergeStrategy::Sum.merge(inputs).unwrap();
+        
+        assert_eq!(tensor::bytes_to_f32(&sum).unwrap(), [1.5, 0.0]);
+    }
+    
+    #[test]
+    fn sum_merge_rejects_mismatched_lengths() {
+        let inputs = vec![tensor::f32_to_bytes(&[1.0, 2.0]), tensor::f32_to_bytes(&[1.0])];
+        
+        assert!(MergeStrategy::Sum.merge(inputs).is_err());
+        assert!(MergeStrategy::Sum.merge(Vec::new()).is_err());
+    }
+    
+    #[tokio::test]
+    async fn fan_in_nodes_merge_their_inputs() {
+        let model_types = image_models(&["left", "right", "joiner"]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("l", "left", &[])).unwrap();
+        graph.add_node(node("r", "right", &[])).unwrap();
+        let mut join = node("join", "joiner", &["r", "l"]);
+        join.merge = MergeStrategy::Concat;
+        graph.add_node(join).unwrap();
+        
+        let output = graph.execute(&model_types, b"x".to_vec(), tag).await.unwrap();
+        
+        assert_eq!(output, b"joiner(right(x)left(x))");
+    }
+
+    #[tokio::test]
+    async fn each_node_runs_its_model_in_order() {
+        let model_types = image_models(&["first", "second"]);
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("b", "second", &["a"])).unwrap();
+        graph.add_node(node("a", "first", &[])).unwrap();
+        
+        let output = graph.execute(&model_types, b"x".to_vec(), tag).await.unwrap();
+        
+        assert_eq!(output, b"second(first(x))");
+    }
+    
+    #[tokio::test]
+    async fn empty_graphs_return_their_input() {
+        let graph = ModelGraph::new();
+        
+        let output = graph.execute(&HashMap::new(), b"x".to_vec(), tag).await.unwrap();
+        
+        assert_eq!(output, b"x");
+    }
+    
+    #[tokio::test]
+    async fn nodes_on_unloaded_models_fail() {
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("a", "missing", &[])).unwrap();
+        
+        let err = graph.execute(&HashMap::new(), b"x".to_vec(), tag).await.unwrap_err();
+        
+        assert!(err.to_string().contains("Model not found: missing"), "{}", err);
+    }
+
+    #[test]
+    fn specs_are_read_from_json_and_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("graph.json");
+        let yaml = dir.path().join("graph.yaml");
+        std::fs::write(&json, r#"{"nodes": [{"id": "a", "model_name": "bert", "merge": "Concat"}]}"#).unwrap();
+        std::fs::write(&yaml, "nodes:\n  - id: a\n    model_name: bert\n    inputs: [input]\n    timeout_ms: 50\n").unwrap();
+        
+        let from_json = GraphSpec::from_file(json.to_str().unwrap()).unwrap();
+        let from_yaml = GraphSpec::from_file(yaml.to_str().unwrap()).unwrap();
+        
+        assert!(matches!(from_json.nodes[0].merge, MergeStrategy::Concat));
+        assert_eq!(from_yaml.nodes[0].inputs, [GRAPH_INPUT]);
+        assert_eq!(from_yaml.nodes[0].timeout_ms, 50);
+        assert_eq!(from_yaml.nodes[0].on_error, OnError::Fail);
+    }
+    
+    #[test]
+    fn invalid_spec_files_are_graph_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.json");
+        std::fs::write(&path, r#"{"nodes": [{"id": "a"}]}"#).unwrap();
+        
+        let err = GraphSpec::from_file(path.to_str().unwrap()).unwrap_err();
+        
+        assert!(matches!(err, SynaptronError::GraphExecution(_)));
+    }
+    
+    #[test]
+    fn specs_with_dangling_references_are_rejected() {
+        let loadable = |model_name: &str| model_name == "bert";
+        let spec = |nodes| GraphSpec { nodes };
+        
+        let unknown_model = spec(vec![node("a", "gpt", &[])]);
+        let unknown_input = spec(vec![node("a", "bert", &["b"])]);
+        let duplicate = spec(vec![node("a", "bert", &[]), node("a", "bert", &[])]);
+        let reserved = spec(vec![node(GRAPH_INPUT, "bert", &[])]);
+        
+        for spec in [unknown_model, unknown_input, duplicate, reserved] {
+            assert!(ModelGraph::from_config(&spec, loadable, &HashMap::new()).is_err(), "{:?}", spec);
+        }
+    }
+    
+    #[test]
+    fn valid_specs_build_graphs_in_execution_order() {
+        let spec = GraphSpec { nodes: vec![node("b", "bert", &["a"]), node("a", "bert", &[GRAPH_INPUT])] };
+        
+        let graph = ModelGraph::from_config(&spec, |_| true, &HashMap::new()).unwrap();
+        
+        assert_eq!(graph.execution_order(), ["a", "b"]);
+        assert!(graph.uses_model("bert"));
+        assert_eq!(graph.spec().nodes[0].id, "a");
+    }
+
+    /// Inference stand-in where `slow` hangs and `broken` fails
+    async fn flaky(model_name: String, input: Vec<u8>) -> Result<Vec<u8>, SynaptronError> {
+        match model_name.as_str() {
+            "slow" => {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                tag(model_name, input).await
+            }
+            "broken" => Err(SynaptronError::Inference("broken".to_string())),
+            _ => tag(model_name, input).await,
+        }
+    }
+    
+    #[tokio::test]
+    async fn slow_nodes_time_out_and_fail_the_execution() {
+        let model_types = image_models(&["slow"]);
+        let mut slow = node("a", "slow", &[]);
+        slow.timeout_ms = 10;
+        let mut graph = ModelGraph::new();
+        graph.add_node(slow).unwrap();
+        
+        let err = graph.execute(&model_types, b"x".to_vec(), flaky).await.unwrap_err();
+        
+        assert!(matches!(err, SynaptronError::GraphNode { ref node, .. } if node == "a"), "{}", err);
+        let timing = &graph.node_timings()["a"];
+        assert_eq!((timing.runs, timing.failures, timing.timeouts), (1, 1, 1));
+    }
+    
+    #[tokio::test]
+    async fn skipped_nodes_pass_their_input_on() {
+        let model_types = image_models(&["encoder", "broken", "classifier"]);
+        let mut broken = node("b", "broken", &["a"]);
+        broken.on_error = OnError::SkipNode;
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("a", "encoder", &[])).unwrap();
+        graph.add_node(broken).unwrap();
+        graph.add_node(node("c", "classifier", &["b"])).unwrap();
+        
+        let output = graph.execute(&model_types, b"x".to_vec(), flaky).await.unwrap();
+        
+        assert_eq!(output, b"classifier(encoder(x))");
+    }
+    
+    #[tokio::test]
+    async fn failed_nodes_can_fall_back_to_an_upstream_output() {
+        let model_types = image_models(&["encoder", "broken"]);
+        let mut broken = node("b", "broken", &["a"]);
+        broken.on_error = OnError::UseFallback(GRAPH_INPUT.to_string());
+        let mut graph = ModelGraph::new();
+        graph.add_node(node("a", "encoder", &[])).unwrap();
+        graph.add_node(broken).unwrap();
+        
+        let output = graph.execute(&model_types, b"x".to_vec(), flaky).await.unwrap();
+        
+        assert_eq!(output, b"x");
+    }
+    
+    #[test]
+    fn fallbacks_must_be_upstream() {
+        let mut downstream = node("a", "bert", &[]);
+        downstream.on_error = OnError::UseFallback("b".to_string());
+        let spec = GraphSpec { nodes: vec![downstream, node("b", "bert", &["a"])] };
+        
+        assert!(ModelGraph::from_config(&spec, |_| true, &HashMap::new()).is_err());
+    }
+}
@@ -9,6 +9,28 @@
 //! - Auto-Optimization Layer: Hardware acceleration with backend auto-selection
 //! - Memory & Performance: LRU cache and async batching
 //! - Advanced API & Dashboard: Prometheus metrics and optional web UI
+//! 
+//! ## Embedding
+//! 
+//! The engine runs without the HTTP server; `start_server` is only needed to
+//! serve the REST API. Loads can be shared between concurrent callers, so tell
+//! errors apart by `SynaptronError::root`.
+//! 
+//! ```no_run
+//! use synaptron::{Config, InferenceEngine};
+//! 
+//! # async fn run() -> synaptron::Result<()> {
+//! let engine = InferenceEngine::new(Config::default()).await?;
+//! engine.load_model("./models_cache/bert-base-uncased.safetensors").await?;
+//! 
+//! let output = engine.infer_with("bert-base-uncased", b"Hello, world".to_vec()).await?;
+//! println!("{} bytes out, {} requests served", output.len(), engine.metrics().get_total_requests());
+//! 
+//! engine.unload_model("bert-base-uncased").await?;
+//! engine.shutdown().await;
+//! # Ok(())
+//! # }
+//! ```
 
 /// Core inference engine module
 pub mod engine;
@@ -67,11 +89,38 @@ pub mod optimizer;
 /// Multi-modal input handling
 pub mod multimodal;
 
+/// Retry budget
+pub mod retry;
+
+/// Per-model circuit breaker
+pub mod breaker;
+
+/// Ordered shutdown coordination
+pub mod shutdown;
+
+/// Input-dependent model routing
+pub mod routing;
+
+/// Warm snapshots of optimized models
+pub mod snapshot;
+
+/// Load testing
+pub mod loadtest;
+
+/// Log output setup
+pub mod logging;
+
+/// GGUF model file parsing
+pub mod gguf;
+
 // Re-export main types
-pub use engine::InferenceEngine;
+pub use backend::Backend;
+pub use engine::{InferenceEngine, ModelScope, Prediction};
+pub use metrics::MetricsCollector;
 pub use model::Model;
 pub use config::Config;
 pub use error::SynaptronError;
+pub use logging::init_logging;
 
 /// Result type
 pub type Result<T> = std::result::Result<T, SynaptronError>;
@@ -0,0 +1,262 @@
+//! Load testing / traffic generation for Synaptron deployments
+
+use crate::{error::SynaptronError, utils::http};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use tracing::{info, debug};
+
+/// Load test settings
+#[derive(Debug, Clone)]
+pub struct LoadTestOptions {
+    /// Endpoint to send requests to
+    pub url: String,
+    
+    /// Number of concurrent workers
+    pub concurrency: usize,
+    
+    /// Measured duration, excluding warmup
+    pub duration: Duration,
+    
+    /// Warmup period whose requests are excluded from stats
+    pub warmup: Duration,
+    
+    /// Maximum requests per second across all workers
+    pub max_rate: Option<u32>,
+    
+    /// Request body sent as JSON
+    pub body: Vec<u8>,
+}
+
+/// Load test results
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LoadTestReport {
+    /// Measured requests
+    pub total_requests: u64,
+    
+    /// 2xx responses
+    pub successes: u64,
+    
+    /// 429 responses
+    pub throttled: u64,
+    
+    /// 503 responses
+    pub unavailable: u64,
+    
+    /// Other failures, including transport errors
+    pub errors: u64,
+    
+    /// Requests per second over the measured duration
+    pub throughput: f64,
+    
+    /// Fraction of measured requests that were not 2xx, so throttled and
+    /// unavailable responses count against it alongside `errors`
+    pub error_rate: f64,
+    
+    /// Median latency in milliseconds
+    pub p50_ms: f64,
+    
+    /// 90th percentile latency in milliseconds
+    pub p90_ms: f64,
+    
+    /// 99th percentile latency in milliseconds
+    pub p99_ms: f64,
+    
+    /// Maximum latency in milliseconds
+    pub max_ms: f64,
+}
+
+/// Outcome of a single request
+enum Outcome {
+    Success,
+    Throttled,
+    Unavailable,
+    Error,
+}
+
+/// Samples collected by the workers
+#[derive(Default)]
+struct Samples {
+    latencies_ms: Vec<f64>,
+    successes: u64,
+    throttled: u64,
+    unavailable: u64,
+    errors: u64,
+}
+
+/// Run a load test against an endpoint
+pub async fn run(options: LoadTestOptions) -> Result<LoadTestReport, SynaptronError> {
+    if options.concurrency == 0 {
+        return Err(SynaptronError::Other("Load test concurrency must be at least 1".to_string()));
+    }
+    
+    info!(
+        "Load testing {} with {} workers for {:?} (warmup {:?})",
+        options.url, options.concurrency, options.duration, options.warmup
+    );
+    
+    let start = Instant::now();
+    let measure_from = start + options.warmup;
+    let deadline = measure_from + options.duration;
+    
+    let samples = Arc::new(Mutex::new(Samples::default()));
+    let limiter: Option<Arc<tokio::sync::Mutex<Interval>>> = options.max_rate.filter(|rate| *rate > 0).map(|rate| {
+        let mut ticker = interval(Duration::from_secs_f64(1.0 / rate as f64));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Arc::new(tokio::sync::Mutex::new(ticker))
+    });
+    let body = Arc::new(options.body);
+    
+    let mut workers = Vec::with_capacity(options.concurrency);
+    
+    for _ in 0..options.concurrency {
+        let url = options.url.clone();
+        let samples = samples.clone();
+        let limiter = limiter.clone();
+        let body = body.clone();
+        
+        workers.push(tokio::spawn(async move {
+            while Instant::now() < deadline {
+                if let Some(limiter) = &limiter {
+                    limiter.lock().await.tick().await;
+                }
+                
+                let sent_at = Instant::now();
+                let outcome = match http::client()
+                    .post(&url)
+                    .header("content-type", "application/json")
+                    .body(body.as_ref().clone())
+                    .send()
+                    .await
+                {
+                    Ok(response) => match response.status().as_u16() {
+                        200..=299 => Outcome::Success,
+                        429 => Outcome::Throttled,
+                        503 => Outcome::Unavailable,
+                        _ => Outcome::Error,
+                    },
+                    Err(e) => {
+                        debug!("Load test request failed: {}", e);
+                        Outcome::Error
+                    }
+                };
+                
+                // Requests issued during warmup are excluded from stats
+                if sent_at < measure_from {
+                    continue;
+                }
+                
+                let mut samples = samples.lock();
+                samples.latencies_ms.push(sent_at.elapsed().as_secs_f64() * 1000.0);
+                match outcome {
+                    Outcome::Success => samples.successes += 1,
+                    Outcome::Throttled => samples.throttled += 1,
+                    Outcome::Unavailable => samples.unavailable += 1,
+                    Outcome::Error => samples.errors += 1,
+                }
+            }
+        }));
+    }
+    
+    for worker in workers {
+        worker.await.map_err(|e| SynaptronError::Other(format!("Load test worker failed: {}", e)))?;
+    }
+    
+    let mut samples = std::mem::take(&mut *samples.lock());
+    samples.latencies_ms.sort_by(|a, b| a.total_cmp(b));
+    
+    let total = samples.latencies_ms.len() as u64;
+    let percentile = |p: f64| -> f64 {
+        if samples.latencies_ms.is_empty() {
+            return 0.0;
+        }
+        let rank = ((p / 100.0) * (samples.latencies_ms.len() - 1) as f64).round() as usize;
+        samples.latencies_ms[rank]
+    };
+    
+    let report = LoadTestReport {
+        total_requests: total,
+        successes: samples.successes,
+        throttled: samples.throttled,
+        unavailable: samples.unavailable,
+        errors: samples.errors,
+        throughput: total as f64 / options.duration.as_secs_f64().max(f64::EPSILON),
+        error_rate: if total > 0 { (total - samples.successes) as f64 / total as f64 } else { 0.0 },
+        p50_ms: percentile(50.0),
+        p90_ms: percentile(90.0),
+        p99_ms: percentile(99.0),
+        max_ms: samples.latencies_ms.last().copied().unwrap_or(0.0),
+    };
+    
+    info!("Load test completed: {} requests, {:.1} req/s", report.total_requests, report.throughput);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serve a mock endpoint cycling through 200, 429 and 503, with the 503s slow
+    async fn mock_server() -> String {
+        let served = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route("/predict", post(move || {
+            let served = served.clone();
+            async move {
+                match served.fetch_add(1, Ordering::SeqCst) % 3 {
+                    0 => StatusCode::OK,
+                    1 => StatusCode::TOO_MANY_REQUESTS,
+                    _ => {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        StatusCode::SERVICE_UNAVAILABLE
+                    }
+                }
+            }
+        }));
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/predict", address)
+    }
+
+    #[tokio::test]
+    async fn responses_are_classified_and_timed() {
+        let url = mock_server().await;
+        
+        let report = run(LoadTestOptions {
+            url,
+            concurrency: 2,
+            duration: Duration::from_millis(300),
+            warmup: Duration::ZERO,
+            max_rate: None,
+            body: b"{}".to_vec(),
+        }).await.unwrap();
+        
+        assert!(report.successes > 0 && report.throttled > 0 && report.unavailable > 0, "{:?}", report);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.successes + report.throttled + report.unavailable, report.total_requests);
+        let not_ok = (report.throttled + report.unavailable) as f64 / report.total_requests as f64;
+        assert!((report.error_rate - not_ok).abs() < 1e-9);
+        
+        assert!(report.p50_ms <= report.p90_ms && report.p90_ms <= report.p99_ms && report.p99_ms <= report.max_ms);
+        assert!(report.p99_ms >= 20.0, "slow 503s missing from the tail: {:?}", report);
+    }
+
+    #[tokio::test]
+    async fn zero_concurrency_is_rejected() {
+        let options = LoadTestOptions {
+            url: "http://127.0.0.1:1/predict".to_string(),
+            concurrency: 0,
+            duration: Duration::from_millis(10),
+            warmup: Duration::ZERO,
+            max_rate: None,
+            body: Vec::new(),
+        };
+        
+        assert!(run(options).await.is_err());
+    }
+}
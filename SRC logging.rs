@@ -0,0 +1,49 @@
+//! Log output setup for the Synaptron inference engine
+
+use crate::{config::{LogFormat, MonitoringConfig}, error::SynaptronError};
+use tracing::Subscriber;
+use tracing_subscriber::{util::SubscriberInitExt, EnvFilter};
+
+/// Install the global tracing subscriber described by `monitoring.log_format` and `monitoring.log_level`
+///
+/// `RUST_LOG`, when set, overrides `log_level`. Fails if the level is not a valid
+/// filter or a global subscriber is already installed.
+pub fn init_logging(config: &MonitoringConfig) -> Result<(), SynaptronError> {
+    subscriber(config)?
+        .try_init()
+        .map_err(|e| SynaptronError::Other(format!("Failed to initialize logging: {}", e)))
+}
+
+/// Subscriber writing logs in `monitoring.log_format`, filtered by `RUST_LOG` or `monitoring.log_level`
+fn subscriber(config: &MonitoringConfig) -> Result<Box<dyn Subscriber + Send + Sync>, SynaptronError> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(&config.log_level).map_err(|e| {
+            SynaptronError::Config(config::ConfigError::Message(format!(
+                "Invalid monitoring.log_level {:?}: {}", config.log_level, e
+            )))
+        })?,
+    };
+    
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    Ok(match config.log_format {
+        LogFormat::Pretty => Box::new(builder.finish()),
+        LogFormat::Json => Box::new(builder.json().finish()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn both_formats_build_a_subscriber() {
+        for log_format in [LogFormat::Pretty, LogFormat::Json] {
+            let config = MonitoringConfig { log_format, ..MonitoringConfig::default() };
+            
+            let subscriber = subscriber(&config).unwrap();
+            
+            tracing::subscriber::with_default(subscriber, || tracing::info!("logging configured"));
+        }
+    }
+}
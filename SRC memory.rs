@@ -0,0 +1,371 @@
+//! Memory pressure monitoring for the Synaptron inference engine
+//!
+//! The model cache and the loaded models each keep to their own limits, which
+//! says nothing about the process as a whole. The monitor samples process
+//! memory and sheds cached and idle models while it is above the high-water mark.
+
+use crate::{cache::ModelCache, config::MemoryConfig};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Memory used by this process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryUsage {
+    /// Resident set size in bytes
+    pub rss_bytes: u64,
+
+    /// GPU memory in bytes, `None` without an NVIDIA driver
+    pub gpu_bytes: Option<u64>,
+}
+
+/// Source of memory usage samples, replaceable for testing
+pub trait MemorySource: Send + Sync {
+    /// Current memory usage of this process
+    fn usage(&self) -> MemoryUsage;
+}
+
+/// Reads memory usage from `/proc` and `nvidia-smi`
+pub struct SystemMemory;
+
+impl SystemMemory {
+    /// Resident set size from `/proc/self/status`, 0 when unavailable
+    fn rss_bytes() -> u64 {
+        std::fs::read_to_string("/proc/self/status")
+            .unwrap_or_default()
+            .lines()
+            .find(|line| line.starts_with("VmRSS:"))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map_or(0, |kb| kb * 1024)
+    }
+
+    /// GPU memory held by this process's CUDA contexts; nvidia-smi reports MiB
+    fn gpu_bytes() -> Option<u64> {
+        if !Path::new("/proc/driver/nvidia/version").exists() {
+            return None;
+        }
+
+        let pid = std::process::id();
+        let output = std::process::Command::new("nvidia-smi")
+            .args(["--query-compute-apps=pid,used_memory", "--format=csv,noheader,nounits"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        Some(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(',').map(|field| field.trim().parse::<u64>().ok());
+                Some((fields.next()??, fields.next()?? << 20))
+            })
+            .filter(|(app_pid, _)| *app_pid == pid as u64)
+            .map(|(_, used)| used)
+            .sum())
+    }
+}
+
+impl MemorySource for SystemMemory {
+    fn usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            rss_bytes: Self::rss_bytes(),
+            gpu_bytes: Self::gpu_bytes(),
+        }
+    }
+}
+
+/// Latest sample and relief counters
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryStats {
+    /// Most recent usage sample
+    #[serde(flatten)]
+    pub usage: MemoryUsage,
+
+    /// Resident memory high-water mark in bytes, 0 for no limit
+    pub high_water_bytes: u64,
+
+    /// Cached models evicted under memory pressure
+    pub cache_evictions: u64,
+
+    /// Idle models unloaded under memory pressure
+    pub idle_unloads: u64,
+}
+
+impl MemoryStats {
+    /// Render the sample and counters in the Prometheus text exposition format
+    pub fn format_prometheus(&self) -> String {
+        let mut lines = vec![
+            "# HELP synaptron_memory_rss_bytes Resident memory of the process".to_string(),
+            "# TYPE synaptron_memory_rss_bytes gauge".to_string(),
+            format!("synaptron_memory_rss_bytes {}", self.usage.rss_bytes),
+        ];
+        if let Some(gpu_bytes) = self.usage.gpu_bytes {
+            lines.extend([
+                "# HELP synaptron_memory_gpu_bytes GPU memory used by the process".to_string(),
+                "# TYPE synaptron_memory_gpu_bytes gauge".to_string(),
+                format!("synaptron_memory_gpu_bytes {}", gpu_bytes),
+            ]);
+        }
+        lines.extend([
+            "# HELP synaptron_memory_pressure_evictions_total Cached models evicted under memory pressure".to_string(),
+            "# TYPE synaptron_memory_pressure_evictions_total counter".to_string(),
+            format!("synaptron_memory_pressure_evictions_total {}", self.cache_evictions),
+            "# HELP synaptron_memory_pressure_unloads_total Idle models unloaded under memory pressure".to_string(),
+            "# TYPE synaptron_memory_pressure_unloads_total counter".to_string(),
+            format!("synaptron_memory_pressure_unloads_total {}", self.idle_unloads),
+        ]);
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Handle to a running memory monitor; stops it when dropped
+pub struct MonitorGuard {
+    /// Cancels the monitor task
+    cancel: CancellationToken,
+}
+
+impl MonitorGuard {
+    /// Stop the monitor
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for MonitorGuard {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Samples process memory and sheds models above the high-water mark
+///
+/// Each check above the mark frees one model, the least recently used cached
+/// one first, so the next sample can show the effect before more is dropped.
+#[derive(Clone)]
+pub struct MemoryMonitor {
+    /// Monitor configuration
+    config: MemoryConfig,
+
+    /// Where usage samples come from
+    source: Arc<dyn MemorySource>,
+
+    /// Cache evicted from first
+    cache: ModelCache,
+
+    /// Most recent sample
+    latest: Arc<Mutex<MemoryUsage>>,
+
+    /// Cached models evicted under pressure
+    evictions: Arc<AtomicU64>,
+
+    /// Idle models unloaded under pressure
+    unloads: Arc<AtomicU64>,
+}
+
+impl MemoryMonitor {
+    /// Create a monitor sampling this process and evicting from `cache`
+    pub fn new(config: &MemoryConfig, cache: ModelCache) -> Self {
+        Self {
+            config: config.clone(),
+            source: Arc::new(SystemMemory),
+            cache,
+            latest: Arc::new(Mutex::new(MemoryUsage::default())),
+            evictions: Arc::new(AtomicU64::new(0)),
+            unloads: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Use a different source of usage samples
+    pub fn with_source(mut self, source: Arc<dyn MemorySource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Whether a sample is above either high-water mark
+    pub fn over_high_water(&self, usage: &MemoryUsage) -> bool {
+        let over_rss = self.config.high_water_bytes > 0 && usage.rss_bytes > self.config.high_water_bytes;
+        let over_gpu = self.config.gpu_high_water_bytes > 0
+            && usage.gpu_bytes.is_some_and(|gpu_bytes| gpu_bytes > self.config.gpu_high_water_bytes);
+        over_rss || over_gpu
+    }
+
+    /// Take a usage sample and, above the high-water mark, free one model
+    ///
+    /// Evicts the least recently used cached model, or once the cache is
+    /// empty and `unload_idle` is set, calls `unload_idle` to unload an idle
+    /// one. Returns whether anything was freed.
+    pub async fn check<F, Fut>(&self, unload_idle: F) -> bool
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        // nvidia-smi runs as a child process, so keep it off the runtime threads
+        let source = self.source.clone();
+        let usage = match tokio::task::spawn_blocking(move || source.usage()).await {
+            Ok(usage) => usage,
+            Err(e) => {
+                warn!("Memory sampling failed: {}", e);
+                return false;
+            }
+        };
+        *self.latest.lock() = usage;
+
+        if !self.over_high_water(&usage) {
+            return false;
+        }
+
+        if self.cache.evict_lru().await {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+            info!("Evicted a cached model under memory pressure ({} bytes resident)", usage.rss_bytes);
+            return true;
+        }
+
+        if self.config.unload_idle && unload_idle().await {
+            self.unloads.fetch_add(1, Ordering::Relaxed);
+            info!("Unloaded an idle model under memory pressure ({} bytes resident)", usage.rss_bytes);
+            return true;
+        }
+
+        debug!("Memory above the high-water mark ({} bytes resident) with nothing left to free", usage.rss_bytes);
+        false
+    }
+
+    /// Spawn a background task running `check` every `interval_ms`
+    ///
+    /// Returns `None` when the interval is 0 or neither high-water mark is
+    /// set, since there would be nothing to act on. The task runs until the
+    /// returned guard is stopped or dropped.
+    pub fn start<F, Fut>(&self, unload_idle: F) -> Option<MonitorGuard>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send,
+    {
+        if self.config.interval_ms == 0 {
+            return None;
+        }
+        if self.config.high_water_bytes == 0 && self.config.gpu_high_water_bytes == 0 {
+            debug!("No memory high-water mark set, not starting the memory monitor");
+            return None;
+        }
+
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let monitor = self.clone();
+        let period = Duration::from_millis(self.config.interval_ms);
+
+        debug!("Starting memory monitor");
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(period) => {
+                        monitor.check(&unload_idle).await;
+                    }
+                }
+            }
+
+            debug!("Memory monitor stopped");
+        });
+
+        Some(MonitorGuard { cancel })
+    }
+
+    /// Latest sample and relief counters
+    pub fn stats(&self) -> MemoryStats {
+        MemoryStats {
+            usage: *self.latest.lock(),
+            high_water_bytes: self.config.high_water_bytes,
+            cache_evictions: self.evictions.load(Ordering::Relaxed),
+            idle_unloads: self.unloads.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CacheConfig;
+    use crate::model::{Model, ModelInputType};
+
+    /// Source reporting the same usage on every sample
+    struct Fixed(MemoryUsage);
+
+    impl MemorySource for Fixed {
+        fn usage(&self) -> MemoryUsage {
+            self.0
+        }
+    }
+
+    /// Monitor with a 1000-byte resident limit over a cache holding one model
+    async fn monitor_at(rss_bytes: u64, unload_idle: bool) -> MemoryMonitor {
+        let cache = ModelCache::new(&CacheConfig::default());
+        cache.put(Model::for_test("bert", ModelInputType::Text, b"weights")).await.unwrap();
+        let config = MemoryConfig { high_water_bytes: 1000, unload_idle, ..MemoryConfig::default() };
+        MemoryMonitor::new(&config, cache)
+            .with_source(Arc::new(Fixed(MemoryUsage { rss_bytes, gpu_bytes: None })))
+    }
+
+    #[tokio::test]
+    async fn pressure_evicts_cached_models_before_unloading_idle_ones() {
+        let monitor = monitor_at(2000, true).await;
+        let unloaded = AtomicU64::new(0);
+        let unload_idle = || async {
+            unloaded.fetch_add(1, Ordering::Relaxed);
+            true
+        };
+
+        assert!(monitor.check(unload_idle).await);
+        assert_eq!(unloaded.load(Ordering::Relaxed), 0);
+        assert!(monitor.check(unload_idle).await);
+        assert_eq!(unloaded.load(Ordering::Relaxed), 1);
+
+        let stats = monitor.stats();
+        assert_eq!((stats.usage.rss_bytes, stats.cache_evictions, stats.idle_unloads), (2000, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn idle_models_stay_loaded_unless_configured() {
+        let monitor = monitor_at(2000, false).await;
+        let unloaded = AtomicU64::new(0);
+        let unload_idle = || async {
+            unloaded.fetch_add(1, Ordering::Relaxed);
+            true
+        };
+
+        assert!(monitor.check(unload_idle).await);
+        assert!(!monitor.check(unload_idle).await);
+        assert_eq!(unloaded.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn nothing_is_freed_below_the_high_water_mark() {
+        let monitor = monitor_at(500, true).await;
+
+        assert!(!monitor.check(|| async { true }).await);
+        assert_eq!(monitor.stats().cache_evictions, 0);
+    }
+
+    #[test]
+    fn gpu_usage_counts_against_its_own_mark() {
+        let config = MemoryConfig { gpu_high_water_bytes: 1000, ..MemoryConfig::default() };
+        let monitor = MemoryMonitor::new(&config, ModelCache::new(&CacheConfig::default()));
+
+        assert!(monitor.over_high_water(&MemoryUsage { rss_bytes: u64::MAX, gpu_bytes: Some(2000) }));
+        assert!(!monitor.over_high_water(&MemoryUsage { rss_bytes: u64::MAX, gpu_bytes: Some(500) }));
+        assert!(!monitor.over_high_water(&MemoryUsage { rss_bytes: u64::MAX, gpu_bytes: None }));
+    }
+
+    #[tokio::test]
+    async fn monitor_does_not_start_without_a_limit() {
+        let monitor = MemoryMonitor::new(&MemoryConfig::default(), ModelCache::new(&CacheConfig::default()));
+
+        assert!(monitor.start(|| async { true }).is_none());
+    }
+}
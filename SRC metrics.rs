@@ -1,20 +1,302 @@
 //! Metrics and monitoring for the Synaptron inference engine
 
-use crate::error::SynaptronError;
-use tracing::info;
-use std::sync::atomic::{AtomicU64, AtomicF64, Ordering};
+use tracing::{info, debug, warn};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+
+/// Number of recent latencies kept per model for percentiles
+const MODEL_LATENCY_WINDOW: usize = 1024;
+
+/// Request latencies buffered between StatsD pushes; later ones are counted but not sent
+const STATSD_MAX_TIMINGS: usize = 1000;
+
+/// Largest StatsD datagram, kept under a typical Ethernet MTU
+const STATSD_MAX_PACKET_BYTES: usize = 1432;
+
+/// Request latencies recorded since the last StatsD push
+#[derive(Default)]
+struct PendingTimings {
+    /// Buffered latencies in milliseconds, at most `STATSD_MAX_TIMINGS`
+    latencies_ms: Vec<f64>,
+    
+    /// Requests recorded, including those past the buffer
+    seen: u64,
+}
+
+/// Running metrics for one model
+#[derive(Default)]
+struct ModelMetrics {
+    /// Requests served
+    requests: u64,
+    
+    /// Failed requests
+    errors: u64,
+    
+    /// Requests served from the response cache
+    cache_hits: u64,
+    
+    /// Total latency in milliseconds
+    total_latency_ms: f64,
+    
+    /// Most recent latencies in milliseconds
+    recent_latencies_ms: VecDeque<f64>,
+    
+    /// UNIX timestamp of the last request
+    last_used: Option<u64>,
+}
+
+/// Operational statistics for one model
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModelStats {
+    /// Requests served
+    pub request_count: u64,
+    
+    /// Average latency in milliseconds
+    pub avg_latency_ms: f64,
+    
+    /// 95th percentile latency over recent requests, in milliseconds
+    pub p95_latency_ms: f64,
+    
+    /// Fraction of requests that failed
+    pub error_rate: f64,
+    
+    /// Fraction of requests served from the response cache
+    pub cache_hit_rate: f64,
+    
+    /// UNIX timestamp of the last request, if the model was ever used
+    pub last_used: Option<u64>,
+}
+
+/// Latency and throughput of repeated inference on one model
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BenchmarkReport {
+    /// Benchmarked model
+    pub model: String,
+    
+    /// Inference runs
+    pub iterations: usize,
+    
+    /// Concurrent runs
+    pub concurrency: usize,
+    
+    /// Failed runs
+    pub errors: usize,
+    
+    /// Runs per second over the whole benchmark
+    pub throughput: f64,
+    
+    /// Mean latency in milliseconds
+    pub mean_ms: f64,
+    
+    /// Median latency in milliseconds
+    pub p50_ms: f64,
+    
+    /// 90th percentile latency in milliseconds
+    pub p90_ms: f64,
+    
+    /// 99th percentile latency in milliseconds
+    pub p99_ms: f64,
+    
+    /// Maximum latency in milliseconds
+    pub max_ms: f64,
+}
+
+impl BenchmarkReport {
+    /// Summarize per-run latencies collected over `elapsed`
+    pub fn new(
+        model: &str,
+        concurrency: usize,
+        errors: usize,
+        mut latencies_ms: Vec<f64>,
+        elapsed: Duration,
+    ) -> Self {
+        latencies_ms.sort_by(|a, b| a.total_cmp(b));
+        
+        let percentile = |p: f64| -> f64 {
+            if latencies_ms.is_empty() {
+                return 0.0;
+            }
+            let rank = ((p / 100.0) * (latencies_ms.len() - 1) as f64).round() as usize;
+            latencies_ms[rank]
+        };
+        
+        let iterations = latencies_ms.len();
+        Self {
+            model: model.to_string(),
+            iterations,
+            concurrency,
+            errors,
+            throughput: iterations as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            mean_ms: if iterations > 0 { latencies_ms.iter().sum::<f64>() / iterations as f64 } else { 0.0 },
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p99_ms: percentile(99.0),
+            max_ms: latencies_ms.last().copied().unwrap_or(0.0),
+        }
+    }
+}
+
+/// Status label of successful requests
+pub const STATUS_OK: &str = "ok";
+
+/// Request counters for one model and outcome
+#[derive(Default)]
+struct LabeledCounters {
+    /// Requests with this outcome
+    requests: AtomicU64,
+    
+    /// Total latency in microseconds
+    latency_us: AtomicU64,
+}
+
+/// Request count and latency for one model and outcome
+#[derive(Debug, Clone, Serialize)]
+pub struct LabeledStats {
+    /// Model that served the requests
+    pub model: String,
+    
+    /// `ok` or the error kind
+    pub status: String,
+    
+    /// Requests with this outcome
+    pub requests: u64,
+    
+    /// Average latency in milliseconds
+    pub avg_latency_ms: f64,
+}
+
+/// Upper bounds in milliseconds of the request latency histogram buckets
+const LATENCY_BUCKETS_MS: &[f64] = &[
+    1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0,
+];
+
+/// Request latency percentiles in milliseconds
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LatencyPercentiles {
+    /// Median latency
+    pub p50_ms: f64,
+    
+    /// 90th percentile latency
+    pub p90_ms: f64,
+    
+    /// 95th percentile latency
+    pub p95_ms: f64,
+    
+    /// 99th percentile latency
+    pub p99_ms: f64,
+}
+
+/// Fixed-bucket latency histogram with lock-free counters
+///
+/// The last bucket counts latencies above the largest bound.
+struct LatencyHistogram {
+    /// Request count per bucket of `LATENCY_BUCKETS_MS`, plus the overflow bucket
+    counts: Vec<AtomicU64>,
+    
+    /// Largest recorded latency in microseconds, bounding the overflow bucket
+    max_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    /// Create an empty histogram
+    fn new() -> Self {
+        Self {
+            counts: (0..=LATENCY_BUCKETS_MS.len()).map(|_| AtomicU64::new(0)).collect(),
+            max_us: AtomicU64::new(0),
+        }
+    }
+    
+    /// Count a latency in its bucket
+    fn record(&self, latency_ms: f64) {
+        let bucket = LATENCY_BUCKETS_MS.iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.max_us.fetch_max(to_micros(latency_ms), Ordering::Relaxed);
+    }
+    
+    /// Estimate a percentile (0-100), interpolating linearly within its bucket
+    fn percentile(&self, p: f64) -> f64 {
+        let counts: Vec<u64> = self.counts.iter().map(|count| count.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        
+        let rank = (p.clamp(0.0, 100.0) / 100.0) * total as f64;
+        let max_ms = self.max_us.load(Ordering::Relaxed) as f64 / 1000.0;
+        let mut seen = 0u64;
+        
+        for (bucket, count) in counts.iter().enumerate() {
+            if *count == 0 || ((seen + count) as f64) < rank {
+                seen += count;
+                continue;
+            }
+            
+            let lower = if bucket == 0 { 0.0 } else { LATENCY_BUCKETS_MS[bucket - 1] };
+            let upper = LATENCY_BUCKETS_MS.get(bucket).copied().unwrap_or(max_ms).min(max_ms.max(lower));
+            let fraction = (rank - seen as f64) / *count as f64;
+            return lower + (upper - lower) * fraction.clamp(0.0, 1.0);
+        }
+        
+        max_ms
+    }
+    
+    /// Cumulative request count at each bucket bound, as Prometheus `le` buckets
+    fn cumulative_buckets(&self) -> Vec<(f64, u64)> {
+        let mut cumulative = 0;
+        LATENCY_BUCKETS_MS.iter()
+            .zip(&self.counts)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (*bound, cumulative)
+            })
+            .collect()
+    }
+    
+    /// Clear all buckets
+    fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+        self.max_us.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Whole microseconds in a millisecond latency, for integer accumulators
+fn to_micros(latency_ms: f64) -> u64 {
+    (latency_ms.max(0.0) * 1000.0).round() as u64
+}
 
 /// Metrics collector
 pub struct MetricsCollector {
     /// Total number of requests
     total_requests: Arc<AtomicU64>,
     
-    /// Total latency in milliseconds
-    total_latency_ms: Arc<AtomicF64>,
+    /// Total latency in microseconds
+    total_latency_us: Arc<AtomicU64>,
     
     /// Total number of successful requests
     successful_requests: Arc<AtomicU64>,
+    
+    /// Distribution of request latencies
+    latency_histogram: Arc<LatencyHistogram>,
+    
+    /// Per-model metrics
+    models: Arc<Mutex<HashMap<String, ModelMetrics>>>,
+    
+    /// Request counters keyed by model name and status
+    labeled: Arc<DashMap<(String, String), LabeledCounters>>,
+    
+    /// Latencies waiting to be pushed to StatsD as timers
+    pending_timings: Arc<Mutex<PendingTimings>>,
 }
 
 impl MetricsCollector {
@@ -22,21 +304,194 @@ impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             total_requests: Arc::new(AtomicU64::new(0)),
-            total_latency_ms: Arc::new(AtomicF64::new(0.0)),
+            total_latency_us: Arc::new(AtomicU64::new(0)),
             successful_requests: Arc::new(AtomicU64::new(0)),
+            latency_histogram: Arc::new(LatencyHistogram::new()),
+            models: Arc::new(Mutex::new(HashMap::new())),
+            labeled: Arc::new(DashMap::new()),
+            pending_timings: Arc::new(Mutex::new(PendingTimings::default())),
         }
     }
     
     /// Record a request
     pub fn record_request(&self, latency_ms: f64, success: bool) {
         self.total_requests.fetch_add(1, Ordering::Relaxed);
-        self.total_latency_ms.fetch_add(latency_ms, Ordering::Relaxed);
+        self.total_latency_us.fetch_add(to_micros(latency_ms), Ordering::Relaxed);
+        self.record_latency_histogram(latency_ms);
+        
+        {
+            let mut pending = self.pending_timings.lock();
+            pending.seen += 1;
+            if pending.latencies_ms.len() < STATSD_MAX_TIMINGS {
+                pending.latencies_ms.push(latency_ms);
+            }
+        }
         
         if success {
             self.successful_requests.fetch_add(1, Ordering::Relaxed);
         }
     }
     
+    /// Record a request latency in the latency histogram
+    pub fn record_latency_histogram(&self, latency_ms: f64) {
+        self.latency_histogram.record(latency_ms);
+    }
+    
+    /// Get a request latency percentile (0-100) in milliseconds, estimated from the latency histogram
+    ///
+    /// Memory stays bounded by the fixed buckets, so the estimate is exact only
+    /// to within a bucket; values are interpolated linearly inside it.
+    pub fn get_percentile(&self, p: f64) -> f64 {
+        self.latency_histogram.percentile(p)
+    }
+    
+    /// Get p50/p90/p95/p99 request latency
+    pub fn get_latency_percentiles(&self) -> LatencyPercentiles {
+        LatencyPercentiles {
+            p50_ms: self.get_percentile(50.0),
+            p90_ms: self.get_percentile(90.0),
+            p95_ms: self.get_percentile(95.0),
+            p99_ms: self.get_percentile(99.0),
+        }
+    }
+    
+    /// Render the collected metrics in the Prometheus text exposition format
+    pub fn format_prometheus(&self) -> String {
+        let total = self.get_total_requests();
+        let percentiles = self.get_latency_percentiles();
+        let mut lines = vec![
+            "# HELP synaptron_predict_requests_total Prediction requests served".to_string(),
+            "# TYPE synaptron_predict_requests_total counter".to_string(),
+            format!("synaptron_predict_requests_total {}", total),
+            "# HELP synaptron_predict_requests_successful_total Prediction requests that succeeded".to_string(),
+            "# TYPE synaptron_predict_requests_successful_total counter".to_string(),
+            format!("synaptron_predict_requests_successful_total {}", self.successful_requests.load(Ordering::Relaxed)),
+            "# HELP synaptron_request_latency_ms Request latency percentiles in milliseconds".to_string(),
+            "# TYPE synaptron_request_latency_ms summary".to_string(),
+        ];
+        
+        for (quantile, value) in [
+            ("0.5", percentiles.p50_ms),
+            ("0.9", percentiles.p90_ms),
+            ("0.95", percentiles.p95_ms),
+            ("0.99", percentiles.p99_ms),
+        ] {
+            lines.push(format!("synaptron_request_latency_ms{{quantile=\"{}\"}} {}", quantile, value));
+        }
+        lines.push(format!(
+            "synaptron_request_latency_ms_sum {}",
+            self.total_latency_us.load(Ordering::Relaxed) as f64 / 1000.0
+        ));
+        lines.push(format!("synaptron_request_latency_ms_count {}", total));
+        
+        lines.push("# HELP synaptron_request_duration_ms Request latency histogram in milliseconds".to_string());
+        lines.push("# TYPE synaptron_request_duration_ms histogram".to_string());
+        for (bound, count) in self.latency_histogram.cumulative_buckets() {
+            lines.push(format!("synaptron_request_duration_ms_bucket{{le=\"{}\"}} {}", bound, count));
+        }
+        lines.push(format!("synaptron_request_duration_ms_bucket{{le=\"+Inf\"}} {}", total));
+        lines.push(format!(
+            "synaptron_request_duration_ms_sum {}",
+            self.total_latency_us.load(Ordering::Relaxed) as f64 / 1000.0
+        ));
+        lines.push(format!("synaptron_request_duration_ms_count {}", total));
+        
+        let labeled = self.labeled_stats();
+        lines.push("# HELP synaptron_requests_total Model inference requests by model and status".to_string());
+        lines.push("# TYPE synaptron_requests_total counter".to_string());
+        for stats in &labeled {
+            lines.push(format!("synaptron_requests_total{{{}}} {}", prometheus_labels(stats), stats.requests));
+        }
+        lines.push("# HELP synaptron_request_latency_ms_avg Average model inference latency by model and status".to_string());
+        lines.push("# TYPE synaptron_request_latency_ms_avg gauge".to_string());
+        for stats in &labeled {
+            lines.push(format!("synaptron_request_latency_ms_avg{{{}}} {}", prometheus_labels(stats), stats.avg_latency_ms));
+        }
+        
+        lines.join("\n") + "\n"
+    }
+    
+    /// Record a request served by a model
+    pub fn record_model_request(&self, model_name: &str, latency_ms: f64, success: bool, cache_hit: bool) {
+        let mut models = self.models.lock();
+        let metrics = models.entry(model_name.to_string()).or_default();
+        
+        metrics.requests += 1;
+        metrics.total_latency_ms += latency_ms;
+        if !success {
+            metrics.errors += 1;
+        }
+        if cache_hit {
+            metrics.cache_hits += 1;
+        }
+        
+        if metrics.recent_latencies_ms.len() == MODEL_LATENCY_WINDOW {
+            metrics.recent_latencies_ms.pop_front();
+        }
+        metrics.recent_latencies_ms.push_back(latency_ms);
+        
+        metrics.last_used = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+    }
+    
+    /// Record a model request under its `(model, status)` labels
+    ///
+    /// `status` is `ok` or the kind of error the request failed with.
+    pub fn record_labeled_request(&self, model_name: &str, status: &str, latency_ms: f64) {
+        let key = (model_name.to_string(), status.to_string());
+        let counters = self.labeled.entry(key).or_default();
+        
+        counters.requests.fetch_add(1, Ordering::Relaxed);
+        counters.latency_us.fetch_add(to_micros(latency_ms), Ordering::Relaxed);
+    }
+    
+    /// Request counts and latency per model and status, sorted by model then status
+    pub fn labeled_stats(&self) -> Vec<LabeledStats> {
+        let mut stats: Vec<LabeledStats> = self.labeled.iter()
+            .map(|entry| {
+                let (model, status) = entry.key();
+                let requests = entry.requests.load(Ordering::Relaxed);
+                let latency_ms = entry.latency_us.load(Ordering::Relaxed) as f64 / 1000.0;
+                
+                LabeledStats {
+                    model: model.clone(),
+                    status: status.clone(),
+                    requests,
+                    avg_latency_ms: if requests > 0 { latency_ms / requests as f64 } else { 0.0 },
+                }
+            })
+            .collect();
+        
+        stats.sort_by(|a, b| (&a.model, &a.status).cmp(&(&b.model, &b.status)));
+        stats
+    }
+    
+    /// Get statistics for a model, zeroed if it has served no requests
+    pub fn model_stats(&self, model_name: &str) -> ModelStats {
+        let models = self.models.lock();
+        let metrics = match models.get(model_name) {
+            Some(metrics) if metrics.requests > 0 => metrics,
+            _ => return ModelStats::default(),
+        };
+        
+        let requests = metrics.requests as f64;
+        
+        let mut sorted: Vec<f64> = metrics.recent_latencies_ms.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let p95_index = ((sorted.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+        
+        ModelStats {
+            request_count: metrics.requests,
+            avg_latency_ms: metrics.total_latency_ms / requests,
+            p95_latency_ms: sorted.get(p95_index).copied().unwrap_or(0.0),
+            error_rate: metrics.errors as f64 / requests,
+            cache_hit_rate: metrics.cache_hits as f64 / requests,
+            last_used: metrics.last_used,
+        }
+    }
+    
     /// Get total requests
     pub fn get_total_requests(&self) -> u64 {
         self.total_requests.load(Ordering::Relaxed)
@@ -45,10 +500,10 @@ impl MetricsCollector {
     /// Get average latency
     pub fn get_avg_latency_ms(&self) -> f64 {
         let total_requests = self.total_requests.load(Ordering::Relaxed);
-        let total_latency = self.total_latency_ms.load(Ordering::Relaxed);
+        let total_latency_ms = self.total_latency_us.load(Ordering::Relaxed) as f64 / 1000.0;
         
         if total_requests > 0 {
-            total_latency / (total_requests as f64)
+            total_latency_ms / (total_requests as f64)
         } else {
             0.0
         }
@@ -75,12 +530,28 @@ impl MetricsCollector {
         }
     }
     
+    /// Drop a model's running statistics once it is unloaded
+    ///
+    /// Labeled request counters are kept, since Prometheus counters must not go backwards.
+    pub fn forget_model(&self, model_name: &str) {
+        self.models.lock().remove(model_name);
+    }
+    
     /// Reset metrics
     pub fn reset(&self) {
         info!("Resetting metrics");
         self.total_requests.store(0, Ordering::Relaxed);
-        self.total_latency_ms.store(0.0, Ordering::Relaxed);
+        self.total_latency_us.store(0, Ordering::Relaxed);
         self.successful_requests.store(0, Ordering::Relaxed);
+        self.latency_histogram.reset();
+        self.models.lock().clear();
+        self.labeled.clear();
+    }
+}
+
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -88,8 +559,317 @@ impl Clone for MetricsCollector {
     fn clone(&self) -> Self {
         Self {
             total_requests: self.total_requests.clone(),
-            total_latency_ms: self.total_latency_ms.clone(),
+            total_latency_us: self.total_latency_us.clone(),
             successful_requests: self.successful_requests.clone(),
+            latency_histogram: self.latency_histogram.clone(),
+            models: self.models.clone(),
+            labeled: self.labeled.clone(),
+            pending_timings: self.pending_timings.clone(),
+        }
+    }
+}
+
+/// `model` and `status` label pairs of a labeled series, with values escaped
+fn prometheus_labels(stats: &LabeledStats) -> String {
+    let escape = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("model=\"{}\",status=\"{}\"", escape(&stats.model), escape(&stats.status))
+}
+
+/// Spawn a background task pushing metrics to a StatsD server using the DogStatsD tag format
+///
+/// Send and resolution failures are logged and retried on the next tick.
+pub fn spawn_statsd_exporter(
+    collector: MetricsCollector,
+    endpoint: String,
+    interval: Duration,
+) -> JoinHandle<()> {
+    info!("Starting StatsD exporter to {}", endpoint);
+    
+    tokio::spawn(async move {
+        let socket = match UdpSocket::bind("0.0.0.0:0").await {
+            Ok(socket) => socket,
+            Err(e) => {
+                warn!("Failed to bind StatsD socket, exporter disabled: {}", e);
+                return;
+            }
+        };
+        
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_total = 0u64;
+        
+        loop {
+            ticker.tick().await;
+            
+            let addr = match tokio::net::lookup_host(&endpoint).await.map(|mut addrs| addrs.next()) {
+                Ok(Some(addr)) => addr,
+                Ok(None) | Err(_) => {
+                    warn!("Could not resolve StatsD endpoint {}, retrying next interval", endpoint);
+                    continue;
+                }
+            };
+            
+            let total = collector.get_total_requests();
+            let timings = std::mem::take(&mut *collector.pending_timings.lock());
+            let lines = format_statsd(&collector, total.saturating_sub(last_total), &timings);
+            
+            let mut sent = true;
+            for packet in statsd_packets(&lines) {
+                if let Err(e) = socket.send_to(packet.as_bytes(), addr).await {
+                    warn!("Failed to push metrics to StatsD at {}: {}", addr, e);
+                    sent = false;
+                    break;
+                }
+            }
+            if sent {
+                debug!("Pushed metrics to StatsD at {}", addr);
+                last_total = total;
+            }
+        }
+    })
+}
+
+/// Format a metrics snapshot as DogStatsD lines, with each buffered request latency as a timer
+fn format_statsd(collector: &MetricsCollector, new_requests: u64, timings: &PendingTimings) -> Vec<String> {
+    const TAGS: &str = "#service:synaptron";
+    
+    let mut lines = vec![
+        format!("synaptron.requests:{}|c|{}", new_requests, TAGS),
+        format!("synaptron.requests_total:{}|g|{}", collector.get_total_requests(), TAGS),
+        format!("synaptron.success_rate:{}|g|{}", collector.get_success_rate(), TAGS),
+    ];
+    
+    // A sample rate tells the server how many requests each sent timing stands for
+    let sample_rate = if timings.seen > timings.latencies_ms.len() as u64 {
+        format!("|@{:.4}", timings.latencies_ms.len() as f64 / timings.seen as f64)
+    } else {
+        String::new()
+    };
+    lines.extend(timings.latencies_ms.iter()
+        .map(|latency_ms| format!("synaptron.latency:{}|ms{}|{}", latency_ms, sample_rate, TAGS)));
+    
+    lines
+}
+
+/// Pack StatsD lines into newline-separated datagrams of at most `STATSD_MAX_PACKET_BYTES`
+fn statsd_packets(lines: &[String]) -> Vec<String> {
+    let mut packets: Vec<String> = Vec::new();
+    
+    for line in lines {
+        match packets.last_mut() {
+            Some(packet) if packet.len() + 1 + line.len() <= STATSD_MAX_PACKET_BYTES => {
+                packet.push('\n');
+                packet.push_str(line);
+            }
+            _ => packets.push(line.clone()),
+        }
+    }
+    
+    packets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collector that has served requests taking 1 to 100 ms, one of each
+    fn uniform_collector() -> MetricsCollector {
+        let collector = MetricsCollector::new();
+        for latency_ms in 1..=100 {
+            collector.record_request(latency_ms as f64, true);
+        }
+        collector
+    }
+
+    #[test]
+    fn percentiles_follow_a_known_distribution() {
+        let percentiles = uniform_collector().get_latency_percentiles();
+
+        assert!((percentiles.p50_ms - 50.0).abs() < 1e-9, "p50 was {}", percentiles.p50_ms);
+        assert!((percentiles.p90_ms - 90.0).abs() < 1e-9, "p90 was {}", percentiles.p90_ms);
+        assert!((percentiles.p95_ms - 95.0).abs() < 1e-9, "p95 was {}", percentiles.p95_ms);
+        assert!((percentiles.p99_ms - 99.0).abs() < 1e-9, "p99 was {}", percentiles.p99_ms);
+    }
+
+    #[test]
+    fn slow_outliers_show_in_the_tail_only() {
+        let collector = MetricsCollector::new();
+        for _ in 0..98 {
+            collector.record_request(1.0, true);
+        }
+        collector.record_request(5_000.0, true);
+        collector.record_request(20_000.0, true);
+
+        assert!(collector.get_percentile(50.0) <= 1.0);
+        assert!(collector.get_percentile(99.5) > 10_000.0);
+        assert!((collector.get_percentile(100.0) - 20_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn histogram_memory_is_bounded() {
+        let collector = MetricsCollector::new();
+        for latency_ms in 0..100_000 {
+            collector.record_request(latency_ms as f64 / 10.0, true);
         }
+
+        assert_eq!(collector.latency_histogram.counts.len(), LATENCY_BUCKETS_MS.len() + 1);
+        assert_eq!(collector.get_total_requests(), 100_000);
+    }
+
+    #[test]
+    fn empty_collector_reports_zero_percentiles() {
+        assert_eq!(MetricsCollector::new().get_percentile(99.0), 0.0);
+    }
+
+    #[test]
+    fn prometheus_output_exposes_quantiles_as_a_summary() {
+        let output = uniform_collector().format_prometheus();
+
+        assert!(output.contains("# TYPE synaptron_request_latency_ms summary\n"));
+        assert!(output.contains("synaptron_request_latency_ms{quantile=\"0.9\"} 90\n"));
+        assert!(output.contains("synaptron_request_latency_ms_sum 5050\n"));
+        assert!(output.contains("synaptron_request_latency_ms_count 100\n"));
+        assert!(output.contains("synaptron_request_duration_ms_bucket{le=\"+Inf\"} 100\n"));
+    }
+
+    #[test]
+    fn statsd_lines_carry_counts_gauges_and_timers() {
+        let collector = MetricsCollector::new();
+        collector.record_request(12.5, true);
+        collector.record_request(7.0, false);
+        let timings = std::mem::take(&mut *collector.pending_timings.lock());
+
+        let lines = format_statsd(&collector, 2, &timings);
+
+        assert_eq!(lines, [
+            "synaptron.requests:2|c|#service:synaptron",
+            "synaptron.requests_total:2|g|#service:synaptron",
+            "synaptron.success_rate:50|g|#service:synaptron",
+            "synaptron.latency:12.5|ms|#service:synaptron",
+            "synaptron.latency:7|ms|#service:synaptron",
+        ]);
+        assert!(collector.pending_timings.lock().latencies_ms.is_empty());
+    }
+
+    #[test]
+    fn timings_past_the_buffer_are_sampled() {
+        let collector = MetricsCollector::new();
+        for _ in 0..STATSD_MAX_TIMINGS * 2 {
+            collector.record_request(1.0, true);
+        }
+        let timings = std::mem::take(&mut *collector.pending_timings.lock());
+
+        let lines = format_statsd(&collector, 0, &timings);
+
+        assert_eq!(timings.latencies_ms.len(), STATSD_MAX_TIMINGS);
+        assert!(lines[3].ends_with("|ms|@0.5000|#service:synaptron"), "{}", lines[3]);
+    }
+
+    #[test]
+    fn statsd_packets_fit_one_datagram_each() {
+        let lines: Vec<String> = (0..500).map(|i| format!("synaptron.latency:{}|ms|#service:synaptron", i)).collect();
+
+        let packets = statsd_packets(&lines);
+
+        assert!(packets.len() > 1);
+        assert!(packets.iter().all(|packet| packet.len() <= STATSD_MAX_PACKET_BYTES));
+        assert_eq!(packets.join("\n"), lines.join("\n"));
+    }
+
+    #[test]
+    fn model_stats_summarize_recent_requests() {
+        let collector = MetricsCollector::new();
+        for latency_ms in 1..=20 {
+            collector.record_model_request("bert", latency_ms as f64, latency_ms % 10 != 0, latency_ms <= 5);
+        }
+
+        let stats = collector.model_stats("bert");
+
+        assert_eq!(stats.request_count, 20);
+        assert!((stats.avg_latency_ms - 10.5).abs() < 1e-9);
+        assert_eq!(stats.p95_latency_ms, 19.0);
+        assert!((stats.error_rate - 0.1).abs() < 1e-9);
+        assert!((stats.cache_hit_rate - 0.25).abs() < 1e-9);
+        assert!(stats.last_used.is_some());
+    }
+
+    #[test]
+    fn unused_and_forgotten_models_have_zeroed_stats() {
+        let collector = MetricsCollector::new();
+        collector.record_model_request("bert", 5.0, true, false);
+        collector.forget_model("bert");
+
+        assert_eq!(collector.model_stats("bert").request_count, 0);
+        assert_eq!(collector.model_stats("gpt").last_used, None);
+    }
+
+    #[test]
+    fn benchmark_report_summarizes_run_latencies() {
+        let latencies_ms = (1..=100).rev().map(|latency_ms| latency_ms as f64).collect();
+
+        let report = BenchmarkReport::new("bert", 4, 2, latencies_ms, Duration::from_secs(2));
+
+        assert_eq!((report.iterations, report.concurrency, report.errors), (100, 4, 2));
+        assert_eq!(report.throughput, 50.0);
+        assert_eq!(report.mean_ms, 50.5);
+        assert_eq!((report.p50_ms, report.p90_ms, report.p99_ms, report.max_ms), (51.0, 90.0, 99.0, 100.0));
+    }
+
+    #[test]
+    fn empty_benchmark_reports_zeros() {
+        let report = BenchmarkReport::new("bert", 1, 3, Vec::new(), Duration::from_secs(1));
+
+        assert_eq!((report.iterations, report.mean_ms, report.p99_ms, report.max_ms), (0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn latency_accumulates_without_float_drift() {
+        let collector = MetricsCollector::new();
+        for _ in 0..1_000_000 {
+            collector.record_request(0.1, true);
+        }
+
+        assert_eq!(collector.total_latency_us.load(Ordering::Relaxed), 100_000_000);
+        assert_eq!(collector.get_avg_latency_ms(), 0.1);
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let collector = MetricsCollector::new();
+        for latency_ms in [0.5, 1.0, 3.0, 20_000.0] {
+            collector.record_request(latency_ms, true);
+        }
+
+        let buckets = collector.latency_histogram.cumulative_buckets();
+
+        assert_eq!(buckets[0], (1.0, 2));
+        assert_eq!(buckets[2], (5.0, 3));
+        assert_eq!(buckets.last(), Some(&(10000.0, 3)));
+    }
+
+    #[test]
+    fn labeled_requests_are_counted_per_model_and_status() {
+        let collector = MetricsCollector::new();
+        collector.record_labeled_request("gpt", STATUS_OK, 4.0);
+        collector.record_labeled_request("bert", "timeout", 30.0);
+        collector.record_labeled_request("bert", STATUS_OK, 2.0);
+        collector.record_labeled_request("bert", STATUS_OK, 6.0);
+
+        let stats = collector.labeled_stats();
+
+        let keys: Vec<(&str, &str, u64)> = stats.iter()
+            .map(|stats| (stats.model.as_str(), stats.status.as_str(), stats.requests))
+            .collect();
+        assert_eq!(keys, [("bert", "ok", 2), ("bert", "timeout", 1), ("gpt", "ok", 1)]);
+        assert_eq!(stats[0].avg_latency_ms, 4.0);
+    }
+
+    #[test]
+    fn prometheus_label_values_are_escaped() {
+        let collector = MetricsCollector::new();
+        collector.record_labeled_request("my \"model\"\\v2", STATUS_OK, 1.0);
+
+        let output = collector.format_prometheus();
+
+        assert!(output.contains(r#"synaptron_requests_total{model="my \"model\"\\v2",status="ok"} 1"#), "{}", output);
     }
 }
@@ -1,21 +1,78 @@
-/! Model management and loading for the Synaptron inference engine
+//! Model management and loading for the Synaptron inference engine
 
-use crate::{config::ModelConfig, error::SynaptronError};
+use crate::{config::ModelConfig, error::SynaptronError, gguf::{Gguf, GGUF_MAGIC}, preprocessing::{PreprocessorConfig, PREPROCESSOR_CONFIG_FILE}, quantization::Safetensors};
 use tracing::{info, debug, warn};
 use std::path::Path;
+use std::time::Duration;
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use futures::StreamExt;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokenizers::Tokenizer;
+use sha2::{Digest, Sha256};
+
+/// Leading bytes of a git-LFS pointer file
+const GIT_LFS_POINTER: &[u8] = b"version https://git-lfs";
+
+/// Leading bytes of a zip archive, used by PyTorch and TorchScript files
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Leading byte of a pickle stream with protocol 2 or later, used by legacy PyTorch files
+const PICKLE_PROTO: u8 = 0x80;
 
 /// Model input types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ModelInputType {
     Text,
     Image,
     Audio,
 }
 
+/// Model bytes shared between clones of a model
+///
+/// Cloning bumps a reference count, so the cache and active models hold one
+/// copy between them. The bytes stay in the buffer they were read into rather
+/// than being copied into a fresh `Arc<[u8]>`.
+#[derive(Clone)]
+pub struct ModelData(Arc<Vec<u8>>);
+
+impl ModelData {
+    /// Read a model file
+    pub async fn read(path: &str) -> Result<Self, SynaptronError> {
+        Ok(fs::read(path).await?.into())
+    }
+
+    /// Whether two handles share the same bytes
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl Deref for ModelData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for ModelData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for ModelData {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(bytes))
+    }
+}
+
 /// Model representation
+#[derive(Clone)]
 pub struct Model {
     /// Model name
     pub name: String,
@@ -32,7 +89,26 @@ pub struct Model {
     /// Model metadata
     pub metadata: ModelMetadata,
 
-    /// Loaded model data
+    /// Loaded model data, shared by clones
+    pub data: ModelData,
+
+    /// Backend chosen by the optimizer, if the model has been optimized
+    pub optimized_backend: Option<String>,
+
+    /// Tokenizer shipped with a text model, loaded from `tokenizer.json` beside it
+    pub tokenizer: Option<Arc<Tokenizer>>,
+}
+
+/// Named output tensor returned by a backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputTensor {
+    /// Tensor dimensions
+    pub shape: Vec<usize>,
+
+    /// Element data type
+    pub dtype: String,
+
+    /// Raw little-endian tensor data
     pub data: Vec<u8>,
 }
 
@@ -59,6 +135,201 @@ pub struct ModelMetadata {
 
     /// Required libraries
     pub required_libs: Vec<String>,
+
+    /// Tokenizer vocabulary size, if known
+    #[serde(default)]
+    pub vocab_size: Option<usize>,
+
+    /// Class labels by output index, for classification models
+    #[serde(default)]
+    pub id2label: Option<HashMap<usize, String>>,
+
+    /// Settings from `preprocessor_config.json` beside the model, if present
+    #[serde(default)]
+    pub preprocessor_config: Option<PreprocessorConfig>,
+
+    /// Hex SHA256 digest of the model data
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// Suffixes of token embedding weights, shaped `[vocab_size, hidden_size]`
+const TOKEN_EMBEDDING_SUFFIXES: &[&str] = &[
+    "word_embeddings.weight", "embed_tokens.weight", "wte.weight", "token_embedding.weight", "shared.weight",
+];
+
+/// Suffixes of position embedding weights, shaped `[max_positions, hidden_size]`
+const POSITION_EMBEDDING_SUFFIXES: &[&str] = &["position_embeddings.weight", "wpe.weight", "embed_positions.weight"];
+
+/// Suffixes of image stem convolution weights, shaped `[out_channels, in_channels, kh, kw]`
+const IMAGE_STEM_SUFFIXES: &[&str] = &[
+    "patch_embeddings.projection.weight", "patch_embed.proj.weight", "embedder.convolution.weight", "conv1.weight",
+];
+
+/// Suffixes of output head weights, shaped `[num_outputs, hidden_size]`
+const OUTPUT_HEAD_SUFFIXES: &[&str] = &["classifier.weight", "lm_head.weight", "score.weight", "head.weight", "fc.weight"];
+
+/// Sequence length used when neither the weights nor config.json give one
+const DEFAULT_SEQUENCE_LENGTH: usize = 512;
+
+/// Image side length used when config.json doesn't give one
+const DEFAULT_IMAGE_SIZE: usize = 224;
+
+/// Metadata derived from a model's weight tensors
+#[derive(Debug, Default)]
+struct ShapeHints {
+    /// Input dimensions
+    input_shape: Option<Vec<usize>>,
+
+    /// Output dimensions
+    output_shape: Option<Vec<usize>>,
+
+    /// Element type of the bulk of the weights
+    data_type: Option<String>,
+
+    /// Token embedding rows
+    vocab_size: Option<usize>,
+
+    /// Architecture recorded in the weights file
+    architecture: Option<String>,
+}
+
+impl ShapeHints {
+    /// Derive shapes from the known weight tensors in a safetensors header
+    ///
+    /// Text models are recognised by a token embedding and image models by a
+    /// stem convolution; config.json supplies sequence length and image size
+    /// when the weights don't.
+    fn from_safetensors(data: &[u8], config: &HashMap<String, serde_json::Value>) -> Result<Self, SynaptronError> {
+        let parsed = Safetensors::parse(data)?;
+        // Shallowest match wins, so `conv1.weight` finds the stem rather than a block's conv
+        let find = |suffixes: &[&str]| parsed.tensors.iter()
+            .filter(|(name, _)| suffixes.iter().any(|suffix| {
+                name == suffix || name.strip_suffix(suffix).is_some_and(|prefix| prefix.ends_with('.'))
+            }))
+            .min_by_key(|(name, _)| name.matches('.').count())
+            .map(|(_, info)| info.shape.clone());
+        let config_usize = |key: &str| config.get(key).and_then(|v| v.as_u64()).map(|v| v as usize);
+        
+        // The dtype holding the most bytes, so a few f32 norms don't mask f16 weights
+        let mut bytes_by_dtype: HashMap<&str, usize> = HashMap::new();
+        for (_, info) in &parsed.tensors {
+            *bytes_by_dtype.entry(info.dtype.as_str()).or_default() += info.data_offsets[1] - info.data_offsets[0];
+        }
+        let data_type = bytes_by_dtype.into_iter()
+            .max_by_key(|(dtype, bytes)| (*bytes, *dtype))
+            .map(|(dtype, _)| dtype.to_lowercase());
+        
+        let mut hints = ShapeHints { data_type, ..Self::default() };
+        let head = find(OUTPUT_HEAD_SUFFIXES).filter(|shape| shape.len() == 2);
+        
+        if let Some(embedding) = find(TOKEN_EMBEDDING_SUFFIXES).filter(|shape| shape.len() == 2) {
+            let sequence_length = find(POSITION_EMBEDDING_SUFFIXES)
+                .and_then(|shape| shape.first().copied())
+                .or_else(|| config_usize("max_position_embeddings"))
+                .unwrap_or(DEFAULT_SEQUENCE_LENGTH);
+            
+            hints.vocab_size = Some(embedding[0]);
+            hints.input_shape = Some(vec![1, sequence_length]);
+            hints.output_shape = Some(match head {
+                Some(head) => vec![1, head[0]],
+                None => vec![1, sequence_length, embedding[1]],
+            });
+        } else if let Some(stem) = find(IMAGE_STEM_SUFFIXES).filter(|shape| shape.len() == 4) {
+            let image_size = config_usize("image_size").unwrap_or(DEFAULT_IMAGE_SIZE);
+            
+            hints.input_shape = Some(vec![1, stem[1], image_size, image_size]);
+            hints.output_shape = head.map(|head| vec![1, head[0]]);
+        }
+        
+        debug!("Safetensors header of {} tensors gives {:?}", parsed.tensors.len(), hints);
+        Ok(hints)
+    }
+    
+    /// Read shapes, vocabulary and quantization from GGUF metadata
+    ///
+    /// GGUF files hold causal language models, so the input is a context of
+    /// tokens and the output is logits over the vocabulary for each position.
+    fn from_gguf(data: &[u8]) -> Result<Self, SynaptronError> {
+        let parsed = Gguf::parse(data)?;
+        let sequence_length = parsed.context_length().unwrap_or(DEFAULT_SEQUENCE_LENGTH);
+        let vocab_size = parsed.vocab_size();
+        
+        let hints = ShapeHints {
+            input_shape: Some(vec![1, sequence_length]),
+            output_shape: vocab_size.or(parsed.embedding_length()).map(|width| vec![1, sequence_length, width]),
+            data_type: parsed.quantization(),
+            vocab_size,
+            architecture: parsed.architecture().map(str::to_string),
+        };
+        
+        debug!("GGUF v{} header of {} tensors gives {:?}", parsed.version, parsed.tensors.len(), hints);
+        Ok(hints)
+    }
+}
+
+/// Why a download attempt failed
+enum DownloadFailure {
+    /// Transient, such as a dropped connection or a 5xx; worth another attempt
+    Retry(SynaptronError),
+
+    /// Permanent, such as a 404 or 401
+    Fatal(SynaptronError),
+}
+
+/// What the server has said about a file across download attempts
+#[derive(Debug, Default)]
+struct RemoteFile {
+    /// ETag of the full response, sent as `If-Range` when resuming
+    etag: Option<String>,
+
+    /// Complete size in bytes
+    total: Option<u64>,
+}
+
+/// Parsed `Content-Range: bytes <start>-<end>/<total>` header
+struct ContentRange {
+    /// First byte of the range
+    start: u64,
+
+    /// Complete size, unless the server sent `*`
+    total: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parse a `Content-Range` header value
+    fn parse(value: &str) -> Option<Self> {
+        let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+        let start = range.split_once('-')?.0.trim().parse().ok()?;
+        Some(Self { start, total: total.trim().parse().ok() })
+    }
+}
+
+/// File stem unique to a path: the path's own stem and a digest of the whole path
+pub fn path_file_stem(path: &str) -> String {
+    let stem = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("model");
+    let digest = format!("{:x}", Sha256::digest(path.as_bytes()));
+    format!("{}-{}", stem, &digest[..16])
+}
+
+/// Model details stored next to a cached model file
+#[derive(Serialize, Deserialize)]
+struct CacheSidecar {
+    /// Model name, absent from files cached before names were recorded
+    #[serde(default)]
+    name: Option<String>,
+
+    /// Model format
+    format: String,
+
+    /// Model input type
+    input_type: ModelInputType,
+
+    /// Model metadata
+    metadata: ModelMetadata,
+
+    /// Size of the cached data in bytes
+    size: usize,
 }
 
 impl Model {
@@ -66,10 +337,25 @@ impl Model {
     pub async fn load(path: &str, config: &ModelConfig) -> Result<Self, SynaptronError> {
         info!("Loading model from: {}", path);
         
+        // Get model name from path
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        
+        // Determine model format and reject disallowed formats before touching the file
+        let format = Self::detect_format(path)?;
+        if !config.is_format_allowed(&format) {
+            return Err(SynaptronError::UnsupportedFormat(format!(
+                "Format '{}' of {} is not in model.allowed_formats", format, path
+            )));
+        }
+        
         // Check if file exists
         if !Path::new(path).exists() {
-            // Try to download from Hugging Face if auto-download is enabled
-            if config.auto_download {
+            // Try to download from Hugging Face if auto-download is enabled for this model
+            if config.auto_download_for(&name) {
                 info!("Model not found locally, attempting to download from Hugging Face");
                 Self::download_from_huggingface(path, config).await?;
             } else {
@@ -77,27 +363,28 @@ impl Model {
             }
         }
         
-        // Read model data
-        let data = fs::read(path).await?;
+        // Read model data and check it is what the extension claims
+        let data = ModelData::read(path).await?;
         let size = data.len();
+        Self::validate_content(path, &format, &data)?;
         
-        // Get model name from path
-        let name = Path::new(path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        
-        // Determine model format
-        let format = Self::detect_format(path)?;
+        // Check the data against the configured or `.sha256` sidecar digest
+        let expected = match config.for_model(&name).and_then(|overrides| overrides.sha256.clone()) {
+            Some(expected) => Some(expected),
+            None => Self::read_digest_file(path).await?,
+        };
+        let (data, digest) = Self::digest(data).await?;
+        Self::verify_digest(path, &digest, expected.as_deref())?;
         
         // Determine input type
         let input_type = Self::detect_input_type(&name, &format)?;
         
-        // Extract metadata from config.json if available
-        let metadata = Self::extract_metadata(path).await?;
+        // Extract metadata from the safetensors or GGUF header and config.json if available
+        let mut metadata = Self::extract_metadata(path, &format, &data).await?;
+        metadata.sha256 = Some(digest);
         
-        info!("Model loaded successfully. Size: {} bytes, Format: {}, Input Type: {:?}", size, format, input_type);
+        info!("Model loaded successfully. Size: {} bytes, Format: {}, Input Type: {:?}, SHA256: {}",
+            size, format, input_type, metadata.sha256.as_deref().unwrap_or_default());
         
         Ok(Self {
             name,
@@ -106,11 +393,51 @@ impl Model {
             input_type,
             metadata,
             data,
+            optimized_backend: None,
+            tokenizer: None,
         })
     }
 
+    /// Hex SHA256 digest of model data, computed off the async runtime
+    async fn digest(data: ModelData) -> Result<(ModelData, String), SynaptronError> {
+        tokio::task::spawn_blocking(move || {
+            let digest = format!("{:x}", Sha256::digest(&data));
+            (data, digest)
+        })
+        .await
+        .map_err(|e| SynaptronError::ModelLoad(format!("Checksum computation failed: {}", e)))
+    }
+
+    /// Expected digest from a `sha256sum`-style `<path>.sha256` file beside the model, if present
+    async fn read_digest_file(path: &str) -> Result<Option<String>, SynaptronError> {
+        let digest_path = format!("{}.sha256", path);
+        if !Path::new(&digest_path).exists() {
+            return Ok(None);
+        }
+        
+        let contents = fs::read_to_string(&digest_path).await?;
+        match contents.split_whitespace().next() {
+            Some(digest) => Ok(Some(digest.to_string())),
+            None => Err(SynaptronError::ModelLoad(format!("Checksum file {} is empty", digest_path))),
+        }
+    }
+
+    /// Fail with `ModelLoad` when a digest doesn't match the expected one
+    fn verify_digest(path: &str, digest: &str, expected: Option<&str>) -> Result<(), SynaptronError> {
+        match expected {
+            Some(expected) if !expected.eq_ignore_ascii_case(digest) => Err(SynaptronError::ModelLoad(format!(
+                "Checksum mismatch for {}: expected SHA256 {}, got {}", path, expected, digest
+            ))),
+            Some(_) => {
+                debug!("Verified SHA256 of {}", path);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Detect model format from file extension
-    fn detect_format(path: &str) -> Result<String, SynaptronError> {
+    pub(crate) fn detect_format(path: &str) -> Result<String, SynaptronError> {
         let path = Path::new(path);
         let extension = path.extension()
             .and_then(|s| s.to_str())
@@ -128,6 +455,105 @@ impl Model {
         }
     }
 
+    /// Check a model file's leading bytes against the format its extension claims
+    ///
+    /// Catches git-LFS pointers and HTML error pages saved under a model's name
+    /// before they fail obscurely in a backend. Formats without a known
+    /// signature are not checked.
+    pub(crate) fn validate_content(path: &str, format: &str, data: &[u8]) -> Result<(), SynaptronError> {
+        if data.starts_with(GIT_LFS_POINTER) {
+            return Err(SynaptronError::ModelLoad(format!(
+                "{} is a git-LFS pointer, not model data; fetch it with `git lfs pull`", path
+            )));
+        }
+        
+        let valid = match format {
+            "gguf" => data.starts_with(GGUF_MAGIC),
+            "safetensors" => Self::is_safetensors(data),
+            "onnx" => Self::is_protobuf(data),
+            "pytorch" => data.starts_with(ZIP_MAGIC) || data.first() == Some(&PICKLE_PROTO),
+            "torchscript" => data.starts_with(ZIP_MAGIC),
+            _ => true,
+        };
+        
+        if !valid {
+            return Err(SynaptronError::ModelLoad(format!(
+                "{} has a {} extension but contains {}",
+                path, format, Self::describe_content(data)
+            )));
+        }
+        
+        Ok(())
+    }
+
+    /// Whether data starts with a u64 LE header length followed by a JSON object within the file
+    fn is_safetensors(data: &[u8]) -> bool {
+        let header_len = match data.get(..8) {
+            Some(len) => u64::from_le_bytes(len.try_into().expect("8 bytes")),
+            None => return false,
+        };
+        
+        header_len >= 2 && header_len <= (data.len() - 8) as u64 && data[8] == b'{'
+    }
+
+    /// Whether data starts with a plausible top-level protobuf field key, as an ONNX `ModelProto` does
+    fn is_protobuf(data: &[u8]) -> bool {
+        match data.first() {
+            Some(&key) => {
+                let field = key >> 3;
+                let wire_type = key & 0x07;
+                (1..=15).contains(&field) && (wire_type == 0 || wire_type == 2)
+            }
+            None => false,
+        }
+    }
+
+    /// Best guess at what a file contains, for error messages
+    fn describe_content(data: &[u8]) -> &'static str {
+        let text = String::from_utf8_lossy(&data[..data.len().min(512)]).trim_start().to_lowercase();
+        
+        if data.is_empty() {
+            "no data"
+        } else if data.starts_with(GGUF_MAGIC) {
+            "GGUF data"
+        } else if Self::is_safetensors(data) {
+            "safetensors data"
+        } else if data.starts_with(ZIP_MAGIC) {
+            "a zip archive"
+        } else if text.starts_with("<!doctype html") || text.starts_with("<html") {
+            "an HTML page"
+        } else if text.starts_with('{') || text.starts_with('[') {
+            "JSON"
+        } else if std::str::from_utf8(&data[..data.len().min(512)]).is_ok() {
+            "text"
+        } else {
+            "unrecognized binary data"
+        }
+    }
+
+    /// Model files of a known format in a directory, sorted by path
+    pub async fn discover(dir: &str) -> Result<Vec<String>, SynaptronError> {
+        let mut paths = Vec::new();
+        if !Path::new(dir).exists() {
+            return Ok(paths);
+        }
+        
+        let mut entries = fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_file() {
+                continue;
+            }
+            if let Some(path) = entry.path().to_str() {
+                if Self::detect_format(path)? != "unknown" {
+                    paths.push(path.to_string());
+                }
+            }
+        }
+        
+        paths.sort();
+        Ok(paths)
+    }
+
     /// Detect input type based on model name and format
     fn detect_input_type(name: &str, format: &str) -> Result<ModelInputType, SynaptronError> {
         let name_lower = name.to_lowercase();
@@ -175,65 +601,290 @@ impl Model {
         }
     }
 
-    /// Extract metadata from config.json
-    async fn extract_metadata(path: &str) -> Result<ModelMetadata, SynaptronError> {
+    /// Extract metadata from the safetensors or GGUF header, config.json and preprocessor_config.json
+    async fn extract_metadata(path: &str, format: &str, data: &[u8]) -> Result<ModelMetadata, SynaptronError> {
         let model_dir = Path::new(path).parent().unwrap_or(Path::new("."));
         let config_path = model_dir.join("config.json");
         
-        if config_path.exists() {
-            let config_data = fs::read_to_string(&config_path).await?;
-            let config: HashMap<String, serde_json::Value> = serde_json::from_str(&config_data)?;
-            
-            // Extract common metadata fields
-            let architecture = config.get("model_type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-            
-            let version = config.get("version")
-                .and_then(|v| v.as_str())
-                .unwrap_or("1.0")
-                .to_string();
-            
-            // Create metadata
-            Ok(ModelMetadata {
-                input_shape: vec![1, 3, 224, 224], // Default values
-                output_shape: vec![1, 1000],
-                data_type: "f32".to_string(),
-                size: 0, // Will be set when loading
-                architecture,
-                version,
-                required_libs: vec![], // Will be populated based on format
-            })
+        let preprocessor_config_path = model_dir.join(PREPROCESSOR_CONFIG_FILE);
+        let preprocessor_config = if preprocessor_config_path.exists() {
+            let config_data = fs::read_to_string(&preprocessor_config_path).await?;
+            Some(serde_json::from_str::<PreprocessorConfig>(&config_data)?)
         } else {
-            // Default metadata
-            Ok(ModelMetadata {
-                input_shape: vec![1, 3, 224, 224],
-                output_shape: vec![1, 1000],
-                data_type: "f32".to_string(),
-                size: 0,
-                architecture: "unknown".to_string(),
-                version: "1.0".to_string(),
-                required_libs: vec![],
-            })
-        }
+            None
+        };
+        
+        let config: HashMap<String, serde_json::Value> = if config_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&config_path).await?)?
+        } else {
+            HashMap::new()
+        };
+        
+        // Real shapes come from the weights; config.json and defaults fill the gaps
+        let hints = match format {
+            "safetensors" => ShapeHints::from_safetensors(data, &config)?,
+            "gguf" => ShapeHints::from_gguf(data)?,
+            _ => ShapeHints::default(),
+        };
+        
+        // Extract common metadata fields
+        let architecture = config.get("model_type")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| hints.architecture.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        
+        let version = config.get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("1.0")
+            .to_string();
+        
+        let vocab_size = config.get("vocab_size")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+        
+        // Hugging Face configs key labels by the index as a string
+        let id2label = config.get("id2label")
+            .and_then(|v| v.as_object())
+            .map(|labels| labels.iter()
+                .filter_map(|(id, label)| Some((id.parse::<usize>().ok()?, label.as_str()?.to_string())))
+                .collect::<HashMap<_, _>>())
+            .filter(|labels| !labels.is_empty());
+        
+        Ok(ModelMetadata {
+            input_shape: hints.input_shape.unwrap_or_else(|| vec![1, 3, 224, 224]),
+            output_shape: hints.output_shape.unwrap_or_else(|| vec![1, 1000]),
+            data_type: hints.data_type.unwrap_or_else(|| "f32".to_string()),
+            size: 0, // Will be set when loading
+            architecture,
+            version,
+            required_libs: vec![], // Will be populated based on format
+            vocab_size: vocab_size.or(hints.vocab_size),
+            id2label,
+            preprocessor_config,
+            sha256: None, // Set once the data is verified
+        })
     }
 
-    /// Download model from Hugging Face
+    /// Download model from Hugging Face, resolving the repo from the per-model overrides
     async fn download_from_huggingface(path: &str, config: &ModelConfig) -> Result<(), SynaptronError> {
-        // This is a simplified implementation
-        // In a real implementation, this would use the Hugging Face API
-        info!("Downloading model to: {}", path);
-        
         // Create cache directory if it doesn't exist
         let cache_dir = Path::new(&config.cache_dir);
         if !cache_dir.exists() {
             fs::create_dir_all(cache_dir).await?;
         }
         
-        // For now, we'll just create an empty file to simulate download
-        // In a real implementation, this would download the actual model
-        fs::write(path, Vec::<u8>::new()).await?;
+        let file_name = Path::new(path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("model")
+            .to_string();
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        
+        let overrides = config.for_model(&name);
+        let repo = overrides.and_then(|o| o.repo.clone()).unwrap_or(name);
+        let revision = overrides.and_then(|o| o.revision.clone()).unwrap_or_else(|| "main".to_string());
+        let file = overrides.and_then(|o| o.file.clone()).unwrap_or(file_name);
+        
+        Self::download(&repo, &revision, &file, path, config).await
+    }
+
+    /// Download a file from a Hugging Face repository
+    ///
+    /// Streams `https://huggingface.co/{repo}/resolve/{revision}/{file}` into a temp
+    /// file next to `path` and renames it into place once complete. `HF_TOKEN` is
+    /// sent as a bearer token for gated repositories. Failed attempts are retried
+    /// up to `model.download_max_attempts` times with exponential backoff, resuming
+    /// the partial file with a Range request where the server allows it.
+    pub async fn download(repo: &str, revision: &str, file: &str, path: &str, config: &ModelConfig) -> Result<(), SynaptronError> {
+        if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await?;
+        }
+        
+        let url = format!("https://huggingface.co/{}/resolve/{}/{}", repo, revision, file);
+        info!("Downloading model from {} to {}", url, path);
+        
+        // Each call gets its own temp file, so concurrent downloads of a path never share one
+        let tmp_path = Self::temp_path(path);
+        
+        let max_attempts = config.download_max_attempts.max(1);
+        let mut remote = RemoteFile::default();
+        let mut attempt = 1;
+        let result = loop {
+            match Self::download_attempt(&url, &tmp_path, &mut remote).await {
+                Ok(size) => break Ok(size),
+                Err(DownloadFailure::Retry(e)) if attempt < max_attempts => {
+                    let delay = Duration::from_millis(config.download_retry_base_ms)
+                        .saturating_mul(1 << (attempt - 1).min(16));
+                    warn!("Download attempt {} of {} failed, retrying in {:?}: {}", attempt, max_attempts, delay, e);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(DownloadFailure::Retry(e)) => break Err(SynaptronError::ModelLoad(format!(
+                    "Download of {} failed after {} attempts: {}", url, attempt, e
+                ))),
+                Err(DownloadFailure::Fatal(e)) => break Err(e),
+            }
+        };
+        
+        match result {
+            Ok(size) => info!("Downloaded {} bytes from {}", size, url),
+            Err(e) => {
+                let _ = fs::remove_file(&tmp_path).await;
+                return Err(e);
+            }
+        }
+        
+        Self::rename_into_place(&tmp_path, path).await
+    }
+
+    /// Fetch the rest of a download into its temp file, returning the complete size
+    ///
+    /// Resumes from the temp file's length with `Range`, guarded by `If-Range` on the
+    /// ETag seen earlier so a changed file is fetched whole rather than spliced.
+    async fn download_attempt(url: &str, tmp_path: &str, remote: &mut RemoteFile) -> Result<u64, DownloadFailure> {
+        let offset = match fs::metadata(tmp_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        
+        let mut request = crate::utils::http::client().get(url);
+        if let Ok(token) = std::env::var("HF_TOKEN") {
+            request = request.bearer_auth(token);
+        }
+        if offset > 0 {
+            debug!("Resuming download of {} from byte {}", url, offset);
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+            if let Some(etag) = &remote.etag {
+                request = request.header(reqwest::header::IF_RANGE, etag.as_str());
+            }
+        }
+        
+        let response = request.send().await
+            .map_err(|e| DownloadFailure::Retry(SynaptronError::ModelLoad(format!("Download of {} failed: {}", url, e))))?;
+        let status = response.status();
+        let etag = response.headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        
+        let append = match status {
+            reqwest::StatusCode::PARTIAL_CONTENT => {
+                let range = response.headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(ContentRange::parse);
+                match range {
+                    Some(range) if range.start == offset && (etag.is_none() || etag == remote.etag) => {
+                        remote.total = range.total.or(remote.total);
+                        true
+                    }
+                    _ => {
+                        // Not the continuation we asked for; start over on the next attempt
+                        let _ = fs::remove_file(tmp_path).await;
+                        return Err(DownloadFailure::Retry(SynaptronError::ModelLoad(format!(
+                            "Download of {} resumed at an unexpected range", url
+                        ))));
+                    }
+                }
+            }
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE if remote.total == Some(offset) => return Ok(offset),
+            status if status.is_success() => {
+                // Full body, either a first attempt or the server ignored the range
+                remote.etag = etag;
+                remote.total = response.content_length();
+                false
+            }
+            status if status.is_server_error()
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+                if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                    let _ = fs::remove_file(tmp_path).await;
+                }
+                return Err(DownloadFailure::Retry(SynaptronError::ModelLoad(format!(
+                    "Download of {} failed with HTTP {}", url, status
+                ))));
+            }
+            status => {
+                return Err(DownloadFailure::Fatal(SynaptronError::ModelLoad(format!(
+                    "Download of {} failed with HTTP {}", url, status
+                ))));
+            }
+        };
+        
+        // Bytes already written stay in the temp file for the next attempt to resume from
+        let size = Self::stream_to_file(response, tmp_path, append).await
+            .map_err(DownloadFailure::Retry)?;
+        
+        match remote.total {
+            Some(total) if size != total => Err(DownloadFailure::Retry(SynaptronError::ModelLoad(format!(
+                "Download of {} ended at {} of {} bytes", url, size, total
+            )))),
+            _ if size == 0 => Err(DownloadFailure::Fatal(SynaptronError::ModelLoad(format!(
+                "Download of {} returned no data", url
+            )))),
+            _ => Ok(size),
+        }
+    }
+
+    /// Stream a response body into a file, returning the file's length afterwards
+    async fn stream_to_file(response: reqwest::Response, path: &str, append: bool) -> Result<u64, SynaptronError> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .await?;
+        let mut body = response.bytes_stream();
+        
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    file.flush().await?;
+                    return Err(SynaptronError::ModelLoad(format!("Download interrupted: {}", e)));
+                }
+            };
+            file.write_all(&chunk).await?;
+        }
+        
+        file.flush().await?;
+        file.sync_all().await?;
+        Ok(file.metadata().await?.len())
+    }
+
+    /// Unique temp file path next to `path`
+    fn temp_path(path: &str) -> String {
+        format!("{}.{}.tmp", path, uuid::Uuid::new_v4().simple())
+    }
+
+    /// Write data to a temp file and rename it into place so readers never see a partial file
+    pub(crate) async fn write_atomic(path: &str, data: &[u8]) -> Result<(), SynaptronError> {
+        let tmp_path = Self::temp_path(path);
+        
+        if let Err(e) = fs::write(&tmp_path, data).await {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e.into());
+        }
+        
+        Self::rename_into_place(&tmp_path, path).await
+    }
+
+    /// Move a completed temp file into place
+    async fn rename_into_place(tmp_path: &str, path: &str) -> Result<(), SynaptronError> {
+        if let Err(e) = fs::rename(tmp_path, path).await {
+            // Rename fails across filesystems, fall back to copy-then-remove
+            debug!("Rename into {} failed ({}), falling back to copy", path, e);
+            let copied = fs::copy(tmp_path, path).await;
+            let _ = fs::remove_file(tmp_path).await;
+            copied?;
+        }
         
         Ok(())
     }
@@ -242,18 +893,43 @@ impl Model {
     pub async fn save_to_cache(&self, cache_dir: &str) -> Result<(), SynaptronError> {
         debug!("Saving model to cache: {}", cache_dir);
         
-        let cache_path = format!("{}/{}.cache", cache_dir, self.name);
-        fs::write(&cache_path, &self.data).await?;
+        let cache_path = Self::cache_file_path(cache_dir, &self.path);
+        Self::write_atomic(&cache_path, &self.data).await?;
+        
+        // Optimization may have rewritten the data, so record the digest of what is written
+        let mut metadata = self.metadata.clone();
+        metadata.sha256 = Some(format!("{:x}", Sha256::digest(&self.data)));
+        
+        let sidecar = CacheSidecar {
+            name: Some(self.name.clone()),
+            format: self.format.clone(),
+            input_type: self.input_type.clone(),
+            metadata,
+            size: self.data.len(),
+        };
+        Self::write_atomic(&Self::sidecar_path(&cache_path), &serde_json::to_vec_pretty(&sidecar)?).await?;
         
         info!("Model cached to: {}", cache_path);
         Ok(())
     }
 
+    /// Path a model loaded from `model_path` is cached at inside `cache_dir`
+    ///
+    /// Named after the whole path, so models sharing a file name don't overwrite each other.
+    pub fn cache_file_path(cache_dir: &str, model_path: &str) -> String {
+        format!("{}/{}.cache", cache_dir, path_file_stem(model_path))
+    }
+
+    /// Path of the metadata sidecar of a cached model file
+    fn sidecar_path(cache_path: &str) -> String {
+        format!("{}.json", cache_path)
+    }
+
     /// Load model from cache
     pub async fn load_from_cache(cache_path: &str) -> Result<Self, SynaptronError> {
         info!("Loading model from cache: {}", cache_path);
         
-        let data = fs::read(cache_path).await?;
+        let data = ModelData::read(cache_path).await?;
         let size = data.len();
         
         let name = Path::new(cache_path)
@@ -262,23 +938,311 @@ impl Model {
             .unwrap_or("unknown")
             .to_string();
         
-        let metadata = ModelMetadata {
-            input_shape: vec![1, 3, 224, 224],
-            output_shape: vec![1, 1000],
-            data_type: "f32".to_string(),
-            size,
-            architecture: "cached".to_string(),
-            version: "1.0".to_string(),
-            required_libs: vec![],
-        };
+        let sidecar_path = Self::sidecar_path(cache_path);
+        
+        // Files cached before metadata was stored alongside them get defaults
+        if !Path::new(&sidecar_path).exists() {
+            let (data, digest) = Self::digest(data).await?;
+            let metadata = ModelMetadata {
+                input_shape: vec![1, 3, 224, 224],
+                output_shape: vec![1, 1000],
+                data_type: "f32".to_string(),
+                size,
+                architecture: "cached".to_string(),
+                version: "1.0".to_string(),
+                required_libs: vec![],
+                vocab_size: None,
+                id2label: None,
+                preprocessor_config: None,
+                sha256: Some(digest),
+            };
+            
+            return Ok(Self {
+                name,
+                path: cache_path.to_string(),
+                format: "cached".to_string(),
+                input_type: ModelInputType::Text, // Default for cached models
+                metadata,
+                data,
+                optimized_backend: None,
+                tokenizer: None,
+            });
+        }
+        
+        let sidecar: CacheSidecar = serde_json::from_str(&fs::read_to_string(&sidecar_path).await?)
+            .map_err(|e| SynaptronError::Cache(format!("Corrupt cache metadata {}: {}", sidecar_path, e)))?;
+        
+        // A size mismatch means the model file was cut short or replaced
+        if sidecar.size != size {
+            return Err(SynaptronError::Cache(format!(
+                "Cached model {} is {} bytes, expected {}", cache_path, size, sidecar.size
+            )));
+        }
+        
+        // A digest mismatch means the cached file was corrupted in place
+        let (data, digest) = Self::digest(data).await?;
+        Self::verify_digest(cache_path, &digest, sidecar.metadata.sha256.as_deref())?;
+        let mut metadata = sidecar.metadata;
+        metadata.sha256 = Some(digest);
         
         Ok(Self {
-            name,
+            name: sidecar.name.unwrap_or(name),
             path: cache_path.to_string(),
-            format: "cached".to_string(),
-            input_type: ModelInputType::Text, // Default for cached models
+            format: sidecar.format,
+            input_type: sidecar.input_type,
             metadata,
             data,
+            optimized_backend: None,
+            tokenizer: None,
         })
     }
 }
+
+#[cfg(test)]
+impl Model {
+    /// In-memory model holding `data`, with small text-sized shapes
+    pub(crate) fn for_test(name: &str, input_type: ModelInputType, data: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            path: format!("models/{}.bin", name),
+            format: "unknown".to_string(),
+            input_type,
+            metadata: ModelMetadata {
+                input_shape: vec![1, 128],
+                output_shape: vec![1, 2],
+                data_type: "f32".to_string(),
+                size: data.len(),
+                architecture: name.to_string(),
+                version: "1.0".to_string(),
+                required_libs: vec![],
+                vocab_size: None,
+                id2label: None,
+                preprocessor_config: None,
+                sha256: None,
+            },
+            data: data.to_vec().into(),
+            optimized_backend: None,
+            tokenizer: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Model with the given data, as if loaded from `path`
+    fn test_model(path: &str, data: &[u8]) -> Model {
+        Model {
+            path: path.to_string(),
+            format: "safetensors".to_string(),
+            ..Model::for_test("bert", ModelInputType::Text, data)
+        }
+    }
+
+    #[tokio::test]
+    async fn intact_cached_model_loads_with_its_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let model = test_model("models/bert.safetensors", b"model weights");
+        model.save_to_cache(cache_dir).await.unwrap();
+
+        let cached = Model::load_from_cache(&Model::cache_file_path(cache_dir, &model.path)).await.unwrap();
+
+        assert_eq!(&*cached.data, b"model weights");
+        assert_eq!(cached.metadata.sha256, Some(format!("{:x}", Sha256::digest(b"model weights"))));
+    }
+
+    #[tokio::test]
+    async fn corrupted_cached_model_fails_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let model = test_model("models/bert.safetensors", b"model weights");
+        model.save_to_cache(cache_dir).await.unwrap();
+
+        // Same size, different bytes, so only the digest can tell
+        let cache_path = Model::cache_file_path(cache_dir, &model.path);
+        std::fs::write(&cache_path, b"model weighs!").unwrap();
+
+        match Model::load_from_cache(&cache_path).await {
+            Err(SynaptronError::ModelLoad(message)) => assert!(message.contains("Checksum mismatch"), "{}", message),
+            Err(e) => panic!("expected a checksum mismatch, got {}", e),
+            Ok(_) => panic!("corrupted cache file loaded"),
+        }
+    }
+
+    #[tokio::test]
+    async fn truncated_cached_model_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let model = test_model("models/bert.safetensors", b"model weights");
+        model.save_to_cache(cache_dir).await.unwrap();
+
+        let cache_path = Model::cache_file_path(cache_dir, &model.path);
+        std::fs::write(&cache_path, b"model").unwrap();
+
+        assert!(Model::load_from_cache(&cache_path).await.is_err());
+    }
+
+    #[test]
+    fn digests_compare_case_insensitively() {
+        let digest = format!("{:x}", Sha256::digest(b"model weights"));
+
+        assert!(Model::verify_digest("bert", &digest, Some(&digest.to_uppercase())).is_ok());
+        assert!(Model::verify_digest("bert", &digest, None).is_ok());
+        assert!(Model::verify_digest("bert", &digest, Some("00")).is_err());
+    }
+
+    #[tokio::test]
+    async fn disallowed_formats_are_rejected_before_reading() {
+        let config = ModelConfig {
+            allowed_formats: vec!["safetensors".to_string()],
+            ..ModelConfig::default()
+        };
+
+        let result = Model::load("models/does-not-exist.pt", &config).await;
+
+        assert!(matches!(result, Err(SynaptronError::UnsupportedFormat(_))));
+    }
+
+    #[tokio::test]
+    async fn atomic_writes_leave_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.bin");
+        let path = path.to_str().unwrap();
+
+        Model::write_atomic(path, b"old weights").await.unwrap();
+        Model::write_atomic(path, b"new weights").await.unwrap();
+
+        assert_eq!(std::fs::read(path).unwrap(), b"new weights");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn concurrent_writers_get_their_own_temp_files() {
+        let first = Model::temp_path("models/bert.safetensors");
+        let second = Model::temp_path("models/bert.safetensors");
+
+        assert_ne!(first, second);
+        assert!(first.starts_with("models/bert.safetensors.") && first.ends_with(".tmp"));
+    }
+
+    #[test]
+    fn file_contents_must_match_their_format() {
+        let safetensors = [&8u64.to_le_bytes()[..], &b"{\"a\": 1}"[..]].concat();
+
+        assert!(Model::validate_content("m.safetensors", "safetensors", &safetensors).is_ok());
+        assert!(Model::validate_content("m.gguf", "gguf", b"GGUF\x03\x00\x00\x00").is_ok());
+        assert!(Model::validate_content("m.pt", "pytorch", b"PK\x03\x04rest").is_ok());
+        assert!(Model::validate_content("m.onnx", "onnx", b"\x08\x07").is_ok());
+
+        assert!(Model::validate_content("m.safetensors", "safetensors", b"<html>404</html>").is_err());
+        assert!(Model::validate_content("m.gguf", "gguf", b"PK\x03\x04rest").is_err());
+        assert!(Model::validate_content("m.onnx", "onnx", b"").is_err());
+    }
+
+    #[test]
+    fn git_lfs_pointers_are_reported_as_such() {
+        let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 42\n";
+
+        match Model::validate_content("m.bin", "unknown", pointer) {
+            Err(SynaptronError::ModelLoad(message)) => assert!(message.contains("git lfs pull"), "{}", message),
+            other => panic!("expected an LFS pointer error, got {:?}", other.err()),
+        }
+    }
+
+    /// Safetensors file of `(name, dtype, shape, byte length)` tensors holding zeros
+    fn safetensors_file(tensors: &[(&str, &str, &[usize], usize)]) -> Vec<u8> {
+        let mut header = serde_json::Map::new();
+        let mut offset = 0;
+        for (name, dtype, shape, len) in tensors {
+            header.insert(name.to_string(), serde_json::json!({
+                "dtype": dtype,
+                "shape": shape,
+                "data_offsets": [offset, offset + len],
+            }));
+            offset += len;
+        }
+        let header = serde_json::to_vec(&header).unwrap();
+
+        [&(header.len() as u64).to_le_bytes()[..], &header[..], &vec![0; offset][..]].concat()
+    }
+
+    #[test]
+    fn text_model_shapes_come_from_its_embeddings() {
+        let data = safetensors_file(&[
+            ("bert.embeddings.word_embeddings.weight", "F16", &[30522, 768], 64),
+            ("bert.embeddings.position_embeddings.weight", "F16", &[256, 768], 64),
+            ("classifier.weight", "F32", &[3, 768], 16),
+        ]);
+
+        let hints = ShapeHints::from_safetensors(&data, &HashMap::new()).unwrap();
+
+        assert_eq!(hints.vocab_size, Some(30522));
+        assert_eq!(hints.input_shape, Some(vec![1, 256]));
+        assert_eq!(hints.output_shape, Some(vec![1, 3]));
+        assert_eq!(hints.data_type.as_deref(), Some("f16"), "most weight bytes are f16");
+    }
+
+    #[test]
+    fn sequence_length_falls_back_to_the_model_config() {
+        let data = safetensors_file(&[("embed_tokens.weight", "BF16", &[32000, 4096], 64)]);
+        let config = HashMap::from([("max_position_embeddings".to_string(), serde_json::json!(2048))]);
+
+        let hints = ShapeHints::from_safetensors(&data, &config).unwrap();
+
+        assert_eq!(hints.input_shape, Some(vec![1, 2048]));
+        assert_eq!(hints.output_shape, Some(vec![1, 2048, 4096]), "without a head, hidden states come out");
+    }
+
+    #[test]
+    fn image_model_shapes_come_from_its_stem() {
+        let data = safetensors_file(&[
+            ("conv1.weight", "F32", &[64, 3, 7, 7], 16),
+            ("layer1.0.conv1.weight", "F32", &[64, 64, 3, 3], 16),
+            ("fc.weight", "F32", &[1000, 2048], 16),
+        ]);
+
+        let hints = ShapeHints::from_safetensors(&data, &HashMap::new()).unwrap();
+
+        assert_eq!(hints.input_shape, Some(vec![1, 3, 224, 224]));
+        assert_eq!(hints.output_shape, Some(vec![1, 1000]));
+        assert_eq!(hints.vocab_size, None);
+    }
+
+    #[tokio::test]
+    async fn model_clones_share_one_copy_of_the_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.bin");
+        std::fs::write(&path, b"model weights").unwrap();
+
+        let data = ModelData::read(path.to_str().unwrap()).await.unwrap();
+        let model = Model { data, ..Model::for_test("bert", ModelInputType::Text, b"") };
+        let clone = model.clone();
+
+        assert_eq!(&*clone.data, b"model weights");
+        assert!(ModelData::ptr_eq(&model.data, &clone.data));
+    }
+
+    #[test]
+    fn model_data_keeps_the_buffer_it_was_read_into() {
+        let bytes = vec![7u8; 4096];
+        let buffer = bytes.as_ptr();
+
+        let data = ModelData::from(bytes);
+
+        assert_eq!(data.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn content_ranges_give_the_resume_offset_and_total() {
+        let range = ContentRange::parse("bytes 1024-4095/4096").unwrap();
+        assert_eq!((range.start, range.total), (1024, Some(4096)));
+
+        let unknown_total = ContentRange::parse("bytes 0-99/*").unwrap();
+        assert_eq!((unknown_total.start, unknown_total.total), (0, None));
+
+        assert!(ContentRange::parse("items 0-99/100").is_none());
+        assert!(ContentRange::parse("bytes */4096").is_none());
+    }
+}
@@ -3,6 +3,57 @@
 use crate::{model::ModelInputType, error::SynaptronError};
 use tracing::debug;
 
+/// File signature: magic bytes at fixed offsets identifying a format
+struct Signature {
+    /// Format name for logging
+    format: &'static str,
+
+    /// Byte offsets and the bytes expected there; all must match
+    parts: &'static [(usize, &'static [u8])],
+
+    /// Input type the format carries
+    input_type: ModelInputType,
+}
+
+/// Known image and audio signatures
+///
+/// RIFF containers are told apart by their form type at bytes 8..12,
+/// since WAV and WEBP share the `RIFF` prefix.
+const SIGNATURES: &[Signature] = &[
+    Signature { format: "jpeg", parts: &[(0, &[0xFF, 0xD8, 0xFF])], input_type: ModelInputType::Image },
+    Signature { format: "png", parts: &[(0, &[0x89, b'P', b'N', b'G'])], input_type: ModelInputType::Image },
+    Signature { format: "gif", parts: &[(0, b"GIF8")], input_type: ModelInputType::Image },
+    Signature { format: "webp", parts: &[(0, b"RIFF"), (8, b"WEBP")], input_type: ModelInputType::Image },
+    Signature { format: "bmp", parts: &[(0, b"BM")], input_type: ModelInputType::Image },
+    Signature { format: "wav", parts: &[(0, b"RIFF"), (8, b"WAVE")], input_type: ModelInputType::Audio },
+    Signature { format: "mp3", parts: &[(0, b"ID3")], input_type: ModelInputType::Audio },
+    Signature { format: "flac", parts: &[(0, b"fLaC")], input_type: ModelInputType::Audio },
+    Signature { format: "ogg", parts: &[(0, b"OggS")], input_type: ModelInputType::Audio },
+];
+
+impl Signature {
+    /// Whether data starts with this signature
+    fn matches(&self, data: &[u8]) -> bool {
+        self.parts.iter().all(|(offset, magic)| {
+            data.get(*offset..offset + magic.len()) == Some(*magic)
+        })
+    }
+}
+
+/// Input type declared by a MIME content type, e.g. `image/png` or `audio/wav`
+///
+/// Returns `None` for types that say nothing about the content, such as
+/// `application/octet-stream`.
+pub fn input_type_for_content_type(content_type: &str) -> Option<ModelInputType> {
+    let essence = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match essence.split('/').next() {
+        Some("image") => Some(ModelInputType::Image),
+        Some("audio") => Some(ModelInputType::Audio),
+        Some("text") => Some(ModelInputType::Text),
+        _ => None,
+    }
+}
+
 /// Multi-modal input processor
 pub struct MultimodalProcessor;
 
@@ -14,23 +65,25 @@ impl MultimodalProcessor {
     
     /// Detect input type from data
     pub fn detect_input_type(&self, data: &[u8]) -> Result<ModelInputType, SynaptronError> {
+        // Default to text if unable to detect
+        Ok(self.detect_known_type(data).unwrap_or(ModelInputType::Text))
+    }
+    
+    /// Detect input type from data, or `None` when it matches no known kind
+    fn detect_known_type(&self, data: &[u8]) -> Option<ModelInputType> {
         debug!("Detecting input type from data");
         
         // Try to detect input type based on data characteristics
         if self.is_text_data(data) {
-            return Ok(ModelInputType::Text);
-        }
-        
-        if self.is_image_data(data) {
-            return Ok(ModelInputType::Image);
+            return Some(ModelInputType::Text);
         }
         
-        if self.is_audio_data(data) {
-            return Ok(ModelInputType::Audio);
+        if let Some(signature) = SIGNATURES.iter().find(|signature| signature.matches(data)) {
+            debug!("Input matches {} signature", signature.format);
+            return Some(signature.input_type.clone());
         }
         
-        // Default to text if unable to detect
-        Ok(ModelInputType::Text)
+        None
     }
     
     /// Check if data is text
@@ -44,77 +97,144 @@ impl MultimodalProcessor {
         }
     }
     
-    /// Check if data is image
-    fn is_image_data(&self, data: &[u8]) -> bool {
-        // Check for common image file signatures
-        if data.len() < 4 {
-            return false;
+    /// Input type of data, falling back to the caller's `hint` when detection is inconclusive
+    pub fn input_type_of(&self, data: &[u8], hint: Option<ModelInputType>) -> ModelInputType {
+        match (self.detect_known_type(data), hint) {
+            (Some(detected), Some(hint)) if detected != hint => {
+                debug!("Input detected as {:?} despite {:?} hint", detected, hint);
+                detected
+            }
+            (Some(detected), _) => detected,
+            (None, Some(hint)) => hint,
+            (None, None) => ModelInputType::Text,
         }
+    }
+    
+    /// Route input to appropriate model based on type
+    ///
+    /// `hint` is the caller's declared input type, used when the data itself is
+    /// ambiguous. Only models accepted by `is_usable` are considered.
+    pub async fn route_input(
+        &self,
+        data: &[u8],
+        models: &std::collections::HashMap<String, crate::model::Model>,
+        hint: Option<ModelInputType>,
+        is_usable: impl Fn(&str) -> bool,
+    ) -> Result<String, SynaptronError> {
+        debug!("Routing input to appropriate model");
         
-        // JPEG signature
-        if data[0] == 0xFF && data[1] == 0xD8 && data[2] == 0xFF {
-            return true;
-        }
+        let input_type = self.input_type_of(data, hint);
         
-        // PNG signature
-        if data[0] == 0x89 && data[1] == 0x50 && data[2] == 0x4E && data[3] == 0x47 {
-            return true;
-        }
+        // Find a model that matches the input type, in name order so routing is stable
+        let mut names: Vec<&String> = models.keys().collect();
+        names.sort();
         
-        // GIF signature
-        if data[0] == 0x47 && data[1] == 0x49 && data[2] == 0x46 {
-            return true;
+        for model_name in names {
+            if models[model_name].input_type == input_type && is_usable(model_name) {
+                return Ok(model_name.clone());
+            }
         }
         
-        false
+        // If no model matches, return an error
+        Err(SynaptronError::Multimodal(
+            format!("No model found for input type: {:?}", input_type)
+        ))
     }
+}
+
+impl Default for MultimodalProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Model;
+    use std::collections::HashMap;
     
-    /// Check if data is audio
-    fn is_audio_data(&self, data: &[u8]) -> bool {
-        // Check for common audio file signatures
-        if data.len() < 4 {
-            return false;
-        }
-        
-        // WAV signature
-        if data[0] == 0x52 && data[1] == 0x49 && data[2] == 0x46 && data[3] == 0x46 {
-            return true;
-        }
+    /// Bytes of a RIFF container with the given form type
+    fn riff(form: &[u8; 4]) -> Vec<u8> {
+        [&b"RIFF"[..], &[0x24, 0, 0, 0][..], &form[..], &[0u8; 8][..]].concat()
+    }
+    
+    #[test]
+    fn signatures_identify_images_and_audio() {
+        let processor = MultimodalProcessor::new();
+        let cases: Vec<(Vec<u8>, ModelInputType)> = vec![
+            (vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00], ModelInputType::Image),
+            (vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A], ModelInputType::Image),
+            (b"GIF89a\x00\x01".to_vec(), ModelInputType::Image),
+            (riff(b"WEBP"), ModelInputType::Image),
+            ([&b"BM"[..], &[0x00, 0xFF, 0x00][..]].concat(), ModelInputType::Image),
+            (riff(b"WAVE"), ModelInputType::Audio),
+            ([&b"ID3"[..], &[0x04, 0x00, 0x00][..]].concat(), ModelInputType::Audio),
+            ([&b"fLaC"[..], &[0x00, 0x00, 0x00, 0x22][..]].concat(), ModelInputType::Audio),
+            ([&b"OggS"[..], &[0x00, 0x02][..]].concat(), ModelInputType::Audio),
+        ];
         
-        // MP3 signature
-        if data.len() >= 3 && data[0] == 0x49 && data[1] == 0x44 && data[2] == 0x33 {
-            return true;
+        for (data, expected) in cases {
+            assert_eq!(processor.detect_input_type(&data).unwrap(), expected, "{:?}", &data[..4]);
         }
+    }
+    
+    #[test]
+    fn riff_form_type_tells_webp_from_wav() {
+        let processor = MultimodalProcessor::new();
         
-        // FLAC signature
-        if data.len() >= 4 && data[0] == 0x66 && data[1] == 0x4C && data[2] == 0x61 && data[3] == 0x43 {
-            return true;
-        }
+        assert_eq!(processor.input_type_of(&riff(b"WEBP"), None), ModelInputType::Image);
+        assert_eq!(processor.input_type_of(&riff(b"WAVE"), None), ModelInputType::Audio);
+        assert_eq!(processor.input_type_of(&riff(b"AVI "), Some(ModelInputType::Audio)), ModelInputType::Audio);
+    }
+    
+    #[test]
+    fn truncated_signatures_do_not_match() {
+        let processor = MultimodalProcessor::new();
         
-        false
+        assert_eq!(processor.input_type_of(&[0xFF, 0xD8], Some(ModelInputType::Audio)), ModelInputType::Audio);
+        assert_eq!(processor.input_type_of(b"RIFF\x00\x00", None), ModelInputType::Text);
     }
     
-    /// Route input to appropriate model based on type
-    pub async fn route_input(
-        &self,
-        data: Vec<u8>,
-        models: &std::collections::HashMap<String, crate::model::Model>,
-    ) -> Result<(Vec<u8>, String), SynaptronError> {
-        debug!("Routing input to appropriate model");
+    #[test]
+    fn plain_text_is_text() {
+        let processor = MultimodalProcessor::new();
         
-        // Detect input type
-        let input_type = self.detect_input_type(&data)?;
+        assert_eq!(processor.detect_input_type(b"hello, world").unwrap(), ModelInputType::Text);
+        assert_eq!(processor.detect_input_type(&[0x00, 0x01, 0x02]).unwrap(), ModelInputType::Text);
+    }
+    
+    #[test]
+    fn detected_type_wins_over_hint() {
+        let processor = MultimodalProcessor::new();
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A];
         
-        // Find a model that matches the input type
-        for (model_name, model) in models {
-            if model.input_type == input_type {
-                return Ok((data, model_name.clone()));
-            }
+        assert_eq!(processor.input_type_of(&png, Some(ModelInputType::Audio)), ModelInputType::Image);
+        assert_eq!(processor.input_type_of(&[0x00, 0x01], Some(ModelInputType::Audio)), ModelInputType::Audio);
+    }
+    
+    #[test]
+    fn content_types_map_to_input_types() {
+        assert_eq!(input_type_for_content_type("image/png"), Some(ModelInputType::Image));
+        assert_eq!(input_type_for_content_type("Audio/WAV; rate=16000"), Some(ModelInputType::Audio));
+        assert_eq!(input_type_for_content_type("text/plain; charset=utf-8"), Some(ModelInputType::Text));
+        assert_eq!(input_type_for_content_type("application/octet-stream"), None);
+    }
+    
+    #[tokio::test]
+    async fn routing_picks_the_first_usable_model_by_name() {
+        let processor = MultimodalProcessor::new();
+        let mut models = HashMap::new();
+        for (name, input_type) in [("vision-b", ModelInputType::Image), ("vision-a", ModelInputType::Image), ("speech", ModelInputType::Audio)] {
+            models.insert(name.to_string(), Model::for_test(name, input_type, b"weights"));
         }
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A];
         
-        // If no model matches, return an error
-        Err(SynaptronError::Multimodal(
-            format!("No model found for input type: {:?}", input_type)
-        ))
+        assert_eq!(processor.route_input(&png, &models, None, |_| true).await.unwrap(), "vision-a");
+        assert_eq!(processor.route_input(&png, &models, None, |name| name != "vision-a").await.unwrap(), "vision-b");
+        assert!(matches!(
+            processor.route_input(b"some text", &models, None, |_| true).await,
+            Err(SynaptronError::Multimodal(_))
+        ));
     }
 }
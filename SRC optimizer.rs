@@ -1,63 +1,166 @@
 //! Auto-optimization layer for the Synaptron inference engine
 
-use crate::{config::BackendConfig, model::Model, error::SynaptronError};
-use tracing::{info, debug};
+use crate::{config::BackendConfig, model::Model, error::SynaptronError, quantization::QuantizationPass};
+use tracing::{info, debug, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Optimization pass applied to a model, such as quantization
+pub trait OptimizationPass: Send + Sync {
+    /// Pass name for logging
+    fn name(&self) -> &str;
+    
+    /// Apply the pass in place
+    fn apply(&self, model: &mut Model) -> Result<(), SynaptronError>;
+}
 
 /// Auto optimizer
 pub struct AutoOptimizer {
     /// Backend configuration
     config: BackendConfig,
+    
+    /// Optimization passes applied in order
+    passes: Vec<Arc<dyn OptimizationPass>>,
+    
+    /// Number of optimization runs
+    runs: Arc<AtomicU64>,
 }
 
 impl AutoOptimizer {
-    /// Create a new auto optimizer
+    /// Create a new auto optimizer, quantizing models when configured
     pub fn new(config: &BackendConfig) -> Self {
-        Self {
+        let optimizer = Self {
             config: config.clone(),
+            passes: Vec::new(),
+            runs: Arc::new(AtomicU64::new(0)),
+        };
+        
+        match config.quantization {
+            Some(mode) => {
+                let calibration = config.calibration_dir.as_deref()
+                    .map(Self::load_calibration)
+                    .unwrap_or_default();
+                optimizer.with_pass(Arc::new(QuantizationPass::new(mode, calibration)))
+            }
+            None => optimizer,
         }
     }
     
+    /// Read every file in a directory as a calibration input, in name order
+    fn load_calibration(dir: &str) -> Vec<Vec<u8>> {
+        let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect(),
+            Err(e) => {
+                warn!("Failed to read calibration directory {}: {}", dir, e);
+                return Vec::new();
+            }
+        };
+        paths.sort();
+        
+        let inputs: Vec<Vec<u8>> = paths.iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| std::fs::read(path)
+                .map_err(|e| warn!("Skipping calibration input {}: {}", path.display(), e))
+                .ok())
+            .collect();
+        
+        info!("Loaded {} calibration inputs from {}", inputs.len(), dir);
+        inputs
+    }
+    
+    /// Add an optimization pass
+    pub fn with_pass(mut self, pass: Arc<dyn OptimizationPass>) -> Self {
+        self.passes.push(pass);
+        self
+    }
+    
+    /// Number of times `optimize` has run
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+    
     /// Optimize a model for a specific device
     pub async fn optimize(&self, model: Model, device: &str) -> Result<Model, SynaptronError> {
         info!("Optimizing model for device: {}", device);
+        self.runs.fetch_add(1, Ordering::Relaxed);
         
         // In a real implementation, this would perform various optimizations:
-        // 1. Quantization (FP16, INT8)
-        // 2. Backend selection (OpenVINO, TensorRT, ONNX Runtime)
-        // 3. Graph optimization
-        // 4. Fusion optimizations
+        // 1. Graph optimization
+        // 2. Fusion optimizations
         
-        // For now, we'll just return the model as-is
-        debug!("Model optimization completed");
-        Ok(model)
+        let backend = self.select_backend(&model, device)?;
+        
+        // Passes run on a copy so a failure part-way never leaves a
+        // partially transformed model behind
+        let mut candidate = model.clone();
+        candidate.optimized_backend = Some(backend.clone());
+        
+        for pass in &self.passes {
+            debug!("Applying optimization pass: {}", pass.name());
+            
+            if let Err(e) = pass.apply(&mut candidate) {
+                if self.config.strict_optimization {
+                    return Err(SynaptronError::Optimization(format!(
+                        "Pass {} failed for model {}: {}", pass.name(), model.name, e
+                    )));
+                }
+                
+                warn!(
+                    "Optimization pass {} failed for model {}, keeping the original model: {}",
+                    pass.name(), model.name, e
+                );
+                return Ok(Model {
+                    optimized_backend: Some(backend),
+                    ..model
+                });
+            }
+        }
+        
+        debug!("Model optimization completed for backend: {}", backend);
+        Ok(candidate)
     }
     
     /// Select the best backend for a model and device
+    ///
+    /// Only backends compiled into this build are chosen; `cpu` is the fallback on CPU.
     pub fn select_backend(&self, model: &Model, device: &str) -> Result<String, SynaptronError> {
         info!("Selecting best backend for model: {} on device: {}", model.name, device);
-        
+        self.backend_for_format(&model.format, device)
+    }
+    
+    /// Backend `select_backend` picks for a model format on a device
+    ///
+    /// Only ONNX Runtime depends on the format, so it's unused without the `onnx` feature.
+    #[cfg_attr(not(feature = "onnx"), allow(unused_variables))]
+    pub fn backend_for_format(&self, format: &str, device: &str) -> Result<String, SynaptronError> {
+
         // In a real implementation, this would benchmark different backends
         // and select the one with the best performance
         
         // For now, we'll use a simple selection based on configuration
         if self.config.auto_select {
-            match device {
-                #[cfg(feature = "openvino")]
-                "cpu" | "gpu" | "vpu" if self.config.openvino => {
-                    return Ok("openvino".to_string());
-                },
-                #[cfg(feature = "tensorrt")]
-                "cuda" if self.config.tensorrt => {
-                    return Ok("tensorrt".to_string());
-                },
-                _ => {
-                    // Default to ONNX Runtime
-                    Ok("onnx_runtime".to_string())
-                }
+            #[cfg(feature = "openvino")]
+            if self.config.openvino && matches!(device, "cpu" | "gpu" | "vpu") {
+                return Ok("openvino".to_string());
+            }
+            
+            #[cfg(feature = "tensorrt")]
+            if self.config.tensorrt && device == "cuda" {
+                return Ok("tensorrt".to_string());
             }
-        } else {
-            // Use default backend
-            Ok("onnx_runtime".to_string())
+            
+            #[cfg(feature = "onnx")]
+            if self.config.onnx_runtime && device == "cpu" && format == "onnx" {
+                return Ok("onnx_runtime".to_string());
+            }
+        }
+        
+        // Use the device's default backend
+        match device {
+            "cpu" => Ok("cpu".to_string()),
+            "gpu" | "vpu" => Ok("openvino".to_string()),
+            "cuda" => Ok("tensorrt".to_string()),
+            _ => Err(SynaptronError::DeviceSelection(format!("No backend for device: {}", device))),
         }
     }
 }
@@ -66,6 +169,106 @@ impl Clone for AutoOptimizer {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
+            passes: self.passes.clone(),
+            runs: self.runs.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelInputType;
+    
+    /// Pass that bumps the model version, then fails when `fail` is set
+    struct Bump {
+        fail: bool,
+    }
+    
+    impl OptimizationPass for Bump {
+        fn name(&self) -> &str {
+            "bump"
+        }
+        
+        fn apply(&self, model: &mut Model) -> Result<(), SynaptronError> {
+            model.metadata.version.push_str("+bump");
+            if self.fail {
+                return Err(SynaptronError::Optimization("bump failed".to_string()));
+            }
+            Ok(())
+        }
+    }
+    
+    /// Test model in a format no optional backend claims
+    fn model() -> Model {
+        Model::for_test("classifier", ModelInputType::Text, b"weights")
+    }
+    
+    #[tokio::test]
+    async fn passes_apply_in_order() {
+        let optimizer = AutoOptimizer::new(&BackendConfig::default())
+            .with_pass(Arc::new(Bump { fail: false }))
+            .with_pass(Arc::new(Bump { fail: false }));
+        
+        let optimized = optimizer.optimize(model(), "cpu").await.unwrap();
+        
+        assert_eq!(optimized.metadata.version, "1.0+bump+bump");
+    }
+    
+    #[tokio::test]
+    async fn failed_pass_keeps_the_original_model() {
+        let optimizer = AutoOptimizer::new(&BackendConfig::default())
+            .with_pass(Arc::new(Bump { fail: false }))
+            .with_pass(Arc::new(Bump { fail: true }));
+        
+        let optimized = optimizer.optimize(model(), "cpu").await.unwrap();
+        
+        assert_eq!(optimized.metadata.version, "1.0");
+        assert_eq!(optimized.optimized_backend.as_deref(), Some("cpu"));
+    }
+    
+    #[tokio::test]
+    async fn failed_pass_is_an_error_when_strict() {
+        let config = BackendConfig { strict_optimization: true, ..BackendConfig::default() };
+        let optimizer = AutoOptimizer::new(&config).with_pass(Arc::new(Bump { fail: true }));
+        
+        assert!(matches!(
+            optimizer.optimize(model(), "cpu").await,
+            Err(SynaptronError::Optimization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn chosen_backend_is_recorded_on_the_model() {
+        let optimizer = AutoOptimizer::new(&BackendConfig::default());
+        let clone = optimizer.clone();
+        
+        let optimized = clone.optimize(model(), "cpu").await.unwrap();
+        
+        assert_eq!(optimized.optimized_backend.as_deref(), Some("cpu"));
+        assert_eq!(optimizer.runs(), 1);
+    }
+    
+    #[test]
+    fn backends_follow_format_and_device() {
+        let optimizer = AutoOptimizer::new(&BackendConfig::default());
+        
+        let onnx = if cfg!(feature = "onnx") { "onnx_runtime" } else { "cpu" };
+        assert_eq!(optimizer.backend_for_format("onnx", "cpu").unwrap(), onnx);
+        assert_eq!(optimizer.backend_for_format("safetensors", "cpu").unwrap(), "cpu");
+        assert_eq!(optimizer.backend_for_format("onnx", "cuda").unwrap(), "tensorrt");
+        assert!(matches!(
+            optimizer.backend_for_format("onnx", "tpu"),
+            Err(SynaptronError::DeviceSelection(_))
+        ));
+    }
+    
+    #[test]
+    fn device_default_is_used_without_auto_select() {
+        let config = BackendConfig { auto_select: false, ..BackendConfig::default() };
+        let optimizer = AutoOptimizer::new(&config);
+        
+        assert_eq!(optimizer.backend_for_format("onnx", "cpu").unwrap(), "cpu");
+        assert_eq!(optimizer.backend_for_format("onnx", "gpu").unwrap(), "openvino");
+    }
+}
@@ -0,0 +1,363 @@
+//! Postprocessing utilities for the Synaptron inference engine
+
+use crate::{error::SynaptronError, model::{Model, ModelInputType}, utils::tensor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::debug;
+
+/// Number of ranked labels returned when a request doesn't ask for a count
+pub const DEFAULT_TOP_K: usize = 5;
+
+/// Class label with its probability
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelScore {
+    /// Class label
+    pub label: String,
+
+    /// Softmax probability
+    pub score: f32,
+}
+
+/// Architecture or name fragments of models producing embeddings
+const EMBEDDING_MARKERS: &[&str] = &["embed", "sentence", "bge", "e5", "clip"];
+
+/// What a model's output represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    /// Logits over class labels
+    Classification,
+
+    /// Generated UTF-8 text
+    Generation,
+
+    /// Little-endian f32 embedding vector
+    Embedding,
+}
+
+impl OutputKind {
+    /// Output kind of a model from its labels, architecture, name and input type
+    pub fn for_model(model: &Model) -> Self {
+        if model.metadata.id2label.is_some() {
+            return OutputKind::Classification;
+        }
+
+        let architecture = model.metadata.architecture.to_lowercase();
+        let name = model.name.to_lowercase();
+        if EMBEDDING_MARKERS.iter().any(|marker| architecture.contains(marker) || name.contains(marker)) {
+            return OutputKind::Embedding;
+        }
+
+        match model.input_type {
+            ModelInputType::Image => OutputKind::Classification,
+            ModelInputType::Text | ModelInputType::Audio => OutputKind::Generation,
+        }
+    }
+}
+
+/// Decoded prediction, tagged with its kind when serialized
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum PredictionResult {
+    /// Most likely class labels, best first
+    Classification { labels: Vec<LabelScore> },
+
+    /// Generated text
+    Generation { text: String },
+
+    /// Embedding vector
+    Embedding { vector: Vec<f32> },
+}
+
+impl PredictionResult {
+    /// Flat string form: the top label or the generated text, empty for embeddings
+    pub fn summary(&self) -> String {
+        match self {
+            PredictionResult::Classification { labels } => labels.first()
+                .map(|top| top.label.clone())
+                .unwrap_or_default(),
+            PredictionResult::Generation { text } => text.clone(),
+            PredictionResult::Embedding { .. } => String::new(),
+        }
+    }
+}
+
+/// Decodes raw model output
+pub struct Postprocessor {
+    /// What the model's output represents
+    kind: OutputKind,
+
+    /// Class labels by output index
+    labels: HashMap<usize, String>,
+}
+
+impl Postprocessor {
+    /// Create a classification postprocessor with a label map
+    pub fn new(labels: HashMap<usize, String>) -> Self {
+        Self {
+            kind: OutputKind::Classification,
+            labels,
+        }
+    }
+
+    /// Create a postprocessor for a model's output kind and labels
+    pub fn for_model(model: &Model) -> Self {
+        Self {
+            kind: OutputKind::for_model(model),
+            labels: model.metadata.id2label.clone().unwrap_or_default(),
+        }
+    }
+
+    /// What the model's output represents
+    pub fn kind(&self) -> OutputKind {
+        self.kind
+    }
+
+    /// Load the `id2label` map from a model's `config.json`
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, SynaptronError> {
+        let config: serde_json::Value = serde_json::from_slice(&std::fs::read(path.as_ref())?)?;
+
+        let labels = config.get("id2label")
+            .and_then(|labels| labels.as_object())
+            .ok_or_else(|| SynaptronError::InvalidInput(format!(
+                "{} has no id2label map", path.as_ref().display()
+            )))?
+            .iter()
+            .map(|(id, label)| {
+                let id = id.parse::<usize>()
+                    .map_err(|_| SynaptronError::InvalidInput(format!("Invalid label id: {}", id)))?;
+                let label = label.as_str()
+                    .ok_or_else(|| SynaptronError::InvalidInput(format!("Label {} is not a string", id)))?;
+                Ok((id, label.to_string()))
+            })
+            .collect::<Result<HashMap<_, _>, SynaptronError>>()?;
+
+        Ok(Self::new(labels))
+    }
+
+    /// Numerically stable softmax
+    pub fn softmax(logits: &[f32]) -> Vec<f32> {
+        let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = logits.iter().map(|logit| (logit - max).exp()).collect();
+        let sum: f32 = exps.iter().sum();
+
+        exps.into_iter().map(|exp| exp / sum).collect()
+    }
+
+    /// Indices and values of the `k` largest values, largest first
+    pub fn argmax_topk(logits: &[f32], k: usize) -> Vec<(usize, f32)> {
+        let mut ranked: Vec<(usize, f32)> = logits.iter().copied().enumerate().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(k);
+        ranked
+    }
+
+    /// Label for an output index, falling back to `LABEL_<index>` like Hugging Face
+    pub fn label(&self, index: usize) -> String {
+        self.labels.get(&index)
+            .cloned()
+            .unwrap_or_else(|| format!("LABEL_{}", index))
+    }
+
+    /// Decode raw output by the model's output kind, ranking the `k` most likely labels
+    pub fn decode(&self, output: &[u8], k: usize) -> Result<PredictionResult, SynaptronError> {
+        Ok(match self.kind {
+            OutputKind::Classification => PredictionResult::Classification { labels: self.classify(output, k)? },
+            OutputKind::Generation => PredictionResult::Generation { text: String::from_utf8_lossy(output).to_string() },
+            OutputKind::Embedding => PredictionResult::Embedding { vector: f32_values(output, "Embedding")? },
+        })
+    }
+
+    /// Rank the `k` most likely labels from little-endian f32 logits
+    pub fn classify(&self, output: &[u8], k: usize) -> Result<Vec<LabelScore>, SynaptronError> {
+        let logits = f32_values(output, "Classifier")?;
+        debug!("Classifying {} logits", logits.len());
+
+        let probabilities = Self::softmax(&logits);
+
+        Ok(Self::argmax_topk(&probabilities, k)
+            .into_iter()
+            .map(|(index, score)| LabelScore { label: self.label(index), score })
+            .collect())
+    }
+}
+
+/// Decode non-empty little-endian f32 output
+fn f32_values(output: &[u8], kind: &str) -> Result<Vec<f32>, SynaptronError> {
+    match tensor::bytes_to_f32(output) {
+        Ok(values) if !values.is_empty() => Ok(values),
+        _ => Err(SynaptronError::Inference(format!(
+            "{} output of {} bytes is not f32 values", kind, output.len()
+        ))),
+    }
+}
+
+/// How a sequence of hidden states is reduced to one embedding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pooling {
+    /// Average over all positions
+    #[default]
+    Mean,
+
+    /// Hidden state of the first position, the `[CLS]` token
+    Cls,
+}
+
+/// Pool f32 model output into one embedding vector
+///
+/// Output of `hidden_size` values is already pooled and returned as is; longer
+/// output is read as one row of `hidden_size` values per position.
+pub fn pool(output: &[u8], hidden_size: Option<usize>, pooling: Pooling) -> Result<Vec<f32>, SynaptronError> {
+    let values = f32_values(output, "Embedding")?;
+    let width = match hidden_size.filter(|width| *width > 0) {
+        Some(width) if values.len() % width != 0 => {
+            return Err(SynaptronError::Inference(format!(
+                "Embedding output of {} values is not a whole number of {}-wide hidden states",
+                values.len(), width
+            )));
+        }
+        Some(width) => width,
+        None => values.len(),
+    };
+    if values.len() == width {
+        return Ok(values);
+    }
+
+    let rows = values.chunks_exact(width);
+    debug!("Pooling {} hidden states of width {} by {:?}", rows.len(), width, pooling);
+    Ok(match pooling {
+        Pooling::Cls => values[..width].to_vec(),
+        Pooling::Mean => {
+            let count = rows.len() as f32;
+            let mut sums = vec![0.0f32; width];
+            for row in rows {
+                for (sum, value) in sums.iter_mut().zip(row) {
+                    *sum += value;
+                }
+            }
+            sums.into_iter().map(|sum| sum / count).collect()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Postprocessor with labels for the first three classes
+    fn animals() -> Postprocessor {
+        Postprocessor::new(HashMap::from([
+            (0, "cat".to_string()),
+            (1, "dog".to_string()),
+            (2, "bird".to_string()),
+        ]))
+    }
+
+    #[test]
+    fn softmax_sums_to_one_and_keeps_order() {
+        let probabilities = Postprocessor::softmax(&[1000.0, 1001.0, 999.0]);
+
+        assert!((probabilities.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(probabilities.iter().all(|probability| probability.is_finite()));
+        assert!(probabilities[1] > probabilities[0] && probabilities[0] > probabilities[2]);
+    }
+
+    #[test]
+    fn topk_ranks_largest_first() {
+        let ranked = Postprocessor::argmax_topk(&[0.1, 0.7, 0.2], 2);
+
+        assert_eq!(ranked, vec![(1, 0.7), (2, 0.2)]);
+        assert_eq!(Postprocessor::argmax_topk(&[0.1, 0.7], 5).len(), 2);
+    }
+
+    #[test]
+    fn classify_labels_the_most_likely_classes() {
+        let logits = tensor::f32_to_bytes(&[0.5, 3.0, 1.0, 2.5]);
+
+        let labels = animals().classify(&logits, 3).unwrap();
+
+        let names: Vec<&str> = labels.iter().map(|label| label.label.as_str()).collect();
+        assert_eq!(names, vec!["dog", "LABEL_3", "bird"]);
+        assert!(labels.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn classify_rejects_output_that_is_not_f32() {
+        assert!(matches!(animals().classify(&[0; 6], 1), Err(SynaptronError::Inference(_))));
+        assert!(matches!(animals().classify(&[], 1), Err(SynaptronError::Inference(_))));
+    }
+
+    #[test]
+    fn labels_load_from_config_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{"id2label": {"0": "negative", "1": "positive"}}"#).unwrap();
+
+        let postprocessor = Postprocessor::from_config_file(&path).unwrap();
+
+        assert_eq!(postprocessor.label(1), "positive");
+        assert_eq!(postprocessor.label(2), "LABEL_2");
+
+        std::fs::write(&path, r#"{"architectures": ["BertModel"]}"#).unwrap();
+        assert!(matches!(Postprocessor::from_config_file(&path), Err(SynaptronError::InvalidInput(_))));
+
+        std::fs::write(&path, r#"{"id2label": {"first": "negative"}}"#).unwrap();
+        assert!(matches!(Postprocessor::from_config_file(&path), Err(SynaptronError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn output_kind_follows_labels_name_and_input_type() {
+        let mut classifier = Model::for_test("sentiment", ModelInputType::Text, b"weights");
+        classifier.metadata.id2label = Some(HashMap::from([(0, "negative".to_string())]));
+
+        assert_eq!(OutputKind::for_model(&classifier), OutputKind::Classification);
+        assert_eq!(OutputKind::for_model(&Model::for_test("bge-small", ModelInputType::Text, b"weights")), OutputKind::Embedding);
+        assert_eq!(OutputKind::for_model(&Model::for_test("resnet", ModelInputType::Image, b"weights")), OutputKind::Classification);
+        assert_eq!(OutputKind::for_model(&Model::for_test("gpt2", ModelInputType::Text, b"weights")), OutputKind::Generation);
+    }
+
+    #[test]
+    fn decode_tags_results_by_kind() {
+        let generator = Postprocessor::for_model(&Model::for_test("gpt2", ModelInputType::Text, b"weights"));
+        let generated = generator.decode(b"hello", DEFAULT_TOP_K).unwrap();
+        assert_eq!(generated.summary(), "hello");
+        assert_eq!(
+            serde_json::to_value(&generated).unwrap(),
+            serde_json::json!({"type": "Generation", "text": "hello"})
+        );
+
+        let embedder = Postprocessor::for_model(&Model::for_test("e5-base", ModelInputType::Text, b"weights"));
+        let embedded = embedder.decode(&tensor::f32_to_bytes(&[0.5, -0.5]), DEFAULT_TOP_K).unwrap();
+        assert_eq!(embedded, PredictionResult::Embedding { vector: vec![0.5, -0.5] });
+        assert_eq!(embedded.summary(), "");
+
+        let classified = animals().decode(&tensor::f32_to_bytes(&[0.0, 2.0]), 1).unwrap();
+        assert_eq!(classified.summary(), "dog");
+        assert_eq!(serde_json::to_value(&classified).unwrap()["type"], "Classification");
+    }
+
+    #[test]
+    fn pooled_output_is_returned_as_is() {
+        let output = tensor::f32_to_bytes(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(pool(&output, Some(3), Pooling::Mean).unwrap(), vec![1.0, 2.0, 3.0]);
+        assert_eq!(pool(&output, None, Pooling::Cls).unwrap(), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn hidden_states_pool_by_mean_or_cls() {
+        let output = tensor::f32_to_bytes(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        assert_eq!(pool(&output, Some(2), Pooling::Mean).unwrap(), vec![3.0, 4.0]);
+        assert_eq!(pool(&output, Some(2), Pooling::Cls).unwrap(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn ragged_hidden_states_are_rejected() {
+        let output = tensor::f32_to_bytes(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert!(matches!(pool(&output, Some(2), Pooling::Mean), Err(SynaptronError::Inference(_))));
+        assert!(matches!(pool(&[], Some(2), Pooling::Mean), Err(SynaptronError::Inference(_))));
+    }
+}
@@ -1,17 +1,127 @@
 //! Preprocessing utilities for the Synaptron inference engine
 
-use crate::error::SynaptronError;
+use crate::{config::ModelConfig, error::SynaptronError, model::{Model, ModelInputType}, utils::tensor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use image::imageops::FilterType;
+use rustfft::{num_complex::Complex, FftPlanner};
+use std::io::Cursor;
 use tokenizers::Tokenizer;
-use tracing::debug;
-use unicode_normalization::UnicodeNormalization;
+use tracing::{debug, warn};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Text cleaning step
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TextStep {
+    /// Convert to lowercase
+    Lowercase,
+    
+    /// Apply Unicode NFKC normalization
+    NormalizeNfkc,
+    
+    /// Remove accents and other combining marks
+    StripAccents,
+    
+    /// Collapse runs of whitespace into single spaces
+    CollapseWhitespace,
+    
+    /// Truncate to the maximum input length in tokens
+    ///
+    /// With a tokenizer the token ids are capped after tokenization; the
+    /// character fallback cuts the text on a character boundary instead.
+    Truncate,
+}
+
+/// Tokenizer file expected next to a text model
+pub const TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Hugging Face preprocessing settings expected next to a model
+pub const PREPROCESSOR_CONFIG_FILE: &str = "preprocessor_config.json";
+
+/// ImageNet channel means, used when a model doesn't configure its own
+const IMAGENET_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+
+/// ImageNet channel standard deviations, used when a model doesn't configure its own
+const IMAGENET_STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+/// Settings read from a Hugging Face `preprocessor_config.json`
+///
+/// Unset fields fall back to the defaults of the preprocessor using them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessorConfig {
+    /// Per-channel mean subtracted after rescaling
+    pub image_mean: Option<Vec<f32>>,
+    
+    /// Per-channel standard deviation divided by after subtracting the mean
+    pub image_std: Option<Vec<f32>>,
+    
+    /// Whether to apply mean/std normalization
+    pub do_normalize: Option<bool>,
+    
+    /// Factor scaling 8-bit pixel values, usually 1/255
+    pub rescale_factor: Option<f32>,
+    
+    /// PIL resampling filter used to resize: 0 nearest, 1 lanczos, 2 bilinear, 3 bicubic
+    pub resample: Option<u32>,
+    
+    /// Audio sample rate the model expects
+    pub sampling_rate: Option<u32>,
+    
+    /// FFT window size in samples
+    pub n_fft: Option<usize>,
+    
+    /// Samples between successive frames
+    pub hop_length: Option<usize>,
+    
+    /// Number of mel bands
+    pub feature_size: Option<usize>,
+}
+
+/// Default audio sample rate
+const DEFAULT_SAMPLE_RATE: u32 = 16_000;
+
+/// Default FFT window size, 25 ms at 16 kHz
+const DEFAULT_N_FFT: usize = 400;
+
+/// Default hop length, 10 ms at 16 kHz
+const DEFAULT_HOP_LENGTH: usize = 160;
+
+/// Default number of mel bands
+const DEFAULT_N_MELS: usize = 80;
+
+/// Floor applied to mel energies before taking the log
+const LOG_MEL_FLOOR: f32 = 1e-10;
+
+/// Default text cleaning pipeline
+pub const DEFAULT_TEXT_STEPS: &[TextStep] = &[
+    TextStep::NormalizeNfkc,
+    TextStep::CollapseWhitespace,
+    TextStep::Truncate,
+];
+
+/// Converts raw request bytes into backend input for one modality
+pub trait InputPreprocessor: Send + Sync {
+    /// Preprocess raw input bytes
+    fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError>;
+}
+
+/// Builds a preprocessor for a model from its configuration
+pub type PreprocessorFactory =
+    Box<dyn Fn(&Model, &ModelConfig) -> Result<Box<dyn InputPreprocessor>, SynaptronError> + Send + Sync>;
 
 /// Preprocessing utilities
 pub struct Preprocessor {
     /// Text tokenizer
-    tokenizer: Option<Tokenizer>,
+    tokenizer: Option<Arc<Tokenizer>>,
     
-    /// Maximum input length
+    /// Maximum input length in tokens
     max_length: usize,
+    
+    /// Ordered text cleaning steps
+    steps: Vec<TextStep>,
 }
 
 impl Preprocessor {
@@ -20,11 +130,56 @@ impl Preprocessor {
         Self {
             tokenizer: None,
             max_length,
+            steps: DEFAULT_TEXT_STEPS.to_vec(),
         }
     }
     
+    /// Set the ordered text cleaning steps
+    pub fn with_steps(mut self, steps: Vec<TextStep>) -> Self {
+        let truncate_last = match steps.iter().rposition(|step| *step == TextStep::Truncate) {
+            Some(index) => index == steps.len() - 1,
+            None => true,
+        };
+        if !truncate_last {
+            warn!("Truncate is not the last text step; later steps may see split sequences");
+        }
+        
+        self.steps = steps;
+        self
+    }
+    
+    /// Create a preprocessor using the tokenizer in a model directory, if it has one
+    pub fn from_pretrained(model_dir: impl AsRef<Path>, max_length: usize) -> Result<Self, SynaptronError> {
+        let preprocessor = Self::new(max_length);
+        
+        Ok(match Self::load_tokenizer(model_dir)? {
+            Some(tokenizer) => preprocessor.with_shared_tokenizer(Arc::new(tokenizer)),
+            None => preprocessor,
+        })
+    }
+    
+    /// Load `tokenizer.json` from a model directory, or `None` when there isn't one
+    pub fn load_tokenizer(model_dir: impl AsRef<Path>) -> Result<Option<Tokenizer>, SynaptronError> {
+        let path = model_dir.as_ref().join(TOKENIZER_FILE);
+        if !path.exists() {
+            warn!("No {} found, falling back to character tokenization", path.display());
+            return Ok(None);
+        }
+        
+        let tokenizer = Tokenizer::from_file(&path)
+            .map_err(|e| SynaptronError::Tokenization(format!("Invalid tokenizer {}: {}", path.display(), e)))?;
+        debug!("Loaded tokenizer from {}", path.display());
+        
+        Ok(Some(tokenizer))
+    }
+    
     /// Set tokenizer
-    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+    pub fn with_tokenizer(self, tokenizer: Tokenizer) -> Self {
+        self.with_shared_tokenizer(Arc::new(tokenizer))
+    }
+    
+    /// Set a tokenizer shared with other preprocessors
+    pub fn with_shared_tokenizer(mut self, tokenizer: Arc<Tokenizer>) -> Self {
         self.tokenizer = Some(tokenizer);
         self
     }
@@ -33,18 +188,24 @@ impl Preprocessor {
     pub fn clean_text(&self, text: &str) -> String {
         debug!("Cleaning text input");
         
-        // Normalize unicode
-        let normalized = text.nfkc().collect::<String>();
+        let mut cleaned = text.to_string();
         
-        // Remove extra whitespace
-        let cleaned = normalized.split_whitespace().collect::<Vec<&str>>().join(" ");
-        
-        // Truncate to max length
-        if cleaned.len() > self.max_length {
-            cleaned[..self.max_length].to_string()
-        } else {
-            cleaned
+        for step in &self.steps {
+            cleaned = match step {
+                TextStep::Lowercase => cleaned.to_lowercase(),
+                TextStep::NormalizeNfkc => cleaned.nfkc().collect::<String>(),
+                TextStep::StripAccents => cleaned.nfd().filter(|c| !is_combining_mark(*c)).collect::<String>(),
+                TextStep::CollapseWhitespace => cleaned.split_whitespace().collect::<Vec<&str>>().join(" "),
+                // A token can span many characters, so tokenized text is capped after tokenization
+                TextStep::Truncate if self.tokenizer.is_some() => cleaned,
+                TextStep::Truncate => match cleaned.char_indices().nth(self.max_length) {
+                    Some((end, _)) => cleaned[..end].to_string(),
+                    None => cleaned,
+                },
+            };
         }
+        
+        cleaned
     }
     
     /// Tokenize text
@@ -69,6 +230,646 @@ impl Preprocessor {
         debug!("Preprocessing text input");
         
         let cleaned = self.clean_text(text);
-        self.tokenize(&cleaned)
+        let mut ids = self.tokenize(&cleaned)?;
+        
+        if self.steps.contains(&TextStep::Truncate) && ids.len() > self.max_length {
+            debug!("Truncating {} tokens to {}", ids.len(), self.max_length);
+            ids.truncate(self.max_length);
+        }
+        
+        Ok(ids)
+    }
+}
+
+impl InputPreprocessor for Preprocessor {
+    fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError> {
+        let text = std::str::from_utf8(input)
+            .map_err(|e| SynaptronError::Tokenization(format!("Text input is not valid UTF-8: {}", e)))?;
+        
+        let ids = self.preprocess_text(text)?;
+        Ok(ids.iter().flat_map(|id| id.to_le_bytes()).collect())
+    }
+}
+
+/// Decodes JPEG or PNG input into a normalized CHW `f32` tensor
+pub struct ImagePreprocessor {
+    /// Output channels, 1 for grayscale or 3 for RGB
+    channels: usize,
+    
+    /// Output height
+    height: u32,
+    
+    /// Output width
+    width: u32,
+    
+    /// Resize filter
+    filter: FilterType,
+    
+    /// Factor scaling 8-bit pixel values
+    rescale_factor: f32,
+    
+    /// Per-channel mean and standard deviation, `None` to skip normalization
+    normalize: Option<(Vec<f32>, Vec<f32>)>,
+}
+
+impl ImagePreprocessor {
+    /// Create an image preprocessor for a model's `[N, C, H, W]` or `[C, H, W]` input shape
+    pub fn for_model(model: &Model) -> Result<Self, SynaptronError> {
+        let (channels, height, width) = match model.metadata.input_shape.as_slice() {
+            [_, c, h, w] | [c, h, w] => (*c, *h as u32, *w as u32),
+            shape => return Err(SynaptronError::Multimodal(format!(
+                "Model {} input shape {:?} is not an image shape", model.name, shape
+            ))),
+        };
+        if channels != 1 && channels != 3 {
+            return Err(SynaptronError::Multimodal(format!(
+                "Model {} expects {} image channels, only 1 or 3 are supported", model.name, channels
+            )));
+        }
+        
+        let config = model.metadata.preprocessor_config.clone().unwrap_or_default();
+        
+        let normalize = if config.do_normalize.unwrap_or(true) {
+            let mean = per_channel(config.image_mean, &IMAGENET_MEAN, channels, "image_mean")?;
+            let std = per_channel(config.image_std, &IMAGENET_STD, channels, "image_std")?;
+            if std.contains(&0.0) {
+                return Err(SynaptronError::Multimodal("image_std must not contain zeros".to_string()));
+            }
+            Some((mean, std))
+        } else {
+            None
+        };
+        
+        let filter = match config.resample {
+            Some(0) => FilterType::Nearest,
+            Some(1) => FilterType::Lanczos3,
+            Some(3) => FilterType::CatmullRom,
+            _ => FilterType::Triangle,
+        };
+        
+        Ok(Self {
+            channels,
+            height,
+            width,
+            filter,
+            rescale_factor: config.rescale_factor.unwrap_or(1.0 / 255.0),
+            normalize,
+        })
+    }
+}
+
+/// Per-channel values, broadcasting a single value and defaulting to ImageNet statistics
+fn per_channel(values: Option<Vec<f32>>, default: &[f32; 3], channels: usize, name: &str) -> Result<Vec<f32>, SynaptronError> {
+    let values = values.unwrap_or_else(|| match channels {
+        // A grayscale channel averages the RGB statistics
+        1 => vec![default.iter().sum::<f32>() / 3.0],
+        _ => default.to_vec(),
+    });
+    
+    match values.len() {
+        1 => Ok(vec![values[0]; channels]),
+        len if len == channels => Ok(values),
+        len => Err(SynaptronError::Multimodal(format!(
+            "{} has {} values for {} channels", name, len, channels
+        ))),
+    }
+}
+
+impl InputPreprocessor for ImagePreprocessor {
+    fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError> {
+        if input.is_empty() {
+            return Err(SynaptronError::Multimodal("Empty input".to_string()));
+        }
+        
+        let decoded = image::load_from_memory(input)
+            .map_err(|e| SynaptronError::Multimodal(format!("Failed to decode image: {}", e)))?;
+        debug!("Resizing {}x{} image to {}x{}", decoded.width(), decoded.height(), self.width, self.height);
+        let resized = decoded.resize_exact(self.width, self.height, self.filter);
+        
+        // Interleaved HWC pixels
+        let pixels = match self.channels {
+            1 => resized.to_luma8().into_raw(),
+            _ => resized.to_rgb8().into_raw(),
+        };
+        
+        // Planar CHW output, one plane per channel
+        let plane = (self.width * self.height) as usize;
+        let mut chw = vec![0f32; self.channels * plane];
+        for (index, value) in pixels.iter().enumerate() {
+            let (pixel, channel) = (index / self.channels, index % self.channels);
+            let mut value = *value as f32 * self.rescale_factor;
+            if let Some((mean, std)) = &self.normalize {
+                value = (value - mean[channel]) / std[channel];
+            }
+            chw[channel * plane + pixel] = value;
+        }
+        
+        Ok(tensor::f32_to_bytes(&chw))
+    }
+}
+
+/// Decodes WAV or FLAC input into a flattened `[n_mels, frames]` log-mel spectrogram
+pub struct AudioPreprocessor {
+    /// Sample rate audio is resampled to
+    sample_rate: u32,
+    
+    /// FFT window size in samples
+    n_fft: usize,
+    
+    /// Samples between successive frames
+    hop_length: usize,
+    
+    /// Periodic Hann window
+    window: Vec<f32>,
+    
+    /// Triangular mel filters over the `n_fft / 2 + 1` frequency bins
+    mel_filters: Vec<Vec<f32>>,
+}
+
+impl AudioPreprocessor {
+    /// Create an audio preprocessor with a target sample rate, FFT size, hop length and mel band count
+    pub fn new(sample_rate: u32, n_fft: usize, hop_length: usize, n_mels: usize) -> Result<Self, SynaptronError> {
+        if sample_rate == 0 || n_fft == 0 || hop_length == 0 || n_mels == 0 {
+            return Err(SynaptronError::Multimodal(
+                "Audio sample rate, n_fft, hop length and mel count must be positive".to_string()
+            ));
+        }
+        
+        let window = (0..n_fft)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n_fft as f32).cos())
+            .collect();
+        
+        Ok(Self {
+            sample_rate,
+            n_fft,
+            hop_length,
+            window,
+            mel_filters: mel_filters(sample_rate, n_fft, n_mels),
+        })
+    }
+    
+    /// Create an audio preprocessor from a model's `preprocessor_config.json`, defaulting to 16 kHz and 80 mels
+    pub fn for_model(model: &Model) -> Result<Self, SynaptronError> {
+        let config = model.metadata.preprocessor_config.clone().unwrap_or_default();
+        
+        Self::new(
+            config.sampling_rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+            config.n_fft.unwrap_or(DEFAULT_N_FFT),
+            config.hop_length.unwrap_or(DEFAULT_HOP_LENGTH),
+            config.feature_size.unwrap_or(DEFAULT_N_MELS),
+        )
+    }
+    
+    /// Number of frames produced for a signal of `samples` samples at the target rate
+    pub fn frame_count(&self, samples: usize) -> usize {
+        1 + samples / self.hop_length
+    }
+    
+    /// Log-mel spectrogram of mono samples at the target rate, mel band major
+    pub fn log_mel(&self, samples: &[f32]) -> Vec<f32> {
+        // Center frames on their hop positions by zero padding half a window each side
+        let pad = self.n_fft / 2;
+        let mut padded = vec![0f32; pad];
+        padded.extend_from_slice(samples);
+        padded.resize(padded.len() + pad, 0.0);
+        
+        let frames = self.frame_count(samples.len());
+        let bins = self.n_fft / 2 + 1;
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(self.n_fft);
+        
+        let mut spectrogram = vec![0f32; self.mel_filters.len() * frames];
+        let mut buffer = vec![Complex::new(0f32, 0f32); self.n_fft];
+        
+        for frame in 0..frames {
+            let start = frame * self.hop_length;
+            for (i, slot) in buffer.iter_mut().enumerate() {
+                let sample = padded.get(start + i).copied().unwrap_or(0.0);
+                *slot = Complex::new(sample * self.window[i], 0.0);
+            }
+            fft.process(&mut buffer);
+            
+            let power: Vec<f32> = buffer[..bins].iter().map(|c| c.norm_sqr()).collect();
+            for (band, filter) in self.mel_filters.iter().enumerate() {
+                let energy: f32 = filter.iter().zip(&power).map(|(weight, p)| weight * p).sum();
+                spectrogram[band * frames + frame] = energy.max(LOG_MEL_FLOOR).log10();
+            }
+        }
+        
+        spectrogram
+    }
+}
+
+/// Convert a frequency in Hz to the HTK mel scale
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+/// Convert an HTK mel value to Hz
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular mel filters spanning 0 Hz to Nyquist
+fn mel_filters(sample_rate: u32, n_fft: usize, n_mels: usize) -> Vec<Vec<f32>> {
+    let bins = n_fft / 2 + 1;
+    let nyquist = sample_rate as f32 / 2.0;
+    let max_mel = hz_to_mel(nyquist);
+    
+    // Band edges, evenly spaced in mel
+    let edges: Vec<f32> = (0..n_mels + 2)
+        .map(|i| mel_to_hz(max_mel * i as f32 / (n_mels + 1) as f32))
+        .collect();
+    let bin_hz = |bin: usize| bin as f32 * sample_rate as f32 / n_fft as f32;
+    
+    (0..n_mels)
+        .map(|band| {
+            let (lower, center, upper) = (edges[band], edges[band + 1], edges[band + 2]);
+            (0..bins)
+                .map(|bin| {
+                    let hz = bin_hz(bin);
+                    let rising = (hz - lower) / (center - lower);
+                    let falling = (upper - hz) / (upper - center);
+                    rising.min(falling).max(0.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Decode WAV or FLAC bytes into mono samples in `[-1, 1]` and their sample rate
+fn decode_audio(input: &[u8]) -> Result<(Vec<f32>, u32), SynaptronError> {
+    let invalid = |e: &dyn std::fmt::Display| SynaptronError::Multimodal(format!("Failed to decode audio: {}", e));
+    
+    let (interleaved, channels, sample_rate) = if input.starts_with(b"RIFF") && input.get(8..12) == Some(b"WAVE".as_slice()) {
+        let mut reader = hound::WavReader::new(Cursor::new(input)).map_err(|e| invalid(&e))?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>(),
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader.samples::<i32>().map(|sample| sample.map(|s| s as f32 / scale)).collect()
+            }
+        }.map_err(|e| invalid(&e))?;
+        (samples, spec.channels as usize, spec.sample_rate)
+    } else if input.starts_with(b"fLaC") {
+        let mut reader = claxon::FlacReader::new(Cursor::new(input)).map_err(|e| invalid(&e))?;
+        let info = reader.streaminfo();
+        let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+        let samples = reader.samples()
+            .map(|sample| sample.map(|s| s as f32 / scale))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| invalid(&e))?;
+        (samples, info.channels as usize, info.sample_rate)
+    } else {
+        return Err(SynaptronError::Multimodal("Unsupported audio format, expected WAV or FLAC".to_string()));
+    };
+    
+    // Downmix to mono by averaging channels
+    let channels = channels.max(1);
+    let mono = interleaved.chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    
+    Ok((mono, sample_rate))
+}
+
+/// Linearly interpolate samples from one rate to another
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio).round() as usize;
+    
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index.min(samples.len() - 1)];
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+impl InputPreprocessor for AudioPreprocessor {
+    fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError> {
+        if input.is_empty() {
+            return Err(SynaptronError::Multimodal("Empty input".to_string()));
+        }
+        
+        let (samples, sample_rate) = decode_audio(input)?;
+        debug!("Resampling {} samples from {} Hz to {} Hz", samples.len(), sample_rate, self.sample_rate);
+        let samples = resample(&samples, sample_rate, self.sample_rate);
+        
+        let features = self.log_mel(&samples);
+        Ok(tensor::f32_to_bytes(&features))
+    }
+}
+
+/// Passes raw bytes through unchanged, rejecting empty input
+pub struct PassthroughPreprocessor;
+
+impl InputPreprocessor for PassthroughPreprocessor {
+    fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError> {
+        if input.is_empty() {
+            return Err(SynaptronError::Multimodal("Empty input".to_string()));
+        }
+        
+        Ok(input.to_vec())
+    }
+}
+
+/// Registry mapping model input types to preprocessor factories
+pub struct PreprocessorRegistry {
+    /// Registered factories
+    factories: HashMap<ModelInputType, PreprocessorFactory>,
+}
+
+impl PreprocessorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+    
+    /// Create a registry with the built-in text, image and audio preprocessors
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        
+        registry.register(ModelInputType::Text, Box::new(|model, config| {
+            let mut preprocessor = Preprocessor::new(config.max_input_length);
+            if let Some(tokenizer) = &model.tokenizer {
+                preprocessor = preprocessor.with_shared_tokenizer(tokenizer.clone());
+            }
+            if let Some(steps) = config.for_model(&model.name).and_then(|o| o.text_steps.clone()) {
+                preprocessor = preprocessor.with_steps(steps);
+            }
+            Ok(Box::new(preprocessor))
+        }));
+        registry.register(ModelInputType::Image, Box::new(|model, _config| {
+            Ok(Box::new(ImagePreprocessor::for_model(model)?))
+        }));
+        registry.register(ModelInputType::Audio, Box::new(|model, _config| {
+            Ok(Box::new(AudioPreprocessor::for_model(model)?))
+        }));
+        
+        registry
+    }
+    
+    /// Register a preprocessor factory for an input type, replacing any existing one
+    pub fn register(&mut self, input_type: ModelInputType, factory: PreprocessorFactory) {
+        debug!("Registering preprocessor for input type: {:?}", input_type);
+        self.factories.insert(input_type, factory);
+    }
+    
+    /// Build the preprocessor for a model
+    pub fn get(&self, model: &Model, config: &ModelConfig) -> Result<Box<dyn InputPreprocessor>, SynaptronError> {
+        let factory = self.factories.get(&model.input_type).ok_or_else(|| {
+            SynaptronError::Multimodal(format!(
+                "No preprocessor registered for input type {:?} of model {}",
+                model.input_type, model.name
+            ))
+        })?;
+        
+        factory(model, config)
+    }
+}
+
+impl Default for PreprocessorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text run through a freshly loaded text model to warm up its backend
+const WARMUP_TEXT: &str = "Synaptron warm-up";
+
+/// Side length of the blank image run through a freshly loaded image model
+const WARMUP_IMAGE_SIZE: u32 = 224;
+
+/// Minimal valid input of a modality, used to warm up a freshly loaded backend
+///
+/// Text is a short sentence, images a blank PNG and audio one second of silent WAV.
+pub fn warmup_input(input_type: &ModelInputType) -> Result<Vec<u8>, SynaptronError> {
+    let failed = |e: &dyn std::fmt::Display| SynaptronError::Other(format!("Failed to build warm-up input: {}", e));
+    
+    match input_type {
+        ModelInputType::Text => Ok(WARMUP_TEXT.as_bytes().to_vec()),
+        ModelInputType::Image => {
+            let mut png = Cursor::new(Vec::new());
+            image::DynamicImage::new_rgb8(WARMUP_IMAGE_SIZE, WARMUP_IMAGE_SIZE)
+                .write_to(&mut png, image::ImageOutputFormat::Png)
+                .map_err(|e| failed(&e))?;
+            Ok(png.into_inner())
+        }
+        ModelInputType::Audio => {
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate: DEFAULT_SAMPLE_RATE,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            let mut wav = Cursor::new(Vec::new());
+            let mut writer = hound::WavWriter::new(&mut wav, spec).map_err(|e| failed(&e))?;
+            for _ in 0..spec.sample_rate {
+                writer.write_sample(0i16).map_err(|e| failed(&e))?;
+            }
+            writer.finalize().map_err(|e| failed(&e))?;
+            Ok(wav.into_inner())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Preprocessor tagging input so tests can tell which one ran
+    struct Tagging;
+    
+    impl InputPreprocessor for Tagging {
+        fn preprocess(&self, input: &[u8]) -> Result<Vec<u8>, SynaptronError> {
+            Ok([&b"tagged:"[..], input].concat())
+        }
+    }
+
+    #[test]
+    fn registry_dispatches_on_input_type() {
+        let mut registry = PreprocessorRegistry::with_defaults();
+        registry.register(ModelInputType::Audio, Box::new(|_, _| Ok(Box::new(Tagging))));
+        let config = ModelConfig::default();
+        
+        let audio = registry.get(&Model::for_test("whisper", ModelInputType::Audio, b""), &config).unwrap();
+        let text = registry.get(&Model::for_test("bert", ModelInputType::Text, b""), &config).unwrap();
+        
+        assert_eq!(audio.preprocess(b"clip").unwrap(), b"tagged:clip");
+        assert_eq!(text.preprocess(b"hi").unwrap(), [104u8, 0, 0, 0, 105, 0, 0, 0]);
+    }
+
+    #[test]
+    fn unregistered_input_types_are_an_error() {
+        let model = Model::for_test("bert", ModelInputType::Text, b"");
+        
+        assert!(matches!(
+            PreprocessorRegistry::new().get(&model, &ModelConfig::default()),
+            Err(SynaptronError::Multimodal(_))
+        ));
+    }
+
+    #[test]
+    fn text_steps_run_in_the_configured_order() {
+        let collapse_first = Preprocessor::new(4)
+            .with_steps(vec![TextStep::CollapseWhitespace, TextStep::Truncate]);
+        let truncate_first = Preprocessor::new(4)
+            .with_steps(vec![TextStep::Truncate, TextStep::CollapseWhitespace]);
+        
+        assert_eq!(collapse_first.clean_text("a   b c"), "a b ");
+        assert_eq!(truncate_first.clean_text("a   b c"), "a");
+    }
+
+    #[test]
+    fn only_configured_steps_run() {
+        let preprocessor = Preprocessor::new(64).with_steps(vec![TextStep::StripAccents, TextStep::Lowercase]);
+        
+        assert_eq!(preprocessor.clean_text("Ça  Va"), "ca  va");
+    }
+
+    #[test]
+    fn character_truncation_respects_multibyte_characters() {
+        let preprocessor = Preprocessor::new(3);
+        
+        assert_eq!(preprocessor.clean_text("héllo wörld"), "hél");
+        assert_eq!(preprocessor.preprocess_text("日本語のテキスト").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn short_text_is_not_truncated() {
+        assert_eq!(Preprocessor::new(16).clean_text("héllo"), "héllo");
+    }
+
+    #[test]
+    fn images_become_normalized_chw_tensors() {
+        let mut model = Model::for_test("resnet", ModelInputType::Image, b"");
+        model.metadata.input_shape = vec![1, 3, 8, 8];
+        let preprocessor = ImagePreprocessor::for_model(&model).unwrap();
+        
+        let input = warmup_input(&ModelInputType::Image).unwrap();
+        let values = tensor::bytes_to_f32(&preprocessor.preprocess(&input).unwrap()).unwrap();
+        
+        // A black image leaves each plane at minus its mean over its standard deviation
+        assert_eq!(values.len(), 3 * 8 * 8);
+        for channel in 0..3 {
+            let expected = -IMAGENET_MEAN[channel] / IMAGENET_STD[channel];
+            assert!(values[channel * 64..(channel + 1) * 64].iter().all(|value| (value - expected).abs() < 1e-5));
+        }
+    }
+
+    #[test]
+    fn grayscale_models_get_one_plane() {
+        let mut model = Model::for_test("mnist-vit", ModelInputType::Image, b"");
+        model.metadata.input_shape = vec![1, 4, 4];
+        let preprocessor = ImagePreprocessor::for_model(&model).unwrap();
+        
+        let output = preprocessor.preprocess(&warmup_input(&ModelInputType::Image).unwrap()).unwrap();
+        
+        assert_eq!(output.len(), 4 * 4 * 4);
+    }
+
+    #[test]
+    fn non_image_shapes_are_rejected() {
+        let model = Model::for_test("resnet", ModelInputType::Image, b"");
+        
+        assert!(matches!(ImagePreprocessor::for_model(&model), Err(SynaptronError::Multimodal(_))));
+    }
+
+    /// Mono 16-bit WAV of `samples` silent samples at `sample_rate`
+    fn silent_wav(sample_rate: u32, samples: u32) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec).unwrap();
+        for _ in 0..samples {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        wav.into_inner()
+    }
+
+    #[test]
+    fn audio_becomes_a_log_mel_spectrogram() {
+        let model = Model::for_test("whisper", ModelInputType::Audio, b"");
+        let preprocessor = AudioPreprocessor::for_model(&model).unwrap();
+        
+        let values = tensor::bytes_to_f32(&preprocessor.preprocess(&silent_wav(16_000, 16_000)).unwrap()).unwrap();
+        
+        // One second at a 160-sample hop is 101 centered frames per mel band
+        assert_eq!(values.len(), DEFAULT_N_MELS * 101);
+        assert!(values.iter().all(|value| (value - LOG_MEL_FLOOR.log10()).abs() < 1e-5));
+    }
+
+    #[test]
+    fn audio_is_resampled_to_the_model_rate() {
+        let preprocessor = AudioPreprocessor::new(16_000, 400, 160, 80).unwrap();
+        
+        let output = preprocessor.preprocess(&silent_wav(8_000, 8_000)).unwrap();
+        
+        assert_eq!(output.len(), 80 * preprocessor.frame_count(16_000) * 4);
+    }
+
+    #[test]
+    fn resampling_interpolates_linearly() {
+        assert_eq!(resample(&[0.0, 1.0], 1, 2), vec![0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(resample(&[0.0, 1.0, 2.0, 3.0], 2, 1), vec![0.0, 2.0]);
+    }
+
+    /// Word-level tokenizer knowing `hello` and `world`
+    const WORD_TOKENIZER: &str = r#"{
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": [],
+        "normalizer": null,
+        "pre_tokenizer": { "type": "Whitespace" },
+        "post_processor": null,
+        "decoder": null,
+        "model": { "type": "WordLevel", "vocab": { "[UNK]": 0, "hello": 1, "world": 2 }, "unk_token": "[UNK]" }
+    }"#;
+
+    #[test]
+    fn tokenizer_next_to_the_model_is_used() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(TOKENIZER_FILE), WORD_TOKENIZER).unwrap();
+        
+        let preprocessor = Preprocessor::from_pretrained(dir.path(), 16).unwrap();
+        
+        assert_eq!(preprocessor.preprocess_text("hello  world again").unwrap(), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn missing_tokenizer_falls_back_to_characters() {
+        let dir = tempfile::tempdir().unwrap();
+        
+        let preprocessor = Preprocessor::from_pretrained(dir.path(), 16).unwrap();
+        
+        assert_eq!(preprocessor.preprocess_text("hi").unwrap(), vec![104, 105]);
+    }
+
+    #[test]
+    fn malformed_tokenizer_is_a_tokenization_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(TOKENIZER_FILE), "{ not a tokenizer").unwrap();
+        
+        assert!(matches!(
+            Preprocessor::from_pretrained(dir.path(), 16),
+            Err(SynaptronError::Tokenization(_))
+        ));
     }
 }
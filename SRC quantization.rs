@@ -0,0 +1,817 @@
+//! Weight quantization for the Synaptron inference engine
+
+use crate::{error::SynaptronError, model::Model, optimizer::OptimizationPass, utils::tensor};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use tracing::{debug, info};
+
+/// Largest magnitude of a symmetric int8 value
+const INT8_MAX: f32 = 127.0;
+
+/// ONNX `TensorProto` element type of f32 tensors
+const ONNX_FLOAT: u64 = 1;
+
+/// ONNX `TensorProto` element type of int8 tensors
+const ONNX_INT8: u64 = 3;
+
+/// ONNX `TensorProto` element type of f16 tensors
+const ONNX_FLOAT16: u64 = 10;
+
+/// Oldest default-domain ONNX opset with `DequantizeLinear`
+const ONNX_MIN_DEQUANTIZE_OPSET: u64 = 10;
+
+/// Quantization mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantMode {
+    /// Convert f32 weights to IEEE half precision
+    Fp16,
+
+    /// Int8 weights with per-tensor scales; activations are quantized at runtime
+    Int8Dynamic,
+
+    /// Int8 weights plus an activation scale fixed from calibration inputs
+    Int8Static,
+}
+
+impl QuantMode {
+    /// Data type name recorded in the quantized model's metadata
+    fn data_type(self) -> &'static str {
+        match self {
+            QuantMode::Fp16 => "f16",
+            QuantMode::Int8Dynamic | QuantMode::Int8Static => "int8",
+        }
+    }
+}
+
+/// Tensor entry of a safetensors header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TensorInfo {
+    /// Element type, e.g. `F32`
+    pub dtype: String,
+
+    /// Tensor dimensions
+    pub shape: Vec<usize>,
+
+    /// Byte range within the data section
+    pub data_offsets: [usize; 2],
+}
+
+/// Parsed safetensors file
+pub(crate) struct Safetensors<'a> {
+    /// Tensors in data order
+    pub tensors: Vec<(String, TensorInfo)>,
+
+    /// Free-form `__metadata__` entries
+    pub metadata: Map<String, Value>,
+
+    /// Data section following the header
+    pub data: &'a [u8],
+}
+
+impl<'a> Safetensors<'a> {
+    /// Parse a safetensors file: a u64 LE header length, a JSON header, then tensor data
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, SynaptronError> {
+        let invalid = |reason: &str| SynaptronError::UnsupportedFormat(format!("Invalid safetensors file: {}", reason));
+
+        let header_len = bytes.get(..8)
+            .map(|len| u64::from_le_bytes(len.try_into().expect("8 bytes")) as usize)
+            .ok_or_else(|| invalid("truncated header length"))?;
+        let header = bytes.get(8..8usize.saturating_add(header_len))
+            .ok_or_else(|| invalid("truncated header"))?;
+        let data = &bytes[8 + header_len..];
+
+        let mut header: Map<String, Value> = serde_json::from_slice(header)
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        let metadata = match header.remove("__metadata__") {
+            Some(Value::Object(metadata)) => metadata,
+            _ => Map::new(),
+        };
+
+        let mut tensors = header.into_iter()
+            .map(|(name, info)| {
+                let info: TensorInfo = serde_json::from_value(info)
+                    .map_err(|e| invalid(&format!("tensor {}: {}", name, e)))?;
+                if info.data_offsets[0] > info.data_offsets[1] || info.data_offsets[1] > data.len() {
+                    return Err(invalid(&format!("tensor {} is out of bounds", name)));
+                }
+                Ok((name, info))
+            })
+            .collect::<Result<Vec<_>, SynaptronError>>()?;
+        tensors.sort_by_key(|(_, info)| info.data_offsets[0]);
+
+        Ok(Self { tensors, metadata, data })
+    }
+
+    /// Raw bytes of a tensor
+    pub fn tensor_data(&self, info: &TensorInfo) -> &'a [u8] {
+        &self.data[info.data_offsets[0]..info.data_offsets[1]]
+    }
+}
+
+/// Serialize tensors and metadata as a safetensors file
+fn write_safetensors(tensors: Vec<(String, String, Vec<usize>, Vec<u8>)>, metadata: Map<String, Value>) -> Result<Vec<u8>, SynaptronError> {
+    let mut header = BTreeMap::new();
+    let mut data = Vec::new();
+
+    for (name, dtype, shape, bytes) in tensors {
+        let info = TensorInfo {
+            dtype,
+            shape,
+            data_offsets: [data.len(), data.len() + bytes.len()],
+        };
+        header.insert(name, serde_json::to_value(info)?);
+        data.extend_from_slice(&bytes);
+    }
+
+    if !metadata.is_empty() {
+        header.insert("__metadata__".to_string(), Value::Object(metadata));
+    }
+
+    let header = serde_json::to_vec(&header)?;
+    let mut out = Vec::with_capacity(8 + header.len() + data.len());
+    out.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&data);
+    Ok(out)
+}
+
+/// Symmetric int8 scale for values, so the largest magnitude maps to 127
+fn int8_scale(values: &[f32]) -> f32 {
+    let max_abs = values.iter().fold(0f32, |max, value| max.max(value.abs()));
+    if max_abs > 0.0 { max_abs / INT8_MAX } else { 1.0 }
+}
+
+/// Quantize values to int8 with a scale
+fn to_int8(values: &[f32], scale: f32) -> Vec<u8> {
+    values.iter()
+        .map(|value| (value / scale).round().clamp(-INT8_MAX, INT8_MAX) as i8 as u8)
+        .collect()
+}
+
+/// Quantize a model's f32 weight tensors
+///
+/// Safetensors and ONNX models are supported; other tensors are copied unchanged.
+pub fn quantize(model: &Model, mode: QuantMode) -> Result<Model, SynaptronError> {
+    quantize_calibrated(model, mode, &[])
+}
+
+/// Quantize a model, using calibration inputs for `Int8Static`
+///
+/// Calibration inputs are little-endian f32 activations; their largest
+/// magnitude fixes the activation scale stored in the model's metadata.
+pub fn quantize_calibrated(model: &Model, mode: QuantMode, calibration: &[Vec<u8>]) -> Result<Model, SynaptronError> {
+    if !matches!(model.format.as_str(), "safetensors" | "onnx") {
+        return Err(SynaptronError::UnsupportedFormat(format!(
+            "Quantization of {} models is not supported", model.format
+        )));
+    }
+
+    if mode == QuantMode::Int8Static && calibration.is_empty() {
+        return Err(SynaptronError::Optimization(
+            "Static int8 quantization needs calibration inputs".to_string()
+        ));
+    }
+
+    info!("Quantizing model {} to {:?}", model.name, mode);
+
+    let activation_scale = match mode {
+        QuantMode::Int8Static => {
+            let mut activations = Vec::new();
+            for input in calibration {
+                activations.extend(tensor::bytes_to_f32(input)?);
+            }
+            let scale = int8_scale(&activations);
+            debug!("Activation scale {} from {} calibration values", scale, activations.len());
+            Some(scale)
+        }
+        QuantMode::Fp16 | QuantMode::Int8Dynamic => None,
+    };
+
+    let (data, converted) = match model.format.as_str() {
+        "onnx" => quantize_onnx(&model.data, mode, activation_scale)?,
+        _ => quantize_safetensors(&model.data, mode, activation_scale)?,
+    };
+    info!("Quantized {} tensors of {}: {} -> {} bytes", converted, model.name, model.data.len(), data.len());
+
+    let mut quantized = model.clone();
+    // ONNX graphs dequantize their own weights, so their inputs keep the declared type
+    if model.format == "safetensors" {
+        quantized.metadata.data_type = mode.data_type().to_string();
+    }
+    quantized.metadata.size = data.len();
+    quantized.data = data.into();
+    Ok(quantized)
+}
+
+/// Quantize the f32 tensors of a safetensors file, returning the file and the number converted
+fn quantize_safetensors(bytes: &[u8], mode: QuantMode, activation_scale: Option<f32>) -> Result<(Vec<u8>, usize), SynaptronError> {
+    let parsed = Safetensors::parse(bytes)?;
+    let mut metadata = parsed.metadata.clone();
+    let mut tensors = Vec::with_capacity(parsed.tensors.len());
+    let mut converted = 0;
+
+    for (name, info) in &parsed.tensors {
+        let bytes = parsed.tensor_data(info);
+
+        if info.dtype != "F32" {
+            tensors.push((name.clone(), info.dtype.clone(), info.shape.clone(), bytes.to_vec()));
+            continue;
+        }
+
+        let values = tensor::bytes_to_f32(bytes)?;
+        let (dtype, data) = match mode {
+            QuantMode::Fp16 => ("F16", tensor::f32_to_f16_bytes(&values)),
+            QuantMode::Int8Dynamic | QuantMode::Int8Static => {
+                let scale = int8_scale(&values);
+                metadata.insert(format!("quantization.scale.{}", name), Value::String(scale.to_string()));
+                ("I8", to_int8(&values, scale))
+            }
+        };
+
+        tensors.push((name.clone(), dtype.to_string(), info.shape.clone(), data));
+        converted += 1;
+    }
+
+    if let Some(scale) = activation_scale {
+        metadata.insert("quantization.activation_scale".to_string(), Value::String(scale.to_string()));
+    }
+    metadata.insert("quantization.mode".to_string(), serde_json::to_value(mode)?);
+
+    Ok((write_safetensors(tensors, metadata)?, converted))
+}
+
+/// Quantize the f32 initializers of an ONNX model, returning the model and the number converted
+///
+/// Each converted weight is stored reduced and restored under its original name
+/// by a `Cast` (f16) or `DequantizeLinear` (int8) node ahead of the graph's own
+/// nodes, so the rest of the graph is untouched. Weights stored as external
+/// data or also declared as graph inputs are left as they are.
+fn quantize_onnx(bytes: &[u8], mode: QuantMode, activation_scale: Option<f32>) -> Result<(Vec<u8>, usize), SynaptronError> {
+    let model_fields = proto::parse(bytes)?;
+
+    if mode != QuantMode::Fp16 {
+        let opset = onnx_default_opset(&model_fields)?;
+        if opset < ONNX_MIN_DEQUANTIZE_OPSET {
+            return Err(SynaptronError::Optimization(format!(
+                "Int8 quantization of ONNX models needs opset {} or later, the model uses {}",
+                ONNX_MIN_DEQUANTIZE_OPSET, opset
+            )));
+        }
+    }
+
+    let graph = model_fields.iter()
+        .find_map(|field| field.bytes(7))
+        .ok_or_else(|| SynaptronError::UnsupportedFormat("Invalid ONNX model: no graph".to_string()))?;
+    let graph_fields = proto::parse(graph)?;
+
+    // Older models list initializers as graph inputs too; a node can't also produce those
+    let mut graph_inputs = HashSet::new();
+    for input in graph_fields.iter().filter_map(|field| field.bytes(11)) {
+        if let Some(name) = proto::parse(input)?.iter().find_map(|field| field.string(1)) {
+            graph_inputs.insert(name.to_string());
+        }
+    }
+
+    let mut nodes = Vec::new();
+    let mut initializers = Vec::new();
+    let mut kept = Vec::new();
+    let mut converted = 0;
+
+    for field in &graph_fields {
+        let weight = match field.bytes(5) {
+            Some(initializer) => onnx_float_initializer(initializer)?,
+            None => None,
+        };
+        let (name, dims, values) = match weight {
+            Some((name, ..)) if graph_inputs.contains(&name) => {
+                field.write(&mut kept);
+                continue;
+            }
+            Some(weight) => weight,
+            None => {
+                field.write(&mut kept);
+                continue;
+            }
+        };
+
+        match mode {
+            QuantMode::Fp16 => {
+                let half = format!("{}_fp16", name);
+                initializers.push(onnx_tensor(&half, ONNX_FLOAT16, &dims, &tensor::f32_to_f16_bytes(&values)));
+                nodes.push(onnx_node("Cast", &[&half], &name, &[onnx_int_attribute("to", ONNX_FLOAT)]));
+            }
+            QuantMode::Int8Dynamic | QuantMode::Int8Static => {
+                let scale = int8_scale(&values);
+                let (quantized, scale_name, zero_point) = (
+                    format!("{}_quantized", name),
+                    format!("{}_scale", name),
+                    format!("{}_zero_point", name),
+                );
+                initializers.push(onnx_tensor(&quantized, ONNX_INT8, &dims, &to_int8(&values, scale)));
+                initializers.push(onnx_tensor(&scale_name, ONNX_FLOAT, &[], &scale.to_le_bytes()));
+                initializers.push(onnx_tensor(&zero_point, ONNX_INT8, &[], &[0]));
+                nodes.push(onnx_node("DequantizeLinear", &[&quantized, &scale_name, &zero_point], &name, &[]));
+            }
+        }
+        converted += 1;
+    }
+
+    // Nodes must be topologically sorted, so the restoring nodes go first
+    let mut graph = Vec::new();
+    for node in &nodes {
+        proto::put_bytes(&mut graph, 1, node);
+    }
+    graph.extend_from_slice(&kept);
+    for initializer in &initializers {
+        proto::put_bytes(&mut graph, 5, initializer);
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    for field in &model_fields {
+        match field.bytes(7) {
+            Some(_) => proto::put_bytes(&mut out, 7, &graph),
+            None => field.write(&mut out),
+        }
+    }
+
+    let mode_name = serde_json::to_value(mode)?.as_str().unwrap_or_default().to_string();
+    proto::put_bytes(&mut out, 14, &onnx_metadata_entry("quantization.mode", &mode_name));
+    if let Some(scale) = activation_scale {
+        proto::put_bytes(&mut out, 14, &onnx_metadata_entry("quantization.activation_scale", &scale.to_string()));
+    }
+
+    Ok((out, converted))
+}
+
+/// Version of the default `ai.onnx` operator set a model imports
+fn onnx_default_opset(model_fields: &[proto::Field<'_>]) -> Result<u64, SynaptronError> {
+    let mut version = 0;
+    for opset in model_fields.iter().filter_map(|field| field.bytes(8)) {
+        let opset = proto::parse(opset)?;
+        let domain = opset.iter().find_map(|field| field.string(1)).unwrap_or_default();
+        if domain.is_empty() || domain == "ai.onnx" {
+            version = opset.iter().find_map(|field| field.varint(2)).unwrap_or(0);
+        }
+    }
+    Ok(version)
+}
+
+/// Name, dimensions and values of an ONNX f32 initializer
+type FloatInitializer = (String, Vec<u64>, Vec<f32>);
+
+/// Name, dimensions and values of an initializer holding inline f32 data
+fn onnx_float_initializer(bytes: &[u8]) -> Result<Option<FloatInitializer>, SynaptronError> {
+    let fields = proto::parse(bytes)?;
+
+    let data_type = fields.iter().find_map(|field| field.varint(2)).unwrap_or(0);
+    let external = fields.iter().find_map(|field| field.varint(14)).unwrap_or(0) == 1;
+    if data_type != ONNX_FLOAT || external {
+        return Ok(None);
+    }
+
+    let name = match fields.iter().find_map(|field| field.string(8)) {
+        Some(name) => name.to_string(),
+        None => return Ok(None),
+    };
+
+    let mut dims = Vec::new();
+    for field in fields.iter().filter(|field| field.number == 1) {
+        match field.value {
+            proto::Value::Varint(dim) => dims.push(dim),
+            proto::Value::Bytes(packed) => dims.extend(proto::packed_varints(packed)?),
+            _ => {}
+        }
+    }
+
+    let values = match fields.iter().find_map(|field| field.bytes(9)) {
+        Some(raw) => tensor::bytes_to_f32(raw)?,
+        None => {
+            let mut values = Vec::new();
+            for field in fields.iter().filter(|field| field.number == 4) {
+                match field.value {
+                    proto::Value::Bytes(packed) => values.extend(tensor::bytes_to_f32(packed)?),
+                    proto::Value::Fixed32(value) => values.extend(tensor::bytes_to_f32(value)?),
+                    _ => {}
+                }
+            }
+            values
+        }
+    };
+
+    if values.len() as u64 != dims.iter().product::<u64>() {
+        debug!("Skipping ONNX initializer {}: {} values for dimensions {:?}", name, values.len(), dims);
+        return Ok(None);
+    }
+
+    Ok(Some((name, dims, values)))
+}
+
+/// Encode an ONNX `TensorProto` holding raw little-endian data
+fn onnx_tensor(name: &str, data_type: u64, dims: &[u64], raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for dim in dims {
+        proto::put_varint(&mut out, 1, *dim);
+    }
+    proto::put_varint(&mut out, 2, data_type);
+    proto::put_bytes(&mut out, 8, name.as_bytes());
+    proto::put_bytes(&mut out, 9, raw);
+    out
+}
+
+/// Encode an ONNX `NodeProto` with one output
+fn onnx_node(op_type: &str, inputs: &[&str], output: &str, attributes: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for input in inputs {
+        proto::put_bytes(&mut out, 1, input.as_bytes());
+    }
+    proto::put_bytes(&mut out, 2, output.as_bytes());
+    proto::put_bytes(&mut out, 3, format!("{}_{}", output, op_type).as_bytes());
+    proto::put_bytes(&mut out, 4, op_type.as_bytes());
+    for attribute in attributes {
+        proto::put_bytes(&mut out, 5, attribute);
+    }
+    out
+}
+
+/// Encode an integer ONNX `AttributeProto`
+fn onnx_int_attribute(name: &str, value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    proto::put_bytes(&mut out, 1, name.as_bytes());
+    proto::put_varint(&mut out, 3, value);
+    // AttributeType::INT
+    proto::put_varint(&mut out, 20, 2);
+    out
+}
+
+/// Encode an ONNX `StringStringEntryProto` for the model's `metadata_props`
+fn onnx_metadata_entry(key: &str, value: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    proto::put_bytes(&mut out, 1, key.as_bytes());
+    proto::put_bytes(&mut out, 2, value.as_bytes());
+    out
+}
+
+/// Just enough of the protobuf wire format to rewrite ONNX models
+mod proto {
+    use crate::error::SynaptronError;
+
+    /// Value of one field
+    #[derive(Debug, Clone, Copy)]
+    pub enum Value<'a> {
+        /// Wire type 0
+        Varint(u64),
+
+        /// Wire type 1
+        Fixed64(&'a [u8]),
+
+        /// Wire type 2: strings, bytes, messages and packed repeated fields
+        Bytes(&'a [u8]),
+
+        /// Wire type 5
+        Fixed32(&'a [u8]),
+    }
+
+    /// One field of a message, in the order it was encoded
+    #[derive(Debug, Clone, Copy)]
+    pub struct Field<'a> {
+        /// Field number
+        pub number: u64,
+
+        /// Field value
+        pub value: Value<'a>,
+    }
+
+    impl<'a> Field<'a> {
+        /// Length-delimited value of field `number`
+        pub fn bytes(&self, number: u64) -> Option<&'a [u8]> {
+            match self.value {
+                Value::Bytes(bytes) if self.number == number => Some(bytes),
+                _ => None,
+            }
+        }
+
+        /// UTF-8 value of field `number`
+        pub fn string(&self, number: u64) -> Option<&'a str> {
+            self.bytes(number).and_then(|bytes| std::str::from_utf8(bytes).ok())
+        }
+
+        /// Varint value of field `number`
+        pub fn varint(&self, number: u64) -> Option<u64> {
+            match self.value {
+                Value::Varint(value) if self.number == number => Some(value),
+                _ => None,
+            }
+        }
+
+        /// Append the field, encoded as it was read
+        pub fn write(&self, out: &mut Vec<u8>) {
+            match self.value {
+                Value::Varint(value) => put_varint(out, self.number, value),
+                Value::Fixed64(bytes) => {
+                    write_varint(out, self.number << 3 | 1);
+                    out.extend_from_slice(bytes);
+                }
+                Value::Bytes(bytes) => put_bytes(out, self.number, bytes),
+                Value::Fixed32(bytes) => {
+                    write_varint(out, self.number << 3 | 5);
+                    out.extend_from_slice(bytes);
+                }
+            }
+        }
+    }
+
+    /// Invalid model error
+    fn invalid(reason: &str) -> SynaptronError {
+        SynaptronError::UnsupportedFormat(format!("Invalid ONNX model: {}", reason))
+    }
+
+    /// Split a message into its fields
+    pub fn parse(bytes: &[u8]) -> Result<Vec<Field<'_>>, SynaptronError> {
+        let mut fields = Vec::new();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            let key = read_varint(bytes, &mut pos)?;
+            let value = match key & 0x07 {
+                0 => Value::Varint(read_varint(bytes, &mut pos)?),
+                1 => Value::Fixed64(take(bytes, &mut pos, 8)?),
+                2 => {
+                    let len = read_varint(bytes, &mut pos)? as usize;
+                    Value::Bytes(take(bytes, &mut pos, len)?)
+                }
+                5 => Value::Fixed32(take(bytes, &mut pos, 4)?),
+                wire_type => return Err(invalid(&format!("unsupported wire type {}", wire_type))),
+            };
+            fields.push(Field { number: key >> 3, value });
+        }
+
+        Ok(fields)
+    }
+
+    /// Values of a packed repeated varint field
+    pub fn packed_varints(bytes: &[u8]) -> Result<Vec<u64>, SynaptronError> {
+        let mut values = Vec::new();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            values.push(read_varint(bytes, &mut pos)?);
+        }
+        Ok(values)
+    }
+
+    /// Append a varint field
+    pub fn put_varint(out: &mut Vec<u8>, number: u64, value: u64) {
+        write_varint(out, number << 3);
+        write_varint(out, value);
+    }
+
+    /// Append a length-delimited field
+    pub fn put_bytes(out: &mut Vec<u8>, number: u64, bytes: &[u8]) {
+        write_varint(out, number << 3 | 2);
+        write_varint(out, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+
+    /// Read a base-128 varint
+    fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, SynaptronError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = *bytes.get(*pos).ok_or_else(|| invalid("truncated varint"))?;
+            *pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint is too long"))
+    }
+
+    /// Write a base-128 varint
+    fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    /// Take `len` bytes from `pos`
+    fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], SynaptronError> {
+        let end = pos.checked_add(len)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| invalid("truncated field"))?;
+        let taken = &bytes[*pos..end];
+        *pos = end;
+        Ok(taken)
+    }
+}
+
+/// Optimization pass quantizing a model's weights
+pub struct QuantizationPass {
+    /// Quantization mode
+    mode: QuantMode,
+
+    /// Calibration inputs for static quantization
+    calibration: Vec<Vec<u8>>,
+}
+
+impl QuantizationPass {
+    /// Create a quantization pass
+    pub fn new(mode: QuantMode, calibration: Vec<Vec<u8>>) -> Self {
+        Self { mode, calibration }
+    }
+}
+
+impl OptimizationPass for QuantizationPass {
+    fn name(&self) -> &str {
+        "quantization"
+    }
+
+    fn apply(&self, model: &mut Model) -> Result<(), SynaptronError> {
+        *model = quantize_calibrated(model, self.mode, &self.calibration)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelInputType;
+
+    /// Model of the given format holding `data`
+    fn model(format: &str, data: Vec<u8>) -> Model {
+        let mut model = Model::for_test("weights", ModelInputType::Text, &data);
+        model.format = format.to_string();
+        model
+    }
+
+    /// Safetensors model with an f32 weight and an i64 position table
+    fn safetensors_model(weights: &[f32]) -> Model {
+        let data = write_safetensors(vec![
+            ("weight".to_string(), "F32".to_string(), vec![weights.len()], tensor::f32_to_bytes(weights)),
+            ("positions".to_string(), "I64".to_string(), vec![2], tensor::i64_to_bytes(&[0, 1])),
+        ], Map::new()).unwrap();
+        model("safetensors", data)
+    }
+
+    /// ONNX model importing `opset` with one f32 initializer, also a graph input when `as_input`
+    fn onnx_model(opset: u64, weights: &[f32], as_input: bool) -> Model {
+        let mut graph = Vec::new();
+        proto::put_bytes(&mut graph, 5, &onnx_tensor("weight", ONNX_FLOAT, &[weights.len() as u64], &tensor::f32_to_bytes(weights)));
+        if as_input {
+            let mut input = Vec::new();
+            proto::put_bytes(&mut input, 1, b"weight");
+            proto::put_bytes(&mut graph, 11, &input);
+        }
+
+        let mut opset_import = Vec::new();
+        proto::put_varint(&mut opset_import, 2, opset);
+
+        let mut data = Vec::new();
+        proto::put_varint(&mut data, 1, 7);
+        proto::put_bytes(&mut data, 8, &opset_import);
+        proto::put_bytes(&mut data, 7, &graph);
+        model("onnx", data)
+    }
+
+    /// Fields of an ONNX model's graph
+    fn graph_fields(model: &[u8]) -> Vec<proto::Field<'_>> {
+        let graph = proto::parse(model).unwrap().iter().find_map(|field| field.bytes(7)).unwrap();
+        proto::parse(graph).unwrap()
+    }
+
+    /// Op types of a graph's nodes, in order
+    fn op_types(graph: &[proto::Field<'_>]) -> Vec<String> {
+        graph.iter()
+            .filter_map(|field| field.bytes(1))
+            .map(|node| proto::parse(node).unwrap().iter().find_map(|field| field.string(4)).unwrap().to_string())
+            .collect()
+    }
+
+    /// Names and element types of a graph's initializers
+    fn initializers(graph: &[proto::Field<'_>]) -> Vec<(String, u64)> {
+        graph.iter()
+            .filter_map(|field| field.bytes(5))
+            .map(|initializer| {
+                let fields = proto::parse(initializer).unwrap();
+                let name = fields.iter().find_map(|field| field.string(8)).unwrap().to_string();
+                (name, fields.iter().find_map(|field| field.varint(2)).unwrap())
+            })
+            .collect()
+    }
+
+    /// Value of an ONNX model's metadata property
+    fn onnx_metadata(model: &[u8], key: &str) -> Option<String> {
+        proto::parse(model).unwrap().iter()
+            .filter_map(|field| field.bytes(14))
+            .map(|entry| proto::parse(entry).unwrap())
+            .find(|entry| entry.iter().any(|field| field.string(1) == Some(key)))
+            .and_then(|entry| entry.iter().find_map(|field| field.string(2)).map(str::to_string))
+    }
+
+    #[test]
+    fn fp16_halves_float_tensors_only() {
+        let quantized = quantize(&safetensors_model(&[1.0, -2.5]), QuantMode::Fp16).unwrap();
+
+        let parsed = Safetensors::parse(&quantized.data).unwrap();
+        let (_, weight) = parsed.tensors.iter().find(|(name, _)| name == "weight").unwrap();
+        let (_, positions) = parsed.tensors.iter().find(|(name, _)| name == "positions").unwrap();
+        assert_eq!(weight.dtype, "F16");
+        assert_eq!(tensor::bytes_f16_to_f32(parsed.tensor_data(weight)).unwrap(), vec![1.0, -2.5]);
+        assert_eq!(tensor::bytes_to_i64(parsed.tensor_data(positions)).unwrap(), vec![0, 1]);
+        assert_eq!(parsed.metadata["quantization.mode"], "fp16");
+        assert_eq!(quantized.metadata.data_type, "f16");
+        assert_eq!(quantized.metadata.size, quantized.data.len());
+    }
+
+    #[test]
+    fn int8_stores_values_with_their_scale() {
+        let quantized = quantize(&safetensors_model(&[0.5, -1.27, 0.0]), QuantMode::Int8Dynamic).unwrap();
+
+        let parsed = Safetensors::parse(&quantized.data).unwrap();
+        let (_, weight) = parsed.tensors.iter().find(|(name, _)| name == "weight").unwrap();
+        assert_eq!(weight.dtype, "I8");
+        assert_eq!(tensor::bytes_to_i8(parsed.tensor_data(weight)), vec![50, -127, 0]);
+        let scale: f32 = parsed.metadata["quantization.scale.weight"].as_str().unwrap().parse().unwrap();
+        assert!((scale - 0.01).abs() < 1e-6);
+        assert!(!parsed.metadata.contains_key("quantization.activation_scale"));
+        assert_eq!(quantized.metadata.data_type, "int8");
+    }
+
+    #[test]
+    fn static_int8_needs_calibration() {
+        let model = safetensors_model(&[0.5, -1.0]);
+
+        assert!(matches!(quantize(&model, QuantMode::Int8Static), Err(SynaptronError::Optimization(_))));
+
+        let calibration = vec![tensor::f32_to_bytes(&[1.0, -2.54]), tensor::f32_to_bytes(&[0.5])];
+        let quantized = quantize_calibrated(&model, QuantMode::Int8Static, &calibration).unwrap();
+
+        let parsed = Safetensors::parse(&quantized.data).unwrap();
+        let scale: f32 = parsed.metadata["quantization.activation_scale"].as_str().unwrap().parse().unwrap();
+        assert!((scale - 0.02).abs() < 1e-6);
+    }
+
+    #[test]
+    fn other_formats_are_not_quantized() {
+        assert!(matches!(
+            quantize(&model("gguf", b"GGUF".to_vec()), QuantMode::Fp16),
+            Err(SynaptronError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn onnx_fp16_weights_are_cast_back() {
+        let quantized = quantize(&onnx_model(13, &[1.0, 2.0], false), QuantMode::Fp16).unwrap();
+
+        let graph = graph_fields(&quantized.data);
+        assert_eq!(op_types(&graph), vec!["Cast"]);
+        assert_eq!(initializers(&graph), vec![("weight_fp16".to_string(), ONNX_FLOAT16)]);
+        assert_eq!(onnx_metadata(&quantized.data, "quantization.mode").as_deref(), Some("fp16"));
+        assert_eq!(quantized.metadata.data_type, "f32");
+    }
+
+    #[test]
+    fn onnx_int8_weights_are_dequantized() {
+        let quantized = quantize(&onnx_model(13, &[1.0, -2.0], false), QuantMode::Int8Dynamic).unwrap();
+
+        let graph = graph_fields(&quantized.data);
+        assert_eq!(op_types(&graph), vec!["DequantizeLinear"]);
+        assert_eq!(initializers(&graph), vec![
+            ("weight_quantized".to_string(), ONNX_INT8),
+            ("weight_scale".to_string(), ONNX_FLOAT),
+            ("weight_zero_point".to_string(), ONNX_INT8),
+        ]);
+    }
+
+    #[test]
+    fn onnx_int8_needs_a_recent_opset() {
+        assert!(matches!(
+            quantize(&onnx_model(9, &[1.0], false), QuantMode::Int8Dynamic),
+            Err(SynaptronError::Optimization(_))
+        ));
+        assert!(quantize(&onnx_model(9, &[1.0], false), QuantMode::Fp16).is_ok());
+    }
+
+    #[test]
+    fn onnx_weights_declared_as_inputs_are_kept() {
+        let model = onnx_model(13, &[1.0, 2.0], true);
+
+        let (data, converted) = quantize_onnx(&model.data, QuantMode::Fp16, None).unwrap();
+
+        assert_eq!(converted, 0);
+        assert!(op_types(&graph_fields(&data)).is_empty());
+        assert_eq!(initializers(&graph_fields(&data)), vec![("weight".to_string(), ONNX_FLOAT)]);
+    }
+
+    #[test]
+    fn quantization_pass_replaces_the_model() {
+        let mut model = safetensors_model(&[1.0, 2.0]);
+
+        QuantizationPass::new(QuantMode::Fp16, Vec::new()).apply(&mut model).unwrap();
+
+        assert_eq!(model.metadata.data_type, "f16");
+    }
+}
@@ -0,0 +1,146 @@
+//! Retry budget for the Synaptron inference engine
+
+use crate::config::RetryConfig;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
+
+/// Token bucket state
+struct BudgetState {
+    /// Available retry tokens
+    tokens: f64,
+    
+    /// Last time-based refill
+    last_refill: Instant,
+}
+
+/// Token-bucket retry budget
+///
+/// Every request deposits `budget_ratio` tokens and the bucket also refills at
+/// `budget_min_per_second`, so retries stay capped at a fraction of traffic.
+pub struct RetryBudget {
+    /// Retry configuration
+    config: RetryConfig,
+    
+    /// Bucket state
+    state: Arc<Mutex<BudgetState>>,
+}
+
+impl RetryBudget {
+    /// Create a new retry budget starting full
+    pub fn new(config: &RetryConfig) -> Self {
+        Self {
+            config: config.clone(),
+            state: Arc::new(Mutex::new(BudgetState {
+                tokens: config.budget_max_tokens,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+    
+    /// Maximum retries allowed for a single request
+    pub fn max_retries(&self) -> u32 {
+        self.config.max_retries
+    }
+    
+    /// Record a request, depositing its share of retry tokens
+    pub fn deposit(&self) {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        state.tokens = (state.tokens + self.config.budget_ratio).min(self.config.budget_max_tokens);
+    }
+    
+    /// Try to withdraw a token for a retry, returning false when the budget is exhausted
+    pub fn try_withdraw(&self) -> bool {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            debug!("Retry budget exhausted ({:.2} tokens left)", state.tokens);
+            false
+        }
+    }
+    
+    /// Currently available retry tokens
+    pub fn available(&self) -> f64 {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        state.tokens
+    }
+    
+    /// Apply the time-based refill
+    fn refill(&self, state: &mut BudgetState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.last_refill = now;
+        state.tokens = (state.tokens + elapsed * self.config.budget_min_per_second)
+            .clamp(0.0, self.config.budget_max_tokens);
+    }
+}
+
+impl Clone for RetryBudget {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Budget without time-based refill, so only deposits add tokens
+    fn budget(max_tokens: f64) -> RetryBudget {
+        RetryBudget::new(&RetryConfig {
+            max_retries: 3,
+            budget_ratio: 0.5,
+            budget_min_per_second: 0.0,
+            budget_max_tokens: max_tokens,
+        })
+    }
+
+    #[test]
+    fn retries_stop_once_the_budget_is_spent() {
+        let budget = budget(2.0);
+        
+        assert!(budget.try_withdraw());
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+    }
+
+    #[test]
+    fn requests_earn_retries_back() {
+        let budget = budget(1.0);
+        assert!(budget.try_withdraw());
+        
+        budget.deposit();
+        assert!(!budget.try_withdraw());
+        budget.deposit();
+        assert!(budget.try_withdraw());
+    }
+
+    #[test]
+    fn deposits_never_exceed_the_maximum() {
+        let budget = budget(1.0);
+        for _ in 0..10 {
+            budget.deposit();
+        }
+        
+        assert_eq!(budget.available(), 1.0);
+    }
+
+    #[test]
+    fn clones_share_one_budget() {
+        let budget = budget(1.0);
+        let clone = budget.clone();
+        
+        assert!(clone.try_withdraw());
+        assert!(!budget.try_withdraw());
+    }
+}
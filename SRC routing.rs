@@ -0,0 +1,139 @@
+//! Input-dependent model routing for the Synaptron inference engine
+
+use crate::{config::{RoutingCondition, RoutingConfig}, error::SynaptronError};
+use regex::Regex;
+use tracing::debug;
+
+/// Routing condition compiled for evaluation
+#[derive(Debug, Clone)]
+enum Condition {
+    /// Input has at least this many characters
+    MinLength(usize),
+    
+    /// Input has at most this many characters
+    MaxLength(usize),
+    
+    /// Input matches a regular expression
+    Regex(Regex),
+}
+
+impl Condition {
+    /// Whether the input text satisfies the condition
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            Condition::MinLength(min) => text.chars().count() >= *min,
+            Condition::MaxLength(max) => text.chars().count() <= *max,
+            Condition::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Compiled routing rules
+#[derive(Debug, Clone, Default)]
+pub struct RoutingRules {
+    /// Conditions and their target models, in evaluation order
+    rules: Vec<(Condition, String)>,
+}
+
+impl RoutingRules {
+    /// Compile routing rules, rejecting invalid regular expressions
+    pub fn new(config: &RoutingConfig) -> Result<Self, SynaptronError> {
+        let rules = config.rules.iter()
+            .map(|rule| {
+                let condition = match &rule.condition {
+                    RoutingCondition::MinLength(min) => Condition::MinLength(*min),
+                    RoutingCondition::MaxLength(max) => Condition::MaxLength(*max),
+                    RoutingCondition::Regex(pattern) => Condition::Regex(Regex::new(pattern).map_err(|e| {
+                        SynaptronError::Config(config::ConfigError::Message(format!(
+                            "Invalid routing regex for {}: {}", rule.target_model, e
+                        )))
+                    })?),
+                };
+                Ok((condition, rule.target_model.clone()))
+            })
+            .collect::<Result<_, SynaptronError>>()?;
+        
+        Ok(Self { rules })
+    }
+    
+    /// Target of the first rule matching the input whose model is usable
+    ///
+    /// Non-UTF-8 input only matches length conditions on its lossy decoding.
+    pub fn route<F>(&self, input: &[u8], is_usable: F) -> Option<&str>
+    where
+        F: Fn(&str) -> bool,
+    {
+        if self.rules.is_empty() {
+            return None;
+        }
+        
+        let text = String::from_utf8_lossy(input);
+        
+        for (condition, target) in &self.rules {
+            if !condition.matches(&text) {
+                continue;
+            }
+            if is_usable(target) {
+                debug!("Routing input to {} by rule {:?}", target, condition);
+                return Some(target);
+            }
+            debug!("Routing rule matched but {} is not loaded, trying the next rule", target);
+        }
+        
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RoutingRule;
+    
+    /// Rules compiled from `(condition, target)` pairs
+    fn rules(rules: Vec<(RoutingCondition, &str)>) -> Result<RoutingRules, SynaptronError> {
+        RoutingRules::new(&RoutingConfig {
+            rules: rules.into_iter()
+                .map(|(condition, target)| RoutingRule { condition, target_model: target.to_string() })
+                .collect(),
+        })
+    }
+    
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = rules(vec![
+            (RoutingCondition::Regex("^translate:".to_string()), "marian"),
+            (RoutingCondition::MinLength(20), "longformer"),
+            (RoutingCondition::MaxLength(19), "distilbert"),
+        ]).unwrap();
+        let all_loaded = |_: &str| true;
+        
+        assert_eq!(rules.route(b"translate: a rather long sentence", all_loaded), Some("marian"));
+        assert_eq!(rules.route(b"a rather long sentence here", all_loaded), Some("longformer"));
+        assert_eq!(rules.route(b"short", all_loaded), Some("distilbert"));
+    }
+    
+    #[test]
+    fn lengths_count_characters_not_bytes() {
+        let rules = rules(vec![(RoutingCondition::MaxLength(3), "small")]).unwrap();
+        
+        assert_eq!(rules.route("日本語".as_bytes(), |_| true), Some("small"));
+    }
+    
+    #[test]
+    fn rules_for_unusable_models_fall_through() {
+        let rules = rules(vec![
+            (RoutingCondition::MinLength(0), "unloaded"),
+            (RoutingCondition::MinLength(0), "loaded"),
+        ]).unwrap();
+        
+        assert_eq!(rules.route(b"hello", |model| model == "loaded"), Some("loaded"));
+        assert_eq!(rules.route(b"hello", |_| false), None);
+    }
+    
+    #[test]
+    fn invalid_regexes_are_config_errors() {
+        let err = rules(vec![(RoutingCondition::Regex("(unclosed".to_string()), "bert")]).unwrap_err();
+        
+        assert!(matches!(err, SynaptronError::Config(_)));
+    }
+}
@@ -0,0 +1,252 @@
+//! Ordered shutdown coordination for the Synaptron inference engine
+
+use crate::config::ShutdownConfig;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Shutdown phases, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownPhase {
+    /// Stop accepting new requests
+    StopAccepting,
+    
+    /// Wait for in-flight requests to finish
+    Drain,
+    
+    /// Process inputs still waiting in the forming batch
+    FlushBatch,
+    
+    /// Persist cache state and log final metrics
+    Persist,
+    
+    /// Unload models and release backends
+    UnloadBackends,
+}
+
+impl ShutdownPhase {
+    /// Per-phase timeout from configuration
+    fn timeout(&self, config: &ShutdownConfig) -> Option<Duration> {
+        match self {
+            ShutdownPhase::StopAccepting => None,
+            ShutdownPhase::Drain => config.drain(),
+            ShutdownPhase::FlushBatch => config.flush(),
+            ShutdownPhase::Persist => config.persist(),
+            ShutdownPhase::UnloadBackends => config.unload(),
+        }
+    }
+}
+
+/// Count of in-flight requests, so shutdown can wait for them to drain
+#[derive(Clone, Default)]
+pub struct InFlight {
+    /// Requests currently running
+    count: Arc<AtomicUsize>,
+    
+    /// Notified when the count drops to zero
+    idle: Arc<Notify>,
+}
+
+impl InFlight {
+    /// Track a request until the returned guard is dropped
+    pub fn enter(&self) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard { in_flight: self.clone() }
+    }
+    
+    /// Number of requests currently running
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+    
+    /// Wait until no requests are running
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.count() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// Guard marking a request as in flight
+pub struct InFlightGuard {
+    /// Tracker to decrement on drop
+    in_flight: InFlight,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.in_flight.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.in_flight.idle.notify_waiters();
+        }
+    }
+}
+
+/// Shutdown coordinator
+///
+/// Runs each phase in order under its own timeout, bounded by the overall
+/// deadline, so a stuck phase is abandoned rather than blocking shutdown forever.
+#[derive(Clone)]
+pub struct Shutdown {
+    /// Phase timeouts
+    config: ShutdownConfig,
+    
+    /// Overall shutdown deadline
+    deadline: Option<Duration>,
+    
+    /// Cancelled once shutdown starts, so the server stops accepting requests
+    token: CancellationToken,
+    
+    /// In-flight request tracker
+    in_flight: InFlight,
+}
+
+impl Shutdown {
+    /// Create a new coordinator
+    pub fn new(config: &ShutdownConfig, deadline: Option<Duration>) -> Self {
+        Self {
+            config: config.clone(),
+            deadline,
+            token: CancellationToken::new(),
+            in_flight: InFlight::default(),
+        }
+    }
+    
+    /// Token cancelled when shutdown starts
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+    
+    /// Whether shutdown has started
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+    
+    /// In-flight request tracker
+    pub fn in_flight(&self) -> &InFlight {
+        &self.in_flight
+    }
+    
+    /// Timeout for a phase, bounded by what remains of the overall deadline
+    pub fn phase_timeout(&self, phase: ShutdownPhase, started: Instant) -> Option<Duration> {
+        let remaining = self.deadline.map(|deadline| deadline.saturating_sub(started.elapsed()));
+        
+        match (phase.timeout(&self.config), remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        }
+    }
+    
+    /// Run a phase under its timeout, logging its outcome
+    ///
+    /// Returns `None` if the phase timed out.
+    pub async fn run_phase<Fut, T>(&self, phase: ShutdownPhase, started: Instant, work: Fut) -> Option<T>
+    where
+        Fut: Future<Output = T>,
+    {
+        info!("Shutdown phase {:?} starting", phase);
+        let phase_start = Instant::now();
+        
+        let result = match self.phase_timeout(phase, started) {
+            Some(timeout) => tokio::time::timeout(timeout, work).await.ok(),
+            None => Some(work.await),
+        };
+        
+        match &result {
+            Some(_) => info!("Shutdown phase {:?} finished in {} ms", phase, phase_start.elapsed().as_millis()),
+            None => warn!("Shutdown phase {:?} timed out after {} ms, moving on", phase, phase_start.elapsed().as_millis()),
+        }
+        
+        result
+    }
+}
+
+/// Resolve when the process receives SIGINT (Ctrl-C) or, on Unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl-C, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn draining_waits_for_in_flight_requests() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.enter();
+        let waiter = tokio::spawn({
+            let in_flight = in_flight.clone();
+            async move { in_flight.wait_idle().await }
+        });
+        
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(guard);
+        
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert_eq!(in_flight.count(), 0);
+    }
+    
+    #[test]
+    fn phase_timeouts_are_bounded_by_the_deadline() {
+        let shutdown = Shutdown::new(&ShutdownConfig::default(), Some(Duration::from_secs(10)));
+        let started = Instant::now();
+        
+        assert_eq!(shutdown.phase_timeout(ShutdownPhase::Persist, started), Some(Duration::from_secs(5)));
+        assert!(shutdown.phase_timeout(ShutdownPhase::Drain, started).unwrap() <= Duration::from_secs(10));
+        assert!(shutdown.phase_timeout(ShutdownPhase::StopAccepting, started).unwrap() <= Duration::from_secs(10));
+    }
+    
+    #[test]
+    fn phases_without_timeouts_are_unbounded() {
+        let config = ShutdownConfig { drain_ms: 0, ..ShutdownConfig::default() };
+        let shutdown = Shutdown::new(&config, None);
+        
+        assert_eq!(shutdown.phase_timeout(ShutdownPhase::Drain, Instant::now()), None);
+    }
+    
+    #[tokio::test]
+    async fn stuck_phases_are_abandoned() {
+        let config = ShutdownConfig { flush_ms: 10, ..ShutdownConfig::default() };
+        let shutdown = Shutdown::new(&config, None);
+        
+        let flushed = shutdown.run_phase(ShutdownPhase::FlushBatch, Instant::now(), std::future::pending::<()>()).await;
+        let persisted = shutdown.run_phase(ShutdownPhase::Persist, Instant::now(), async { 42 }).await;
+        
+        assert_eq!(flushed, None);
+        assert_eq!(persisted, Some(42));
+    }
+}
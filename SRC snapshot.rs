@@ -0,0 +1,297 @@
+//! Warm snapshots of optimized models for the Synaptron inference engine
+//!
+//! A snapshot stores a model after `AutoOptimizer` has run, together with the
+//! device, precision, quantization and backend it was optimized for and the
+//! identity of its source file,
+//! so a restart with matching configuration can skip loading and re-optimization.
+
+use crate::{config::ModelConfig, model::{self, Model, ModelInputType, ModelMetadata}, error::SynaptronError, quantization::QuantMode};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use tracing::{info, debug, warn};
+
+/// Snapshot file format version, bumped on incompatible layout changes
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// Snapshot file extension
+const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// Device, precision and optimizations a snapshot was built for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotTarget {
+    /// Device the model was optimized for
+    pub device: String,
+    
+    /// Compute precision the model was optimized for
+    pub precision: String,
+    
+    /// Weight quantization applied by the optimizer, if any
+    pub quantization: Option<QuantMode>,
+    
+    /// Backend the optimizer selects for the model
+    pub optimized_backend: Option<String>,
+}
+
+impl std::fmt::Display for SnapshotTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.device, self.precision)?;
+        if let Some(mode) = self.quantization {
+            write!(f, " quantized to {:?}", mode)?;
+        }
+        if let Some(backend) = &self.optimized_backend {
+            write!(f, " on {}", backend)?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot header, stored as length-prefixed JSON ahead of the model data
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotHeader {
+    /// Snapshot format version
+    version: u32,
+    
+    /// Optimization target
+    target: SnapshotTarget,
+    
+    /// Size of the source model file when the snapshot was taken
+    source_size: u64,
+    
+    /// Modification time of the source model file, in seconds since the epoch
+    source_modified: u64,
+    
+    /// Model name
+    name: String,
+    
+    /// Model format
+    format: String,
+    
+    /// Model input type
+    input_type: ModelInputType,
+    
+    /// Model metadata
+    metadata: ModelMetadata,
+    
+    /// Backend chosen by the optimizer
+    #[serde(default)]
+    optimized_backend: Option<String>,
+}
+
+/// Snapshot path for a model source path under the cache directory
+///
+/// Named by a digest of the canonical source path, so models with the same
+/// file name in different directories get separate snapshots.
+pub fn path_for(cache_dir: &str, model_path: &str) -> String {
+    let canonical = std::fs::canonicalize(model_path)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| model_path.to_string());
+    format!("{}/{}.{}", cache_dir, model::path_file_stem(&canonical), SNAPSHOT_EXTENSION)
+}
+
+/// Size and modification time of a source model file
+async fn source_identity(model_path: &str) -> Result<(u64, u64), SynaptronError> {
+    let metadata = fs::metadata(model_path).await?;
+    let modified = metadata.modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok((metadata.len(), modified))
+}
+
+/// Write a snapshot of an optimized model
+pub async fn save(
+    model: &Model,
+    snapshot_path: &str,
+    model_path: &str,
+    target: &SnapshotTarget,
+) -> Result<(), SynaptronError> {
+    let (source_size, source_modified) = source_identity(model_path).await?;
+    
+    let header = SnapshotHeader {
+        version: SNAPSHOT_VERSION,
+        target: target.clone(),
+        source_size,
+        source_modified,
+        name: model.name.clone(),
+        format: model.format.clone(),
+        input_type: model.input_type.clone(),
+        metadata: model.metadata.clone(),
+        optimized_backend: model.optimized_backend.clone(),
+    };
+    let header = serde_json::to_vec(&header)?;
+    
+    let mut data = Vec::with_capacity(4 + header.len() + model.data.len());
+    data.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    data.extend_from_slice(&header);
+    data.extend_from_slice(&model.data);
+    
+    Model::write_atomic(snapshot_path, &data).await?;
+    info!("Wrote warm snapshot of {} to {}", model.name, snapshot_path);
+    Ok(())
+}
+
+/// Load a snapshot if one exists for this source file and optimization target
+///
+/// Snapshots for a different optimization target, of a changed source file, of
+/// a format `config` no longer allows, or that can't be read are rejected with
+/// a warning so the caller falls back to a normal load.
+pub async fn load(
+    snapshot_path: &str,
+    model_path: &str,
+    target: &SnapshotTarget,
+    config: &ModelConfig,
+) -> Option<Model> {
+    if !Path::new(snapshot_path).exists() {
+        debug!("No warm snapshot at {}", snapshot_path);
+        return None;
+    }
+    
+    match read(snapshot_path, model_path, target, config).await {
+        Ok(model) => {
+            info!("Loaded warm snapshot of {} from {}", model.name, snapshot_path);
+            Some(model)
+        }
+        Err(e) => {
+            warn!("Rejecting warm snapshot {}: {}", snapshot_path, e);
+            None
+        }
+    }
+}
+
+/// Read and validate a snapshot file
+async fn read(
+    snapshot_path: &str,
+    model_path: &str,
+    target: &SnapshotTarget,
+    config: &ModelConfig,
+) -> Result<Model, SynaptronError> {
+    let invalid = |message: String| SynaptronError::ModelLoad(message);
+    
+    let mut data = fs::read(snapshot_path).await?;
+    if data.len() < 4 {
+        return Err(invalid("truncated snapshot".to_string()));
+    }
+    
+    let header_len = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let header_end = 4 + header_len;
+    if data.len() < header_end {
+        return Err(invalid("truncated snapshot header".to_string()));
+    }
+    
+    let header: SnapshotHeader = serde_json::from_slice(&data[4..header_end])?;
+    
+    if header.version != SNAPSHOT_VERSION {
+        return Err(invalid(format!("format version {} is not {}", header.version, SNAPSHOT_VERSION)));
+    }
+    if &header.target != target {
+        return Err(invalid(format!(
+            "built for {}, but this configuration targets {}", header.target, target
+        )));
+    }
+    if source_identity(model_path).await.ok() != Some((header.source_size, header.source_modified)) {
+        return Err(invalid(format!("source model {} changed since the snapshot was taken", model_path)));
+    }
+    
+    if !config.is_format_allowed(&header.format) {
+        return Err(SynaptronError::UnsupportedFormat(format!(
+            "Format '{}' of {} is not in model.allowed_formats", header.format, model_path
+        )));
+    }
+    
+    let model_data = data.split_off(header_end);
+    Model::validate_content(snapshot_path, &header.format, &model_data)?;
+    
+    Ok(Model {
+        name: header.name,
+        path: model_path.to_string(),
+        format: header.format,
+        input_type: header.input_type,
+        metadata: header.metadata,
+        data: model_data.into(),
+        optimized_backend: header.optimized_backend,
+        tokenizer: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// CPU f32 target without quantization
+    fn cpu_target() -> SnapshotTarget {
+        SnapshotTarget {
+            device: "cpu".to_string(),
+            precision: "f32".to_string(),
+            quantization: None,
+            optimized_backend: None,
+        }
+    }
+    
+    /// Source model file and a snapshot of it taken for `cpu_target`, kept alive by the returned guard
+    async fn snapshotted() -> (tempfile::TempDir, String, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let model_path = dir.path().join("bert.bin").to_string_lossy().into_owned();
+        let snapshot_path = path_for(&dir.path().to_string_lossy(), &model_path);
+        std::fs::write(&model_path, b"source weights").unwrap();
+        
+        let model = Model::for_test("bert", ModelInputType::Text, b"optimized weights");
+        save(&model, &snapshot_path, &model_path, &cpu_target()).await.unwrap();
+        
+        (dir, model_path, snapshot_path)
+    }
+    
+    #[tokio::test]
+    async fn snapshots_round_trip() {
+        let (_dir, model_path, snapshot_path) = snapshotted().await;
+        
+        let model = load(&snapshot_path, &model_path, &cpu_target(), &ModelConfig::default()).await.unwrap();
+        
+        assert_eq!(model.name, "bert");
+        assert_eq!(model.path, model_path);
+        assert_eq!(&*model.data, b"optimized weights");
+    }
+    
+    #[tokio::test]
+    async fn snapshots_for_another_target_are_rejected() {
+        let (_dir, model_path, snapshot_path) = snapshotted().await;
+        let gpu = SnapshotTarget { device: "cuda:0".to_string(), ..cpu_target() };
+        let quantized = SnapshotTarget { quantization: Some(QuantMode::Int8Dynamic), ..cpu_target() };
+        
+        assert!(load(&snapshot_path, &model_path, &gpu, &ModelConfig::default()).await.is_none());
+        assert!(load(&snapshot_path, &model_path, &quantized, &ModelConfig::default()).await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn snapshots_of_changed_sources_are_rejected() {
+        let (_dir, model_path, snapshot_path) = snapshotted().await;
+        
+        std::fs::write(&model_path, b"retrained source weights").unwrap();
+        
+        assert!(load(&snapshot_path, &model_path, &cpu_target(), &ModelConfig::default()).await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn truncated_snapshots_are_rejected() {
+        let (_dir, model_path, snapshot_path) = snapshotted().await;
+        let data = std::fs::read(&snapshot_path).unwrap();
+        
+        std::fs::write(&snapshot_path, &data[..10]).unwrap();
+        
+        assert!(load(&snapshot_path, &model_path, &cpu_target(), &ModelConfig::default()).await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn snapshots_of_disallowed_formats_are_rejected() {
+        let (_dir, model_path, snapshot_path) = snapshotted().await;
+        let config = ModelConfig { allowed_formats: vec!["safetensors".to_string()], ..ModelConfig::default() };
+        
+        assert!(load(&snapshot_path, &model_path, &cpu_target(), &config).await.is_none());
+    }
+    
+    #[test]
+    fn models_with_one_file_name_get_separate_snapshots() {
+        assert_ne!(path_for("cache", "a/model.onnx"), path_for("cache", "b/model.onnx"));
+    }
+}
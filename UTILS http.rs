@@ -0,0 +1,18 @@
+//! Shared HTTP client for the Synaptron inference engine
+
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+/// Shared client so connection pools are reused across callers
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .user_agent(concat!("synaptron/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .expect("failed to build HTTP client")
+});
+
+/// Get the shared HTTP client
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
@@ -0,0 +1,4 @@
+//! Utilities and helpers for the Synaptron inference engine
+
+pub mod http;
+pub mod tensor;
@@ -0,0 +1,278 @@
+//! Tensor byte conversions for the Synaptron inference engine
+//!
+//! Tensors travel between preprocessing, backends and postprocessing as
+//! little-endian bytes; these helpers pack and unpack them.
+
+use crate::error::SynaptronError;
+use serde::{Deserialize, Serialize};
+
+/// Element type of a tensor
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DType {
+    /// 32-bit float
+    F32,
+
+    /// IEEE half precision float
+    F16,
+
+    /// Signed 8-bit integer
+    I8,
+
+    /// Signed 64-bit integer
+    I64,
+
+    /// Unsigned byte
+    U8,
+}
+
+impl DType {
+    /// Bytes per element
+    pub fn size(self) -> usize {
+        match self {
+            DType::F32 => 4,
+            DType::F16 => 2,
+            DType::I8 | DType::U8 => 1,
+            DType::I64 => 8,
+        }
+    }
+
+    /// Lowercase name, as used in `OutputTensor::dtype`
+    pub fn name(self) -> &'static str {
+        match self {
+            DType::F32 => "f32",
+            DType::F16 => "f16",
+            DType::I8 => "i8",
+            DType::I64 => "i64",
+            DType::U8 => "u8",
+        }
+    }
+
+    /// Parse a type name such as `f32`, `float32`, `int8` or safetensors' `I64`
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "f32" | "float32" | "float" => DType::F32,
+            "f16" | "float16" | "half" => DType::F16,
+            "i8" | "int8" => DType::I8,
+            "i64" | "int64" => DType::I64,
+            "u8" | "uint8" => DType::U8,
+            _ => return None,
+        })
+    }
+}
+
+/// Decode little-endian elements of `N` bytes
+fn decode_le<const N: usize, T>(bytes: &[u8], decode: fn([u8; N]) -> T) -> Result<Vec<T>, SynaptronError> {
+    if !bytes.len().is_multiple_of(N) {
+        return Err(SynaptronError::InvalidInput(format!(
+            "{} bytes is not a whole number of {}-byte elements", bytes.len(), N
+        )));
+    }
+
+    Ok(bytes.chunks_exact(N)
+        .map(|chunk| decode(chunk.try_into().expect("chunk of N bytes")))
+        .collect())
+}
+
+/// Decode little-endian f32 values
+pub fn bytes_to_f32(bytes: &[u8]) -> Result<Vec<f32>, SynaptronError> {
+    decode_le(bytes, f32::from_le_bytes)
+}
+
+/// Encode f32 values as little-endian bytes
+pub fn f32_to_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// Decode little-endian half precision values to f32
+pub fn bytes_f16_to_f32(bytes: &[u8]) -> Result<Vec<f32>, SynaptronError> {
+    decode_le(bytes, |bytes| half::f16::from_le_bytes(bytes).to_f32())
+}
+
+/// Encode f32 values as little-endian half precision bytes
+pub fn f32_to_f16_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|value| half::f16::from_f32(*value).to_le_bytes()).collect()
+}
+
+/// Decode little-endian i64 values
+pub fn bytes_to_i64(bytes: &[u8]) -> Result<Vec<i64>, SynaptronError> {
+    decode_le(bytes, i64::from_le_bytes)
+}
+
+/// Encode i64 values as little-endian bytes
+pub fn i64_to_bytes(values: &[i64]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// Reinterpret bytes as i8 values
+pub fn bytes_to_i8(bytes: &[u8]) -> Vec<i8> {
+    bytes.iter().map(|byte| *byte as i8).collect()
+}
+
+/// Reinterpret i8 values as bytes
+pub fn i8_to_bytes(values: &[i8]) -> Vec<u8> {
+    values.iter().map(|value| *value as u8).collect()
+}
+
+/// Number of elements in a shape, `None` on overflow
+pub fn element_count(shape: &[usize]) -> Option<usize> {
+    shape.iter().try_fold(1usize, |count, dim| count.checked_mul(*dim))
+}
+
+/// Raw little-endian tensor with its shape and element type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor {
+    /// Little-endian element bytes
+    pub data: Vec<u8>,
+
+    /// Dimensions, outermost first
+    pub shape: Vec<usize>,
+
+    /// Element type
+    pub dtype: DType,
+}
+
+impl Tensor {
+    /// Create a tensor, checking the data holds exactly the shape's elements
+    pub fn new(data: Vec<u8>, shape: Vec<usize>, dtype: DType) -> Result<Self, SynaptronError> {
+        let expected = element_count(&shape)
+            .and_then(|count| count.checked_mul(dtype.size()))
+            .ok_or_else(|| SynaptronError::InvalidInput(format!("Tensor shape {:?} is too large", shape)))?;
+
+        if data.len() != expected {
+            return Err(SynaptronError::InvalidInput(format!(
+                "Tensor of shape {:?} and type {} needs {} bytes, got {}",
+                shape, dtype.name(), expected, data.len()
+            )));
+        }
+
+        Ok(Self { data, shape, dtype })
+    }
+
+    /// Create an f32 tensor from values
+    pub fn from_f32(values: &[f32], shape: Vec<usize>) -> Result<Self, SynaptronError> {
+        Self::new(f32_to_bytes(values), shape, DType::F32)
+    }
+
+    /// Create an i8 tensor from values
+    pub fn from_i8(values: &[i8], shape: Vec<usize>) -> Result<Self, SynaptronError> {
+        Self::new(i8_to_bytes(values), shape, DType::I8)
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.data.len() / self.dtype.size()
+    }
+
+    /// Whether the tensor has no elements
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Values as f32, widening half precision; errors for integer tensors
+    pub fn to_f32(&self) -> Result<Vec<f32>, SynaptronError> {
+        match self.dtype {
+            DType::F32 => bytes_to_f32(&self.data),
+            DType::F16 => bytes_f16_to_f32(&self.data),
+            other => Err(SynaptronError::InvalidInput(format!(
+                "Tensor of type {} is not floating point", other.name()
+            ))),
+        }
+    }
+
+    /// Values as i8; errors for other types
+    pub fn to_i8(&self) -> Result<Vec<i8>, SynaptronError> {
+        match self.dtype {
+            DType::I8 => Ok(bytes_to_i8(&self.data)),
+            other => Err(SynaptronError::InvalidInput(format!(
+                "Tensor of type {} is not i8", other.name()
+            ))),
+        }
+    }
+
+    /// Same data under a new shape with the same number of elements
+    pub fn reshape(self, shape: Vec<usize>) -> Result<Self, SynaptronError> {
+        if element_count(&shape) != Some(self.len()) {
+            return Err(SynaptronError::InvalidInput(format!(
+                "Cannot reshape {:?} to {:?}, element counts differ", self.shape, shape
+            )));
+        }
+
+        Ok(Self { shape, ..self })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip() {
+        let floats = [0.0, -1.5, 3.25, f32::MAX];
+        assert_eq!(bytes_to_f32(&f32_to_bytes(&floats)).unwrap(), floats);
+
+        let halves = [0.0, -1.5, 3.25, 65504.0];
+        assert_eq!(bytes_f16_to_f32(&f32_to_f16_bytes(&halves)).unwrap(), halves);
+
+        let longs = [0, -1, i64::MAX, i64::MIN];
+        assert_eq!(bytes_to_i64(&i64_to_bytes(&longs)).unwrap(), longs);
+
+        let bytes = [0, -1, i8::MAX, i8::MIN];
+        assert_eq!(bytes_to_i8(&i8_to_bytes(&bytes)), bytes);
+    }
+
+    #[test]
+    fn values_are_little_endian() {
+        assert_eq!(f32_to_bytes(&[1.0]), vec![0x00, 0x00, 0x80, 0x3F]);
+        assert_eq!(f32_to_f16_bytes(&[1.0]), vec![0x00, 0x3C]);
+        assert_eq!(i64_to_bytes(&[1]), vec![1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn partial_elements_are_rejected() {
+        assert!(matches!(bytes_to_f32(&[0; 6]), Err(SynaptronError::InvalidInput(_))));
+        assert!(matches!(bytes_f16_to_f32(&[0; 3]), Err(SynaptronError::InvalidInput(_))));
+        assert!(matches!(bytes_to_i64(&[0; 12]), Err(SynaptronError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn dtype_names_parse_back() {
+        for dtype in [DType::F32, DType::F16, DType::I8, DType::I64, DType::U8] {
+            assert_eq!(DType::parse(dtype.name()), Some(dtype));
+        }
+        assert_eq!(DType::parse("float32"), Some(DType::F32));
+        assert_eq!(DType::parse("I64"), Some(DType::I64));
+        assert_eq!(DType::parse("bf16"), None);
+    }
+
+    #[test]
+    fn tensor_data_must_match_its_shape() {
+        let tensor = Tensor::from_f32(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], vec![2, 3]).unwrap();
+        assert_eq!(tensor.len(), 6);
+        assert_eq!(tensor.to_f32().unwrap(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        assert!(Tensor::from_f32(&[1.0, 2.0], vec![3]).is_err());
+        assert!(Tensor::new(vec![], vec![usize::MAX, 2], DType::F32).is_err());
+    }
+
+    #[test]
+    fn reshape_keeps_the_element_count() {
+        let tensor = Tensor::from_i8(&[1, 2, 3, 4, 5, 6], vec![6]).unwrap();
+
+        let reshaped = tensor.clone().reshape(vec![3, 2]).unwrap();
+        assert_eq!(reshaped.shape, vec![3, 2]);
+        assert_eq!(reshaped.to_i8().unwrap(), vec![1, 2, 3, 4, 5, 6]);
+
+        assert!(tensor.reshape(vec![4, 2]).is_err());
+    }
+
+    #[test]
+    fn typed_views_check_the_dtype() {
+        let ints = Tensor::from_i8(&[1, -1], vec![2]).unwrap();
+        assert!(ints.to_f32().is_err());
+
+        let halves = Tensor::new(f32_to_f16_bytes(&[0.5, 2.0]), vec![2], DType::F16).unwrap();
+        assert_eq!(halves.to_f32().unwrap(), vec![0.5, 2.0]);
+        assert!(halves.to_i8().is_err());
+    }
+}
@@ -0,0 +1,15 @@
+//! Build script embedding git and build metadata for the `/version` endpoint
+
+use vergen::EmitBuilder;
+
+fn main() {
+    // Builds outside a git checkout must still succeed, the SHA is reported as "unknown"
+    if let Err(e) = EmitBuilder::builder()
+        .build_timestamp()
+        .git_sha(false)
+        .rustc_semver()
+        .emit()
+    {
+        println!("cargo:warning=Failed to emit build metadata: {}", e);
+    }
+}
@@ -2,21 +2,189 @@
 //!
 //! High-performance multi-modal inference engine with dynamic model graph and auto-optimization.
 
-use synaptron::{config::Config, engine::InferenceEngine, Result};
+use clap::Parser;
+use std::path::Path;
+use std::time::Duration;
+use synaptron::{
+    cache::CacheMode,
+    cli::{BenchArgs, Cli, Command, DownloadArgs, InferArgs, ModelsCommand},
+    config::Config,
+    engine::{InferenceEngine, ModelScope},
+    init_logging,
+    loadtest::{self, LoadTestOptions},
+    postprocessing::{PredictionResult, DEFAULT_TOP_K},
+    Model, Result,
+};
+use tracing::warn;
+
+/// Input benchmarked when `bench` is given no input file
+const BENCH_INPUT: &[u8] = b"The quick brown fox jumps over the lazy dog";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logger
-    tracing_subscriber::fmt::init();
+    let cli = Cli::parse();
+    
+    // Load configuration; only load tests run without it
+    let config = Config::load_from(cli.config.as_deref());
+    
+    // Initialize logger, -v overrides monitoring.log_level and RUST_LOG overrides both
+    let mut monitoring = config.as_ref().map(|config| config.monitoring.clone()).unwrap_or_default();
+    match cli.verbose {
+        0 => {}
+        1 => monitoring.log_level = "debug".to_string(),
+        _ => monitoring.log_level = "trace".to_string(),
+    }
+    init_logging(&monitoring)?;
+    
+    // Configuration warnings are logged while loading, before the logger exists
+    if let Ok(config) = &config {
+        for warning in config.validate() {
+            warn!("Contradictory configuration: {}", warning);
+        }
+    }
     
-    // Load configuration
-    let config = Config::load()?;
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => {
+            let config = config?;
+            
+            // Create inference engine
+            let engine = InferenceEngine::new(config).await?;
+            
+            // Start server
+            engine.start_server().await?;
+        }
+        Command::Infer(args) => infer(config?, args, cli.json).await?,
+        Command::Models { command: ModelsCommand::List } => {
+            let engine = InferenceEngine::new(config?).await?;
+            let models = engine.available_models().await?;
+            
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&models)?);
+            } else if models.is_empty() {
+                println!("No models in {}", engine.config().model.cache_dir);
+            } else {
+                for model in models {
+                    let status = if model.loaded { "loaded" } else { "available" };
+                    println!("{:<32} {:<10} {}", model.name, status, model.path);
+                }
+            }
+        }
+        Command::Download(args) => download(config?, args, cli.json).await?,
+        Command::Bench(args) => bench(config?, args, cli.json).await?,
+        Command::Loadtest(args) => {
+            let options = LoadTestOptions {
+                url: args.url,
+                concurrency: args.concurrency,
+                duration: Duration::from_secs(args.duration),
+                warmup: Duration::from_secs(args.warmup),
+                max_rate: args.rate,
+                body: tokio::fs::read(&args.input).await?,
+            };
+            
+            let report = loadtest::run(options).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
     
-    // Create inference engine
+    Ok(())
+}
+
+/// Name a model is registered under once loaded from a path
+fn model_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Load a model, run one input through it and print the decoded prediction
+async fn infer(config: Config, args: InferArgs, json: bool) -> Result<()> {
     let engine = InferenceEngine::new(config).await?;
+    engine.load_model(&args.model).await?;
+    
+    let input = tokio::fs::read(&args.input).await?;
+    let scope = ModelScope { model: Some(model_name(&args.model)), ..ModelScope::default() };
+    let prediction = engine.infer_with_fallback(input, CacheMode::Bypass, &scope).await?;
+    
+    let result = match engine.postprocessor(&prediction.model).await {
+        Some(postprocessor) => postprocessor.decode(&prediction.output, args.top_k.unwrap_or(DEFAULT_TOP_K))?,
+        None => PredictionResult::Generation { text: String::from_utf8_lossy(&prediction.output).to_string() },
+    };
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "model": prediction.model,
+            "degraded": prediction.degraded,
+            "result": result,
+        }))?);
+        return Ok(());
+    }
+    
+    match result {
+        PredictionResult::Classification { labels } => {
+            for label in labels {
+                println!("{:<32} {:.4}", label.label, label.score);
+            }
+        }
+        PredictionResult::Generation { text } => println!("{}", text),
+        PredictionResult::Embedding { vector } => {
+            println!("{}-dimensional embedding: {:?}", vector.len(), vector);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Download a model file into the model cache directory or a given path
+async fn download(config: Config, args: DownloadArgs, json: bool) -> Result<()> {
+    let path = match &args.output {
+        Some(output) => output.to_string_lossy().to_string(),
+        None => {
+            let name = args.repo.rsplit('/').next().unwrap_or(&args.repo);
+            match Path::new(&args.file).extension().and_then(|s| s.to_str()) {
+                Some(extension) => format!("{}/{}.{}", config.model.cache_dir, name, extension),
+                None => format!("{}/{}", config.model.cache_dir, name),
+            }
+        }
+    };
+    
+    Model::download(&args.repo, &args.revision, &args.file, &path, &config.model).await?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "repo": args.repo,
+            "revision": args.revision,
+            "file": args.file,
+            "path": path,
+        }))?);
+    } else {
+        println!("Downloaded {}/{} to {}", args.repo, args.file, path);
+    }
+    
+    Ok(())
+}
+
+/// Load a model and report its inference latency and throughput
+async fn bench(config: Config, args: BenchArgs, json: bool) -> Result<()> {
+    let engine = InferenceEngine::new(config).await?;
+    engine.load_model(&args.model).await?;
+    
+    let input = match &args.input {
+        Some(path) => tokio::fs::read(path).await?,
+        None => BENCH_INPUT.to_vec(),
+    };
+    let report = engine.benchmark(&model_name(&args.model), input, args.iterations, args.concurrency).await?;
     
-    // Start server
-    engine.start_server().await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Model:       {}", report.model);
+        println!("Runs:        {} ({} failed, {} concurrent)", report.iterations, report.errors, report.concurrency);
+        println!("Throughput:  {:.1} runs/s", report.throughput);
+        println!("Latency:     mean {:.2} ms, p50 {:.2} ms, p90 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+            report.mean_ms, report.p50_ms, report.p90_ms, report.p99_ms, report.max_ms);
+    }
     
     Ok(())
 }